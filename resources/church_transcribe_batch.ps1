@@ -1,22 +1,79 @@
+[CmdletBinding(DefaultParameterSetName = "Run")]
 param(
-  [Parameter(Mandatory=$true)][string]$InputFolder,
-  [Parameter(Mandatory=$true)][string]$OutputFolder,
-  [Parameter(Mandatory=$true)][string]$ModelFile,
+  [Parameter(Mandatory=$true, ParameterSetName = "Run")][string]$InputFolder,
+  [Parameter(Mandatory=$true, ParameterSetName = "Run")][string]$OutputFolder,
+  [Parameter(Mandatory=$true, ParameterSetName = "Run")][string]$ModelFile,
   [string]$WhisperExe = "whisper-cli.exe",
   [string]$FfmpegExe = "ffmpeg.exe",
   [switch]$Force,
   [switch]$NoRecursive,
   [int]$Limit = 0,
   [string]$BeforeDate = "",
+  [string]$AfterDate = "",
   [int]$Threads = 0,
   [switch]$FastScan,
   [switch]$KeepAudio,
-  [string]$PauseFlagFile = ""
+  [string]$PauseFlagFile = "",
+  [string]$StopFlagFile = "",
+  [string]$PlanFile = "",
+  [string]$AudioFilter = "",
+  [string]$SilenceDetect = "",
+  [switch]$SplitChannels,
+  [switch]$TranslationPass,
+  [switch]$DenoiseStats,
+  [string]$MusicDetect = "",
+  [int]$MusicMinSecs = 60,
+  [ValidateSet("mark", "skip")][string]$MusicMode = "mark",
+  [string]$Language = "",
+  [string]$Temperature = "",
+  [int]$BeamSize = 0,
+  [int]$BestOf = 0,
+  [string]$EntropyThreshold = "",
+  [ValidateSet("whisper-cpp", "faster-whisper", "cloud")][string]$Engine = "whisper-cpp",
+  [string]$Device = "",
+  [string]$ComputeType = "",
+  [string]$CloudEndpoint = "",
+  [string]$CloudModel = "whisper-1",
+  [int]$CloudChunkSecs = 600,
+  [int]$CheckpointMinutes = 0,
+  [int]$ChunkOverMinutes = 0,
+  [int]$ChunkMinutes = 10,
+  [int]$ChunkOverlapSecs = 5,
+  [int]$ParallelChunks = 1,
+  [switch]$NoGarbageRetry,
+  [string]$FallbackModel = "",
+  [switch]$NoCloudFallback,
+  [int]$GpuDevice = -1,
+  [string]$ClaimDir = "",
+  [string]$WorkerName = "",
+  [Parameter(ParameterSetName = "Probe")][switch]$Probe
 )
 
 Set-StrictMode -Version Latest
 $ErrorActionPreference = "Stop"
 
+# Progress lines carry source paths; UTF-8 keeps accents and dashes intact for
+# the app instead of the console's legacy code page. Without a console the
+# setter can throw, and output is already UTF-8 there.
+try {
+  [Console]::OutputEncoding = New-Object System.Text.UTF8Encoding $false
+  $OutputEncoding = [Console]::OutputEncoding
+} catch {
+}
+
+# Preflight: proves the script itself is allowed to run and reports the
+# environment it runs in.
+if ($Probe) {
+  $probeInfo = @{
+    psVersion = $PSVersionTable.PSVersion.ToString()
+    psMajor = $PSVersionTable.PSVersion.Major
+    languageMode = $ExecutionContext.SessionState.LanguageMode.ToString()
+    executionPolicy = (Get-ExecutionPolicy).ToString()
+  }
+  Write-Output ("[probe] " + ($probeInfo | ConvertTo-Json -Compress))
+  exit 0
+}
+
 function Wait-IfPaused {
   param(
     [string]$PauseFlagPath,
@@ -44,6 +101,92 @@ function Wait-IfPaused {
   }
 }
 
+# Workers sharing a plan each take a file by creating its claim marker; only
+# one of them can. Without a claim folder every file is this worker's.
+function Request-FileClaim {
+  param([string]$Path)
+
+  if ([string]::IsNullOrWhiteSpace($ClaimDir)) { return $true }
+  $sha = [System.Security.Cryptography.SHA1]::Create()
+  try {
+    $hash = $sha.ComputeHash([System.Text.Encoding]::UTF8.GetBytes($Path.ToLowerInvariant()))
+  } finally {
+    $sha.Dispose()
+  }
+  $key = -join ($hash | ForEach-Object { $_.ToString("x2") })
+  try {
+    $stream = [System.IO.File]::Open((Join-Path $ClaimDir "$key.claim"), [System.IO.FileMode]::CreateNew)
+    $stream.Dispose()
+    return $true
+  } catch {
+    return $false
+  }
+}
+
+# In copy-local mode the app copies each source to a local staging path and
+# drops a .ready (or .failed) marker next to it. Returns $null when the copy is
+# ready, otherwise the copier's error.
+function Wait-StagedCopy {
+  param([string]$StagedPath)
+
+  $readyPath = "$StagedPath.ready"
+  $failedPath = "$StagedPath.failed"
+  $announced = $false
+  while (-not (Test-Path -LiteralPath $readyPath)) {
+    if (Test-Path -LiteralPath $failedPath) {
+      $reason = Get-Content -LiteralPath $failedPath -Raw -ErrorAction SilentlyContinue
+      Remove-Item -LiteralPath $failedPath -Force -ErrorAction SilentlyContinue
+      if ([string]::IsNullOrWhiteSpace($reason)) { $reason = "copy failed" }
+      return $reason
+    }
+    if (-not $announced) {
+      Write-Host "[copy] Waiting for local copy: $StagedPath"
+      $announced = $true
+    }
+    Start-Sleep -Milliseconds 500
+  }
+  return $null
+}
+
+function Remove-StagedCopy {
+  param([string]$StagedPath)
+
+  if ([string]::IsNullOrWhiteSpace($StagedPath)) { return }
+  Remove-Item -LiteralPath $StagedPath -Force -ErrorAction SilentlyContinue
+  Remove-Item -LiteralPath "$StagedPath.ready" -Force -ErrorAction SilentlyContinue
+}
+
+# Long paths (deeply nested NAS folders) need the \\?\ prefix before .NET and
+# Win32 accept them; shorter paths are left alone.
+function ConvertTo-LongPath {
+  param([string]$Path)
+
+  if ([string]::IsNullOrEmpty($Path) -or $Path.StartsWith("\\?\") -or $Path.Length -lt 248) {
+    return $Path
+  }
+  $Path = $Path.Replace("/", "\")
+  if ($Path.StartsWith("\\")) {
+    return "\\?\UNC\" + $Path.Substring(2)
+  }
+  return "\\?\" + $Path
+}
+
+# The path as the user knows it, for progress lines, metadata and the index.
+function ConvertFrom-LongPath {
+  param([string]$Path)
+
+  if ([string]::IsNullOrEmpty($Path)) {
+    return $Path
+  }
+  if ($Path.StartsWith("\\?\UNC\")) {
+    return "\\" + $Path.Substring(8)
+  }
+  if ($Path.StartsWith("\\?\")) {
+    return $Path.Substring(4)
+  }
+  return $Path
+}
+
 function Emit-Progress {
   param(
     [int]$Done,
@@ -57,17 +200,593 @@ function Emit-Progress {
     $line += " status=$Status"
   }
   if (-not [string]::IsNullOrWhiteSpace($Source)) {
-    $line += " source=$Source"
+    $line += " source=$(ConvertFrom-LongPath $Source)"
   }
 
   Write-Host $line
 }
 
+# Integrated loudness (ebur128) plus overall RMS and noise floor (astats) for
+# the QC report's before/after denoise comparison. Unmeasured values are $null.
+function Measure-AudioStats {
+  param([string]$Path)
+
+  $ErrorActionPreference = "Continue"
+  $culture = [Globalization.CultureInfo]::InvariantCulture
+  $stats = [ordered]@{ integratedLufs = $null; rmsDb = $null; noiseFloorDb = $null }
+  $log = & $FfmpegExe -hide_banner -nostats -i $Path -vn -af "ebur128=framelog=quiet,astats" -f null - 2>&1
+  foreach ($entry in $log) {
+    $line = "$entry"
+    if ($line -match "^\s*I:\s*(-?[0-9.]+) LUFS") {
+      $stats.integratedLufs = [double]::Parse($Matches[1], $culture)
+    } elseif ($line -match "RMS level dB:\s*(-?[0-9.]+)\s*$") {
+      $stats.rmsDb = [double]::Parse($Matches[1], $culture)
+    } elseif ($line -match "Noise floor dB:\s*(-?[0-9.]+)\s*$") {
+      $stats.noiseFloorDb = [double]::Parse($Matches[1], $culture)
+    }
+  }
+  return $stats
+}
+
+# Finds stretches of at least $MinSecs without a single short pause. Speech
+# stops for breath every few seconds; congregational singing over a band does
+# not. Returns ranges in seconds on the audio's own timeline.
+function Get-MusicRanges {
+  param(
+    [string]$AudioPath,
+    [string]$Filter,
+    [int]$MinSecs
+  )
+
+  $ErrorActionPreference = "Continue"
+  $culture = [Globalization.CultureInfo]::InvariantCulture
+  $ranges = New-Object System.Collections.Generic.List[object]
+  # Sound runs from each silence_end (or the start) to the next silence_start.
+  $soundStart = 0.0
+  $log = & $FfmpegExe -hide_banner -nostats -i $AudioPath -af "silencedetect=$Filter" -f null - 2>&1
+  $duration = $null
+  foreach ($entry in $log) {
+    $line = "$entry"
+    if ($line -match "silence_start:\s*(-?[0-9.]+)") {
+      $soundEnd = [math]::Max(0.0, [double]::Parse($Matches[1], $culture))
+      if ($null -ne $soundStart -and $soundEnd - $soundStart -ge $MinSecs) {
+        $ranges.Add([pscustomobject]@{ Start = $soundStart; End = $soundEnd })
+      }
+      $soundStart = $null
+    } elseif ($line -match "silence_end:\s*([0-9.]+)") {
+      $soundStart = [double]::Parse($Matches[1], $culture)
+    } elseif ($line -match "Duration:\s*(\d+):(\d+):([0-9.]+)") {
+      $duration = [int]$Matches[1] * 3600 + [int]$Matches[2] * 60 + [double]::Parse($Matches[3], $culture)
+    }
+  }
+  if ($LASTEXITCODE -ne 0) { return @() }
+  if ($null -ne $soundStart -and $null -ne $duration -and $duration - $soundStart -ge $MinSecs) {
+    $ranges.Add([pscustomobject]@{ Start = $soundStart; End = $duration })
+  }
+  return ,$ranges.ToArray()
+}
+
+# Finds leading/trailing silence with ffmpeg silencedetect. Returns the window
+# to keep (seconds, End may be $null) or $null when nothing should be trimmed.
+function Get-SpeechWindow {
+  param(
+    [string]$AudioPath,
+    [string]$Filter
+  )
+
+  # silencedetect reports on stderr; don't let Stop turn those lines into errors.
+  $ErrorActionPreference = "Continue"
+  $culture = [Globalization.CultureInfo]::InvariantCulture
+  $padSeconds = 0.5
+  $starts = New-Object System.Collections.Generic.List[double]
+  $ends = New-Object System.Collections.Generic.List[double]
+
+  $log = & $FfmpegExe -hide_banner -nostats -i $AudioPath -af "silencedetect=$Filter" -f null - 2>&1
+  foreach ($entry in $log) {
+    $line = "$entry"
+    if ($line -match "silence_start:\s*(-?[0-9.]+)") {
+      $starts.Add([double]::Parse($Matches[1], $culture))
+    } elseif ($line -match "silence_end:\s*([0-9.]+)") {
+      $ends.Add([double]::Parse($Matches[1], $culture))
+    }
+  }
+  if ($LASTEXITCODE -ne 0 -or $starts.Count -eq 0) { return $null }
+
+  $start = 0.0
+  if ($starts[0] -le $padSeconds -and $ends.Count -gt 0) {
+    $start = [math]::Max(0.0, $ends[0] - $padSeconds)
+  }
+  # An unmatched final silence_start means the silence ran to the end of the file.
+  $end = $null
+  if ($starts.Count -gt $ends.Count) {
+    $end = $starts[$starts.Count - 1] + $padSeconds
+  }
+
+  if ($start -eq 0.0 -and $null -eq $end) { return $null }
+  if ($null -ne $end -and $end -le $start) { return $null }
+  return [pscustomobject]@{ Start = $start; End = $end }
+}
+
+# Extracts one channel of the source (same filter and trim window as the main
+# audio) and writes channel-<name>.txt/.json next to the main transcript.
+function Invoke-ChannelTranscript {
+  param(
+    [string]$SourcePath,
+    [string]$ServiceDir,
+    [string]$Name,
+    [int]$Index,
+    $Window
+  )
+
+  $culture = [Globalization.CultureInfo]::InvariantCulture
+  $channelAudio = Join-Path $ServiceDir "audio-$Name.wav"
+  $channelBase = Join-Path $ServiceDir "channel-$Name"
+
+  $filter = "pan=mono|c0=c$Index"
+  if (-not [string]::IsNullOrWhiteSpace($AudioFilter)) {
+    $filter += ",$AudioFilter"
+  }
+  $channelArgs = @("-y", "-loglevel", "error")
+  if ($null -ne $Window) {
+    $channelArgs += @("-ss", $Window.Start.ToString("0.###", $culture))
+  }
+  $channelArgs += @("-i", $SourcePath)
+  if ($null -ne $Window -and $null -ne $Window.End) {
+    $channelArgs += @("-t", ($Window.End - $Window.Start).ToString("0.###", $culture))
+  }
+  $channelArgs += @("-vn", "-af", $filter, "-ar", "16000", $channelAudio)
+
+  & $FfmpegExe @channelArgs
+  if ($LASTEXITCODE -ne 0) {
+    Write-Warning "Could not extract $Name channel (is the recording stereo?): $SourcePath"
+    return
+  }
+
+  if (-not (Invoke-Transcriber -AudioPath $channelAudio -OutBase $channelBase)) {
+    Write-Warning "whisper failed on $Name channel: $SourcePath"
+  }
+  Remove-Item -LiteralPath $channelAudio -Force -ErrorAction SilentlyContinue
+}
+
+# Decoding overrides in the engine's own flag names; floats arrive
+# preformatted with "." so culture can't change them.
+function Get-DecodingArgs {
+  $fasterWhisper = $Engine -eq "faster-whisper"
+  $decoding = @()
+  if (-not [string]::IsNullOrWhiteSpace($Temperature)) {
+    $decoding += @("--temperature", $Temperature)
+  }
+  if ($BeamSize -gt 0) {
+    $decoding += @($(if ($fasterWhisper) { "--beam_size" } else { "--beam-size" }), "$BeamSize")
+  }
+  if ($BestOf -gt 0) {
+    $decoding += @($(if ($fasterWhisper) { "--best_of" } else { "--best-of" }), "$BestOf")
+  }
+  if (-not [string]::IsNullOrWhiteSpace($EntropyThreshold)) {
+    # faster-whisper's closest equivalent is the compression ratio threshold.
+    $decoding += @($(if ($fasterWhisper) { "--compression_ratio_threshold" } else { "--entropy-thold" }), $EntropyThreshold)
+  }
+  return ,$decoding
+}
+
+# Decoding for a retry after garbage output: no conditioning on the previous
+# text, which is what keeps a repetition loop going, a little more temperature
+# and a beam search when none was set.
+function Get-RetryDecodingArgs {
+  $fasterWhisper = $Engine -eq "faster-whisper"
+  $culture = [Globalization.CultureInfo]::InvariantCulture
+  $temperature = 0.0
+  if (-not [double]::TryParse($Temperature, [Globalization.NumberStyles]::Float, $culture, [ref]$temperature)) { $temperature = 0.0 }
+  $decoding = @("--temperature", ([math]::Min(1.0, $temperature + 0.2)).ToString("0.0#", $culture))
+  $decoding += @($(if ($fasterWhisper) { "--beam_size" } else { "--beam-size" }), "$([math]::Max($BeamSize, 5))")
+  if ($BestOf -gt 0) {
+    $decoding += @($(if ($fasterWhisper) { "--best_of" } else { "--best-of" }), "$BestOf")
+  }
+  if ($fasterWhisper) {
+    $decoding += @("--condition_on_previous_text", "False")
+  } else {
+    $decoding += @("--max-context", "0")
+  }
+  return ,$decoding
+}
+
+# Why the engine output at $OutBase looks like garbage, or "" when it looks
+# fine: nothing for audio that has speech, one line repeated over and over, or
+# far too few words for the length of the audio.
+function Test-GarbageOutput {
+  param(
+    [Parameter(Mandatory=$true)][string]$OutBase,
+    [double]$SpeechSecs
+  )
+
+  $text = ""
+  if (Test-Path -LiteralPath "$OutBase.txt") {
+    $text = Get-Content -LiteralPath "$OutBase.txt" -Raw -Encoding UTF8
+  }
+  $words = @("$text" -split "\s+" | Where-Object { $_ -ne "" }).Count
+  if ($words -eq 0 -and $SpeechSecs -ge 30) { return "empty transcript for $([math]::Round($SpeechSecs))s of audio" }
+
+  $lines = @()
+  if (Test-Path -LiteralPath "$OutBase.json") {
+    try {
+      $parsed = Get-Content -LiteralPath "$OutBase.json" -Raw -Encoding UTF8 | ConvertFrom-Json
+      $lines = if ($parsed.PSObject.Properties.Name -contains "transcription") { @($parsed.transcription) } else { @($parsed.segments) }
+      $lines = @($lines | ForEach-Object { ("$($_.text)".ToLowerInvariant() -replace "[^\p{L}\p{N} ]", "").Trim() })
+    } catch { }
+  }
+  $streak = 1
+  $longest = 1
+  for ($i = 1; $i -lt $lines.Count; $i++) {
+    $streak = if ($lines[$i] -ne "" -and $lines[$i] -eq $lines[$i - 1]) { $streak + 1 } else { 1 }
+    $longest = [math]::Max($longest, $streak)
+  }
+  if ($longest -ge 10) { return "the same line repeated $longest times" }
+
+  if ($SpeechSecs -ge 300) {
+    $perMinute = $words * 60.0 / $SpeechSecs
+    if ($perMinute -lt 20) { return "only $([math]::Round($perMinute)) words a minute" }
+  }
+  return ""
+}
+
+# Uploads the WAV in chunks to an OpenAI-compatible /audio/transcriptions
+# endpoint and stitches the segments into "$OutBase.json" and "$OutBase.txt".
+# The API key comes from the environment so it never appears on a command line.
+function Invoke-CloudTranscript {
+  param(
+    [Parameter(Mandatory=$true)][string]$AudioPath,
+    [Parameter(Mandatory=$true)][string]$OutBase
+  )
+
+  $chunkDir = "$OutBase-chunks"
+  $client = $null
+  try {
+    New-Item -ItemType Directory -Force -Path $chunkDir | Out-Null
+    & $FfmpegExe -y -loglevel error -i $AudioPath -f segment -segment_time "$CloudChunkSecs" -c copy (Join-Path $chunkDir "chunk-%03d.wav") | Out-Host
+    if ($LASTEXITCODE -ne 0) { return $false }
+
+    Add-Type -AssemblyName System.Net.Http
+    $client = New-Object System.Net.Http.HttpClient
+    $client.Timeout = [TimeSpan]::FromMinutes(15)
+    $apiKey = $env:CHURCH_TRANSCRIBER_CLOUD_KEY
+    if (-not [string]::IsNullOrWhiteSpace($apiKey)) {
+      $client.DefaultRequestHeaders.Authorization = New-Object System.Net.Http.Headers.AuthenticationHeaderValue("Bearer", $apiKey)
+    }
+    $task = if ($Translate) { "translations" } else { "transcriptions" }
+    $url = "$($CloudEndpoint.TrimEnd('/'))/audio/$task"
+
+    $segments = New-Object System.Collections.Generic.List[object]
+    $offset = 0.0
+    foreach ($chunk in (Get-ChildItem -LiteralPath $chunkDir -Filter "chunk-*.wav" | Sort-Object Name)) {
+      $form = New-Object System.Net.Http.MultipartFormDataContent
+      $stream = [IO.File]::OpenRead($chunk.FullName)
+      try {
+        $fileContent = New-Object System.Net.Http.StreamContent($stream)
+        $fileContent.Headers.ContentType = [System.Net.Http.Headers.MediaTypeHeaderValue]::Parse("audio/wav")
+        $form.Add($fileContent, "file", $chunk.Name)
+        $form.Add((New-Object System.Net.Http.StringContent($CloudModel)), "model")
+        $form.Add((New-Object System.Net.Http.StringContent("verbose_json")), "response_format")
+        # The translations endpoint always answers in English and takes no language.
+        if (-not $Translate -and -not [string]::IsNullOrWhiteSpace($Language)) {
+          $form.Add((New-Object System.Net.Http.StringContent($Language)), "language")
+        }
+        if (-not [string]::IsNullOrWhiteSpace($Temperature)) {
+          $form.Add((New-Object System.Net.Http.StringContent($Temperature)), "temperature")
+        }
+        $response = $client.PostAsync($url, $form).GetAwaiter().GetResult()
+        $body = $response.Content.ReadAsStringAsync().GetAwaiter().GetResult()
+      } finally {
+        $stream.Dispose()
+        $form.Dispose()
+      }
+      if (-not $response.IsSuccessStatusCode) {
+        Write-Warning "Cloud transcription failed ($([int]$response.StatusCode)): $body"
+        return $false
+      }
+
+      $parsed = $body | ConvertFrom-Json
+      # 16 kHz mono 16-bit PCM after a 44-byte header, when the API omits duration.
+      $duration = ($chunk.Length - 44) / 32000.0
+      if ($parsed.PSObject.Properties.Name -contains "duration" -and $null -ne $parsed.duration) {
+        $duration = [double]$parsed.duration
+      }
+      if ($parsed.PSObject.Properties.Name -contains "segments" -and $null -ne $parsed.segments) {
+        foreach ($segment in $parsed.segments) {
+          $scored = [ordered]@{ start = $offset + $segment.start; end = $offset + $segment.end; text = $segment.text }
+          if ($segment.PSObject.Properties.Name -contains "avg_logprob") {
+            $scored.avg_logprob = $segment.avg_logprob
+          }
+          $segments.Add([pscustomobject]$scored)
+        }
+      } elseif (-not [string]::IsNullOrWhiteSpace($parsed.text)) {
+        $segments.Add([pscustomobject]@{ start = $offset; end = $offset + $duration; text = $parsed.text })
+      }
+      $offset += $duration
+    }
+
+    (@{ segments = $segments.ToArray() } | ConvertTo-Json -Depth 4) | Set-Content -LiteralPath "$OutBase.json" -Encoding UTF8
+    $lines = @($segments | ForEach-Object { "$($_.text)".Trim() })
+    ($lines -join "`n") | Set-Content -LiteralPath "$OutBase.txt" -Encoding UTF8
+    return $true
+  } catch {
+    Write-Warning "Cloud transcription failed: $($_.Exception.Message)"
+    return $false
+  } finally {
+    if ($null -ne $client) { $client.Dispose() }
+    Remove-Item -LiteralPath $chunkDir -Recurse -Force -ErrorAction SilentlyContinue
+  }
+}
+
+# Runs the configured engine on one WAV and leaves "$OutBase.txt" plus the
+# engine's JSON at "$OutBase.json". Returns $false when the engine fails.
+function Invoke-Transcriber {
+  param(
+    [Parameter(Mandatory=$true)][string]$AudioPath,
+    [Parameter(Mandatory=$true)][string]$OutBase
+  )
+
+  if ($Engine -eq "cloud") {
+    if (Invoke-CloudTranscript -AudioPath $AudioPath -OutBase $OutBase) {
+      $script:usedEngine = "cloud"
+      return $true
+    }
+    if ($NoCloudFallback) { return $false }
+    Write-Warning "Falling back to local whisper.cpp for $AudioPath"
+  }
+
+  if ($Engine -eq "faster-whisper") {
+    $outDir = Split-Path -Parent $OutBase
+    # Verbose prints each segment as it is decoded, which keyword alerts watch for.
+    $engineArgs = @($AudioPath, "--output_dir", $outDir, "--output_format", "json", "--verbose", "True")
+    if (Test-Path -LiteralPath $ModelFile -PathType Container) {
+      $engineArgs += @("--model_directory", $ModelFile)
+    } else {
+      $engineArgs += @("--model", $ModelFile)
+    }
+    if ($Threads -gt 0) {
+      $engineArgs += @("--threads", "$Threads")
+    }
+    if (-not [string]::IsNullOrWhiteSpace($Language)) {
+      $engineArgs += @("--language", $Language)
+    }
+    if ($Translate) {
+      $engineArgs += @("--task", "translate")
+    }
+    if (-not [string]::IsNullOrWhiteSpace($Device)) {
+      $engineArgs += @("--device", $Device)
+    }
+    if (-not [string]::IsNullOrWhiteSpace($ComputeType)) {
+      $engineArgs += @("--compute_type", $ComputeType)
+    }
+    $engineArgs += $decodingArgs
+    & $WhisperExe @engineArgs | Out-Host
+    if ($LASTEXITCODE -ne 0) { return $false }
+
+    # Output is named after the audio file; the JSON's segments make the text file.
+    $produced = Join-Path $outDir ([IO.Path]::GetFileNameWithoutExtension($AudioPath) + ".json")
+    if (-not (Test-Path -LiteralPath $produced)) { return $false }
+    if ($produced -ne "$OutBase.json") {
+      Move-Item -LiteralPath $produced -Destination "$OutBase.json" -Force
+    }
+    $parsed = Get-Content -LiteralPath "$OutBase.json" -Raw -Encoding UTF8 | ConvertFrom-Json
+    $lines = @($parsed.segments | ForEach-Object { $_.text.Trim() })
+    ($lines -join "`n") | Set-Content -LiteralPath "$OutBase.txt" -Encoding UTF8
+    $script:usedEngine = "faster-whisper"
+    return $true
+  }
+
+  $script:usedEngine = "whisper-cpp"
+  # whisper-cli reads its arguments in the ANSI code page, which can't spell
+  # every accented folder name; run it from the output folder with bare names.
+  $workDir = [IO.Path]::GetDirectoryName($OutBase)
+  $audioArg = $AudioPath
+  $outArg = $OutBase
+  $modelArg = $ModelFile
+  $relative = "$AudioPath$OutBase" -match "[^\x00-\x7F]" -and $workDir -eq [IO.Path]::GetDirectoryName($AudioPath)
+  if ($relative) {
+    $audioArg = [IO.Path]::GetFileName($AudioPath)
+    $outArg = [IO.Path]::GetFileName($OutBase)
+    $modelArg = [IO.Path]::GetFullPath($ModelFile)
+  }
+  # -ojf adds token probabilities to the JSON, which the quality report scores.
+  $engineArgs = @("-m", $modelArg, "-f", $audioArg, "-of", $outArg, "-otxt", "-ojf")
+  if ($Threads -gt 0) {
+    $engineArgs += @("-t", "$Threads")
+  }
+  if (-not [string]::IsNullOrWhiteSpace($Language)) {
+    $engineArgs += @("-l", $Language)
+  }
+  if ($Translate) {
+    $engineArgs += "-tr"
+  }
+  $engineArgs += $decodingArgs
+  # Out-Host keeps the engine's output in the log rather than in this function's result.
+  if ($relative) {
+    Push-Location -LiteralPath $workDir
+    try {
+      & $WhisperExe @engineArgs | Out-Host
+    } finally {
+      Pop-Location
+    }
+  } else {
+    & $WhisperExe @engineArgs | Out-Host
+  }
+  return ($LASTEXITCODE -eq 0)
+}
+
+# Transcribes long audio in $ChunkSecs pieces under .chunks/ next to $OutBase,
+# keeping each finished piece's output so a rerun after a crash resumes at the
+# first unfinished one. Each piece runs $OverlapSecs past its end and up to
+# $Parallel pieces are transcribed at once. Leaves the stitched "$OutBase.txt"
+# and "$OutBase.json" in the first piece's engine format, like Invoke-Transcriber.
+function Invoke-ChunkedTranscriber {
+  param(
+    [Parameter(Mandatory=$true)][string]$AudioPath,
+    [Parameter(Mandatory=$true)][string]$OutBase,
+    [Parameter(Mandatory=$true)][int]$ChunkSecs,
+    [int]$OverlapSecs = 0,
+    [int]$Parallel = 1,
+    [string]$SourceKey
+  )
+
+  $chunkDir = Join-Path (Split-Path -Parent $OutBase) ".chunks"
+  $keyPath = Join-Path $chunkDir "source.txt"
+  $audioBytes = (Get-Item -LiteralPath $AudioPath).Length
+  # 16 kHz mono 16-bit PCM after a 44-byte header.
+  $count = [int][math]::Ceiling(($audioBytes - 44) / 32000.0 / $ChunkSecs)
+  # Pieces from another recording, model or piece length can't be reused.
+  $key = "$SourceKey|$ModelFile|$Language|$Translate|$ChunkSecs|$OverlapSecs|$audioBytes"
+  $reuse = (Test-Path -LiteralPath $keyPath) -and ((Get-Content -LiteralPath $keyPath -Raw -Encoding UTF8).Trim() -eq $key)
+  if (-not $reuse) {
+    Remove-Item -LiteralPath $chunkDir -Recurse -Force -ErrorAction SilentlyContinue
+    New-Item -ItemType Directory -Force -Path $chunkDir | Out-Null
+    Set-Content -LiteralPath $keyPath -Value $key -Encoding UTF8
+  }
+
+  $pieces = @(for ($i = 0; $i -lt $count; $i++) {
+    [pscustomobject]@{ Index = $i; Start = $i * $ChunkSecs; Base = Join-Path $chunkDir ("chunk-{0:D3}" -f $i) }
+  })
+  $pending = @($pieces | Where-Object { -not (Test-Path -LiteralPath "$($_.Base).done") })
+  if ($pending.Count -lt $pieces.Count) {
+    Write-Host "[chunks] $($pieces.Count - $pending.Count) of $count piece(s) already transcribed"
+  }
+  foreach ($piece in $pending) {
+    & $FfmpegExe -y -loglevel error -ss "$($piece.Start)" -t "$($ChunkSecs + $OverlapSecs)" -i $AudioPath -c copy "$($piece.Base).wav" | Out-Host
+    if ($LASTEXITCODE -ne 0) { return $false }
+  }
+
+  if ($Parallel -le 1) {
+    foreach ($piece in $pending) {
+      Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before piece $($piece.Index + 1)"
+      Write-Host "[chunks] piece $($piece.Index + 1)/$count from $($piece.Start)s"
+      if (-not (Invoke-Transcriber -AudioPath "$($piece.Base).wav" -OutBase $piece.Base)) { return $false }
+      Set-Content -LiteralPath "$($piece.Base).done" -Value $usedEngine -Encoding ASCII
+    }
+  } else {
+    # Background jobs are separate processes: hand them the engine functions
+    # and the settings those read. Switches become plain booleans on the way.
+    $definitions = (@("Invoke-Transcriber", "Invoke-CloudTranscript") | ForEach-Object {
+      "function $_ {$((Get-Item -LiteralPath "function:$_").Definition)}"
+    }) -join "`n"
+    $state = @{
+      Engine = $Engine; WhisperExe = $WhisperExe; FfmpegExe = $FfmpegExe; ModelFile = $ModelFile
+      Language = $Language; Translate = [bool]$Translate; Device = $Device; ComputeType = $ComputeType
+      Temperature = $Temperature; decodingArgs = $decodingArgs; NoCloudFallback = [bool]$NoCloudFallback
+      CloudEndpoint = $CloudEndpoint; CloudModel = $CloudModel; CloudChunkSecs = $CloudChunkSecs
+      Threads = $(if ($Threads -gt 0) { [math]::Max(1, [int][math]::Floor($Threads / $Parallel)) } else { 0 })
+    }
+    $worker = {
+      param($Definitions, $State, $AudioPath, $OutBase)
+      $ErrorActionPreference = "Stop"
+      foreach ($entry in $State.GetEnumerator()) { Set-Variable -Name $entry.Key -Value $entry.Value -Scope Script }
+      $script:usedEngine = $State.Engine
+      . ([ScriptBlock]::Create($Definitions))
+      if (Invoke-Transcriber -AudioPath $AudioPath -OutBase $OutBase) {
+        Set-Content -LiteralPath "$OutBase.done" -Value $script:usedEngine -Encoding ASCII
+      }
+    }
+
+    $jobs = @()
+    $next = 0
+    while ($next -lt $pending.Count -or $jobs.Count -gt 0) {
+      while ($next -lt $pending.Count -and $jobs.Count -lt $Parallel) {
+        $piece = $pending[$next]
+        Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before piece $($piece.Index + 1)"
+        Write-Host "[chunks] piece $($piece.Index + 1)/$count from $($piece.Start)s"
+        $jobs += Start-Job -ScriptBlock $worker -ArgumentList $definitions, $state, "$($piece.Base).wav", $piece.Base
+        $next += 1
+      }
+      $finished = Wait-Job -Job $jobs -Any
+      Receive-Job -Job $finished -ErrorAction Continue | Out-Host
+      Remove-Job -Job $finished -Force
+      $jobs = @($jobs | Where-Object { $_.Id -ne $finished.Id })
+    }
+    $failed = @($pending | Where-Object { -not (Test-Path -LiteralPath "$($_.Base).done") })
+    if ($failed.Count -gt 0) {
+      Write-Warning "$($failed.Count) piece(s) failed; finished pieces are kept for the next run."
+      return $false
+    }
+  }
+
+  # Each seam is cut in the middle of its overlap, so both sides keep the half
+  # they heard with context; a line repeated across the seam is kept once.
+  $items = New-Object System.Collections.Generic.List[object]
+  $texts = New-Object System.Collections.Generic.List[string]
+  $merged = $null
+  $mergedEngine = ""
+  $lastText = ""
+  foreach ($piece in $pieces) {
+    $parsed = Get-Content -LiteralPath "$($piece.Base).json" -Raw -Encoding UTF8 | ConvertFrom-Json
+    if ($null -eq $merged) {
+      $merged = $parsed
+      $mergedEngine = (Get-Content -LiteralPath "$($piece.Base).done" -Raw -Encoding ASCII).Trim()
+    }
+    $whisperCpp = $merged.PSObject.Properties.Name -contains "transcription"
+    $keepFrom = if ($piece.Index -gt 0) { $piece.Start + $OverlapSecs / 2.0 } else { 0.0 }
+    $keepUntil = if ($piece.Index -lt $count - 1) { $piece.Start + $ChunkSecs + $OverlapSecs / 2.0 } else { [double]::MaxValue }
+
+    $found = if ($parsed.PSObject.Properties.Name -contains "transcription") {
+      @($parsed.transcription | ForEach-Object { @{ Start = $_.offsets.from / 1000.0; End = $_.offsets.to / 1000.0; Item = $_ } })
+    } else {
+      @($parsed.segments | ForEach-Object { @{ Start = [double]$_.start; End = [double]$_.end; Item = $_ } })
+    }
+    foreach ($entry in $found) {
+      $start = $piece.Start + $entry.Start
+      $end = $piece.Start + $entry.End
+      $middle = ($start + $end) / 2.0
+      if ($middle -lt $keepFrom -or $middle -ge $keepUntil) { continue }
+      $text = "$($entry.Item.text)".Trim()
+      if ($text.Length -eq 0 -or $text -eq $lastText) { continue }
+      $lastText = $text
+      $item = $entry.Item
+      # A cloud fallback can leave pieces in different formats; convert to the first's.
+      if ($whisperCpp) {
+        if ($item.PSObject.Properties.Name -notcontains "offsets") {
+          $item = [pscustomobject]@{ offsets = [pscustomobject]@{ from = 0; to = 0 }; text = $item.text }
+        }
+        $item.offsets.from = [int64]($start * 1000)
+        $item.offsets.to = [int64]($end * 1000)
+      } else {
+        if ($item.PSObject.Properties.Name -notcontains "start") {
+          $item = [pscustomobject]@{ start = 0.0; end = 0.0; text = $item.text }
+        }
+        $item.start = $start
+        $item.end = $end
+      }
+      $items.Add($item)
+      $texts.Add($text)
+    }
+  }
+  if ($null -eq $merged) { return $false }
+
+  if ($merged.PSObject.Properties.Name -contains "transcription") {
+    $merged.transcription = $items.ToArray()
+  } else {
+    $merged.segments = $items.ToArray()
+  }
+  ($merged | ConvertTo-Json -Depth 20) | Set-Content -LiteralPath "$OutBase.json" -Encoding UTF8
+  ($texts -join "`n") | Set-Content -LiteralPath "$OutBase.txt" -Encoding UTF8
+  $script:usedEngine = $mergedEngine
+  Remove-Item -LiteralPath $chunkDir -Recurse -Force -ErrorAction SilentlyContinue
+  return $true
+}
+
+# Letters FormD doesn't split into base letter plus accent.
+$slugFolds = @{
+  ([string][char]0x00DF) = "ss"; ([string][char]0x00E6) = "ae"; ([string][char]0x0153) = "oe"
+  ([string][char]0x00F8) = "o"; ([string][char]0x0142) = "l"; ([string][char]0x0111) = "d"
+}
+
 function Get-Slug {
   param([string]$Text)
   if ([string]::IsNullOrWhiteSpace($Text)) { return "service" }
 
+  # Accented letters keep their base letter, matching the app's slugs.
   $s = $Text.ToLowerInvariant()
+  foreach ($fold in $slugFolds.GetEnumerator()) {
+    $s = $s.Replace($fold.Key, $fold.Value)
+  }
+  $s = [regex]::Replace($s.Normalize([Text.NormalizationForm]::FormD), "\p{Mn}", "")
   $s = [regex]::Replace($s, "[^a-z0-9]+", "-")
   $s = [regex]::Replace($s, "-+", "-")
   $s = $s.Trim("-")
@@ -152,7 +871,7 @@ function Build-SummaryMarkdown {
 
   $flat = [regex]::Replace(($RawText ?? ""), "\s+", " ").Trim()
   if ($flat.Length -gt 520) {
-    $flat = $flat.Substring(0, 520).Trim() + "…"
+    $flat = $flat.Substring(0, 520).Trim() + [char]0x2026
   }
 
   $overview = $flat
@@ -161,7 +880,7 @@ function Build-SummaryMarkdown {
   }
 
   $lines = @(
-    "# Summary — $Title",
+    "# Summary $([char]0x2014) $Title",
     "",
     "- **Source file:** `$SourceFile`",
     "",
@@ -177,15 +896,18 @@ function Build-SummaryMarkdown {
   return ($lines -join "`r`n")
 }
 
+$InputFolder = ConvertTo-LongPath $InputFolder
+$OutputFolder = ConvertTo-LongPath $OutputFolder
 if (-not (Test-Path -LiteralPath $InputFolder)) {
   throw "Input folder not found: $InputFolder"
 }
 
-$inputResolved = (Resolve-Path -LiteralPath $InputFolder).Path
+# ProviderPath: .Path prefixes UNC inputs with the FileSystem provider name.
+$inputResolved = (Resolve-Path -LiteralPath $InputFolder).ProviderPath
 if (-not (Test-Path -LiteralPath $OutputFolder)) {
-  New-Item -ItemType Directory -Path $OutputFolder -Force | Out-Null
+  [void][System.IO.Directory]::CreateDirectory($OutputFolder)
 }
-$outputResolved = (Resolve-Path -LiteralPath $OutputFolder).Path
+$outputResolved = (Resolve-Path -LiteralPath $OutputFolder).ProviderPath
 
 if (-not (Get-Command $FfmpegExe -ErrorAction SilentlyContinue)) {
   throw "ffmpeg not found on PATH. Set -FfmpegExe or install ffmpeg."
@@ -195,7 +917,8 @@ if (-not (Get-Command $WhisperExe -ErrorAction SilentlyContinue)) {
   throw "whisper executable not found: $WhisperExe"
 }
 
-if (-not (Test-Path -LiteralPath $ModelFile)) {
+# faster-whisper also accepts a model name, downloaded on first use.
+if ($Engine -eq "whisper-cpp" -and -not (Test-Path -LiteralPath $ModelFile)) {
   throw "Model file not found: $ModelFile"
 }
 
@@ -218,18 +941,34 @@ $mediaExts = @(
   ".mp3", ".m4a", ".wav", ".aac", ".flac", ".ogg", ".wma"
 )
 
-$scanMode = if ($NoRecursive) { "non-recursive" } else { "recursive" }
-Write-Host "Scanning $scanMode in: $inputResolved"
-
-$allFiles = if ($NoRecursive) {
-  Get-ChildItem -LiteralPath $inputResolved -File
+# When the app supplies a plan, it has already applied every filter and skip
+# decision; the script only processes the listed files into their output dirs.
+$plannedOutputs = @{}
+$usePlan = -not [string]::IsNullOrWhiteSpace($PlanFile)
+
+if ($usePlan) {
+  $plan = Get-Content -LiteralPath $PlanFile -Raw -Encoding UTF8 | ConvertFrom-Json
+  $mediaFiles = @()
+  foreach ($entry in @($plan.files)) {
+    $item = Get-Item -LiteralPath (ConvertTo-LongPath $entry.source)
+    $plannedOutputs[$item.FullName] = $entry
+    $mediaFiles += $item
+  }
+  Write-Host "Using backend plan for: $inputResolved"
 } else {
-  Get-ChildItem -LiteralPath $inputResolved -File -Recurse
-}
+  $scanMode = if ($NoRecursive) { "non-recursive" } else { "recursive" }
+  Write-Host "Scanning $scanMode in: $inputResolved"
+
+  $allFiles = if ($NoRecursive) {
+    Get-ChildItem -LiteralPath $inputResolved -File
+  } else {
+    Get-ChildItem -LiteralPath $inputResolved -File -Recurse
+  }
 
-$mediaFiles = $allFiles | Where-Object { $mediaExts -contains $_.Extension.ToLowerInvariant() } | Sort-Object FullName
-if ($Limit -gt 0) {
-  $mediaFiles = $mediaFiles | Select-Object -First $Limit
+  $mediaFiles = $allFiles | Where-Object { $mediaExts -contains $_.Extension.ToLowerInvariant() } | Sort-Object FullName
+  if ($Limit -gt 0) {
+    $mediaFiles = $mediaFiles | Select-Object -First $Limit
+  }
 }
 
 if (-not $mediaFiles -or $mediaFiles.Count -eq 0) {
@@ -254,33 +993,83 @@ if ($FastScan) {
   Write-Host "FastScan: skipping clean.md and summary.md generation"
 }
 
+if ($Engine -ne "whisper-cpp") {
+  Write-Host "Transcription engine: $Engine"
+}
+if ($GpuDevice -ge 0) {
+  # Whisper and everything it starts see only this GPU, as their device 0.
+  $env:CUDA_VISIBLE_DEVICES = "$GpuDevice"
+  $env:GGML_VK_VISIBLE_DEVICES = "$GpuDevice"
+  Write-Host "Worker $WorkerName pinned to GPU $GpuDevice"
+}
+$decodingArgs = Get-DecodingArgs
+# The engine that produced the current file's output; cloud runs can fall back.
+$usedEngine = $Engine
+if ($decodingArgs.Count -gt 0) {
+  Write-Host "Decoding options: $($decodingArgs -join ' ')"
+}
+
 $results = New-Object System.Collections.Generic.List[object]
+$stagedCopy = $null
+# Plan entries can override these per file from a folder's .transcriber.toml.
+$runLanguage = $Language
+$runModelFile = $ModelFile
+$Translate = $false
 
 foreach ($file in $mediaFiles) {
+  Remove-StagedCopy -StagedPath $stagedCopy
+  $stagedCopy = $null
   Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before next file"
+  if (-not [string]::IsNullOrWhiteSpace($StopFlagFile) -and (Test-Path -LiteralPath $StopFlagFile)) {
+    Write-Host "[stop] Stop requested; not starting any more files."
+    break
+  }
+  if (-not (Request-FileClaim -Path $file.FullName)) {
+    continue
+  }
 
   Write-Host ""
-  Write-Host "=== $($file.FullName) ==="
+  if ([string]::IsNullOrWhiteSpace($WorkerName)) {
+    Write-Host "=== $($file.FullName) ==="
+  } else {
+    Write-Host "=== $($file.FullName) === [$WorkerName]"
+  }
 
-  $dateBucket = Get-DateBucket -File $file
-  if (-not [string]::IsNullOrWhiteSpace($BeforeDate) -and $dateBucket -gt $BeforeDate) {
+  $plannedEntry = $plannedOutputs[$file.FullName]
+  $dateBucket = if ($plannedEntry) { $plannedEntry.dateBucket } else { Get-DateBucket -File $file }
+  $Language = $runLanguage
+  $ModelFile = $runModelFile
+  $Translate = $false
+  if ($plannedEntry) {
+    if ($plannedEntry.PSObject.Properties["language"] -and $plannedEntry.language) { $Language = $plannedEntry.language }
+    if ($plannedEntry.PSObject.Properties["modelFile"] -and $plannedEntry.modelFile) { $ModelFile = $plannedEntry.modelFile }
+    if ($plannedEntry.PSObject.Properties["translate"] -and $plannedEntry.translate) { $Translate = $true }
+  }
+  if ($Language -ne $runLanguage -or $ModelFile -ne $runModelFile -or $Translate) {
+    Write-Host "[folder] language=$Language model=$ModelFile translate=$Translate"
+  }
+  if (-not $usePlan -and -not [string]::IsNullOrWhiteSpace($BeforeDate) -and $dateBucket -gt $BeforeDate) {
     Write-Host "[skip] date $dateBucket is after cutoff $BeforeDate"
     $results.Add([pscustomobject]@{ Status = "skipped-date"; Source = $file.FullName; Output = "" })
     $processed += 1
     Emit-Progress -Done $processed -Total $totalFiles -Status "skipped-date" -Source $file.FullName
     continue
   }
-
-  $slug = Get-Slug -Text $file.BaseName
-
-  $dateDir = Join-Path $outputResolved $dateBucket
-  if (-not (Test-Path -LiteralPath $dateDir)) {
-    New-Item -ItemType Directory -Path $dateDir -Force | Out-Null
+  if (-not $usePlan -and -not [string]::IsNullOrWhiteSpace($AfterDate) -and $dateBucket -lt $AfterDate) {
+    Write-Host "[skip] date $dateBucket is before start $AfterDate"
+    $results.Add([pscustomobject]@{ Status = "skipped-date"; Source = $file.FullName; Output = "" })
+    $processed += 1
+    Emit-Progress -Done $processed -Total $totalFiles -Status "skipped-date" -Source $file.FullName
+    continue
   }
 
-  $serviceDir = Join-Path $dateDir $slug
+  $serviceDir = if ($plannedEntry) {
+    ConvertTo-LongPath $plannedEntry.outputDir
+  } else {
+    ConvertTo-LongPath (Join-Path (Join-Path $outputResolved $dateBucket) (Get-Slug -Text $file.BaseName))
+  }
   if (-not (Test-Path -LiteralPath $serviceDir)) {
-    New-Item -ItemType Directory -Path $serviceDir -Force | Out-Null
+    [void][System.IO.Directory]::CreateDirectory($serviceDir)
   }
 
   $rawPath = Join-Path $serviceDir "raw.txt"
@@ -289,7 +1078,7 @@ foreach ($file in $mediaFiles) {
   $timestampsPath = Join-Path $serviceDir "timestamps.json"
   $metadataPath = Join-Path $serviceDir "metadata.json"
 
-  if ((Test-Path -LiteralPath $rawPath) -and (-not $Force)) {
+  if (-not $usePlan -and (Test-Path -LiteralPath $rawPath) -and (-not $Force)) {
     Write-Host "[skip] raw.txt exists"
     $results.Add([pscustomobject]@{ Status = "skipped"; Source = $file.FullName; Output = $serviceDir })
     $processed += 1
@@ -297,11 +1086,35 @@ foreach ($file in $mediaFiles) {
     continue
   }
 
+  $inputPath = $file.FullName
+  if ($plannedEntry -and $plannedEntry.PSObject.Properties["stagedSource"] -and $plannedEntry.stagedSource) {
+    $stagedCopy = $plannedEntry.stagedSource
+    $copyError = Wait-StagedCopy -StagedPath $stagedCopy
+    if ($copyError) {
+      Write-Warning "local copy failed: $($file.FullName): $copyError"
+      $results.Add([pscustomobject]@{ Status = "error"; Source = $file.FullName; Output = $serviceDir; Reason = "copy" })
+      $processed += 1
+      Emit-Progress -Done $processed -Total $totalFiles -Status "error-copy" -Source $file.FullName
+      continue
+    }
+    $inputPath = $stagedCopy
+  }
+
   $audioPath = Join-Path $serviceDir "audio-source.wav"
   $baseOut = Join-Path $serviceDir "audio-source"
 
+  $ffmpegArgs = @("-y", "-loglevel", "error", "-i", $inputPath, "-vn")
+  if (-not [string]::IsNullOrWhiteSpace($AudioFilter)) {
+    $ffmpegArgs += @("-af", $AudioFilter)
+  }
+  $ffmpegArgs += @("-ac", "1", "-ar", "16000", $audioPath)
+
   Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before ffmpeg"
-  & $FfmpegExe -y -loglevel error -i $file.FullName -vn -ac 1 -ar 16000 $audioPath
+  $loudnessStats = $null
+  if ($DenoiseStats) {
+    $loudnessStats = [ordered]@{ before = (Measure-AudioStats -Path $inputPath); after = $null }
+  }
+  & $FfmpegExe @ffmpegArgs
   if ($LASTEXITCODE -ne 0) {
     Write-Warning "ffmpeg failed: $($file.FullName)"
     $results.Add([pscustomobject]@{ Status = "error"; Source = $file.FullName; Output = $serviceDir; Reason = "ffmpeg" })
@@ -309,15 +1122,144 @@ foreach ($file in $mediaFiles) {
     Emit-Progress -Done $processed -Total $totalFiles -Status "error-ffmpeg" -Source $file.FullName
     continue
   }
+  if ($null -ne $loudnessStats) {
+    $loudnessStats.after = Measure-AudioStats -Path $audioPath
+    Write-Host "[denoise] noise floor $($loudnessStats.before.noiseFloorDb) dB -> $($loudnessStats.after.noiseFloorDb) dB"
+  }
 
-  $whisperArgs = @("-m", $ModelFile, "-f", $audioPath, "-of", $baseOut, "-otxt", "-oj")
-  if ($Threads -gt 0) {
-    $whisperArgs += @("-t", "$Threads")
+  # Whisper sees the trimmed audio; trimStartMs in metadata.json maps its
+  # timestamps back onto the original recording.
+  $trimStartMs = 0
+  $window = $null
+  if (-not [string]::IsNullOrWhiteSpace($SilenceDetect)) {
+    $window = Get-SpeechWindow -AudioPath $audioPath -Filter $SilenceDetect
+    if ($null -ne $window) {
+      $culture = [Globalization.CultureInfo]::InvariantCulture
+      $trimmedPath = Join-Path $serviceDir "audio-trimmed.wav"
+      $trimArgs = @("-y", "-loglevel", "error", "-ss", $window.Start.ToString("0.###", $culture), "-i", $audioPath)
+      if ($null -ne $window.End) {
+        $trimArgs += @("-t", ($window.End - $window.Start).ToString("0.###", $culture))
+      }
+      $trimArgs += @("-c", "copy", $trimmedPath)
+
+      & $FfmpegExe @trimArgs
+      if ($LASTEXITCODE -eq 0) {
+        Move-Item -LiteralPath $trimmedPath -Destination $audioPath -Force
+        $trimStartMs = [int64]($window.Start * 1000)
+        $trailing = if ($null -ne $window.End) { "trimmed after $([math]::Round($window.End))s" } else { "no trailing trim" }
+        Write-Host "[trim] Skipping $([math]::Round($window.Start))s of leading silence ($trailing)"
+      } else {
+        Write-Warning "Silence trim failed; transcribing untrimmed audio: $($file.FullName)"
+        $window = $null
+        Remove-Item -LiteralPath $trimmedPath -Force -ErrorAction SilentlyContinue
+      }
+    }
+  }
+
+  # Music ranges go in metadata.json on the recording's timeline; the app
+  # marks or drops the segments inside them. Skip mode also mutes them so
+  # whisper has no lyrics to hallucinate from.
+  $musicRanges = @()
+  if (-not [string]::IsNullOrWhiteSpace($MusicDetect)) {
+    $found = Get-MusicRanges -AudioPath $audioPath -Filter $MusicDetect -MinSecs $MusicMinSecs
+    $culture = [Globalization.CultureInfo]::InvariantCulture
+    $musicRanges = @($found | ForEach-Object {
+      [ordered]@{ startMs = [int64]($_.Start * 1000) + $trimStartMs; endMs = [int64]($_.End * 1000) + $trimStartMs }
+    })
+    if ($found.Count -gt 0) {
+      $musicSecs = ($found | ForEach-Object { $_.End - $_.Start } | Measure-Object -Sum).Sum
+      Write-Host "[music] $($found.Count) music range(s), $([math]::Round($musicSecs))s ($MusicMode)"
+    }
+    if ($MusicMode -eq "skip" -and $found.Count -gt 0) {
+      $between = ($found | ForEach-Object {
+        "between(t,{0},{1})" -f $_.Start.ToString("0.###", $culture), $_.End.ToString("0.###", $culture)
+      }) -join "+"
+      $mutedPath = Join-Path $serviceDir "audio-muted.wav"
+      & $FfmpegExe -y -loglevel error -i $audioPath -af "volume=volume=0:enable='$between'" $mutedPath
+      if ($LASTEXITCODE -eq 0) {
+        Move-Item -LiteralPath $mutedPath -Destination $audioPath -Force
+      } else {
+        Write-Warning "Could not mute music; transcribing it: $($file.FullName)"
+        Remove-Item -LiteralPath $mutedPath -Force -ErrorAction SilentlyContinue
+      }
+    }
   }
 
   Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before whisper"
-  & $WhisperExe @whisperArgs
-  if ($LASTEXITCODE -ne 0) {
+  # Long recordings are transcribed in checkpointed pieces so a crash late in
+  # the file doesn't cost the hours before it; split ones also overlap and run
+  # side by side.
+  $checkpointSecs = $CheckpointMinutes * 60
+  $audioSecs = ((Get-Item -LiteralPath $audioPath).Length - 44) / 32000.0
+  $transcribe = {
+    if ($ChunkOverMinutes -gt 0 -and $audioSecs -gt $ChunkOverMinutes * 60) {
+      Invoke-ChunkedTranscriber -AudioPath $audioPath -OutBase $baseOut -ChunkSecs ($ChunkMinutes * 60) -OverlapSecs $ChunkOverlapSecs -Parallel $ParallelChunks -SourceKey $file.FullName
+    } elseif ($checkpointSecs -gt 0 -and $audioSecs -gt $checkpointSecs) {
+      Invoke-ChunkedTranscriber -AudioPath $audioPath -OutBase $baseOut -ChunkSecs $checkpointSecs -SourceKey $file.FullName
+    } else {
+      Invoke-Transcriber -AudioPath $audioPath -OutBase $baseOut
+    }
+  }
+  $transcribed = & $transcribe
+
+  # Garbage output gets one more try with decoding that breaks repetition
+  # loops; metadata.json records what was wrong and whether the retry fixed it.
+  $garbageRetry = $null
+  $garbage = ""
+  $speechSecs = $audioSecs
+  if ($MusicMode -eq "skip") {
+    foreach ($range in $musicRanges) { $speechSecs -= ($range.endMs - $range.startMs) / 1000.0 }
+  }
+  $useFallback = -not [string]::IsNullOrWhiteSpace($FallbackModel) -and $FallbackModel -ne $ModelFile
+  if ($transcribed -and (-not $NoGarbageRetry -or $useFallback)) {
+    $garbage = Test-GarbageOutput -OutBase $baseOut -SpeechSecs $speechSecs
+  }
+  if ($garbage -and -not $NoGarbageRetry) {
+    Write-Warning "[garbage] $garbage; retrying with adjusted decoding: $($file.FullName)"
+    Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before retry"
+    $firstGarbage = $garbage
+    $runDecodingArgs = $decodingArgs
+    $decodingArgs = Get-RetryDecodingArgs
+    try {
+      $retried = & $transcribe
+    } finally {
+      $decodingArgs = $runDecodingArgs
+    }
+    $garbage = if ($retried) { Test-GarbageOutput -OutBase $baseOut -SpeechSecs $speechSecs } else { "the retry failed" }
+    if ($garbage) {
+      Write-Warning "[garbage] still $garbage after the retry: $($file.FullName)"
+    } else {
+      Write-Host "[garbage] the retry produced a usable transcript"
+    }
+    $garbageRetry = [ordered]@{ reason = $firstGarbage; remaining = $garbage }
+    $transcribed = $retried -or (Test-Path -LiteralPath "$baseOut.txt")
+  }
+
+  # A failure or lasting garbage gets one run with the fallback model, which
+  # then stays the model for the rest of this file.
+  $fallbackFrom = ""
+  if ($useFallback -and (-not $transcribed -or $garbage)) {
+    $why = if ($transcribed) { $garbage } else { "the engine failed" }
+    if ($Engine -eq "whisper-cpp" -and -not (Test-Path -LiteralPath $FallbackModel)) {
+      Write-Warning "[fallback] Fallback model not found: $FallbackModel"
+    } else {
+      Write-Warning "[fallback] $why with $ModelFile; retrying with $FallbackModel`: $($file.FullName)"
+      Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before fallback"
+      $fallbackFrom = $ModelFile
+      $ModelFile = $FallbackModel
+      $transcribed = & $transcribe
+      $garbage = if ($transcribed) { Test-GarbageOutput -OutBase $baseOut -SpeechSecs $speechSecs } else { "" }
+      if ($garbageRetry) {
+        $garbageRetry.remaining = $garbage
+      } elseif ($garbage) {
+        $garbageRetry = [ordered]@{ reason = $why; remaining = $garbage }
+      }
+      if ($transcribed) {
+        Write-Host "[fallback] final output from $ModelFile$(if ($garbage) { " (still $garbage)" })"
+      }
+    }
+  }
+  if (-not $transcribed) {
     Write-Warning "whisper failed: $($file.FullName)"
     $results.Add([pscustomobject]@{ Status = "error"; Source = $file.FullName; Output = $serviceDir; Reason = "whisper" })
     $processed += 1
@@ -325,9 +1267,28 @@ foreach ($file in $mediaFiles) {
     continue
   }
 
+  # Channel transcripts run next and may use a different engine after a fallback.
+  $fileEngine = $usedEngine
   $rawCandidate = "$baseOut.txt"
   $jsonCandidate = "$baseOut.json"
 
+  # Move the output this run replaces into .versions/<generated>-<model>/ rather than overwriting it.
+  if (Test-Path -LiteralPath $rawPath) {
+    $previousModel = "unknown"
+    if (Test-Path -LiteralPath $metadataPath) {
+      try {
+        $previousMeta = Get-Content -LiteralPath $metadataPath -Raw -Encoding UTF8 | ConvertFrom-Json
+        if ($previousMeta.modelFile) { $previousModel = [System.IO.Path]::GetFileNameWithoutExtension($previousMeta.modelFile) }
+      } catch { }
+    }
+    $generated = (Get-Item -LiteralPath $rawPath).LastWriteTime.ToString("yyyyMMdd-HHmmss")
+    $versionDir = Join-Path (Join-Path $serviceDir ".versions") ("{0}-{1}" -f $generated, (Get-Slug -Text $previousModel))
+    New-Item -ItemType Directory -Path $versionDir -Force | Out-Null
+    Get-ChildItem -LiteralPath $serviceDir -File |
+      Where-Object { -not $_.Name.StartsWith("audio-source") -and $_.Name -ne "audio-trimmed.wav" } |
+      ForEach-Object { Move-Item -LiteralPath $_.FullName -Destination $versionDir -Force }
+  }
+
   if (Test-Path -LiteralPath $rawCandidate) {
     Move-Item -LiteralPath $rawCandidate -Destination $rawPath -Force
   }
@@ -335,6 +1296,37 @@ foreach ($file in $mediaFiles) {
     Move-Item -LiteralPath $jsonCandidate -Destination $timestampsPath -Force
   }
 
+  if ($SplitChannels) {
+    foreach ($channel in @(@{ Name = "left"; Index = 0 }, @{ Name = "right"; Index = 1 })) {
+      Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before $($channel.Name) channel"
+      Invoke-ChannelTranscript -SourcePath $inputPath -ServiceDir $serviceDir -Name $channel.Name -Index $channel.Index -Window $window
+    }
+  }
+
+  # A second pass with the engine's translate task leaves English next to the
+  # original language; the app renders it from timestamps.en.json.
+  $translationEngine = ""
+  if ($TranslationPass -and -not $Translate) {
+    Wait-IfPaused -PauseFlagPath $PauseFlagFile -Context "before translation"
+    $translatedBase = Join-Path $serviceDir "audio-source-en"
+    $Translate = $true
+    try {
+      if (Invoke-Transcriber -AudioPath $audioPath -OutBase $translatedBase) {
+        $translationEngine = $usedEngine
+        if (Test-Path -LiteralPath "$translatedBase.txt") {
+          Move-Item -LiteralPath "$translatedBase.txt" -Destination (Join-Path $serviceDir "raw.en.txt") -Force
+        }
+        if (Test-Path -LiteralPath "$translatedBase.json") {
+          Move-Item -LiteralPath "$translatedBase.json" -Destination (Join-Path $serviceDir "timestamps.en.json") -Force
+        }
+      } else {
+        Write-Warning "Translation pass failed: $($file.FullName)"
+      }
+    } finally {
+      $Translate = $false
+    }
+  }
+
   $rawText = ""
   if (Test-Path -LiteralPath $rawPath) {
     $rawText = Get-Content -LiteralPath $rawPath -Raw -Encoding UTF8
@@ -344,25 +1336,38 @@ foreach ($file in $mediaFiles) {
   if ([string]::IsNullOrWhiteSpace($title)) { $title = $file.Name }
 
   if (-not $FastScan) {
-    $cleanMd = Build-CleanMarkdown -Title $title -SourceFile $file.FullName -RawText $rawText
+    $cleanMd = Build-CleanMarkdown -Title $title -SourceFile (ConvertFrom-LongPath $file.FullName) -RawText $rawText
     Set-Content -LiteralPath $cleanPath -Value $cleanMd -Encoding UTF8
 
-    $summaryMd = Build-SummaryMarkdown -Title $title -SourceFile $file.FullName -RawText $rawText
+    $summaryMd = Build-SummaryMarkdown -Title $title -SourceFile (ConvertFrom-LongPath $file.FullName) -RawText $rawText
     Set-Content -LiteralPath $summaryPath -Value $summaryMd -Encoding UTF8
   }
 
   $meta = [ordered]@{
-    sourceFile = $file.FullName
+    sourceFile = ConvertFrom-LongPath $file.FullName
     generatedAt = (Get-Date).ToUniversalTime().ToString("o")
     dateBucket = $dateBucket
-    modelFile = (Resolve-Path -LiteralPath $ModelFile).Path
+    modelFile = $(if (Test-Path -LiteralPath $ModelFile) { (Resolve-Path -LiteralPath $ModelFile).Path } else { $ModelFile })
     whisperExe = $WhisperExe
+    engine = $fileEngine
     ffmpegExe = $FfmpegExe
-    outputDir = $serviceDir
+    outputDir = ConvertFrom-LongPath $serviceDir
     threads = $Threads
     fastScan = [bool]$FastScan
     beforeDate = $BeforeDate
-    pauseFlagFile = $PauseFlagFile
+    afterDate = $AfterDate
+    pauseFlagFile = ConvertFrom-LongPath $PauseFlagFile
+    audioFilter = $AudioFilter
+    language = $Language
+    translate = $Translate
+    translationEngine = $translationEngine
+    denoiseStats = $loudnessStats
+    musicRanges = $musicRanges
+    garbageRetry = $garbageRetry
+    fallbackFrom = $fallbackFrom
+    musicMode = $(if ($musicRanges.Count -gt 0) { $MusicMode } else { "" })
+    decodingArgs = $decodingArgs
+    trimStartMs = $trimStartMs
   }
   ($meta | ConvertTo-Json -Depth 6) | Set-Content -LiteralPath $metadataPath -Encoding UTF8
 
@@ -374,8 +1379,21 @@ foreach ($file in $mediaFiles) {
   $processed += 1
   Emit-Progress -Done $processed -Total $totalFiles -Status "ok" -Source $file.FullName
 }
+Remove-StagedCopy -StagedPath $stagedCopy
 
 $indexPath = Join-Path $outputResolved "INDEX.md"
+$indexRows = @($results | ForEach-Object { "| $($_.Status) | `$(ConvertFrom-LongPath $_.Source)` | `$(ConvertFrom-LongPath $_.Output)` |" })
+$indexMutex = $null
+if (-not [string]::IsNullOrWhiteSpace($ClaimDir)) {
+  # Each worker leaves its rows in the claim folder and rewrites the index
+  # from all of them, so the last one to finish lists every file.
+  Set-Content -LiteralPath (Join-Path $ClaimDir "index-$WorkerName.txt") -Value ($indexRows -join "`r`n") -Encoding UTF8
+  $indexMutex = New-Object System.Threading.Mutex($false, "Local\ChurchTranscriberIndex")
+  [void]$indexMutex.WaitOne()
+  $indexRows = @(Get-ChildItem -LiteralPath $ClaimDir -Filter "index-*.txt" |
+    ForEach-Object { Get-Content -LiteralPath $_.FullName -Encoding UTF8 } |
+    Where-Object { -not [string]::IsNullOrWhiteSpace($_) })
+}
 $indexLines = New-Object System.Collections.Generic.List[string]
 $indexLines.Add("# Transcript Index")
 $indexLines.Add("")
@@ -384,11 +1402,18 @@ $indexLines.Add("")
 $indexLines.Add("| Status | Source | Transcript Folder |")
 $indexLines.Add("|---|---|---|")
 
-foreach ($r in $results) {
-  $indexLines.Add("| $($r.Status) | `$($r.Source)` | `$($r.Output)` |")
+foreach ($row in $indexRows) {
+  $indexLines.Add($row)
 }
 
-Set-Content -LiteralPath $indexPath -Value ($indexLines -join "`r`n") -Encoding UTF8
+try {
+  Set-Content -LiteralPath $indexPath -Value ($indexLines -join "`r`n") -Encoding UTF8
+} finally {
+  if ($indexMutex) {
+    $indexMutex.ReleaseMutex()
+    $indexMutex.Dispose()
+  }
+}
 
 $ok = ($results | Where-Object { $_.Status -eq "ok" }).Count
 $err = ($results | Where-Object { $_.Status -eq "error" }).Count
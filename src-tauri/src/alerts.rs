@@ -0,0 +1,101 @@
+use crate::{highlights::normalize, notify, settings, transcript};
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordEvent {
+    pub run_id: Option<String>,
+    pub source_file: Option<String>,
+    /// The watched phrases found, as written in settings.
+    pub phrases: Vec<String>,
+    /// On the transcribed audio's timeline.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// The watch-list for one engine process, read from settings when it starts.
+pub struct KeywordWatch {
+    /// (phrase, normalized phrase)
+    phrases: Vec<(String, String)>,
+    desktop: bool,
+    /// (source, phrase) pairs already shown as desktop notifications.
+    notified: HashSet<(String, String)>,
+}
+
+impl KeywordWatch {
+    /// `None` when no phrases are configured.
+    pub fn load(app: &AppHandle) -> Option<Self> {
+        let alerts = settings::load(app).keyword_alerts;
+        let phrases: Vec<(String, String)> = alerts
+            .phrases
+            .iter()
+            .map(|p| (p.trim().to_string(), normalize(p)))
+            .filter(|(_, needle)| !needle.trim().is_empty())
+            .collect();
+        (!phrases.is_empty()).then(|| Self {
+            phrases,
+            desktop: alerts.desktop_notification,
+            notified: HashSet::new(),
+        })
+    }
+
+    /// Checks a line of engine output; whisper.cpp and faster-whisper print
+    /// each segment as `[00:01:02.000 --> 00:01:05.500]  text`.
+    pub fn check_line(&mut self, app: &AppHandle, line: &str, run_id: Option<String>, source_file: Option<String>) {
+        let Some((start_ms, end_ms, text)) = parse_segment_line(line) else {
+            return;
+        };
+        let normalized = normalize(text);
+        let phrases: Vec<String> = self
+            .phrases
+            .iter()
+            .filter(|(_, needle)| normalized.contains(needle.as_str()))
+            .map(|(phrase, _)| phrase.clone())
+            .collect();
+        if phrases.is_empty() {
+            return;
+        }
+
+        crate::emit_log(
+            app,
+            "system",
+            format!("Keyword alert ({}) at {}: {text}", phrases.join(", "), transcript::format_timestamp(start_ms)),
+        );
+        if self.desktop {
+            let source = source_file.clone().unwrap_or_default();
+            let fresh: Vec<&String> = phrases
+                .iter()
+                .filter(|phrase| self.notified.insert((source.clone(), phrase.to_string())))
+                .collect();
+            if !fresh.is_empty() {
+                let title = fresh.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(", ");
+                notify::desktop(app, &format!("Mentioned: {title}"), text);
+            }
+        }
+        let _ = app.emit(
+            "transcribe://keyword",
+            KeywordEvent {
+                run_id,
+                source_file,
+                phrases,
+                start_ms,
+                end_ms,
+                text: text.to_string(),
+            },
+        );
+    }
+}
+
+fn parse_segment_line(line: &str) -> Option<(u64, u64, &str)> {
+    let rest = line.trim_start().strip_prefix('[')?;
+    let (times, text) = rest.split_once(']')?;
+    let (start, end) = times.split_once("-->")?;
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some((transcript::parse_timestamp(start)?, transcript::parse_timestamp(end)?, text))
+}
@@ -0,0 +1,410 @@
+use crate::{
+    chapters::Chapter,
+    transcript::{EnrichedTranscript, ENRICHED_FILE},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+const RAW_FILE: &str = "raw.txt";
+const METADATA_FILE: &str = "metadata.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub relative_dir: String,
+    pub title: String,
+    pub date_bucket: String,
+    pub source_file: Option<String>,
+    #[serde(default)]
+    pub model_file: Option<String>,
+    pub files: Vec<String>,
+    /// Where the sermon chapter starts, so the index can jump to it.
+    #[serde(default)]
+    pub sermon_start_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveMatch {
+    pub relative_dir: String,
+    pub title: String,
+    pub snippet: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptMetadata {
+    source_file: Option<String>,
+    model_file: Option<String>,
+    date_bucket: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptChapters {
+    #[serde(default)]
+    chapters: Vec<Chapter>,
+}
+
+fn sermon_start_ms(dir: &Path) -> Option<u64> {
+    let body = fs::read_to_string(dir.join(ENRICHED_FILE)).ok()?;
+    serde_json::from_str::<TranscriptChapters>(&body)
+        .ok()?
+        .chapters
+        .into_iter()
+        .find(|c| c.title == "Sermon")
+        .map(|c| c.start_ms)
+}
+
+/// One output folder of an archive that can span several: the run's own
+/// output folder plus any per-input overrides. Folders under an override are
+/// listed under `prefix`, the override folder's name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveRoot {
+    pub path: PathBuf,
+    pub prefix: String,
+}
+
+impl ArchiveRoot {
+    pub fn single(path: PathBuf) -> Self {
+        Self {
+            path,
+            prefix: String::new(),
+        }
+    }
+
+    /// `relative` as it appears in the combined archive.
+    pub fn prefixed(&self, relative: &str) -> String {
+        if self.prefix.is_empty() {
+            relative.to_string()
+        } else {
+            format!("{}/{relative}", self.prefix)
+        }
+    }
+
+    /// The inverse of `prefixed`; `None` when `relative` isn't under this root.
+    fn unprefixed<'a>(&self, relative: &'a str) -> Option<&'a str> {
+        if self.prefix.is_empty() {
+            return Some(relative);
+        }
+        relative
+            .trim_start_matches(['/', '\\'])
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|rest| rest.strip_prefix(['/', '\\']))
+    }
+}
+
+/// `primary` and each folder of `extra` not already inside another root,
+/// named after their folder.
+pub fn roots(primary: &str, extra: &[String]) -> Vec<ArchiveRoot> {
+    let mut roots = vec![ArchiveRoot::single(PathBuf::from(primary.trim()))];
+    for folder in extra.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        let path = PathBuf::from(folder);
+        let covered = roots.iter().any(|r| {
+            crate::scanner::same_path(&r.path.to_string_lossy(), folder) || path.starts_with(&r.path)
+        });
+        if covered {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .filter(|n| !n.starts_with('.'))
+            .unwrap_or_else(|| "output".to_string());
+        let mut prefix = name.clone();
+        let mut n = 2;
+        while roots.iter().any(|r| r.prefix == prefix) {
+            prefix = format!("{name}-{n}");
+            n += 1;
+        }
+        roots.push(ArchiveRoot { path, prefix });
+    }
+    roots
+}
+
+/// The root holding `relative_dir` and the path within it. Override roots are
+/// tried first so their prefix wins over a same-named folder in the main one.
+fn locate<'a, 'b>(roots: &'a [ArchiveRoot], relative_dir: &'b str) -> Option<(&'a ArchiveRoot, &'b str)> {
+    roots
+        .iter()
+        .filter(|r| !r.prefix.is_empty())
+        .chain(roots.iter().filter(|r| r.prefix.is_empty()))
+        .find_map(|r| r.unprefixed(relative_dir).map(|inner| (r, inner)))
+}
+
+fn relative_string(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Transcript folders are any directories under the archive root holding a `raw.txt`.
+pub fn list_entries(root: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    if !root.is_dir() {
+        return Err(format!("Archive folder not found: {}", root.display()));
+    }
+
+    let mut entries = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(read) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        let mut files = Vec::new();
+        for entry in read.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if !hidden {
+                    pending.push(path);
+                }
+            } else {
+                files.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+
+        if !files.iter().any(|f| f == RAW_FILE) {
+            continue;
+        }
+        files.sort();
+
+        let metadata = fs::read_to_string(dir.join(METADATA_FILE))
+            .ok()
+            .and_then(|body| {
+                serde_json::from_str::<TranscriptMetadata>(body.trim_start_matches('\u{feff}')).ok()
+            });
+        let (source_file, model_file, recorded_bucket) = metadata
+            .map(|m| (m.source_file, m.model_file, m.date_bucket))
+            .unwrap_or_default();
+
+        let title = source_file
+            .as_deref()
+            .map(crate::scanner::title_from_source)
+            .unwrap_or_else(|| {
+                dir.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+
+        // Output templates can nest folders, so prefer the date the script recorded.
+        let date_bucket = recorded_bucket.unwrap_or_else(|| {
+            dir.parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+        entries.push(ArchiveEntry {
+            relative_dir: relative_string(root, &dir),
+            title,
+            date_bucket,
+            source_file,
+            model_file,
+            sermon_start_ms: files.iter().any(|f| f == ENRICHED_FILE).then(|| sermon_start_ms(&dir)).flatten(),
+            files,
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        b.date_bucket
+            .cmp(&a.date_bucket)
+            .then_with(|| a.relative_dir.cmp(&b.relative_dir))
+    });
+    Ok(entries)
+}
+
+/// `list_entries` across every root, with override folders under their
+/// prefix and the `withheld` files left out. An override root that's missing
+/// (say, an unplugged drive) is skipped.
+pub fn list_all(roots: &[ArchiveRoot], withheld: &[&str]) -> Result<Vec<ArchiveEntry>, String> {
+    let mut entries = Vec::new();
+    for root in roots {
+        if !root.prefix.is_empty() && !root.path.is_dir() {
+            continue;
+        }
+        entries.extend(list_entries(&root.path)?.into_iter().map(|mut entry| {
+            entry.relative_dir = root.prefixed(&entry.relative_dir);
+            entry.files.retain(|f| !withheld.contains(&f.as_str()));
+            entry
+        }));
+    }
+    entries.sort_by(|a, b| {
+        b.date_bucket
+            .cmp(&a.date_bucket)
+            .then_with(|| a.relative_dir.cmp(&b.relative_dir))
+    });
+    Ok(entries)
+}
+
+/// Joins a client-supplied relative path onto the archive root, rejecting anything
+/// that could escape it or reach into a hidden folder such as `.private`.
+pub fn resolve_within(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let rel = Path::new(relative.trim());
+    let safe = rel.components().all(|c| match c {
+        Component::Normal(part) => !part.to_string_lossy().starts_with('.'),
+        Component::CurDir => true,
+        _ => false,
+    });
+    if !safe {
+        return Err(format!("Path is outside the archive: {relative}"));
+    }
+    Ok(root.join(rel))
+}
+
+pub fn read_file(root: &Path, relative_dir: &str, name: &str) -> Result<String, String> {
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid transcript file name: {name}"));
+    }
+
+    let path = resolve_within(root, relative_dir)?.join(name);
+    fs::read_to_string(&path).map_err(|err| format!("Could not read {}: {err}", path.display()))
+}
+
+/// The folder behind a `relative_dir` listed by `list_all`.
+pub fn resolve_listed(roots: &[ArchiveRoot], relative_dir: &str) -> Option<PathBuf> {
+    let (root, inner) = locate(roots, relative_dir)?;
+    resolve_within(&root.path, inner).ok()
+}
+
+/// `read_file` with `relative_dir` as listed by `list_all`.
+pub fn read_file_in(roots: &[ArchiveRoot], relative_dir: &str, name: &str, withheld: &[&str]) -> Result<String, String> {
+    if withheld.contains(&name) {
+        return Err(format!("{name} is not shared while a redaction list is set."));
+    }
+    let (root, inner) =
+        locate(roots, relative_dir).ok_or_else(|| format!("Path is outside the archive: {relative_dir}"))?;
+    read_file(&root.path, inner, name)
+}
+
+fn snippet_around(text: &str, byte_index: usize, needle_len: usize) -> String {
+    let start = text[..byte_index]
+        .char_indices()
+        .rev()
+        .nth(80)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let end = text[byte_index + needle_len..]
+        .char_indices()
+        .nth(80)
+        .map(|(i, _)| byte_index + needle_len + i)
+        .unwrap_or(text.len());
+
+    let mut snippet = text[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// The text `search` scans: the published segments of `transcript.json`,
+/// else `raw.txt` unless it's withheld.
+fn searchable_text(roots: &[ArchiveRoot], relative_dir: &str, withheld: &[&str]) -> Option<String> {
+    let published = read_file_in(roots, relative_dir, ENRICHED_FILE, withheld)
+        .ok()
+        .and_then(|body| serde_json::from_str::<EnrichedTranscript>(&body).ok())
+        .map(|t| t.segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" "));
+    published.or_else(|| read_file_in(roots, relative_dir, RAW_FILE, withheld).ok())
+}
+
+/// Plain case-insensitive scan of every transcript in the archive.
+pub fn search(
+    roots: &[ArchiveRoot],
+    query: &str,
+    max_results: usize,
+    withheld: &[&str],
+) -> Result<Vec<ArchiveMatch>, String> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut matches = Vec::new();
+    for entry in list_all(roots, withheld)? {
+        let Some(text) = searchable_text(roots, &entry.relative_dir, withheld) else {
+            continue;
+        };
+        let lowered = text.to_lowercase();
+        // Lowercasing can change byte lengths for some scripts; only trust the
+        // index when it lines up with the original text.
+        if let Some(index) = lowered.find(&needle) {
+            let aligned = lowered.len() == text.len()
+                && text.is_char_boundary(index)
+                && text.is_char_boundary(index + needle.len());
+            let snippet = if aligned {
+                snippet_around(&text, index, needle.len())
+            } else {
+                snippet_around(&lowered, index, needle.len())
+            };
+            matches.push(ArchiveMatch {
+                relative_dir: entry.relative_dir,
+                title: entry.title,
+                snippet,
+            });
+            if matches.len() >= max_results {
+                break;
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_roots_are_named_after_their_folder() {
+        let extra = vec![
+            "/archive".to_string(),
+            "/archive/nested".to_string(),
+            "/spanish/Transcripts".to_string(),
+            "/youth/Transcripts".to_string(),
+        ];
+        let prefixes: Vec<String> = roots("/archive", &extra).into_iter().map(|r| r.prefix).collect();
+        assert_eq!(prefixes, ["", "Transcripts", "Transcripts-2"]);
+    }
+
+    #[test]
+    fn override_folders_are_listed_and_read_under_their_prefix() {
+        let base = std::env::temp_dir().join(format!("church-transcriber-archive-roots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        for (root, text) in [("main", "english"), ("Spanish", "español")] {
+            let dir = base.join(root).join("2024-05-05").join("sermon");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(RAW_FILE), text).unwrap();
+        }
+
+        let roots = roots(
+            &base.join("main").to_string_lossy(),
+            &[base.join("Spanish").to_string_lossy().to_string()],
+        );
+        let dirs: Vec<String> = list_all(&roots, &[]).unwrap().into_iter().map(|e| e.relative_dir).collect();
+        assert_eq!(dirs, ["2024-05-05/sermon", "Spanish/2024-05-05/sermon"]);
+        assert_eq!(read_file_in(&roots, "Spanish/2024-05-05/sermon", RAW_FILE, &[]).unwrap(), "español");
+        assert_eq!(read_file_in(&roots, "2024-05-05/sermon", RAW_FILE, &[]).unwrap(), "english");
+        assert_eq!(search(&roots, "ESPAÑOL", 10, &[]).unwrap()[0].relative_dir, "Spanish/2024-05-05/sermon");
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn hidden_folders_and_withheld_files_are_not_served() {
+        let root = Path::new("/archive");
+        assert!(resolve_within(root, "2024-05-05/sermon").is_ok());
+        assert!(resolve_within(root, "2024-05-05/sermon/.private").is_err());
+        assert!(resolve_within(root, ".versions/old").is_err());
+        assert!(resolve_within(root, "../elsewhere").is_err());
+
+        let roots = [ArchiveRoot::single(root.to_path_buf())];
+        assert!(read_file_in(&roots, "2024-05-05/sermon", RAW_FILE, &[RAW_FILE]).is_err());
+    }
+}
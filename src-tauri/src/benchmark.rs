@@ -0,0 +1,430 @@
+use crate::RunnerState;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Instant,
+};
+use tauri::{AppHandle, Manager};
+
+const SAMPLE_FILE: &str = "benchmark-sample.mp3";
+const RESULTS_FILE: &str = "benchmarks.json";
+const SAMPLE_SECS: u32 = 60;
+/// 16 kHz mono 16-bit PCM, the format the batch script hands whisper.
+const WAV_BYTES_PER_SEC: f64 = 32_000.0;
+const WAV_HEADER_BYTES: u64 = 44;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BenchmarkRequest {
+    pub whisper_exe: String,
+    pub model_file: String,
+    pub threads: u32,
+    pub language: Option<String>,
+    /// Recording to take the first minute of; defaults to the bundled sample.
+    pub sample_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub sample: String,
+    pub model_file: String,
+    pub threads: u32,
+    pub audio_secs: f64,
+    pub decode_secs: f64,
+    pub whisper_secs: f64,
+    /// Audio seconds transcribed per wall-clock second; above 1 is faster
+    /// than realtime.
+    pub realtime_factor: f64,
+    /// Wall-clock hours a run would need per hour of recordings.
+    pub hours_per_audio_hour: f64,
+    pub words: usize,
+}
+
+fn results_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(RESULTS_FILE))
+}
+
+/// The latest benchmark of each model file measured on this machine.
+pub fn recorded(app: &AppHandle) -> Vec<BenchmarkResult> {
+    results_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn record(app: &AppHandle, results: &[BenchmarkResult]) {
+    let mut all = recorded(app);
+    all.retain(|old| !results.iter().any(|new| new.model_file == old.model_file));
+    all.extend(results.iter().cloned());
+    let saved = results_path(app).and_then(|path| {
+        let body = serde_json::to_string_pretty(&all)
+            .map_err(|err| format!("Failed to serialize benchmark results: {err}"))?;
+        fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+    });
+    if let Err(err) = saved {
+        crate::emit_log(app, "system", err);
+    }
+}
+
+fn resolve_sample(app: &AppHandle, requested: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(path) = requested.map(str::trim).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        return if path.exists() {
+            Ok(path)
+        } else {
+            Err(format!("Benchmark sample does not exist: {}", path.display()))
+        };
+    }
+    app.path()
+        .resource_dir()
+        .ok()
+        .into_iter()
+        .flat_map(|dir| [dir.join(SAMPLE_FILE), dir.join("resources").join(SAMPLE_FILE)])
+        .find(|p| p.exists())
+        .ok_or_else(|| "No bundled benchmark sample found; choose a recording to benchmark with.".to_string())
+}
+
+fn run(program: &str, args: &[&str], label: &str) -> Result<f64, String> {
+    let started = Instant::now();
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("Could not start {label}: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or_default();
+        return Err(format!("{label} failed (code {:?}): {last}", output.status.code()));
+    }
+    Ok(started.elapsed().as_secs_f64())
+}
+
+/// The first minute of the sample as 16 kHz mono WAV.
+struct DecodedSample {
+    sample: PathBuf,
+    wav: PathBuf,
+    audio_secs: f64,
+    decode_secs: f64,
+}
+
+fn decode_sample(app: &AppHandle, sample_path: Option<&str>, work_dir: &Path) -> Result<DecodedSample, String> {
+    let sample = resolve_sample(app, sample_path)?;
+    let wav = work_dir.join("sample.wav");
+    let wav_arg = wav.display().to_string();
+    let sample_arg = sample.display().to_string();
+    let duration = SAMPLE_SECS.to_string();
+    let decode_secs = run(
+        "ffmpeg",
+        &["-y", "-loglevel", "error", "-i", &sample_arg, "-t", &duration, "-vn", "-ac", "1", "-ar", "16000", &wav_arg],
+        "ffmpeg",
+    )?;
+    let audio_secs = fs::metadata(&wav)
+        .map(|m| m.len().saturating_sub(WAV_HEADER_BYTES) as f64 / WAV_BYTES_PER_SEC)
+        .unwrap_or(0.0);
+    if audio_secs < 1.0 {
+        return Err(format!("{} has no usable audio.", sample.display()));
+    }
+    Ok(DecodedSample {
+        sample,
+        wav,
+        audio_secs,
+        decode_secs,
+    })
+}
+
+/// Runs whisper on the decoded sample, returning its time and transcript.
+fn transcribe(
+    whisper_exe: &str,
+    model: &Path,
+    decoded: &DecodedSample,
+    threads: u32,
+    language: Option<&str>,
+    out_base: &Path,
+) -> Result<(f64, String), String> {
+    if !model.exists() {
+        return Err(format!("Model file not found: {}", model.display()));
+    }
+    let model_arg = model.display().to_string();
+    let wav_arg = decoded.wav.display().to_string();
+    let out_arg = out_base.display().to_string();
+    let threads_arg = threads.to_string();
+    let mut args = vec!["-m", &model_arg, "-f", &wav_arg, "-of", &out_arg, "-otxt", "-np"];
+    if threads > 0 {
+        args.extend(["-t", &threads_arg]);
+    }
+    if let Some(language) = language.map(str::trim).filter(|l| !l.is_empty()) {
+        args.extend(["-l", language]);
+    }
+    let secs = run(whisper_exe.trim(), &args, "whisper")?;
+    let text = fs::read_to_string(format!("{out_arg}.txt")).unwrap_or_default();
+    Ok((secs, text.split_whitespace().collect::<Vec<_>>().join(" ")))
+}
+
+fn result_for(decoded: &DecodedSample, model: &Path, threads: u32, whisper_secs: f64, text: &str) -> BenchmarkResult {
+    let total_secs = decoded.decode_secs + whisper_secs;
+    BenchmarkResult {
+        sample: decoded.sample.display().to_string(),
+        model_file: model.display().to_string(),
+        threads,
+        audio_secs: decoded.audio_secs,
+        decode_secs: decoded.decode_secs,
+        whisper_secs,
+        realtime_factor: decoded.audio_secs / total_secs.max(0.001),
+        hours_per_audio_hour: total_secs / decoded.audio_secs,
+        words: text.split_whitespace().count(),
+    }
+}
+
+fn benchmark(app: &AppHandle, request: &BenchmarkRequest, work_dir: &Path) -> Result<BenchmarkResult, String> {
+    let decoded = decode_sample(app, request.sample_path.as_deref(), work_dir)?;
+    let model = PathBuf::from(request.model_file.trim());
+    let (whisper_secs, text) = transcribe(
+        &request.whisper_exe,
+        &model,
+        &decoded,
+        request.threads,
+        request.language.as_deref(),
+        &work_dir.join("sample"),
+    )?;
+    Ok(result_for(&decoded, &model, request.threads, whisper_secs, &text))
+}
+
+fn in_work_dir<T>(name: &str, work: impl FnOnce(&Path) -> Result<T, String>) -> Result<T, String> {
+    let work_dir = std::env::temp_dir().join(format!("church-transcriber-{name}-{}", crate::now_epoch()));
+    fs::create_dir_all(&work_dir).map_err(|err| format!("Could not create {}: {err}", work_dir.display()))?;
+    let result = work(&work_dir);
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Same,
+    /// In this model's transcript but not the reference.
+    Added,
+    /// In the reference but missing here.
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffChunk {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRun {
+    pub result: Option<BenchmarkResult>,
+    pub error: Option<String>,
+    pub transcript: String,
+    /// Word-level diff against the reference model's transcript.
+    pub diff: Vec<DiffChunk>,
+    /// Share of words that differ from the reference, 0-100.
+    pub difference_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelComparison {
+    pub sample: String,
+    pub audio_secs: f64,
+    /// The largest model, taken as the most accurate.
+    pub reference_model: String,
+    pub models: Vec<ModelRun>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ComparisonRequest {
+    pub whisper_exe: String,
+    /// Folder to find models in; `models` picks specific files instead.
+    pub model_folder: String,
+    pub models: Vec<String>,
+    pub threads: u32,
+    pub language: Option<String>,
+    pub sample_path: Option<String>,
+}
+
+/// Word diff by longest common subsequence, with case and punctuation ignored
+/// when matching. Runs of the same kind are merged.
+pub fn word_diff(reference: &str, other: &str) -> (Vec<DiffChunk>, f64) {
+    let a: Vec<&str> = reference.split_whitespace().collect();
+    let b: Vec<&str> = other.split_whitespace().collect();
+    let key = |w: &str| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+    let (ka, kb): (Vec<String>, Vec<String>) = (a.iter().map(|w| key(w)).collect(), b.iter().map(|w| key(w)).collect());
+
+    let mut lcs = vec![vec![0usize; kb.len() + 1]; ka.len() + 1];
+    for i in (0..ka.len()).rev() {
+        for j in (0..kb.len()).rev() {
+            lcs[i][j] = if ka[i] == kb[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    let mut push = |kind: DiffKind, word: &str| match chunks.last_mut() {
+        Some(last) if last.kind == kind => {
+            last.text.push(' ');
+            last.text.push_str(word);
+        }
+        _ => chunks.push(DiffChunk {
+            kind,
+            text: word.to_string(),
+        }),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && ka[i] == kb[j] {
+            push(DiffKind::Same, b[j]);
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push(DiffKind::Added, b[j]);
+            j += 1;
+        } else {
+            push(DiffKind::Removed, a[i]);
+            i += 1;
+        }
+    }
+
+    let common = lcs[0][0];
+    let longest = a.len().max(b.len());
+    let percent = if longest == 0 {
+        0.0
+    } else {
+        (longest - common) as f64 * 100.0 / longest as f64
+    };
+    (chunks, percent)
+}
+
+fn compare(app: &AppHandle, request: &ComparisonRequest, work_dir: &Path) -> Result<ModelComparison, String> {
+    let mut models: Vec<(PathBuf, u64)> = if request.models.is_empty() {
+        crate::hardware::installed_models(Path::new(request.model_folder.trim()))
+    } else {
+        request
+            .models
+            .iter()
+            .map(|m| PathBuf::from(m.trim()))
+            .map(|p| {
+                let len = fs::metadata(&p).map(|m| m.len()).unwrap_or(0);
+                (p, len)
+            })
+            .collect()
+    };
+    if models.is_empty() {
+        return Err(format!("No model files found in {}", request.model_folder.trim()));
+    }
+    // Smallest first, so the quick results arrive before the slow ones.
+    models.sort_by_key(|(_, len)| *len);
+
+    let decoded = decode_sample(app, request.sample_path.as_deref(), work_dir)?;
+    let mut runs = Vec::new();
+    for (index, (model, _)) in models.iter().enumerate() {
+        crate::emit_log(app, "system", format!("Benchmarking {}...", model.display()));
+        let outcome = transcribe(
+            &request.whisper_exe,
+            model,
+            &decoded,
+            request.threads,
+            request.language.as_deref(),
+            &work_dir.join(format!("model-{index}")),
+        );
+        runs.push(outcome.map(|(secs, text)| (result_for(&decoded, model, request.threads, secs, &text), text)));
+    }
+
+    let reference_index = runs.iter().rposition(Result::is_ok).unwrap_or(runs.len() - 1);
+    let reference_text = runs[reference_index].as_ref().map(|(_, text)| text.clone()).unwrap_or_default();
+    let models_out = runs
+        .into_iter()
+        .map(|run| match run {
+            Ok((result, text)) => {
+                let (diff, difference_percent) = word_diff(&reference_text, &text);
+                ModelRun {
+                    result: Some(result),
+                    error: None,
+                    transcript: text,
+                    diff,
+                    difference_percent,
+                }
+            }
+            Err(err) => ModelRun {
+                result: None,
+                error: Some(err),
+                transcript: String::new(),
+                diff: Vec::new(),
+                difference_percent: 100.0,
+            },
+        })
+        .collect();
+
+    Ok(ModelComparison {
+        sample: decoded.sample.display().to_string(),
+        audio_secs: decoded.audio_secs,
+        reference_model: models[reference_index].0.display().to_string(),
+        models: models_out,
+    })
+}
+
+/// Transcribes a minute of audio with the given settings and reports how
+/// fast it went, to compare thread counts and models before a long run.
+#[tauri::command]
+pub async fn run_benchmark(app: AppHandle, request: BenchmarkRequest) -> Result<BenchmarkResult, String> {
+    if crate::get_status(&app.state::<RunnerState>()).running {
+        return Err("Benchmarks can't run while a transcription run is in progress.".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = in_work_dir("benchmark", |dir| benchmark(&app, &request, dir));
+        if let Ok(result) = &result {
+            record(&app, std::slice::from_ref(result));
+            crate::emit_log(
+                &app,
+                "system",
+                format!(
+                    "Benchmark: {:.0}s of audio in {:.1}s with {} threads ({:.1}x realtime).",
+                    result.audio_secs,
+                    result.decode_secs + result.whisper_secs,
+                    result.threads,
+                    result.realtime_factor
+                ),
+            );
+        }
+        result
+    })
+    .await
+    .map_err(|err| format!("Benchmark task failed: {err}"))?
+}
+
+/// Runs the same sample through every installed model (or the listed ones)
+/// and returns each one's speed and transcript, diffed against the largest.
+#[tauri::command]
+pub async fn compare_models(app: AppHandle, request: ComparisonRequest) -> Result<ModelComparison, String> {
+    if crate::get_status(&app.state::<RunnerState>()).running {
+        return Err("Benchmarks can't run while a transcription run is in progress.".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let comparison = in_work_dir("compare", |dir| compare(&app, &request, dir));
+        if let Ok(comparison) = &comparison {
+            let results: Vec<_> = comparison.models.iter().filter_map(|m| m.result.clone()).collect();
+            record(&app, &results);
+        }
+        comparison
+    })
+    .await
+    .map_err(|err| format!("Model comparison task failed: {err}"))?
+}
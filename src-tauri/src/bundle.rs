@@ -0,0 +1,101 @@
+use crate::{
+    profiles::{self, RunProfile},
+    schedule::{self, Schedule},
+    settings::{self, AppSettings},
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tauri::AppHandle;
+
+const BUNDLE_VERSION: u32 = 1;
+
+/// Everything needed to set up a second machine the same way. Settings include
+/// credentials (SMTP, S3, OAuth tokens), so the file should be handled like a
+/// password.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfigBundle {
+    pub bundle_version: u32,
+    pub app_version: String,
+    pub exported_at_epoch: u64,
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub profiles: Vec<RunProfile>,
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub profiles: usize,
+    pub schedules: usize,
+}
+
+/// Writes the bundle to `path`; a folder gets a timestamped file inside it.
+#[tauri::command]
+pub fn export_app_config(app: AppHandle, path: String) -> Result<String, String> {
+    let mut target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err("Export path is required.".to_string());
+    }
+    if target.is_dir() {
+        target = target.join(format!("church-transcriber-config-{}.json", crate::now_epoch()));
+    }
+
+    let bundle = AppConfigBundle {
+        bundle_version: BUNDLE_VERSION,
+        app_version: app.package_info().version.to_string(),
+        exported_at_epoch: crate::now_epoch(),
+        settings: settings::load(&app),
+        profiles: profiles::load(&app),
+        schedules: schedule::load(&app),
+    };
+    let body = serde_json::to_string_pretty(&bundle)
+        .map_err(|err| format!("Failed to serialize config bundle: {err}"))?;
+    fs::write(&target, body).map_err(|err| format!("Failed to write {}: {err}", target.display()))?;
+    Ok(target.display().to_string())
+}
+
+/// Replaces settings outright; profiles and schedules are merged, overwriting
+/// ones with the same name or id.
+#[tauri::command]
+pub fn import_app_config(app: AppHandle, path: String) -> Result<ImportSummary, String> {
+    let path = PathBuf::from(path.trim());
+    let body = fs::read_to_string(&path).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    let bundle: AppConfigBundle = serde_json::from_str(body.trim_start_matches('\u{feff}'))
+        .map_err(|err| format!("{} is not a config bundle: {err}", path.display()))?;
+    if bundle.bundle_version > BUNDLE_VERSION {
+        return Err(format!(
+            "Config bundle version {} is newer than this app supports ({BUNDLE_VERSION}); update the app first.",
+            bundle.bundle_version
+        ));
+    }
+
+    settings::save(&app, &bundle.settings)?;
+    crate::remote::apply_settings(&app, &bundle.settings.remote_api);
+
+    let imported_profiles = bundle.profiles.len();
+    let mut current = profiles::load(&app);
+    for profile in bundle.profiles {
+        current.retain(|p| !p.name.trim().eq_ignore_ascii_case(profile.name.trim()));
+        current.push(profile);
+    }
+    current.sort_by_key(|p| p.name.to_lowercase());
+    profiles::save(&app, &current)?;
+
+    let schedules = schedule::import(&app, bundle.schedules)?;
+
+    crate::emit_log(
+        &app,
+        "system",
+        format!(
+            "Imported settings, {imported_profiles} profile(s) and {schedules} schedule(s) from {}",
+            path.display()
+        ),
+    );
+    Ok(ImportSummary {
+        profiles: imported_profiles,
+        schedules,
+    })
+}
@@ -0,0 +1,110 @@
+use crate::{
+    engine::Engine,
+    postprocess::WordMask,
+    redaction::Redactor,
+    transcript::{self, format_timestamp, Segment},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const CHANNELS_FILE: &str = "channels.md";
+/// Whisper's output for each channel, as the batch script writes it.
+pub const CHANNEL_OUTPUT_FILES: [&str; 2] = ["channel-left.json", "channel-right.json"];
+
+/// Transcribes the left and right channels separately (e.g. pulpit mic on one
+/// side, congregation on the other) in addition to the mixed transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChannelOptions {
+    pub enabled: bool,
+    pub left_label: String,
+    pub right_label: String,
+}
+
+impl Default for ChannelOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            left_label: "Pulpit".to_string(),
+            right_label: "Congregation".to_string(),
+        }
+    }
+}
+
+fn channel_segments(output_dir: &Path, file: &str, offset_ms: u64, engine: Engine) -> Vec<Segment> {
+    let path = output_dir.join(file);
+    if !path.exists() {
+        return Vec::new();
+    }
+    transcript::load_whisper_json(&path, offset_ms, engine).unwrap_or_default()
+}
+
+/// Interleaves the per-channel whisper output (`channel-left.json`,
+/// `channel-right.json`) by time into `channels.md`, one labeled paragraph per
+/// run of speech on a channel. Returns `None` when the script produced neither.
+pub fn write_channel_transcript(
+    output_dir: &Path,
+    title: &str,
+    options: &ChannelOptions,
+    redactor: Option<&Redactor>,
+    mask: Option<&WordMask>,
+) -> Result<Option<PathBuf>, String> {
+    let offset = transcript::trim_offset_ms(output_dir);
+    let engine = transcript::engine(output_dir);
+    let mut tagged: Vec<(&str, Segment)> = [
+        (options.left_label.as_str(), CHANNEL_OUTPUT_FILES[0]),
+        (options.right_label.as_str(), CHANNEL_OUTPUT_FILES[1]),
+    ]
+    .into_iter()
+    .flat_map(|(label, file)| {
+        let segments = channel_segments(output_dir, file, offset, engine);
+        let segments = match redactor {
+            Some(redactor) => redactor.apply_segments(&segments),
+            None => segments,
+        };
+        let segments = match mask {
+            Some(mask) => mask.apply_segments(&segments),
+            None => segments,
+        };
+        segments.into_iter().map(move |s| (label, s))
+    })
+    .collect();
+    if tagged.is_empty() {
+        return Ok(None);
+    }
+    tagged.sort_by_key(|(_, s)| s.start_ms);
+
+    let mut lines = vec![
+        format!("# {title}"),
+        String::new(),
+        format!(
+            "_Left channel: {}. Right channel: {}._",
+            options.left_label, options.right_label
+        ),
+    ];
+    let mut current: Option<(&str, u64, Vec<String>)> = None;
+    for (label, segment) in tagged {
+        match current.as_mut() {
+            Some((open, _, texts)) if *open == label => texts.push(segment.text),
+            _ => {
+                if let Some((open, start, texts)) = current.take() {
+                    lines.push(String::new());
+                    lines.push(format!("**[{}] {open}:** {}", format_timestamp(start), texts.join(" ")));
+                }
+                current = Some((label, segment.start_ms, vec![segment.text]));
+            }
+        }
+    }
+    if let Some((open, start, texts)) = current {
+        lines.push(String::new());
+        lines.push(format!("**[{}] {open}:** {}", format_timestamp(start), texts.join(" ")));
+    }
+    lines.push(String::new());
+
+    let path = output_dir.join(CHANNELS_FILE);
+    fs::write(&path, lines.join("\n")).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(Some(path))
+}
@@ -0,0 +1,270 @@
+use crate::transcript::Segment;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+pub const FFMETADATA_FILE: &str = "chapters.ffmetadata";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChapterOptions {
+    pub enabled: bool,
+    pub use_silence_detection: bool,
+    pub silence_noise_db: f64,
+    pub min_silence_secs: f64,
+    pub min_chapter_secs: f64,
+    pub sermon_words_per_minute: f64,
+}
+
+impl Default for ChapterOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            use_silence_detection: true,
+            silence_noise_db: -35.0,
+            min_silence_secs: 2.0,
+            min_chapter_secs: 180.0,
+            sermon_words_per_minute: 90.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub title: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Gap {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// A stretch between silences, at least `min_chapter_secs` long.
+#[derive(Debug, Clone, Copy)]
+pub struct Block {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Spoken at sermon pace or faster.
+    pub dense: bool,
+}
+
+fn silence_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"silence_(start|end):\s*(-?[0-9.]+)").expect("silence pattern is valid")
+    })
+}
+
+fn secs_to_ms(secs: f64) -> u64 {
+    (secs.max(0.0) * 1000.0) as u64
+}
+
+/// Runs ffmpeg's `silencedetect` filter over the source media's audio track.
+pub fn silence_gaps(source: &Path, options: &ChapterOptions) -> Result<Vec<Gap>, String> {
+    let filter = format!(
+        "silencedetect=noise={}dB:d={}",
+        options.silence_noise_db, options.min_silence_secs
+    );
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i"])
+        .arg(source)
+        .args(["-vn", "-af", &filter, "-f", "null", "-"])
+        .output()
+        .map_err(|err| format!("Could not run ffmpeg for silence detection: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg silence detection failed for {} (code {:?})",
+            source.display(),
+            output.status.code()
+        ));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut gaps = Vec::new();
+    let mut open = None;
+    for caps in silence_regex().captures_iter(&stderr) {
+        let ms = secs_to_ms(caps[2].parse().unwrap_or(0.0));
+        if &caps[1] == "start" {
+            open = Some(ms);
+        } else if let Some(start_ms) = open.take() {
+            gaps.push(Gap { start_ms, end_ms: ms });
+        }
+    }
+    Ok(gaps)
+}
+
+/// Fallback when ffmpeg is unavailable: treats long pauses between whisper
+/// segments as silence.
+pub fn segment_gaps(segments: &[Segment], options: &ChapterOptions) -> Vec<Gap> {
+    let min_gap_ms = secs_to_ms(options.min_silence_secs);
+    segments
+        .windows(2)
+        .filter(|pair| pair[1].start_ms.saturating_sub(pair[0].end_ms) >= min_gap_ms)
+        .map(|pair| Gap {
+            start_ms: pair[0].end_ms,
+            end_ms: pair[1].start_ms,
+        })
+        .collect()
+}
+
+/// Whisper marks music as `[Music]`, `(music)` or `♪`; those don't count as speech.
+fn spoken_words(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|w| !w.starts_with(['[', '(', '♪']) && !w.ends_with([']', ')', '♪']))
+        .count()
+}
+
+pub fn build_blocks(segments: &[Segment], gaps: &[Gap], end_ms: u64, options: &ChapterOptions) -> Vec<Block> {
+    let mut cuts: Vec<u64> = gaps
+        .iter()
+        .map(|g| g.start_ms + (g.end_ms.saturating_sub(g.start_ms)) / 2)
+        .filter(|&c| c > 0 && c < end_ms)
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let min_chapter_ms = secs_to_ms(options.min_chapter_secs);
+    let mut bounds: Vec<(u64, u64)> = Vec::new();
+    let mut start = 0;
+    for cut in cuts.into_iter().chain(std::iter::once(end_ms)) {
+        if cut.saturating_sub(start) < min_chapter_ms && cut != end_ms {
+            continue;
+        }
+        bounds.push((start, cut));
+        start = cut;
+    }
+    // A short tail folds into the block before it.
+    if bounds.len() > 1 {
+        let (last_start, last_end) = bounds[bounds.len() - 1];
+        if last_end - last_start < min_chapter_ms {
+            bounds.pop();
+            if let Some(previous) = bounds.last_mut() {
+                previous.1 = last_end;
+            }
+        }
+    }
+
+    bounds
+        .into_iter()
+        .map(|(start_ms, end_ms)| {
+            let words: usize = segments
+                .iter()
+                .filter(|s| {
+                    let mid = s.start_ms + s.end_ms.saturating_sub(s.start_ms) / 2;
+                    mid >= start_ms && mid < end_ms
+                })
+                .map(|s| spoken_words(&s.text))
+                .sum();
+            let minutes = (end_ms - start_ms) as f64 / 60_000.0;
+            Block {
+                start_ms,
+                end_ms,
+                dense: minutes > 0.0 && words as f64 / minutes >= options.sermon_words_per_minute,
+            }
+        })
+        .collect()
+}
+
+fn chapter(title: &str, start_ms: u64, end_ms: u64) -> Chapter {
+    Chapter {
+        title: title.to_string(),
+        start_ms,
+        end_ms,
+    }
+}
+
+/// Splits the recording at silences and labels the pieces. The sermon is the
+/// longest stretch of continuous dense speech; sparse stretches before it are
+/// worship, anything spoken ahead of those is the intro, and whatever follows
+/// the sermon is the closing.
+pub fn build_chapters(segments: &[Segment], gaps: &[Gap], options: &ChapterOptions) -> Vec<Chapter> {
+    let Some(end_ms) = segments.iter().map(|s| s.end_ms).max().filter(|&e| e > 0) else {
+        return Vec::new();
+    };
+    let blocks = build_blocks(segments, gaps, end_ms, options);
+
+    let mut best: Option<(usize, usize)> = None;
+    let mut index = 0;
+    while index < blocks.len() {
+        if !blocks[index].dense {
+            index += 1;
+            continue;
+        }
+        let first = index;
+        while index + 1 < blocks.len() && blocks[index + 1].dense {
+            index += 1;
+        }
+        let length = blocks[index].end_ms - blocks[first].start_ms;
+        let best_length = best.map(|(a, b)| blocks[b].end_ms - blocks[a].start_ms);
+        if best_length.is_none_or(|l| length > l) {
+            best = Some((first, index));
+        }
+        index += 1;
+    }
+
+    let Some((first, last)) = best else {
+        return vec![chapter("Service", 0, end_ms)];
+    };
+    let sermon_start = blocks[first].start_ms;
+    let sermon_end = blocks[last].end_ms;
+
+    let mut chapters = Vec::new();
+    if first > 0 {
+        match blocks[..first].iter().position(|b| !b.dense) {
+            Some(0) => chapters.push(chapter("Worship", 0, sermon_start)),
+            Some(p) => {
+                chapters.push(chapter("Intro", 0, blocks[p].start_ms));
+                chapters.push(chapter("Worship", blocks[p].start_ms, sermon_start));
+            }
+            None => chapters.push(chapter("Intro", 0, sermon_start)),
+        }
+    }
+    chapters.push(chapter("Sermon", sermon_start, sermon_end));
+    if sermon_end < end_ms {
+        chapters.push(chapter("Closing", sermon_end, end_ms));
+    }
+    chapters
+}
+
+fn escape_metadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Writes an ffmpeg `FFMETADATA1` file that can be muxed back in with
+/// `ffmpeg -i media -i chapters.ffmetadata -map_metadata 1 -codec copy out`.
+pub fn write_ffmetadata(output_dir: &Path, title: &str, chapters: &[Chapter]) -> Result<PathBuf, String> {
+    let mut lines = vec![
+        ";FFMETADATA1".to_string(),
+        format!("title={}", escape_metadata(title)),
+    ];
+    for chapter in chapters {
+        lines.push(String::new());
+        lines.push("[CHAPTER]".to_string());
+        lines.push("TIMEBASE=1/1000".to_string());
+        lines.push(format!("START={}", chapter.start_ms));
+        lines.push(format!("END={}", chapter.end_ms));
+        lines.push(format!("title={}", escape_metadata(&chapter.title)));
+    }
+    lines.push(String::new());
+
+    let path = output_dir.join(FFMETADATA_FILE);
+    fs::write(&path, lines.join("\n"))
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
@@ -0,0 +1,62 @@
+use crate::StartRequest;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+const CHECKPOINT_FILE: &str = "run-checkpoint.json";
+
+/// Where the active run is up to. Completed files live in the run's history
+/// report, which is saved as results arrive; the checkpoint only adds what is
+/// needed to restart the worker. Removed once a run finishes successfully.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCheckpoint {
+    pub run_id: String,
+    pub request: StartRequest,
+    pub folder_index: usize,
+    pub current_folder: String,
+    pub updated_at_epoch: u64,
+}
+
+fn checkpoint_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(CHECKPOINT_FILE))
+}
+
+pub fn load(app: &AppHandle) -> Option<RunCheckpoint> {
+    checkpoint_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+}
+
+pub fn save(app: &AppHandle, checkpoint: &RunCheckpoint) -> Result<(), String> {
+    let path = checkpoint_path(app)?;
+    let body = serde_json::to_string_pretty(checkpoint)
+        .map_err(|err| format!("Failed to serialize run checkpoint: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Moves the checkpoint to the folder the worker is starting.
+pub fn advance(app: &AppHandle, folder_index: usize, folder: &str) {
+    let Some(mut checkpoint) = load(app) else {
+        return;
+    };
+    checkpoint.folder_index = folder_index;
+    checkpoint.current_folder = folder.to_string();
+    checkpoint.updated_at_epoch = crate::now_epoch();
+    if let Err(err) = save(app, &checkpoint) {
+        crate::emit_log(app, "system", err);
+    }
+}
+
+pub fn clear(app: &AppHandle) {
+    if let Ok(path) = checkpoint_path(app) {
+        let _ = fs::remove_file(path);
+    }
+}
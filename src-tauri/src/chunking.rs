@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Splits long recordings into overlapping pieces that are transcribed
+/// independently, several at once, and stitched back together. The overlap
+/// gives each piece context at its edges; the merge keeps each side's half of
+/// it and drops a repeated line at the seam.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChunkOptions {
+    pub enabled: bool,
+    /// Only recordings longer than this are split.
+    pub split_over_minutes: u32,
+    pub chunk_minutes: u32,
+    pub overlap_secs: u32,
+    /// Pieces transcribed at once; 0 picks one per four CPU cores.
+    pub parallel: u32,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            split_over_minutes: 45,
+            chunk_minutes: 10,
+            overlap_secs: 5,
+            parallel: 0,
+        }
+    }
+}
+
+impl ChunkOptions {
+    pub fn parallel_pieces(&self) -> u32 {
+        if self.parallel > 0 {
+            return self.parallel.min(16);
+        }
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) as u32;
+        (cores / 4).clamp(1, 8)
+    }
+}
+
+/// Adds the chunking options to the batch script's command line.
+pub fn configure(cmd: &mut Command, options: &ChunkOptions) {
+    if !options.enabled {
+        return;
+    }
+    let chunk_minutes = options.chunk_minutes.max(1);
+    cmd.arg("-ChunkOverMinutes")
+        .arg(options.split_over_minutes.max(chunk_minutes).to_string())
+        .arg("-ChunkMinutes")
+        .arg(chunk_minutes.to_string())
+        .arg("-ChunkOverlapSecs")
+        .arg(options.overlap_secs.min(chunk_minutes * 30).to_string())
+        .arg("-ParallelChunks")
+        .arg(options.parallel_pieces().to_string());
+}
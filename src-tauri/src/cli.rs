@@ -0,0 +1,119 @@
+use crate::{RunnerState, StartRequest};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const USAGE: &str = "Usage: church-transcriber --config <run.json> [--log <file>]";
+
+pub struct HeadlessOptions {
+    pub config: PathBuf,
+    pub log_file: Option<PathBuf>,
+}
+
+/// Present only when the app was launched with `--config`; the runner checks
+/// for it to mirror logs and exit when the run ends.
+pub struct HeadlessState {
+    log: Mutex<Option<File>>,
+}
+
+/// Returns `None` for a normal GUI launch.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<HeadlessOptions>, String> {
+    let mut config = None;
+    let mut log_file = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                config = Some(PathBuf::from(args.next().ok_or("--config needs a file path.")?))
+            }
+            "--log" => log_file = Some(PathBuf::from(args.next().ok_or("--log needs a file path.")?)),
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => {}
+        }
+    }
+
+    match (config, log_file) {
+        (Some(config), log_file) => Ok(Some(HeadlessOptions { config, log_file })),
+        (None, Some(_)) => Err("--log is only valid together with --config.".to_string()),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Loads the run config (the same JSON the UI sends to `start_transcription`)
+/// and starts the shared runner.
+pub fn start(app: &AppHandle, options: HeadlessOptions) -> Result<(), String> {
+    let log = match &options.log_file {
+        Some(path) => Some(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|err| format!("Could not open log file {}: {err}", path.display()))?,
+        ),
+        None => None,
+    };
+    app.manage(HeadlessState {
+        log: Mutex::new(log),
+    });
+
+    let request = load_request(&options.config)?;
+    crate::start_transcription(app.clone(), app.state::<RunnerState>(), request).map(|_| ())
+}
+
+fn load_request(config: &Path) -> Result<StartRequest, String> {
+    let body = fs::read_to_string(config)
+        .map_err(|err| format!("Could not read config {}: {err}", config.display()))?;
+    serde_json::from_str(body.trim_start_matches('\u{feff}'))
+        .map_err(|err| format!("Invalid config {}: {err}", config.display()))
+}
+
+/// Launch arguments from a second copy of the app, which exits after handing
+/// them over. A `--config` run is started here instead of in a competing
+/// runner; the window is brought to the front either way.
+pub fn handoff(app: &AppHandle, args: Vec<String>) {
+    crate::tray::show_main_window(app);
+    let _ = app.emit("transcribe://second-instance", args.clone());
+
+    match parse_args(args.into_iter().skip(1)) {
+        Ok(Some(options)) => {
+            crate::emit_log(
+                app,
+                "system",
+                format!("Another launch asked to run {}.", options.config.display()),
+            );
+            if options.log_file.is_some() {
+                crate::emit_log(app, "system", "--log is ignored for runs handed to an open window.");
+            }
+            let result = load_request(&options.config).and_then(|request| {
+                crate::start_transcription(app.clone(), app.state::<RunnerState>(), request)
+            });
+            if let Err(err) = result {
+                crate::emit_log(app, "system", format!("Handed-over run did not start: {err}"));
+            }
+        }
+        Ok(None) => {}
+        Err(err) => crate::emit_log(app, "system", format!("Ignored launch arguments: {err}")),
+    }
+}
+
+pub fn is_headless(app: &AppHandle) -> bool {
+    app.try_state::<HeadlessState>().is_some()
+}
+
+pub fn write_log(app: &AppHandle, stream: &str, line: &str) {
+    let Some(state) = app.try_state::<HeadlessState>() else {
+        return;
+    };
+    let text = format!("[{stream}] {line}");
+    println!("{text}");
+    let Ok(mut log) = state.log.lock() else {
+        return;
+    };
+    if let Some(file) = log.as_mut() {
+        let _ = writeln!(file, "{text}");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    export, manifest, scanner, subtitles,
+    transcript::{self, Segment},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tauri::AppHandle;
+
+const CLIPS_DIR: &str = "clips";
+/// Highlight clips are for social media; anything longer is likely a typo.
+const MAX_CLIP_MS: u64 = 10 * 60 * 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CaptionStyle {
+    pub font_name: String,
+    pub font_size: u32,
+    /// Audiogram background for audio-only recordings, `#rrggbb`.
+    pub background: String,
+    /// Audiogram frame size; video clips keep the recording's own.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for CaptionStyle {
+    fn default() -> Self {
+        Self {
+            font_name: "Arial".to_string(),
+            font_size: 28,
+            background: "#1e2a38".to_string(),
+            width: 1080,
+            height: 1080,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedClip {
+    pub path: String,
+    /// The clip's captions, also burned into the video.
+    pub captions_path: String,
+    /// The transcript text the clip covers, for the post.
+    pub text: String,
+}
+
+/// The recording and transcript folder for `source`, which may be either.
+fn resolve(app: &AppHandle, source: &Path) -> Result<(PathBuf, PathBuf), String> {
+    let text = source.display().to_string();
+    let entries = manifest::entries(app);
+    if source.is_dir() {
+        // The manifest follows archived sources; metadata.json keeps the original path.
+        let media = entries
+            .iter()
+            .find(|e| scanner::same_path(&e.output_dir, &text))
+            .map(|e| e.source.clone())
+            .filter(|s| Path::new(s).is_file())
+            .or_else(|| transcript::source_file(source).filter(|s| Path::new(s).is_file()))
+            .ok_or_else(|| format!("The recording for {text} was not found."))?;
+        Ok((PathBuf::from(media), source.to_path_buf()))
+    } else if source.is_file() {
+        let output_dir = entries
+            .iter()
+            .filter(|e| scanner::same_path(&e.source, &text))
+            .max_by_key(|e| e.transcribed_at_epoch)
+            .map(|e| PathBuf::from(&e.output_dir))
+            .ok_or_else(|| format!("{text} has not been transcribed yet."))?;
+        Ok((source.to_path_buf(), output_dir))
+    } else {
+        Err(format!("Not found: {text}"))
+    }
+}
+
+/// Segments overlapping the clip, clamped to it and timed from its start.
+fn clip_segments(segments: &[Segment], start_ms: u64, end_ms: u64) -> Vec<Segment> {
+    segments
+        .iter()
+        .filter(|s| s.end_ms > start_ms && s.start_ms < end_ms)
+        .map(|s| Segment {
+            start_ms: s.start_ms.max(start_ms) - start_ms,
+            end_ms: s.end_ms.min(end_ms) - start_ms,
+            text: s.text.clone(),
+            speaker: None,
+        })
+        .collect()
+}
+
+fn has_video(media: &Path) -> bool {
+    Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v", "-show_entries", "stream=codec_type", "-of", "csv=p=0"])
+        .arg(media)
+        .stdin(Stdio::null())
+        .output()
+        .map(|o| o.status.success() && !String::from_utf8_lossy(&o.stdout).trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// `#1e2a38` as ffmpeg's `0x1e2a38`.
+fn ffmpeg_color(hex: &str) -> Result<String, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(format!("0x{digits}"))
+    } else {
+        Err(format!("Background {hex} must be a #rrggbb colour."))
+    }
+}
+
+fn render(app: &AppHandle, source: &str, start: &str, end: &str, style: &CaptionStyle) -> Result<RenderedClip, String> {
+    let start_ms = transcript::parse_timestamp(start).ok_or_else(|| format!("Invalid start time {start}"))?;
+    let end_ms = transcript::parse_timestamp(end).ok_or_else(|| format!("Invalid end time {end}"))?;
+    if end_ms <= start_ms {
+        return Err("The clip must end after it starts.".to_string());
+    }
+    if end_ms - start_ms > MAX_CLIP_MS {
+        return Err("Clips are limited to 10 minutes.".to_string());
+    }
+    let (media, output_dir) = resolve(app, Path::new(source.trim()))?;
+    let segments = clip_segments(&transcript::load_segments(&output_dir)?, start_ms, end_ms);
+    if segments.is_empty() {
+        return Err("The transcript has no text in that range.".to_string());
+    }
+
+    let clips_dir = output_dir.join(CLIPS_DIR);
+    fs::create_dir_all(&clips_dir).map_err(|err| format!("Could not create {}: {err}", clips_dir.display()))?;
+    let name = format!(
+        "clip-{}-{}",
+        transcript::format_timestamp(start_ms).replace(':', ""),
+        transcript::format_timestamp(end_ms).replace(':', "")
+    );
+    let captions = clips_dir.join(format!("{name}.srt"));
+    fs::write(&captions, export::render_srt(&segments))
+        .map_err(|err| format!("Failed to write {}: {err}", captions.display()))?;
+    let path = clips_dir.join(format!("{name}.mp4"));
+
+    let seconds = |ms: u64| format!("{:.3}", ms as f64 / 1000.0);
+    let duration = seconds(end_ms - start_ms);
+    let captions_filter = subtitles::subtitles_filter(
+        &captions.file_name().unwrap_or_default().to_string_lossy(),
+        &style.font_name,
+        style.font_size,
+    );
+    let mut cmd = Command::new("ffmpeg");
+    cmd.current_dir(&clips_dir).args(["-hide_banner", "-nostats", "-loglevel", "error", "-y"]);
+    if has_video(&media) {
+        cmd.args(["-ss", &seconds(start_ms), "-t", &duration, "-i"])
+            .arg(&media)
+            .args(["-vf", &captions_filter]);
+    } else {
+        // Audiogram: a waveform on a plain background under the captions.
+        let size = format!("{}x{}", style.width.clamp(240, 3840), style.height.clamp(240, 3840));
+        let background = format!("color=c={}:s={size}:r=30:d={duration}", ffmpeg_color(&style.background)?);
+        let graph = format!(
+            "[1:a]showwaves=s={}x{}:mode=cline:colors=white[wave];[0:v][wave]overlay=0:(H-h)/2,{captions_filter}[v]",
+            style.width.clamp(240, 3840),
+            style.height.clamp(240, 3840) / 4
+        );
+        cmd.args(["-f", "lavfi", "-i", &background])
+            .args(["-ss", &seconds(start_ms), "-t", &duration, "-i"])
+            .arg(&media)
+            .args(["-filter_complex", &graph, "-map", "[v]", "-map", "1:a", "-shortest"]);
+    }
+    let output = cmd
+        .args(["-c:v", "libx264", "-pix_fmt", "yuv420p", "-c:a", "aac", "-b:a", "128k", "-movflags", "+faststart"])
+        .arg(&path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("Could not run ffmpeg to render the clip: {err}"))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&path);
+        return Err(format!(
+            "ffmpeg could not render the clip: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(RenderedClip {
+        path: path.display().to_string(),
+        captions_path: captions.display().to_string(),
+        text: segments.iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" "),
+    })
+}
+
+/// Cuts `start_ts`-`end_ts` (`HH:MM:SS`, `MM:SS` or seconds, on the
+/// transcript's timeline) from a recording and burns in the transcript's
+/// text for that stretch. `source` is the recording or its transcript
+/// folder; audio-only recordings become an audiogram. The MP4 and its SRT
+/// go in the transcript's `clips` folder.
+#[tauri::command]
+pub async fn render_clip(
+    app: AppHandle,
+    source: String,
+    start_ts: String,
+    end_ts: String,
+    caption_style: Option<CaptionStyle>,
+) -> Result<RenderedClip, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let clip = render(&app, &source, &start_ts, &end_ts, &caption_style.unwrap_or_default())?;
+        crate::emit_log(&app, "system", format!("Clip rendered: {}", clip.path));
+        Ok(clip)
+    })
+    .await
+    .map_err(|err| format!("Clip task failed: {err}"))?
+}
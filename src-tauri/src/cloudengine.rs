@@ -0,0 +1,101 @@
+use crate::{scanner, settings::CloudTranscriptionSettings, PreflightCheck, PreflightRequest};
+use std::{path::Path, process::Command};
+
+/// Files probed for the cost estimate; larger archives are extrapolated.
+const COST_SAMPLE_FILES: usize = 200;
+/// Passes the key to the batch script without putting it on a command line.
+const API_KEY_ENV: &str = "CHURCH_TRANSCRIBER_CLOUD_KEY";
+
+fn check(key: &str, ok: bool, warning: bool, detail: String, fix: &str) -> PreflightCheck {
+    PreflightCheck {
+        key: key.to_string(),
+        ok,
+        warning,
+        detail,
+        fix: fix.to_string(),
+    }
+}
+
+/// Total audio seconds across the input folders, probing up to
+/// `COST_SAMPLE_FILES` and scaling by file count beyond that.
+fn estimate_audio_secs(request: &PreflightRequest) -> (usize, u64) {
+    let files: Vec<_> = request
+        .input_folders
+        .iter()
+        .filter_map(|folder| scanner::media_files(Path::new(folder.trim()), &request.extensions, !request.no_recursive).ok())
+        .flatten()
+        .collect();
+    let probed: Vec<u64> = files
+        .iter()
+        .take(COST_SAMPLE_FILES)
+        .filter_map(|file| scanner::media_duration_secs(file))
+        .collect();
+    if probed.is_empty() {
+        return (files.len(), 0);
+    }
+    let average = probed.iter().sum::<u64>() / probed.len() as u64;
+    (files.len(), average * files.len() as u64)
+}
+
+/// Endpoint and key checks, plus what the input folders would cost to upload.
+pub fn preflight_checks(settings: &CloudTranscriptionSettings, request: &PreflightRequest) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+    let endpoint = settings.endpoint.trim();
+    checks.push(check(
+        "cloudEndpoint",
+        endpoint.starts_with("http://") || endpoint.starts_with("https://"),
+        false,
+        format!("Cloud transcription endpoint: {endpoint} (model {})", settings.model.trim()),
+        "Set an OpenAI-compatible endpoint such as https://api.openai.com/v1 in settings.",
+    ));
+    if settings.api_key.trim().is_empty() {
+        checks.push(check(
+            "cloudApiKey",
+            true,
+            true,
+            "No cloud API key is saved; only endpoints without authentication will work.".to_string(),
+            "Add the API key in settings.",
+        ));
+    }
+
+    let (files, secs) = estimate_audio_secs(request);
+    if files > 0 {
+        let minutes = secs as f64 / 60.0;
+        checks.push(check(
+            "cloudCost",
+            true,
+            true,
+            format!(
+                "About {:.1} hours of audio in {files} file(s); up to ${:.2} at ${}/minute (already transcribed files are skipped).",
+                minutes / 60.0,
+                minutes * settings.cost_per_minute,
+                settings.cost_per_minute
+            ),
+            "",
+        ));
+    }
+    if !settings.fallback_to_local {
+        checks.push(check(
+            "cloudFallback",
+            true,
+            true,
+            "Local fallback is off; files fail when an upload fails.".to_string(),
+            "Turn on local fallback in settings to transcribe with whisper.cpp instead.",
+        ));
+    }
+    checks
+}
+
+/// Adds the cloud settings to the batch script's command line and environment.
+pub fn configure(cmd: &mut Command, settings: &CloudTranscriptionSettings) {
+    cmd.arg("-CloudEndpoint")
+        .arg(settings.endpoint.trim())
+        .arg("-CloudModel")
+        .arg(settings.model.trim())
+        .arg("-CloudChunkSecs")
+        .arg((settings.chunk_minutes.max(1) * 60).to_string())
+        .env(API_KEY_ENV, settings.api_key.trim());
+    if !settings.fallback_to_local {
+        cmd.arg("-NoCloudFallback");
+    }
+}
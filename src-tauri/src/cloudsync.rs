@@ -0,0 +1,640 @@
+use crate::{
+    archive::{self, ArchiveRoot},
+    history, preprocess,
+    publish::describe_error,
+    redaction,
+    settings::{self, CloudProvider, CloudSyncSettings},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const STATE_FILE: &str = "cloud-sync-state.json";
+const API_TIMEOUT: Duration = Duration::from_secs(300);
+
+const GOOGLE_DEVICE_CODE_URL: &str = "https://oauth2.googleapis.com/device/code";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_DRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive.file";
+const DRIVE_FILES_URL: &str = "https://www.googleapis.com/drive/v3/files";
+const DRIVE_UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3/files";
+const DRIVE_FOLDER_MIME: &str = "application/vnd.google-apps.folder";
+
+const DROPBOX_AUTHORIZE_URL: &str = "https://www.dropbox.com/oauth2/authorize";
+const DROPBOX_TOKEN_URL: &str = "https://api.dropboxapi.com/oauth2/token";
+const DROPBOX_UPLOAD_URL: &str = "https://content.dropboxapi.com/2/files/upload";
+
+#[derive(Default)]
+pub struct CloudSyncState {
+    pending_verifier: Mutex<Option<String>>,
+    syncing: Mutex<bool>,
+}
+
+/// What the UI shows to finish sign-in: Drive uses the device flow (enter
+/// `user_code` at the URL); Dropbox has no device flow, so the user pastes the
+/// code it displays back into `cloud_sync_finish_auth`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudAuthPrompt {
+    pub provider: CloudProvider,
+    pub verification_url: String,
+    pub user_code: Option<String>,
+    pub expires_in_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudAuthEvent {
+    pub connected: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+    pub uploaded: usize,
+    pub updated: usize,
+    pub renamed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedFile {
+    size: u64,
+    modified_epoch: u64,
+    remote_id: String,
+    rev: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct SyncState {
+    provider: Option<CloudProvider>,
+    files: HashMap<String, SyncedFile>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_url: String,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(API_TIMEOUT).build()
+}
+
+fn state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(STATE_FILE))
+}
+
+fn load_state(app: &AppHandle) -> SyncState {
+    state_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(app: &AppHandle, state: &SyncState) -> Result<(), String> {
+    let path = state_path(app)?;
+    let body = serde_json::to_string_pretty(state)
+        .map_err(|err| format!("Failed to serialize sync state: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+fn token_url(provider: CloudProvider) -> &'static str {
+    match provider {
+        CloudProvider::GoogleDrive => GOOGLE_TOKEN_URL,
+        CloudProvider::Dropbox => DROPBOX_TOKEN_URL,
+    }
+}
+
+fn store_refresh_token(app: &AppHandle, refresh_token: String) -> Result<(), String> {
+    let mut current = settings::load(app);
+    current.cloud_sync.refresh_token = refresh_token;
+    settings::save(app, &current)
+}
+
+fn access_token(sync: &CloudSyncSettings) -> Result<String, String> {
+    if sync.client_id.trim().is_empty() {
+        return Err("Cloud sync client ID is not configured.".to_string());
+    }
+    if sync.refresh_token.trim().is_empty() {
+        return Err("Cloud sync is not connected. Sign in from Settings first.".to_string());
+    }
+
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", sync.refresh_token.trim()),
+        ("client_id", sync.client_id.trim()),
+    ];
+    if !sync.client_secret.trim().is_empty() {
+        form.push(("client_secret", sync.client_secret.trim()));
+    }
+
+    agent()
+        .post(token_url(sync.provider))
+        .send_form(&form)
+        .map_err(|err| describe_error("Cloud token refresh", err))?
+        .into_json::<TokenResponse>()
+        .map(|t| t.access_token)
+        .map_err(|err| format!("Unexpected token response: {err}"))
+}
+
+fn emit_auth(app: &AppHandle, connected: bool, message: impl Into<String>) {
+    let _ = app.emit(
+        "cloud-sync://auth",
+        CloudAuthEvent {
+            connected,
+            message: message.into(),
+        },
+    );
+}
+
+/// Polls Google's token endpoint until the user approves the device code.
+fn poll_device_code(app: AppHandle, sync: CloudSyncSettings, device: DeviceCodeResponse) {
+    let deadline = Instant::now() + Duration::from_secs(device.expires_in);
+    let mut interval = Duration::from_secs(device.interval.unwrap_or(5).max(1));
+
+    while Instant::now() < deadline {
+        thread::sleep(interval);
+        let response = agent().post(GOOGLE_TOKEN_URL).send_form(&[
+            ("client_id", sync.client_id.trim()),
+            ("client_secret", sync.client_secret.trim()),
+            ("device_code", device.device_code.as_str()),
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+        ]);
+
+        let tokens = match response {
+            Ok(response) => response.into_json::<TokenResponse>(),
+            Err(ureq::Error::Status(_, response)) => {
+                let error = response
+                    .into_json::<Value>()
+                    .ok()
+                    .and_then(|v| v["error"].as_str().map(str::to_string))
+                    .unwrap_or_default();
+                match error.as_str() {
+                    "authorization_pending" => continue,
+                    "slow_down" => {
+                        interval += Duration::from_secs(5);
+                        continue;
+                    }
+                    _ => {
+                        emit_auth(&app, false, format!("Google Drive sign-in failed: {error}"));
+                        return;
+                    }
+                }
+            }
+            Err(err) => {
+                emit_auth(&app, false, format!("Google Drive sign-in failed: {err}"));
+                return;
+            }
+        };
+
+        let result = tokens
+            .map_err(|err| format!("Unexpected token response: {err}"))
+            .and_then(|t| {
+                t.refresh_token
+                    .ok_or_else(|| "Google did not return a refresh token.".to_string())
+            })
+            .and_then(|token| store_refresh_token(&app, token));
+        match result {
+            Ok(()) => emit_auth(&app, true, "Google Drive connected."),
+            Err(err) => emit_auth(&app, false, err),
+        }
+        return;
+    }
+
+    emit_auth(&app, false, "Google Drive sign-in code expired.");
+}
+
+fn pkce_pair() -> (String, String) {
+    let verifier = format!("{}{}", settings::generate_token(), settings::generate_token());
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+#[tauri::command]
+pub fn cloud_sync_begin_auth(
+    app: AppHandle,
+    state: State<CloudSyncState>,
+) -> Result<CloudAuthPrompt, String> {
+    let sync = settings::load(&app).cloud_sync;
+    if sync.client_id.trim().is_empty() {
+        return Err("Cloud sync client ID is not configured.".to_string());
+    }
+
+    match sync.provider {
+        CloudProvider::GoogleDrive => {
+            let device: DeviceCodeResponse = agent()
+                .post(GOOGLE_DEVICE_CODE_URL)
+                .send_form(&[
+                    ("client_id", sync.client_id.trim()),
+                    ("scope", GOOGLE_DRIVE_SCOPE),
+                ])
+                .map_err(|err| describe_error("Google device code request", err))?
+                .into_json()
+                .map_err(|err| format!("Unexpected device code response: {err}"))?;
+
+            let prompt = CloudAuthPrompt {
+                provider: sync.provider,
+                verification_url: device.verification_url.clone(),
+                user_code: Some(device.user_code.clone()),
+                expires_in_secs: device.expires_in,
+            };
+            thread::spawn(move || poll_device_code(app, sync, device));
+            Ok(prompt)
+        }
+        CloudProvider::Dropbox => {
+            let (verifier, challenge) = pkce_pair();
+            let url = tauri::Url::parse_with_params(
+                DROPBOX_AUTHORIZE_URL,
+                [
+                    ("client_id", sync.client_id.trim()),
+                    ("response_type", "code"),
+                    ("token_access_type", "offline"),
+                    ("code_challenge", challenge.as_str()),
+                    ("code_challenge_method", "S256"),
+                ],
+            )
+            .map_err(|err| format!("Could not build Dropbox sign-in URL: {err}"))?;
+            if let Ok(mut pending) = state.pending_verifier.lock() {
+                *pending = Some(verifier);
+            }
+            Ok(CloudAuthPrompt {
+                provider: sync.provider,
+                verification_url: url.to_string(),
+                user_code: None,
+                expires_in_secs: 600,
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub fn cloud_sync_finish_auth(
+    app: AppHandle,
+    state: State<CloudSyncState>,
+    code: String,
+) -> Result<(), String> {
+    let sync = settings::load(&app).cloud_sync;
+    let verifier = state
+        .pending_verifier
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take())
+        .ok_or_else(|| "Start Dropbox sign-in first.".to_string())?;
+
+    let tokens: TokenResponse = agent()
+        .post(DROPBOX_TOKEN_URL)
+        .send_form(&[
+            ("code", code.trim()),
+            ("grant_type", "authorization_code"),
+            ("client_id", sync.client_id.trim()),
+            ("code_verifier", verifier.as_str()),
+        ])
+        .map_err(|err| describe_error("Dropbox token request", err))?
+        .into_json()
+        .map_err(|err| format!("Unexpected Dropbox token response: {err}"))?;
+    let refresh_token = tokens
+        .refresh_token
+        .ok_or_else(|| "Dropbox did not return a refresh token.".to_string())?;
+    store_refresh_token(&app, refresh_token)?;
+    emit_auth(&app, true, "Dropbox connected.");
+    Ok(())
+}
+
+/// Inserts ` (n)` before the extension: `raw.txt` -> `raw (2).txt`.
+fn numbered_name(name: &str, n: usize) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem} ({n}).{ext}"),
+        _ => format!("{name} ({n})"),
+    }
+}
+
+fn drive_query_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+struct DriveClient {
+    token: String,
+    folders: HashMap<String, String>,
+}
+
+impl DriveClient {
+    fn find_child(&self, parent: &str, name: &str, folder: bool) -> Result<Option<String>, String> {
+        let mut query = format!(
+            "name = '{}' and '{}' in parents and trashed = false",
+            drive_query_escape(name),
+            drive_query_escape(parent)
+        );
+        if folder {
+            query.push_str(&format!(" and mimeType = '{DRIVE_FOLDER_MIME}'"));
+        }
+        let listing: Value = agent()
+            .get(DRIVE_FILES_URL)
+            .query("q", &query)
+            .query("fields", "files(id)")
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .call()
+            .map_err(|err| describe_error("Drive lookup", err))?
+            .into_json()
+            .map_err(|err| format!("Unexpected Drive response: {err}"))?;
+        Ok(listing["files"][0]["id"].as_str().map(str::to_string))
+    }
+
+    fn create_folder(&self, parent: &str, name: &str) -> Result<String, String> {
+        let created: Value = agent()
+            .post(DRIVE_FILES_URL)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .send_json(json!({ "name": name, "mimeType": DRIVE_FOLDER_MIME, "parents": [parent] }))
+            .map_err(|err| describe_error("Drive folder create", err))?
+            .into_json()
+            .map_err(|err| format!("Unexpected Drive response: {err}"))?;
+        created["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Drive did not return a folder ID.".to_string())
+    }
+
+    /// Resolves (creating as needed) the folder chain for a `/`-separated path
+    /// under My Drive.
+    fn folder_for(&mut self, path: &str) -> Result<String, String> {
+        let mut parent = "root".to_string();
+        let mut walked = String::new();
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            walked.push('/');
+            walked.push_str(part);
+            if let Some(id) = self.folders.get(&walked) {
+                parent = id.clone();
+                continue;
+            }
+            let id = match self.find_child(&parent, part, true)? {
+                Some(id) => id,
+                None => self.create_folder(&parent, part)?,
+            };
+            self.folders.insert(walked.clone(), id.clone());
+            parent = id;
+        }
+        Ok(parent)
+    }
+
+    fn upload(&mut self, remote_dir: &str, name: &str, path: &Path, existing: Option<&SyncedFile>) -> Result<(SyncedFile, bool), String> {
+        let body = fs::read(path).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+
+        if let Some(existing) = existing {
+            let updated = agent()
+                .request("PATCH", &format!("{DRIVE_UPLOAD_URL}/{}", existing.remote_id))
+                .query("uploadType", "media")
+                .set("Authorization", &format!("Bearer {}", self.token))
+                .send_bytes(&body);
+            match updated {
+                Ok(_) => return Ok((file_record(path, existing.remote_id.clone(), None), false)),
+                // Deleted remotely; fall through and upload a fresh copy.
+                Err(ureq::Error::Status(404, _)) => {}
+                Err(err) => return Err(describe_error("Drive update", err)),
+            }
+        }
+
+        let folder = self.folder_for(remote_dir)?;
+        let mut remote_name = name.to_string();
+        let mut n = 2;
+        while self.find_child(&folder, &remote_name, false)?.is_some() {
+            remote_name = numbered_name(name, n);
+            n += 1;
+        }
+
+        let boundary = format!("sync-{}", settings::generate_token());
+        let metadata = json!({ "name": remote_name, "parents": [folder] });
+        let mut payload = format!(
+            "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n--{boundary}\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        payload.extend_from_slice(&body);
+        payload.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let created: Value = agent()
+            .post(DRIVE_UPLOAD_URL)
+            .query("uploadType", "multipart")
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Content-Type", &format!("multipart/related; boundary={boundary}"))
+            .send_bytes(&payload)
+            .map_err(|err| describe_error("Drive upload", err))?
+            .into_json()
+            .map_err(|err| format!("Unexpected Drive response: {err}"))?;
+        let id = created["id"]
+            .as_str()
+            .ok_or_else(|| "Drive did not return a file ID.".to_string())?;
+        Ok((file_record(path, id.to_string(), None), remote_name != name))
+    }
+}
+
+/// Dropbox handles conflicts itself: `autorename` keeps both copies when the
+/// name is taken or our last known revision is stale.
+fn dropbox_upload(token: &str, remote_path: &str, path: &Path, existing: Option<&SyncedFile>) -> Result<(SyncedFile, bool), String> {
+    let body = fs::read(path).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    let mode = match existing.and_then(|e| e.rev.as_deref()) {
+        Some(rev) => json!({ ".tag": "update", "update": rev }),
+        None => json!("add"),
+    };
+    let arg = json!({ "path": remote_path, "mode": mode, "autorename": true, "mute": true });
+
+    let uploaded: Value = agent()
+        .post(DROPBOX_UPLOAD_URL)
+        .set("Authorization", &format!("Bearer {token}"))
+        .set("Content-Type", "application/octet-stream")
+        .set("Dropbox-API-Arg", &arg.to_string())
+        .send_bytes(&body)
+        .map_err(|err| describe_error("Dropbox upload", err))?
+        .into_json()
+        .map_err(|err| format!("Unexpected Dropbox response: {err}"))?;
+
+    let stored_path = uploaded["path_display"].as_str().unwrap_or(remote_path);
+    let renamed = !stored_path.eq_ignore_ascii_case(remote_path);
+    Ok((
+        file_record(
+            path,
+            uploaded["id"].as_str().unwrap_or_default().to_string(),
+            uploaded["rev"].as_str().map(str::to_string),
+        ),
+        renamed,
+    ))
+}
+
+fn modified_epoch(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn file_record(path: &Path, remote_id: String, rev: Option<String>) -> SyncedFile {
+    SyncedFile {
+        size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        modified_epoch: modified_epoch(path),
+        remote_id,
+        rev,
+    }
+}
+
+/// The configured folder, or every output folder of the latest run; folders
+/// set per input folder are mirrored under their own name.
+fn local_roots(app: &AppHandle, sync: &CloudSyncSettings) -> Result<Vec<ArchiveRoot>, String> {
+    let configured = sync.local_folder.trim();
+    if !configured.is_empty() {
+        return Ok(vec![ArchiveRoot::single(PathBuf::from(configured))]);
+    }
+    history::list_reports(app)
+        .into_iter()
+        .next()
+        .map(|r| r.archive_roots())
+        .ok_or_else(|| "No local folder configured and no previous run to sync from.".to_string())
+}
+
+/// Mirrors transcript folders under the local root to the remote folder,
+/// uploading files that are new or changed since the last sync.
+fn sync_folder(app: &AppHandle) -> Result<SyncSummary, String> {
+    let sync = settings::load(app).cloud_sync;
+    let roots = local_roots(app, &sync)?;
+    let token = access_token(&sync)?;
+    let remote_root = sync.remote_folder.trim().trim_matches('/').to_string();
+
+    let mut state = load_state(app);
+    if state.provider != Some(sync.provider) {
+        state = SyncState {
+            provider: Some(sync.provider),
+            ..SyncState::default()
+        };
+    }
+
+    let mut drive = DriveClient {
+        token: token.clone(),
+        folders: HashMap::new(),
+    };
+    let mut summary = SyncSummary::default();
+
+    let withheld = redaction::withheld_files(&settings::load(app).redaction);
+    for entry in archive::list_all(&roots, withheld)? {
+        let Some(dir) = archive::resolve_listed(&roots, &entry.relative_dir) else {
+            continue;
+        };
+        for name in entry.files.iter().filter(|f| !preprocess::is_kept_audio(f)) {
+            let relative = format!("{}/{name}", entry.relative_dir);
+            let path = dir.join(name);
+            let existing = state.files.get(&relative).cloned();
+            let unchanged = existing.as_ref().is_some_and(|e| {
+                e.size == fs::metadata(&path).map(|m| m.len()).unwrap_or(0)
+                    && e.modified_epoch == modified_epoch(&path)
+            });
+            if unchanged {
+                summary.unchanged += 1;
+                continue;
+            }
+
+            let remote_dir = format!("{remote_root}/{}", entry.relative_dir);
+            let result = match sync.provider {
+                CloudProvider::GoogleDrive => drive.upload(&remote_dir, name, &path, existing.as_ref()),
+                CloudProvider::Dropbox => {
+                    dropbox_upload(&token, &format!("/{remote_dir}/{name}"), &path, existing.as_ref())
+                }
+            };
+
+            match result {
+                Ok((record, renamed)) => {
+                    if existing.is_some() {
+                        summary.updated += 1;
+                    } else {
+                        summary.uploaded += 1;
+                    }
+                    if renamed {
+                        summary.renamed += 1;
+                    }
+                    state.files.insert(relative, record);
+                }
+                Err(err) => {
+                    summary.failed += 1;
+                    crate::emit_log(app, "system", format!("Sync failed for {relative}: {err}"));
+                }
+            }
+        }
+        // Persist as we go so an interrupted sync doesn't re-upload everything.
+        save_state(app, &state)?;
+    }
+
+    Ok(summary)
+}
+
+fn run_exclusive(app: &AppHandle) -> Result<SyncSummary, String> {
+    let state = app.state::<CloudSyncState>();
+    {
+        let mut syncing = state
+            .syncing
+            .lock()
+            .map_err(|_| "Cloud sync state is unavailable.".to_string())?;
+        if *syncing {
+            return Err("A cloud sync is already running.".to_string());
+        }
+        *syncing = true;
+    }
+
+    let result = sync_folder(app);
+    if let Ok(mut syncing) = state.syncing.lock() {
+        *syncing = false;
+    }
+    result
+}
+
+/// Called after every run when sync is enabled.
+pub fn sync_after_run(app: &AppHandle) {
+    let sync = settings::load(app).cloud_sync;
+    if !sync.enabled || !sync.sync_after_run {
+        return;
+    }
+
+    match run_exclusive(app) {
+        Ok(summary) => crate::emit_log(
+            app,
+            "system",
+            format!(
+                "Cloud sync: {} uploaded, {} updated, {} renamed to avoid conflicts, {} failed.",
+                summary.uploaded, summary.updated, summary.renamed, summary.failed
+            ),
+        ),
+        Err(err) => crate::emit_log(app, "system", format!("Cloud sync failed: {err}")),
+    }
+}
+
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || run_exclusive(&app))
+        .await
+        .map_err(|err| format!("Cloud sync task failed: {err}"))?
+}
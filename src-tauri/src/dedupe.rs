@@ -0,0 +1,42 @@
+use crate::scanner::{FolderPlan, SkipReason, SkippedFile};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// Moves recordings already planned earlier in this run (`seen`, fingerprint ->
+/// source) into the plan's skipped list. Copies of recordings from earlier runs
+/// are caught by the manifest, which also fills in the fingerprints used here.
+pub fn skip_duplicates(app: &AppHandle, plan: &mut FolderPlan, seen: &mut HashMap<String, String>) {
+    let mut kept = Vec::new();
+    let mut duplicates = 0usize;
+
+    for file in plan.files.drain(..) {
+        let Some(fingerprint) = file.fingerprint.clone() else {
+            kept.push(file);
+            continue;
+        };
+
+        match seen.get(&fingerprint) {
+            Some(first) => {
+                duplicates += 1;
+                plan.skipped.push(SkippedFile {
+                    detail: format!("Same recording as {first} (earlier in this run)"),
+                    source: file.source,
+                    reason: SkipReason::Duplicate,
+                });
+            }
+            None => {
+                seen.insert(fingerprint, file.source.clone());
+                kept.push(file);
+            }
+        }
+    }
+
+    plan.files = kept;
+    if duplicates > 0 {
+        crate::emit_log(
+            app,
+            "system",
+            format!("Deduplicated {duplicates} recording(s) in {}.", plan.input_folder),
+        );
+    }
+}
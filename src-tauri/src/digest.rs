@@ -0,0 +1,269 @@
+use crate::{
+    history, mailer, postprocess, scanner,
+    settings::{self, AppSettings},
+    transcript,
+};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager};
+
+const STATE_FILE: &str = "digest-state.json";
+const SUMMARY_FILE: &str = "summary.md";
+const SUMMARY_FALLBACK_CHARS: usize = 320;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct DigestState {
+    last_generated_epoch: u64,
+}
+
+#[derive(Debug, Clone)]
+struct DigestItem {
+    title: String,
+    source: String,
+    transcript_path: PathBuf,
+    duration_ms: Option<u64>,
+    summary: String,
+    finished_at_epoch: u64,
+}
+
+fn state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(STATE_FILE))
+}
+
+fn load_state(app: &AppHandle) -> DigestState {
+    state_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(app: &AppHandle, state: &DigestState) -> Result<(), String> {
+    let path = state_path(app)?;
+    let body = serde_json::to_string_pretty(state)
+        .map_err(|err| format!("Failed to serialize digest state: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+fn local_date(epoch: u64) -> String {
+    DateTime::from_timestamp(epoch as i64, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+pub fn format_duration(ms: u64) -> String {
+    let minutes = ms / 60_000;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+pub fn file_link(path: &Path) -> String {
+    let normalized = path.display().to_string().replace('\\', "/");
+    let encoded = normalized.replace(' ', "%20");
+    if encoded.starts_with('/') {
+        format!("file://{encoded}")
+    } else {
+        format!("file:///{encoded}")
+    }
+}
+
+/// Prefers the "Brief overview" section of `summary.md`, falling back to the
+/// opening of the raw transcript.
+pub fn summary_for(output_dir: &Path) -> String {
+    if let Ok(body) = fs::read_to_string(output_dir.join(SUMMARY_FILE)) {
+        let overview: Vec<&str> = body
+            .lines()
+            .skip_while(|l| !l.trim().eq_ignore_ascii_case("## Brief overview"))
+            .skip(1)
+            .take_while(|l| !l.trim_start().starts_with("##"))
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !overview.is_empty() {
+            return overview.join(" ");
+        }
+    }
+
+    let raw = fs::read_to_string(output_dir.join("raw.txt")).unwrap_or_default();
+    let flat = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > SUMMARY_FALLBACK_CHARS {
+        let cut: String = flat.chars().take(SUMMARY_FALLBACK_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        flat
+    }
+}
+
+fn collect_items(app: &AppHandle, since_epoch: u64) -> Vec<DigestItem> {
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+
+    for report in history::list_reports(app) {
+        for result in report.results {
+            if result.status != "ok" || result.finished_at_epoch < since_epoch {
+                continue;
+            }
+            let Some(output_dir) = result.output_dir.map(PathBuf::from) else {
+                continue;
+            };
+            if !output_dir.join("raw.txt").exists() || !seen.insert(output_dir.clone()) {
+                continue;
+            }
+
+            let readable = output_dir.join(postprocess::READABLE_MD_FILE);
+            let transcript_path = if readable.exists() {
+                readable
+            } else {
+                output_dir.join("raw.txt")
+            };
+
+            items.push(DigestItem {
+                title: scanner::title_from_source(&result.source),
+                source: result.source,
+                transcript_path,
+                duration_ms: transcript::load_segments(&output_dir)
+                    .ok()
+                    .and_then(|s| s.last().map(|seg| seg.end_ms)),
+                summary: summary_for(&output_dir),
+                finished_at_epoch: result.finished_at_epoch,
+            });
+        }
+    }
+
+    items.sort_by_key(|i| i.finished_at_epoch);
+    items
+}
+
+fn render_markdown(items: &[DigestItem], since_epoch: u64, until_epoch: u64) -> String {
+    let total_ms: u64 = items.iter().filter_map(|i| i.duration_ms).sum();
+    let mut lines = vec![
+        format!(
+            "# Transcript Digest — {} to {}",
+            local_date(since_epoch),
+            local_date(until_epoch)
+        ),
+        String::new(),
+        format!(
+            "{} new transcript(s), {} of audio.",
+            items.len(),
+            format_duration(total_ms)
+        ),
+        String::new(),
+    ];
+
+    if items.is_empty() {
+        lines.push("No new transcripts were produced in this period.".to_string());
+        lines.push(String::new());
+    }
+
+    for item in items {
+        lines.push(format!("## {}", item.title));
+        lines.push(String::new());
+        lines.push(format!(
+            "- **Duration:** {}",
+            item.duration_ms
+                .map(format_duration)
+                .unwrap_or_else(|| "unknown".to_string())
+        ));
+        lines.push(format!("- **Transcribed:** {}", local_date(item.finished_at_epoch)));
+        lines.push(format!("- **Source:** `{}`", item.source));
+        lines.push(format!(
+            "- **Transcript:** [{}]({})",
+            item.transcript_path.display(),
+            file_link(&item.transcript_path)
+        ));
+        lines.push(String::new());
+        if !item.summary.is_empty() {
+            lines.push(item.summary.clone());
+            lines.push(String::new());
+        }
+    }
+
+    lines.join("\r\n")
+}
+
+fn generate(app: &AppHandle, settings: &AppSettings, since_epoch: u64) -> Result<PathBuf, String> {
+    let folder = settings.digest.folder.trim();
+    if folder.is_empty() {
+        return Err("Digest folder is not configured.".to_string());
+    }
+
+    let until_epoch = crate::now_epoch();
+    let items = collect_items(app, since_epoch);
+    let body = render_markdown(&items, since_epoch, until_epoch);
+
+    let folder = PathBuf::from(folder);
+    fs::create_dir_all(&folder)
+        .map_err(|err| format!("Could not create digest folder {}: {err}", folder.display()))?;
+    let path = folder.join(format!("transcript-digest-{}.md", local_date(until_epoch)));
+    fs::write(&path, &body).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+
+    save_state(
+        app,
+        &DigestState {
+            last_generated_epoch: until_epoch,
+        },
+    )?;
+
+    if settings.digest.email {
+        let subject = format!(
+            "Transcript digest: {} new transcript(s) ({})",
+            items.len(),
+            local_date(until_epoch)
+        );
+        mailer::send_mail(&settings.smtp, &settings.digest.recipients, &subject, &body)?;
+    }
+
+    Ok(path)
+}
+
+fn default_since(settings: &AppSettings, state: &DigestState) -> u64 {
+    if state.last_generated_epoch > 0 {
+        state.last_generated_epoch
+    } else {
+        crate::now_epoch().saturating_sub(u64::from(settings.digest.interval_days.max(1)) * 86_400)
+    }
+}
+
+/// Called after every run; writes a digest once the configured interval has passed.
+pub fn run_if_due(app: &AppHandle) {
+    let settings = settings::load(app);
+    if !settings.digest.enabled {
+        return;
+    }
+
+    let state = load_state(app);
+    let interval = u64::from(settings.digest.interval_days.max(1)) * 86_400;
+    if state.last_generated_epoch > 0
+        && crate::now_epoch().saturating_sub(state.last_generated_epoch) < interval
+    {
+        return;
+    }
+
+    match generate(app, &settings, default_since(&settings, &state)) {
+        Ok(path) => crate::emit_log(app, "system", format!("Transcript digest written: {}", path.display())),
+        Err(err) => crate::emit_log(app, "system", format!("Transcript digest failed: {err}")),
+    }
+}
+
+#[tauri::command]
+pub fn generate_digest_now(app: AppHandle) -> Result<String, String> {
+    let settings = settings::load(&app);
+    let since = default_since(&settings, &load_state(&app));
+    generate(&app, &settings, since).map(|p| p.display().to_string())
+}
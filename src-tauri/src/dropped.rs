@@ -0,0 +1,111 @@
+use crate::{longpath, scanner};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DroppedKind {
+    Folder,
+    MediaFile,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedPath {
+    pub path: String,
+    pub kind: DroppedKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedPath {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DropRequest {
+    /// Paths from the webview's drop event, files and folders mixed.
+    pub paths: Vec<String>,
+    /// The input folders (and files) already queued.
+    pub queue: Vec<String>,
+    /// The run's extension filter; empty allows every supported format.
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropResult {
+    /// The queue with the accepted paths added; start the run with it as
+    /// `inputFolders`.
+    pub queue: Vec<String>,
+    pub added: Vec<DroppedPath>,
+    pub rejected: Vec<RejectedPath>,
+}
+
+fn classify(path: &Path, extensions: &[String]) -> Result<DroppedKind, String> {
+    let long = longpath::extended(path);
+    if !path.is_absolute() {
+        Err("Dropped paths must be absolute.".to_string())
+    } else if long.is_dir() {
+        Ok(DroppedKind::Folder)
+    } else if !long.is_file() {
+        Err("Not found.".to_string())
+    } else if scanner::is_media_file(path, extensions) {
+        Ok(DroppedKind::MediaFile)
+    } else if extensions.is_empty() {
+        Err("Not a supported media format.".to_string())
+    } else {
+        Err("Not in the extension filter.".to_string())
+    }
+}
+
+/// Why `path` is already covered by the queue, if it is.
+fn queued(queue: &[String], path: &str) -> Option<String> {
+    if queue.iter().any(|entry| scanner::same_path(entry, path)) {
+        return Some("Already queued.".to_string());
+    }
+    queue
+        .iter()
+        .find(|entry| Path::new(entry).is_dir() && Path::new(path).starts_with(entry))
+        .map(|folder| format!("Already queued with {folder}."))
+}
+
+/// Checks paths dropped on the window and adds the usable ones to the queue:
+/// folders are scanned as usual, and a media file is transcribed on its own.
+/// Files already queued inside a dropped folder are folded into it.
+#[tauri::command]
+pub fn add_dropped_paths(request: DropRequest) -> DropResult {
+    let mut result = DropResult {
+        queue: request
+            .queue
+            .iter()
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect(),
+        ..DropResult::default()
+    };
+
+    for raw in &request.paths {
+        let path = longpath::plain(raw.trim());
+        if path.is_empty() {
+            continue;
+        }
+        let checked = classify(Path::new(&path), &request.extensions)
+            .and_then(|kind| queued(&result.queue, &path).map_or(Ok(kind), Err));
+        match checked {
+            Ok(kind) => {
+                if kind == DroppedKind::Folder {
+                    result
+                        .queue
+                        .retain(|entry| !(Path::new(entry).is_file() && Path::new(entry).starts_with(&path)));
+                }
+                result.queue.push(path.clone());
+                result.added.push(DroppedPath { path, kind });
+            }
+            Err(reason) => result.rejected.push(RejectedPath { path, reason }),
+        }
+    }
+    result
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+/// Speech-to-text backend the batch script runs for each file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Engine {
+    /// whisper.cpp's `whisper-cli` with a ggml model file.
+    #[default]
+    WhisperCpp,
+    /// faster-whisper (CTranslate2) through the `whisper-ctranslate2` CLI;
+    /// the model is a name like `large-v3` or a converted model folder.
+    FasterWhisper,
+    /// An OpenAI-compatible `/audio/transcriptions` API, falling back to
+    /// whisper.cpp per file when the upload fails.
+    Cloud,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppJson {
+    transcription: Vec<WhisperCppSegment>,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppSegment {
+    offsets: WhisperCppOffsets,
+    text: String,
+    /// Present with `-ojf` (full JSON output).
+    #[serde(default)]
+    tokens: Vec<WhisperCppToken>,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppToken {
+    text: String,
+    p: f32,
+}
+
+#[derive(Deserialize)]
+struct WhisperCppOffsets {
+    from: u64,
+    to: u64,
+}
+
+#[derive(Deserialize)]
+struct FasterWhisperJson {
+    segments: Vec<FasterWhisperSegment>,
+}
+
+#[derive(Deserialize)]
+struct FasterWhisperSegment {
+    /// Seconds.
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    avg_logprob: Option<f64>,
+}
+
+/// One timed segment of engine output.
+pub struct EngineSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// 0-1; the mean token probability, or `exp(avg_logprob)` for
+    /// faster-whisper and cloud output.
+    pub confidence: Option<f32>,
+}
+
+/// Mean probability of the spoken tokens, skipping markers like `[_BEG_]`.
+fn mean_token_probability(tokens: &[WhisperCppToken]) -> Option<f32> {
+    let spoken: Vec<f32> = tokens.iter().filter(|t| !t.text.starts_with("[_")).map(|t| t.p).collect();
+    (!spoken.is_empty()).then(|| spoken.iter().sum::<f32>() / spoken.len() as f32)
+}
+
+impl Engine {
+    /// The `-Engine` value the batch script takes, also written to `metadata.json`.
+    pub fn cli_name(self) -> &'static str {
+        match self {
+            Engine::WhisperCpp => "whisper-cpp",
+            Engine::FasterWhisper => "faster-whisper",
+            Engine::Cloud => "cloud",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Engine> {
+        [Engine::WhisperCpp, Engine::FasterWhisper, Engine::Cloud]
+            .into_iter()
+            .find(|engine| engine.cli_name() == name.trim())
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Engine::WhisperCpp => "whisper.cpp",
+            Engine::FasterWhisper => "faster-whisper",
+            Engine::Cloud => "cloud API",
+        }
+    }
+
+    /// Whether the model setting must be a file on disk; faster-whisper also
+    /// takes a model name and downloads it. Cloud runs keep a local whisper.cpp
+    /// model for fallback.
+    pub fn requires_model_file(self) -> bool {
+        self != Engine::FasterWhisper
+    }
+
+    /// Parses the engine's JSON output.
+    pub fn parse_output(self, body: &str) -> Result<Vec<EngineSegment>, serde_json::Error> {
+        Ok(match self {
+            Engine::WhisperCpp => serde_json::from_str::<WhisperCppJson>(body)?
+                .transcription
+                .into_iter()
+                .map(|s| EngineSegment {
+                    start_ms: s.offsets.from,
+                    end_ms: s.offsets.to,
+                    confidence: mean_token_probability(&s.tokens),
+                    text: s.text,
+                })
+                .collect(),
+            // The batch script writes cloud results in the same shape.
+            Engine::FasterWhisper | Engine::Cloud => serde_json::from_str::<FasterWhisperJson>(body)?
+                .segments
+                .into_iter()
+                .map(|s| EngineSegment {
+                    start_ms: (s.start * 1000.0) as u64,
+                    end_ms: (s.end * 1000.0) as u64,
+                    text: s.text,
+                    confidence: s.avg_logprob.map(|p| p.exp() as f32),
+                })
+                .collect(),
+        })
+    }
+}
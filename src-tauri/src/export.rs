@@ -0,0 +1,598 @@
+use crate::{
+    postprocess::Paragraph,
+    transcript::{format_timestamp, Segment},
+};
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+pub const DOCX_FILE: &str = "transcript.docx";
+pub const PDF_FILE: &str = "transcript.pdf";
+pub const SRT_FILE: &str = "captions.srt";
+pub const VTT_FILE: &str = "captions.vtt";
+pub const HTML_FILE: &str = "transcript.html";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Docx,
+    Pdf,
+    Srt,
+    Vtt,
+    /// A web page whose timestamps seek an embedded player.
+    Html,
+}
+
+/// Caption size limits; unset keeps whisper's segments as they are.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CaptionLimits {
+    /// Characters per caption, shown on up to two lines.
+    pub max_chars: Option<usize>,
+    pub max_duration_ms: Option<u64>,
+}
+
+/// Everything the document exports print above the transcript body.
+pub struct DocumentHeader<'a> {
+    pub title: &'a str,
+    pub date: &'a str,
+    pub source: &'a str,
+    /// Service details such as preacher and series, as label and value.
+    pub fields: &'a [(&'a str, String)],
+}
+
+/// The kept audio an HTML export plays.
+pub struct PlayerAudio<'a> {
+    /// File name next to the page, so both can be uploaded together.
+    pub file: &'a str,
+    /// Silence trimmed from the start, which the audio doesn't have but the
+    /// timestamps count.
+    pub offset_ms: u64,
+}
+
+pub fn write_exports(
+    output_dir: &Path,
+    header: &DocumentHeader,
+    paragraphs: &[Paragraph],
+    segments: &[Segment],
+    formats: &[ExportFormat],
+    captions: CaptionLimits,
+    audio: Option<&PlayerAudio>,
+) -> Result<Vec<PathBuf>, String> {
+    let captions = fit_captions(segments, captions);
+    let mut written = Vec::new();
+    for format in formats {
+        let path = match format {
+            ExportFormat::Docx => write_docx(&output_dir.join(DOCX_FILE), header, paragraphs)?,
+            ExportFormat::Pdf => write_pdf(&output_dir.join(PDF_FILE), header, paragraphs)?,
+            ExportFormat::Srt => write_captions(&output_dir.join(SRT_FILE), &render_srt(&captions))?,
+            ExportFormat::Vtt => write_captions(&output_dir.join(VTT_FILE), &render_vtt(&captions))?,
+            ExportFormat::Html => write_captions(&output_dir.join(HTML_FILE), &render_html(header, paragraphs, audio))?,
+        };
+        if !written.contains(&path) {
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// Breaks a caption onto two lines at the space nearest its middle.
+fn two_lines(text: &str) -> String {
+    let middle = text.len() / 2;
+    match text
+        .match_indices(' ')
+        .map(|(i, _)| i)
+        .min_by_key(|i| i.abs_diff(middle))
+    {
+        Some(i) => format!("{}\n{}", &text[..i], &text[i + 1..]),
+        None => text.to_string(),
+    }
+}
+
+/// Splits segments at word boundaries so no caption exceeds the limits,
+/// sharing each segment's time out by character count.
+pub fn fit_captions(segments: &[Segment], limits: CaptionLimits) -> Vec<Segment> {
+    let max_chars = limits.max_chars.filter(|c| *c > 0);
+    let max_duration = limits.max_duration_ms.filter(|d| *d > 0);
+    if max_chars.is_none() && max_duration.is_none() {
+        return segments.to_vec();
+    }
+
+    let mut fitted = Vec::new();
+    for segment in segments {
+        let text = segment.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let total_chars = text.chars().count().max(1);
+        let duration = segment.end_ms.saturating_sub(segment.start_ms);
+        let pieces = max_duration.map(|d| duration.div_ceil(d).max(1) as usize).unwrap_or(1);
+        let target = max_chars.unwrap_or(usize::MAX).min(total_chars.div_ceil(pieces));
+
+        let mut chunks: Vec<String> = Vec::new();
+        for word in text.split(' ') {
+            match chunks.last_mut() {
+                Some(chunk) if chunk.chars().count() + 1 + word.chars().count() <= target => {
+                    chunk.push(' ');
+                    chunk.push_str(word);
+                }
+                _ => chunks.push(word.to_string()),
+            }
+        }
+
+        let mut consumed = 0;
+        for chunk in chunks {
+            let start_ms = segment.start_ms + duration * consumed as u64 / total_chars as u64;
+            consumed = (consumed + chunk.chars().count() + 1).min(total_chars);
+            let end_ms = segment.start_ms + duration * consumed as u64 / total_chars as u64;
+            let text = match max_chars {
+                Some(max) if chunk.chars().count() > max / 2 => two_lines(&chunk),
+                _ => chunk,
+            };
+            fitted.push(Segment {
+                start_ms,
+                end_ms,
+                text,
+                speaker: segment.speaker.clone(),
+            });
+        }
+    }
+    fitted
+}
+
+fn caption_time(ms: u64, separator: char) -> String {
+    format!("{}{separator}{:03}", format_timestamp(ms), ms % 1000)
+}
+
+/// SRT has no speaker markup, so a labelled speaker's name leads the first
+/// caption after each change of speaker.
+pub fn render_srt(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let changed = i == 0 || segments[i - 1].speaker != s.speaker;
+            let text = match s.speaker.as_deref().filter(|_| changed) {
+                Some(name) => format!("{name}: {}", s.text),
+                None => s.text.clone(),
+            };
+            format!(
+                "{}\r\n{} --> {}\r\n{text}\r\n",
+                i + 1,
+                caption_time(s.start_ms, ','),
+                caption_time(s.end_ms, ','),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// Labelled speakers go in `<v Name>` voice tags.
+pub fn render_vtt(segments: &[Segment]) -> String {
+    let cues = segments.iter().map(|s| {
+        let voice = s
+            .speaker
+            .as_deref()
+            .map(|name| format!("<v {}>", name.replace(['<', '>'], "")))
+            .unwrap_or_default();
+        format!(
+            "{} --> {}\n{voice}{}\n",
+            caption_time(s.start_ms, '.'),
+            caption_time(s.end_ms, '.'),
+            s.text
+        )
+    });
+    std::iter::once("WEBVTT\n".to_string())
+        .chain(cues)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn write_captions(path: &Path, body: &str) -> Result<PathBuf, String> {
+    fs::write(path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path.to_path_buf())
+}
+
+const HTML_PLAYER_SCRIPT: &str = r#"<script>
+const player = document.getElementById("player");
+const paragraphs = [...document.querySelectorAll("p[data-start]")];
+for (const p of paragraphs) {
+  p.addEventListener("click", () => {
+    player.currentTime = Number(p.dataset.start);
+    player.play();
+  });
+}
+player.addEventListener("timeupdate", () => {
+  const now = player.currentTime;
+  for (const p of paragraphs) {
+    p.classList.toggle("playing", Number(p.dataset.start) <= now && now < Number(p.dataset.end));
+  }
+});
+</script>"#;
+
+/// A standalone page: header, then one paragraph per block. With `audio`,
+/// clicking a paragraph plays the recording from there and the playing
+/// paragraph is highlighted.
+fn render_html(header: &DocumentHeader, paragraphs: &[Paragraph], audio: Option<&PlayerAudio>) -> String {
+    let fields: String = header
+        .fields
+        .iter()
+        .map(|(label, value)| format!("<li><strong>{}:</strong> {}</li>", xml_escape(label), xml_escape(value)))
+        .collect();
+    let seconds = |ms: u64| audio.map_or(0, |a| ms.saturating_sub(a.offset_ms)) as f64 / 1000.0;
+    let body: String = paragraphs
+        .iter()
+        .map(|p| {
+            let stamp = format!("<span class=\"ts\">[{}]</span>", format_timestamp(p.start_ms));
+            match audio {
+                Some(_) => format!(
+                    "<p data-start=\"{:.2}\" data-end=\"{:.2}\">{stamp} {}</p>\n",
+                    seconds(p.start_ms),
+                    seconds(p.end_ms),
+                    xml_escape(&p.text)
+                ),
+                None => format!("<p>{stamp} {}</p>\n", xml_escape(&p.text)),
+            }
+        })
+        .collect();
+    let (player, script) = match audio {
+        Some(audio) => (
+            format!(
+                "<audio id=\"player\" controls preload=\"metadata\" src=\"{}\"></audio>\n",
+                xml_escape(audio.file)
+            ),
+            HTML_PLAYER_SCRIPT,
+        ),
+        None => (String::new(), ""),
+    };
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>{title}</title>\n\
+         <style>body{{font-family:Georgia,serif;max-width:46em;margin:2em auto;padding:0 1em;line-height:1.6}}\
+         audio{{width:100%;position:sticky;top:0}}.ts{{color:#7f7f7f;font-family:sans-serif;font-size:.85em}}\
+         p[data-start]{{cursor:pointer}}p.playing{{background:#fff6d5}}</style></head><body>\n\
+         <h1>{title}</h1>\n<p class=\"ts\">{date}</p>\n<ul>{fields}</ul>\n{player}{body}{script}</body></html>\n",
+        title = xml_escape(header.title),
+        date = xml_escape(header.date),
+    )
+}
+
+/// Escapes markup characters and drops control characters, which XML 1.0
+/// doesn't allow even escaped and which make Word refuse the whole document.
+pub fn xml_escape(value: &str) -> String {
+    value
+        .replace(|c: char| c.is_control() && !matches!(c, '\t' | '\n' | '\r'), "")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const DOCX_CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+</Types>"#;
+
+const DOCX_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>"#;
+
+const DOCX_DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+</Relationships>"#;
+
+const DOCX_STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:docDefaults>
+<w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault>
+<w:pPrDefault><w:pPr><w:spacing w:after="160" w:line="276" w:lineRule="auto"/></w:pPr></w:pPrDefault>
+</w:docDefaults>
+<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+<w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="80"/></w:pPr><w:rPr><w:b/><w:sz w:val="40"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Subtitle"><w:name w:val="Subtitle"/><w:basedOn w:val="Normal"/><w:rPr><w:color w:val="595959"/><w:sz w:val="20"/></w:rPr></w:style>
+</w:styles>"#;
+
+fn docx_paragraph(style: Option<&str>, runs: &str) -> String {
+    let properties = style
+        .map(|s| format!(r#"<w:pPr><w:pStyle w:val="{s}"/></w:pPr>"#))
+        .unwrap_or_default();
+    format!("<w:p>{properties}{runs}</w:p>")
+}
+
+fn docx_run(text: &str, bold: bool, color: Option<&str>) -> String {
+    let mut properties = String::new();
+    if bold {
+        properties.push_str("<w:b/>");
+    }
+    if let Some(color) = color {
+        properties.push_str(&format!(r#"<w:color w:val="{color}"/>"#));
+    }
+    let properties = if properties.is_empty() {
+        String::new()
+    } else {
+        format!("<w:rPr>{properties}</w:rPr>")
+    };
+    format!(
+        r#"<w:r>{properties}<w:t xml:space="preserve">{}</w:t></w:r>"#,
+        xml_escape(text)
+    )
+}
+
+fn docx_document(header: &DocumentHeader, paragraphs: &[Paragraph]) -> String {
+    let mut body = vec![
+        docx_paragraph(Some("Title"), &docx_run(header.title, false, None)),
+        docx_paragraph(
+            Some("Subtitle"),
+            &docx_run(&format!("{} — {}", header.date, header.source), false, None),
+        ),
+    ];
+    for (label, value) in header.fields {
+        let runs = format!("{}{}", docx_run(&format!("{label}: "), true, None), docx_run(value, false, None));
+        body.push(docx_paragraph(None, &runs));
+    }
+    for p in paragraphs {
+        let runs = format!(
+            "{}{}",
+            docx_run(&format!("[{}] ", format_timestamp(p.start_ms)), true, Some("7F7F7F")),
+            docx_run(&p.text, false, None)
+        );
+        body.push(docx_paragraph(None, &runs));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body.join("")
+    )
+}
+
+/// Builds a minimal WordprocessingML package by hand rather than through
+/// docx-rs: a transcript needs only these five parts, and the zip crate is
+/// already a dependency. Word, LibreOffice and Google Docs all open it.
+fn write_docx(path: &Path, header: &DocumentHeader, paragraphs: &[Paragraph]) -> Result<PathBuf, String> {
+    let file = File::create(path).map_err(|err| format!("Failed to create {}: {err}", path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let parts = [
+        ("[Content_Types].xml", DOCX_CONTENT_TYPES.to_string()),
+        ("_rels/.rels", DOCX_RELS.to_string()),
+        ("word/_rels/document.xml.rels", DOCX_DOCUMENT_RELS.to_string()),
+        ("word/styles.xml", DOCX_STYLES.to_string()),
+        ("word/document.xml", docx_document(header, paragraphs)),
+    ];
+
+    for (name, body) in parts {
+        zip.start_file(name, SimpleFileOptions::default())
+            .and_then(|_| zip.write_all(body.as_bytes()).map_err(Into::into))
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    }
+    zip.finish()
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path.to_path_buf())
+}
+
+const PAGE_WIDTH_MM: f32 = 215.9;
+const PAGE_HEIGHT_MM: f32 = 279.4;
+const MARGIN_MM: f32 = 22.0;
+const BODY_PT: f32 = 11.0;
+const PT_TO_MM: f32 = 0.3528;
+
+/// The built-in PDF fonts only cover WinAnsi, so fold common typographic
+/// characters to ASCII and drop anything else.
+fn pdf_safe(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\u{2018}' | '\u{2019}' => Some('\''),
+            '\u{201c}' | '\u{201d}' => Some('"'),
+            '\u{2013}' | '\u{2014}' => Some('-'),
+            '\u{2026}' => Some('.'),
+            c if c.is_ascii() || ('\u{a0}'..='\u{ff}').contains(&c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Greedy word wrap using Helvetica's average glyph width, which is close enough
+/// for body text without shipping font metrics.
+fn wrap(text: &str, size_pt: f32, width_mm: f32) -> Vec<String> {
+    let max_chars = ((width_mm / (size_pt * 0.5 * PT_TO_MM)) as usize).max(20);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct PdfCursor {
+    layer: PdfLayerReference,
+    y_mm: f32,
+}
+
+fn write_pdf(path: &Path, header: &DocumentHeader, paragraphs: &[Paragraph]) -> Result<PathBuf, String> {
+    let (doc, page, layer) =
+        PdfDocument::new(header.title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Transcript");
+    let font_error = |err| format!("Failed to load PDF font: {err}");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(font_error)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(font_error)?;
+
+    let text_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let mut cursor = PdfCursor {
+        layer: doc.get_page(page).get_layer(layer),
+        y_mm: PAGE_HEIGHT_MM - MARGIN_MM,
+    };
+
+    let write_lines = |cursor: &mut PdfCursor, text: &str, size: f32, font: &IndirectFontRef| {
+        let line_height = size * PT_TO_MM * 1.4;
+        for line in wrap(&pdf_safe(text), size, text_width) {
+            if cursor.y_mm - line_height < MARGIN_MM {
+                let (page, layer) = doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Transcript");
+                cursor.layer = doc.get_page(page).get_layer(layer);
+                cursor.y_mm = PAGE_HEIGHT_MM - MARGIN_MM;
+            }
+            cursor.y_mm -= line_height;
+            cursor.layer.use_text(line, size, Mm(MARGIN_MM), Mm(cursor.y_mm), font);
+        }
+    };
+
+    write_lines(&mut cursor, header.title, 20.0, &bold);
+    write_lines(
+        &mut cursor,
+        &format!("{} - {}", header.date, header.source),
+        9.0,
+        &regular,
+    );
+    for (label, value) in header.fields {
+        write_lines(&mut cursor, &format!("{label}: {value}"), 9.0, &regular);
+    }
+    cursor.y_mm -= 4.0;
+
+    for p in paragraphs {
+        write_lines(&mut cursor, &format!("[{}]", format_timestamp(p.start_ms)), 9.0, &bold);
+        write_lines(&mut cursor, &p.text, BODY_PT, &regular);
+        cursor.y_mm -= 3.0;
+    }
+
+    let file = File::create(path).map_err(|err| format!("Failed to create {}: {err}", path.display()))?;
+    doc.save(&mut BufWriter::new(file))
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::io::Read;
+
+    fn header<'a>(fields: &'a [(&'a str, String)]) -> DocumentHeader<'a> {
+        DocumentHeader {
+            title: "Faith & <Works>",
+            date: "2024-03-10",
+            source: "sermon.mp3",
+            fields,
+        }
+    }
+
+    fn paragraphs() -> Vec<Paragraph> {
+        vec![
+            Paragraph {
+                start_ms: 0,
+                end_ms: 4_000,
+                text: "Welcome, \"friends\".".to_string(),
+                speaker: None,
+            },
+            Paragraph {
+                start_ms: 65_000,
+                end_ms: 70_000,
+                text: "Turn to James 2\u{1b}.".to_string(),
+                speaker: None,
+            },
+        ]
+    }
+
+    /// Checks that every tag is closed in order, which is what Word needs
+    /// before it will open a part.
+    fn assert_balanced(xml: &str) {
+        let tag = Regex::new(r"<(/?)([A-Za-z_][\w:.-]*)[^>]*?(/?)>").unwrap();
+        let mut open: Vec<&str> = Vec::new();
+        for caps in tag.captures_iter(xml) {
+            let name = caps.get(2).unwrap().as_str();
+            if !caps[3].is_empty() {
+                continue;
+            }
+            if caps[1].is_empty() {
+                open.push(name);
+            } else {
+                assert_eq!(open.pop(), Some(name), "unexpected </{name}>");
+            }
+        }
+        assert!(open.is_empty(), "unclosed {open:?}");
+    }
+
+    #[test]
+    fn escapes_markup_and_drops_control_characters() {
+        assert_eq!(xml_escape(r#"a & b < c > "d""#), "a &amp; b &lt; c &gt; &quot;d&quot;");
+        assert_eq!(xml_escape("tab\there\u{0}\u{1b}[0m"), "tab\there[0m");
+        assert_eq!(xml_escape("Sermón — Juan"), "Sermón — Juan");
+    }
+
+    #[test]
+    fn document_xml_is_well_formed() {
+        let fields = [("Preacher", "Pastor Dave".to_string())];
+        let xml = docx_document(&header(&fields), &paragraphs());
+        assert_balanced(&xml);
+        assert!(xml.contains("Faith &amp; &lt;Works&gt;"));
+        assert!(xml.contains(r#"<w:t xml:space="preserve">Preacher: </w:t>"#));
+        assert!(xml.contains("[00:01:05] "));
+        assert!(xml.contains("Turn to James 2."));
+        assert_eq!(xml.matches("<w:p>").count(), 5);
+    }
+
+    #[test]
+    fn docx_package_round_trips() {
+        let dir = std::env::temp_dir().join(format!("church-transcriber-docx-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = write_docx(&dir.join(DOCX_FILE), &header(&[]), &paragraphs()).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "word/_rels/document.xml.rels",
+                "word/document.xml",
+                "word/styles.xml",
+            ]
+        );
+        for name in names {
+            let mut body = String::new();
+            archive.by_name(&name).unwrap().read_to_string(&mut body).unwrap();
+            assert!(body.starts_with("<?xml"), "{name}");
+            assert_balanced(&body);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn captions_name_labelled_speakers() {
+        let segment = |start_ms: u64, text: &str, speaker: Option<&str>| Segment {
+            start_ms,
+            end_ms: start_ms + 1_000,
+            text: text.to_string(),
+            speaker: speaker.map(str::to_string),
+        };
+        let segments = [
+            segment(0, "Good morning.", Some("Pastor Dave")),
+            segment(1_000, "Please stand.", Some("Pastor Dave")),
+            segment(2_000, "Amen.", None),
+        ];
+
+        let srt = render_srt(&segments);
+        assert!(srt.contains("Pastor Dave: Good morning."));
+        assert!(srt.contains("\r\nPlease stand.\r\n"));
+        assert!(srt.contains("\r\nAmen.\r\n"));
+
+        let vtt = render_vtt(&segments);
+        assert!(vtt.contains("<v Pastor Dave>Good morning."));
+        assert!(vtt.contains("<v Pastor Dave>Please stand."));
+        assert!(vtt.contains("\nAmen.\n"));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{manifest, scanner, settings};
+use chrono::{Local, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+use tauri::AppHandle;
+
+/// Optional file next to the recordings, keyed by file name; a `*` entry
+/// applies to every file in the folder.
+pub const FOLDER_FILE: &str = "service-info.json";
+const ALL_FILES_KEY: &str = "*";
+const PATTERN_GROUPS: &[&str] = &["year", "month", "day", "title", "speaker", "series"];
+
+/// Details about one recording that override what the file name suggests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FileMetadata {
+    pub title: Option<String>,
+    #[serde(alias = "preacher")]
+    pub speaker: Option<String>,
+    #[serde(alias = "series")]
+    pub series_name: Option<String>,
+    /// `YYYY-MM-DD`; replaces the date taken from the file name or folder.
+    pub service_date: Option<String>,
+    /// The sermon text, e.g. `John 3:1-21`.
+    pub scripture: Option<String>,
+}
+
+fn set(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+impl FileMetadata {
+    /// Fields set here replace those in `base`.
+    fn over(self, base: FileMetadata) -> FileMetadata {
+        FileMetadata {
+            title: set(self.title).or(base.title),
+            speaker: set(self.speaker).or(base.speaker),
+            series_name: set(self.series_name).or(base.series_name),
+            service_date: set(self.service_date).or(base.service_date),
+            scripture: set(self.scripture).or(base.scripture),
+        }
+    }
+}
+
+/// Everything a recording's metadata is resolved from besides its folder.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataSources {
+    /// Set with `set_file_metadata`, keyed by source path.
+    pub saved: HashMap<String, FileMetadata>,
+    /// The file name patterns from settings.
+    pub patterns: Vec<Regex>,
+}
+
+pub fn sources(app: &AppHandle) -> MetadataSources {
+    MetadataSources {
+        saved: manifest::file_metadata(app),
+        patterns: compile_patterns(&settings::load(app).filename_patterns).unwrap_or_default(),
+    }
+}
+
+/// Compiles the file name patterns, rejecting any without a named group
+/// the parser reads.
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let regex = Regex::new(p).map_err(|err| format!("Invalid file name pattern {p}: {err}"))?;
+            if !regex.capture_names().flatten().any(|name| PATTERN_GROUPS.contains(&name)) {
+                return Err(format!(
+                    "File name pattern {p} needs a named group such as (?P<title>...); use {}",
+                    PATTERN_GROUPS.join(", ")
+                ));
+            }
+            Ok(regex)
+        })
+        .collect()
+}
+
+/// Underscores to spaces, with runs of whitespace collapsed.
+fn tidy(text: &str) -> String {
+    text.replace('_', " ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Metadata from the first pattern matching the file name. Two-digit years
+/// are taken as 20xx.
+fn from_file_name(patterns: &[Regex], source: &Path) -> FileMetadata {
+    let stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let Some(caps) = patterns.iter().find_map(|p| p.captures(&stem)) else {
+        return FileMetadata::default();
+    };
+    let group = |name: &str| set(caps.name(name).map(|m| tidy(m.as_str())));
+    let number = |name: &str| group(name).and_then(|v| v.parse::<u32>().ok());
+    let service_date = match (number("year"), number("month"), number("day")) {
+        (Some(year), Some(month), Some(day)) => {
+            let year = if year < 100 { year + 2000 } else { year };
+            NaiveDate::from_ymd_opt(year as i32, month, day).map(|d| d.format("%Y-%m-%d").to_string())
+        }
+        _ => None,
+    };
+    FileMetadata {
+        title: group("title"),
+        speaker: group("speaker"),
+        series_name: group("series"),
+        service_date,
+        scripture: None,
+    }
+}
+
+/// The source folder's `service-info.json`: its `*` entry under the entry
+/// for this file.
+fn from_folder(source: &Path) -> FileMetadata {
+    let Some(folder_file) = source.parent().map(|dir| dir.join(FOLDER_FILE)) else {
+        return FileMetadata::default();
+    };
+    let Some(mut entries) = fs::read_to_string(folder_file)
+        .ok()
+        .and_then(|body| serde_json::from_str::<HashMap<String, FileMetadata>>(body.trim_start_matches('\u{feff}')).ok())
+    else {
+        return FileMetadata::default();
+    };
+    let name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let all = entries.remove(ALL_FILES_KEY).unwrap_or_default();
+    let file = entries
+        .into_iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+        .map(|(_, metadata)| metadata)
+        .unwrap_or_default();
+    file.over(all)
+}
+
+/// Metadata for `source`: what was saved for it, then the folder's
+/// `service-info.json`, then what the file name patterns extract.
+pub fn resolve(sources: &MetadataSources, source: &str) -> FileMetadata {
+    let source = source.trim();
+    let saved = &sources.saved;
+    let entry = saved
+        .get(source)
+        .or_else(|| saved.iter().find(|(key, _)| scanner::same_path(key, source)).map(|(_, m)| m))
+        .cloned()
+        .unwrap_or_default();
+    let path = Path::new(source);
+    entry.over(from_folder(path).over(from_file_name(&sources.patterns, path)))
+}
+
+pub fn lookup(app: &AppHandle, source: &str) -> FileMetadata {
+    resolve(&sources(app), source)
+}
+
+/// The title to publish `source` under.
+pub fn title(metadata: &FileMetadata, source: &str) -> String {
+    metadata.title.clone().unwrap_or_else(|| scanner::title_from_source(source))
+}
+
+/// Label and value lines for a transcript header, in print order. `date` is
+/// used when no service date was set.
+pub fn header_fields(metadata: &FileMetadata, date: &str, model: Option<&str>) -> Vec<(&'static str, String)> {
+    let model = model.map(|m| {
+        Path::new(m)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| m.to_string())
+    });
+    [
+        ("Service date", metadata.service_date.clone().or_else(|| set(Some(date.to_string())))),
+        ("Preacher", metadata.speaker.clone()),
+        ("Series", metadata.series_name.clone()),
+        ("Scripture", metadata.scripture.clone()),
+        ("Model", model),
+        ("Generated", Some(Local::now().format("%Y-%m-%d").to_string())),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.map(|v| (label, v)))
+    .collect()
+}
+
+#[tauri::command]
+pub fn get_file_metadata(app: AppHandle, path: String) -> FileMetadata {
+    lookup(&app, &path)
+}
+
+/// Saves details for one recording to the manifest; empty fields fall back
+/// to the folder's `service-info.json` and the file name patterns. Output templates, exports and the
+/// podcast feed pick them up on the next run. Returns the merged result.
+#[tauri::command]
+pub fn set_file_metadata(app: AppHandle, path: String, metadata: FileMetadata) -> Result<FileMetadata, String> {
+    let path = path.trim().to_string();
+    if path.is_empty() {
+        return Err("Source file is required.".to_string());
+    }
+    let metadata = metadata.over(FileMetadata::default());
+    if let Some(date) = &metadata.service_date {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Service date {date} must be YYYY-MM-DD."))?;
+    }
+    manifest::set_file_metadata(&app, &path, metadata)?;
+    Ok(lookup(&app, &path))
+}
@@ -0,0 +1,107 @@
+use crate::longpath;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const CONFIG_FILE: &str = ".transcriber.toml";
+
+/// Settings from a `.transcriber.toml`; every key is optional.
+///
+/// ```toml
+/// language = "es"
+/// model = "ggml-large-v3.bin"
+/// translate = true
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FolderConfig {
+    /// Whisper language code for recordings under the folder.
+    pub language: Option<String>,
+    /// A model file, relative to the config file's folder or absolute, or a
+    /// faster-whisper model name.
+    pub model: Option<String>,
+    /// Translate the speech into English instead of transcribing it.
+    pub translate: Option<bool>,
+}
+
+impl FolderConfig {
+    /// `self` with `nearer`'s keys taking precedence.
+    fn merged(mut self, nearer: &FolderConfig) -> Self {
+        if nearer.language.is_some() {
+            self.language = nearer.language.clone();
+        }
+        if nearer.model.is_some() {
+            self.model = nearer.model.clone();
+        }
+        if nearer.translate.is_some() {
+            self.translate = nearer.translate;
+        }
+        self
+    }
+}
+
+fn read(dir: &Path) -> Result<Option<FolderConfig>, String> {
+    let path = dir.join(CONFIG_FILE);
+    let Ok(body) = fs::read_to_string(longpath::extended(&path)) else {
+        return Ok(None);
+    };
+    let mut config: FolderConfig =
+        toml::from_str(&body).map_err(|err| format!("Invalid {}: {err}", path.display()))?;
+    let set = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    config.language = set(config.language);
+    config.model = set(config.model).map(|model| {
+        let relative = dir.join(&model);
+        if Path::new(&model).is_relative() && relative.exists() {
+            relative.display().to_string()
+        } else {
+            model
+        }
+    });
+    Ok(Some(config))
+}
+
+/// Reads each folder's config once while a plan is built.
+pub struct FolderConfigs {
+    root: PathBuf,
+    cache: HashMap<PathBuf, Option<FolderConfig>>,
+}
+
+impl FolderConfigs {
+    pub fn new(root: &Path) -> Self {
+        // A single-file input still takes its own folder's config.
+        let root = if longpath::extended(root).is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+        Self {
+            root: root.to_path_buf(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The settings for `file`: every config from the input folder down to
+    /// the file's own folder, the nearest winning key by key.
+    pub fn for_file(&mut self, file: &Path) -> Result<FolderConfig, String> {
+        let dirs: Vec<PathBuf> = file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .map(Path::to_path_buf)
+            .collect();
+        let mut config = FolderConfig::default();
+        for dir in dirs.into_iter().rev() {
+            if !self.cache.contains_key(&dir) {
+                let read = read(&dir)?;
+                self.cache.insert(dir.clone(), read);
+            }
+            if let Some(Some(found)) = self.cache.get(&dir) {
+                config = config.merged(found);
+            }
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Runs one batch script per GPU on machines with more than one. Each worker
+/// gets the folder's whole plan and claims files from it as it goes, so the
+/// queue balances itself: a worker that draws short recordings takes more.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GpuWorkerOptions {
+    pub enabled: bool,
+    /// One worker per device, numbered as `nvidia-smi` lists them.
+    pub devices: Vec<u32>,
+}
+
+impl Default for GpuWorkerOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            devices: vec![0, 1],
+        }
+    }
+}
+
+impl GpuWorkerOptions {
+    /// Devices to start a worker on; empty when workers are off.
+    pub fn worker_devices(&self) -> Vec<u32> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut devices: Vec<u32> = Vec::new();
+        for device in &self.devices {
+            if !devices.contains(device) {
+                devices.push(*device);
+            }
+        }
+        devices
+    }
+}
+
+/// A copy of the batch script command for the worker pinned to `device`.
+/// Workers sharing `claim_dir` never start the same file.
+pub fn worker_command(base: &Command, device: u32, claim_dir: &Path) -> Command {
+    let mut cmd = Command::new(base.get_program());
+    cmd.args(base.get_args());
+    for (key, value) in base.get_envs() {
+        match value {
+            Some(value) => cmd.env(key, value),
+            None => cmd.env_remove(key),
+        };
+    }
+    if let Some(dir) = base.get_current_dir() {
+        cmd.current_dir(dir);
+    }
+    cmd.arg("-GpuDevice")
+        .arg(device.to_string())
+        .arg("-ClaimDir")
+        .arg(claim_dir)
+        .arg("-WorkerName")
+        .arg(format!("gpu{device}"))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use sysinfo::System;
+use tauri::AppHandle;
+
+const GIB: u64 = 1 << 30;
+const MIB: u64 = 1 << 20;
+/// whisper.cpp needs the weights plus its compute buffers; 20% + 512 MiB is a
+/// conservative fit for every ggml size.
+const HEADROOM_MIB: u64 = 512;
+/// Rough CPU speed of whisper.cpp: realtime factor is about
+/// `cores * CPU_MIB_PER_CORE / model MiB` (large-v3 runs near 1x on 8 cores).
+const CPU_MIB_PER_CORE: f64 = 400.0;
+/// How much faster a CUDA or Metal build runs than the CPU estimate.
+const GPU_SPEEDUP: f64 = 8.0;
+
+/// Standard ggml model names with their approximate file size, largest first.
+const MODEL_LADDER: &[(&str, u64)] = &[
+    ("ggml-large-v3.bin", 3100 * MIB),
+    ("ggml-medium.bin", 1500 * MIB),
+    ("ggml-small.bin", 466 * MIB),
+    ("ggml-base.bin", 142 * MIB),
+    ("ggml-tiny.bin", 75 * MIB),
+];
+
+pub struct MemoryFit {
+    /// False only when the model can't fit in physical memory at all.
+    pub ok: bool,
+    /// Set when the model fits in RAM but not in what is currently free.
+    pub warning: bool,
+    pub detail: String,
+    pub fix: String,
+}
+
+/// Memory whisper.cpp will need for a model file of `model_bytes`.
+pub fn required_bytes(model_bytes: u64) -> u64 {
+    model_bytes + model_bytes / 5 + HEADROOM_MIB * MIB
+}
+
+fn format_gib(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / GIB as f64)
+}
+
+/// Model files (`.bin`/`.gguf`) in `dir` with their sizes.
+pub fn installed_models(dir: &Path) -> Vec<(PathBuf, u64)> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| {
+                    let name = e.file_name().to_string_lossy().to_lowercase();
+                    name.ends_with(".bin") || name.ends_with(".gguf")
+                })
+                .filter_map(|e| e.metadata().ok().map(|m| (e.path(), m.len())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The biggest model that fits in `budget` bytes: another model file next to
+/// the current one if there is one, otherwise a standard ggml name.
+fn smaller_model(model: &Path, budget: u64) -> Option<String> {
+    let model_bytes = fs::metadata(model).map(|m| m.len()).unwrap_or(u64::MAX);
+    let local = model.parent().and_then(|dir| {
+        installed_models(dir)
+            .into_iter()
+            .filter(|(_, len)| *len < model_bytes && required_bytes(*len) <= budget)
+            .max_by_key(|(_, len)| *len)
+            .map(|(path, _)| path.display().to_string())
+    });
+    local.or_else(|| {
+        MODEL_LADDER
+            .iter()
+            .find(|(_, len)| *len < model_bytes && required_bytes(*len) <= budget)
+            .map(|(name, _)| name.to_string())
+    })
+}
+
+/// Compares the model's memory needs against physical and currently
+/// available RAM.
+pub fn model_memory_fit(model: &Path) -> Option<MemoryFit> {
+    let model_bytes = fs::metadata(model).ok()?.len();
+    let mut system = System::new();
+    system.refresh_memory();
+    let total = system.total_memory();
+    let available = system.available_memory();
+    if total == 0 {
+        return None;
+    }
+
+    let needed = required_bytes(model_bytes);
+    let summary = format!(
+        "Model needs about {}; {} of {} RAM is available",
+        format_gib(needed),
+        format_gib(available),
+        format_gib(total)
+    );
+    let fix = |budget: u64| match smaller_model(model, budget) {
+        Some(smaller) => format!("Use a smaller model such as {smaller}, or close other programs."),
+        None => "Close other programs or use a machine with more memory.".to_string(),
+    };
+
+    Some(if needed > total {
+        MemoryFit {
+            ok: false,
+            warning: false,
+            detail: format!("{summary}; this model will not fit in memory."),
+            fix: fix(total),
+        }
+    } else if needed > available {
+        MemoryFit {
+            ok: true,
+            warning: true,
+            detail: format!("{summary}; expect heavy swapping."),
+            fix: fix(available),
+        }
+    } else {
+        MemoryFit {
+            ok: true,
+            warning: false,
+            detail: summary,
+            fix: String::new(),
+        }
+    })
+}
+
+/// The first NVIDIA GPU's name and memory, when `nvidia-smi` exists. Apple
+/// Silicon is reported as a Metal GPU sharing system memory.
+fn detect_gpu(total_memory: u64) -> Option<(String, u64)> {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        return Some(("Apple Silicon (Metal)".to_string(), total_memory));
+    }
+    let output = Command::new("nvidia-smi")
+        .args(["--query-gpu=name,memory.total", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut fields = text.lines().next()?.split(',').map(str::trim);
+    let name = fields.next()?.to_string();
+    let memory_mib: u64 = fields.next()?.parse().ok()?;
+    Some((name, memory_mib * MIB))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SuggestModelRequest {
+    /// Folder of installed models; standard ggml names are considered too.
+    pub model_folder: String,
+    /// Audio hours wanted per wall-clock hour; 1.0 keeps up with recording.
+    pub target_realtime_factor: f64,
+}
+
+impl Default for SuggestModelRequest {
+    fn default() -> Self {
+        Self {
+            model_folder: String::new(),
+            target_realtime_factor: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelEstimate {
+    pub model: String,
+    pub installed: bool,
+    pub size_bytes: u64,
+    pub fits_in_memory: bool,
+    pub realtime_factor: f64,
+    /// True when the figure comes from a benchmark rather than an estimate.
+    pub measured: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSuggestion {
+    pub model: String,
+    pub installed: bool,
+    /// Use a CUDA or Metal build of whisper-cli.
+    pub use_gpu: bool,
+    pub gpu_name: Option<String>,
+    pub cpu_count: usize,
+    pub total_memory_bytes: u64,
+    pub estimated_realtime_factor: f64,
+    pub reason: String,
+    pub candidates: Vec<ModelEstimate>,
+}
+
+/// Recommends the most accurate model that still meets the target speed on
+/// this machine, using benchmark results where they exist and a CPU/GPU
+/// estimate otherwise.
+#[tauri::command]
+pub fn suggest_model(app: AppHandle, request: SuggestModelRequest) -> Result<ModelSuggestion, String> {
+    let target = if request.target_realtime_factor > 0.0 {
+        request.target_realtime_factor
+    } else {
+        1.0
+    };
+    let mut system = System::new();
+    system.refresh_memory();
+    let total_memory = system.total_memory();
+    let cpu_count = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let gpu = detect_gpu(total_memory);
+    let measured = crate::benchmark::recorded(&app);
+
+    let mut models: Vec<(String, bool, u64)> = installed_models(Path::new(request.model_folder.trim()))
+        .into_iter()
+        .map(|(path, len)| (path.display().to_string(), true, len))
+        .collect();
+    for (name, len) in MODEL_LADDER {
+        let installed = models.iter().any(|(path, _, _)| {
+            Path::new(path).file_name().is_some_and(|f| f.eq_ignore_ascii_case(name))
+        });
+        if !installed {
+            models.push((name.to_string(), false, *len));
+        }
+    }
+    if models.is_empty() {
+        return Err("No models to compare.".to_string());
+    }
+
+    let gpu_memory = gpu.as_ref().map(|(_, bytes)| *bytes).unwrap_or(0);
+    let mut candidates: Vec<ModelEstimate> = models
+        .into_iter()
+        .map(|(model, installed, size)| {
+            let benchmark = measured.iter().find(|b| b.model_file == model);
+            let on_gpu = required_bytes(size) <= gpu_memory;
+            let estimate = cpu_count as f64 * CPU_MIB_PER_CORE / (size / MIB).max(1) as f64;
+            ModelEstimate {
+                fits_in_memory: total_memory == 0 || required_bytes(size) <= total_memory,
+                realtime_factor: benchmark
+                    .map(|b| b.realtime_factor)
+                    .unwrap_or(if on_gpu { estimate * GPU_SPEEDUP } else { estimate }),
+                measured: benchmark.is_some(),
+                model,
+                installed,
+                size_bytes: size,
+            }
+        })
+        .collect();
+    // Largest first; among equal sizes prefer what is already installed.
+    candidates.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(b.installed.cmp(&a.installed)));
+
+    let fitting: Vec<&ModelEstimate> = candidates.iter().filter(|c| c.fits_in_memory).collect();
+    let (choice, reason) = match fitting.iter().find(|c| c.realtime_factor >= target) {
+        Some(choice) => (
+            *choice,
+            format!(
+                "The most accurate model that runs at {:.1}x realtime or better here (about {:.1}x).",
+                target, choice.realtime_factor
+            ),
+        ),
+        None => {
+            let fastest = fitting
+                .iter()
+                .copied()
+                .max_by(|a, b| a.realtime_factor.total_cmp(&b.realtime_factor))
+                .or_else(|| candidates.last())
+                .ok_or_else(|| "No models to compare.".to_string())?;
+            (
+                fastest,
+                format!(
+                    "No model reaches {:.1}x realtime on this machine; this is the fastest (about {:.1}x).",
+                    target, fastest.realtime_factor
+                ),
+            )
+        }
+    };
+    let use_gpu = gpu.is_some() && required_bytes(choice.size_bytes) <= gpu_memory;
+
+    Ok(ModelSuggestion {
+        model: choice.model.clone(),
+        installed: choice.installed,
+        use_gpu,
+        gpu_name: gpu.map(|(name, _)| name),
+        cpu_count,
+        total_memory_bytes: total_memory,
+        estimated_realtime_factor: choice.realtime_factor,
+        reason,
+        candidates: candidates.clone(),
+    })
+}
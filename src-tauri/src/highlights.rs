@@ -0,0 +1,130 @@
+use crate::{history::RunReport, settings, transcript};
+use serde::Serialize;
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Lead-in and run-out around the matching segments, so a clip starts and
+/// ends on context rather than mid-thought.
+const CLIP_PADDING_MS: u64 = 15_000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    pub source: String,
+    pub output_dir: String,
+    /// The configured keywords found, as written in settings.
+    pub keywords: Vec<String>,
+    /// The matching segments' span on the transcript's timeline.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// `HH:MM:SS` of `start_ms`.
+    pub timestamp: String,
+    /// A padded window to pass to `render_clip`.
+    pub clip_start_ms: u64,
+    pub clip_end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunHighlights {
+    pub run_id: String,
+    pub generated_at_epoch: u64,
+    pub keywords: Vec<String>,
+    pub highlights: Vec<Highlight>,
+    pub json_path: String,
+}
+
+/// Lowercase words separated by single spaces, padded so phrases match on
+/// word boundaries.
+pub fn normalize(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+fn file_highlights(source: &str, output_dir: &str, keywords: &[(String, String)]) -> Vec<Highlight> {
+    let segments = transcript::load_segments(&PathBuf::from(output_dir)).unwrap_or_default();
+    let mut found: Vec<Highlight> = Vec::new();
+    for segment in &segments {
+        let text = normalize(&segment.text);
+        let matched: Vec<&String> = keywords
+            .iter()
+            .filter(|(_, needle)| text.contains(needle.as_str()))
+            .map(|(keyword, _)| keyword)
+            .collect();
+        if matched.is_empty() {
+            continue;
+        }
+        // A match inside the previous candidate's window extends it.
+        if let Some(last) = found.last_mut().filter(|h| segment.start_ms <= h.clip_end_ms) {
+            last.end_ms = last.end_ms.max(segment.end_ms);
+            last.clip_end_ms = last.end_ms + CLIP_PADDING_MS;
+            last.text = format!("{} {}", last.text, segment.text.trim());
+            for keyword in matched {
+                if !last.keywords.contains(keyword) {
+                    last.keywords.push(keyword.clone());
+                }
+            }
+            continue;
+        }
+        found.push(Highlight {
+            source: source.to_string(),
+            output_dir: output_dir.to_string(),
+            keywords: matched.into_iter().cloned().collect(),
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            timestamp: transcript::format_timestamp(segment.start_ms),
+            clip_start_ms: segment.start_ms.saturating_sub(CLIP_PADDING_MS),
+            clip_end_ms: segment.end_ms + CLIP_PADDING_MS,
+            text: segment.text.trim().to_string(),
+        });
+    }
+    // Don't run past the recording; `render_clip` wants text in range.
+    if let Some(last_ms) = segments.last().map(|s| s.end_ms) {
+        for highlight in &mut found {
+            highlight.clip_end_ms = highlight.clip_end_ms.min(last_ms);
+        }
+    }
+    found
+}
+
+/// Looks through the run's new transcripts for the `highlightKeywords` in
+/// settings, writes `highlights-<run id>.json` into the output folder and
+/// emits `transcribe://highlights` with the candidates. Returns `None` when
+/// no keywords are configured.
+pub fn write_run_highlights(app: &AppHandle, report: &RunReport) -> Result<Option<RunHighlights>, String> {
+    let configured = settings::load(app).highlight_keywords;
+    let keywords: Vec<(String, String)> = configured
+        .iter()
+        .map(|k| (k.trim().to_string(), normalize(k)))
+        .filter(|(_, needle)| !needle.trim().is_empty())
+        .collect();
+    if keywords.is_empty() {
+        return Ok(None);
+    }
+
+    let highlights = report
+        .results
+        .iter()
+        .filter(|r| r.status == "ok")
+        .filter_map(|r| r.output_dir.as_deref().map(|dir| file_highlights(&r.source, dir, &keywords)))
+        .flatten()
+        .collect();
+    let json_path = PathBuf::from(report.output_folder.trim()).join(format!("highlights-{}.json", report.run_id));
+    let run = RunHighlights {
+        run_id: report.run_id.clone(),
+        generated_at_epoch: crate::now_epoch(),
+        keywords: keywords.into_iter().map(|(keyword, _)| keyword).collect(),
+        highlights,
+        json_path: json_path.display().to_string(),
+    };
+
+    let body = serde_json::to_string_pretty(&run).map_err(|err| format!("Failed to serialize highlights: {err}"))?;
+    fs::write(&json_path, body).map_err(|err| format!("Failed to write {}: {err}", json_path.display()))?;
+    let _ = app.emit("transcribe://highlights", run.clone());
+    Ok(Some(run))
+}
@@ -0,0 +1,174 @@
+use crate::{
+    archive::{self, ArchiveRoot},
+    scanner::{PlannedFile, SkippedFile},
+    StartRequest,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileResult {
+    pub source: String,
+    pub status: String,
+    pub output_dir: Option<String>,
+    pub finished_at_epoch: u64,
+}
+
+impl FileResult {
+    /// `error-ffmpeg`, `error-whisper`, ...
+    pub fn failed(&self) -> bool {
+        self.status.starts_with("error")
+    }
+}
+
+/// A proofreading save made after the run, see `save_transcript_edit`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptEdit {
+    pub output_dir: String,
+    pub edited_at_epoch: u64,
+    pub segments_changed: usize,
+    pub files: Vec<String>,
+    pub backup_dir: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub run_id: String,
+    pub started_at_epoch: u64,
+    pub finished_at_epoch: Option<u64>,
+    pub input_folders: Vec<String>,
+    pub output_folder: String,
+    /// Output folders set per input folder that differ from `output_folder`.
+    #[serde(default)]
+    pub extra_output_folders: Vec<String>,
+    pub planned: Vec<PlannedFile>,
+    pub skipped: Vec<SkippedFile>,
+    #[serde(default)]
+    pub results: Vec<FileResult>,
+    pub success: Option<bool>,
+    pub code: Option<i32>,
+    pub message: Option<String>,
+    /// Settings the run was started with; lets `retry_failed` re-queue files.
+    #[serde(default)]
+    pub request: Option<StartRequest>,
+    #[serde(default)]
+    pub edits: Vec<TranscriptEdit>,
+}
+
+impl RunReport {
+    pub fn new(run_id: String, input_folders: Vec<String>, output_folder: String) -> Self {
+        Self {
+            run_id,
+            started_at_epoch: crate::now_epoch(),
+            finished_at_epoch: None,
+            input_folders,
+            output_folder,
+            extra_output_folders: Vec::new(),
+            planned: Vec::new(),
+            skipped: Vec::new(),
+            results: Vec::new(),
+            success: None,
+            code: None,
+            message: None,
+            request: None,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Every output folder the run wrote transcripts into.
+    pub fn archive_roots(&self) -> Vec<ArchiveRoot> {
+        archive::roots(&self.output_folder, &self.extra_output_folders)
+    }
+
+    /// Sources whose last result in this run was a failure.
+    pub fn failed_sources(&self) -> Vec<String> {
+        let mut failed: Vec<String> = Vec::new();
+        for result in &self.results {
+            failed.retain(|s| s != &result.source);
+            if result.failed() {
+                failed.push(result.source.clone());
+            }
+        }
+        failed
+    }
+}
+
+fn runs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?
+        .join("runs");
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create run history folder {}: {err}", dir.display()))?;
+    Ok(dir)
+}
+
+fn is_valid_run_id(run_id: &str) -> bool {
+    !run_id.is_empty()
+        && run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn save_report(app: &AppHandle, report: &RunReport) -> Result<PathBuf, String> {
+    let path = runs_dir(app)?.join(format!("{}.json", report.run_id));
+    let body = serde_json::to_string_pretty(report)
+        .map_err(|err| format!("Failed to serialize run report: {err}"))?;
+    fs::write(&path, body)
+        .map_err(|err| format!("Failed to write run report {}: {err}", path.display()))?;
+    Ok(path)
+}
+
+pub fn load_report(app: &AppHandle, run_id: &str) -> Result<RunReport, String> {
+    let run_id = run_id.trim();
+    if !is_valid_run_id(run_id) {
+        return Err(format!("Invalid run id: {run_id}"));
+    }
+
+    let path = runs_dir(app)?.join(format!("{run_id}.json"));
+    let body = fs::read_to_string(&path)
+        .map_err(|_| format!("No run report found for run id {run_id}"))?;
+    serde_json::from_str(&body)
+        .map_err(|err| format!("Run report {} is corrupt: {err}", path.display()))
+}
+
+/// Every stored run report, newest first.
+pub fn list_reports(app: &AppHandle) -> Vec<RunReport> {
+    let Ok(dir) = runs_dir(app) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<RunReport> = entries
+        .flatten()
+        .filter(|e| e.path().extension().map(|x| x == "json").unwrap_or(false))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|body| serde_json::from_str(&body).ok())
+        .collect();
+
+    reports.sort_by_key(|r| std::cmp::Reverse(r.started_at_epoch));
+    reports
+}
+
+/// Attaches an edit to the newest run that produced `edit.output_dir`. Returns
+/// that run's id, or `None` for transcripts no stored run knows about.
+pub fn record_edit(app: &AppHandle, edit: TranscriptEdit) -> Result<Option<String>, String> {
+    let produced = |r: &RunReport| {
+        r.results
+            .iter()
+            .any(|f| f.output_dir.as_deref().is_some_and(|d| crate::scanner::same_path(d, &edit.output_dir)))
+    };
+    let Some(mut report) = list_reports(app).into_iter().find(produced) else {
+        return Ok(None);
+    };
+    report.edits.push(edit);
+    save_report(app, &report)?;
+    Ok(Some(report.run_id))
+}
@@ -1,38 +1,384 @@
+mod alerts;
+mod archive;
+mod benchmark;
+mod bundle;
+mod channels;
+mod chapters;
+mod checkpoint;
+mod chunking;
+mod cli;
+mod clip;
+mod cloudengine;
+mod cloudsync;
+mod dedupe;
+mod digest;
+mod dropped;
+mod engine;
+mod export;
+mod filemeta;
+mod folderconfig;
+mod gpuworkers;
+mod hardware;
+mod highlights;
+mod history;
+mod logfile;
+mod logfilter;
+mod longpath;
+mod mailer;
+mod manifest;
+mod modelinfo;
+mod music;
+mod notify;
+mod podcast;
+mod postprocess;
+mod power;
+mod preprocess;
+mod proctree;
+mod profiles;
+mod publish;
+mod qcreport;
+mod quality;
+mod recent;
+mod recovery;
+mod redaction;
+mod remote;
+mod rename;
+mod reveal;
+mod runsummary;
+mod s3;
+mod scanner;
+mod schedule;
+mod scripture;
+mod search;
+mod sections;
+mod settings;
+mod sourcearchive;
+mod speakers;
+mod staging;
+mod stats;
+mod stream;
+mod subtitles;
+mod summarize;
+mod support;
+mod telemetry;
+mod transcript;
+mod transcriptdiff;
+mod translation;
+mod tray;
+mod updater;
+mod versions;
+mod viewer;
+mod whisperbin;
+
+use alerts::KeywordWatch;
+use channels::ChannelOptions;
+use chapters::ChapterOptions;
+use chunking::ChunkOptions;
+use engine::Engine;
+use export::{CaptionLimits, DocumentHeader, ExportFormat, PlayerAudio};
+use gpuworkers::GpuWorkerOptions;
+use history::{FileResult, RunReport};
+use music::MusicOptions;
+use postprocess::{ReadableOptions, WordMask};
+use preprocess::{DenoiseOptions, KeptAudioOptions, LoudnessOptions, SilenceTrimOptions};
+use settings::LogLevel;
+use sourcearchive::SourceArchiveOptions;
+use speakers::SpeakerOptions;
+use scanner::{FolderPlan, PlannedFile, QueueOrder, ScanOptions, SkipReason, SkippedFile};
+use sections::SectionOptions;
+use transcript::EnrichedTranscript;
+use translation::{TranslationMethod, TranslationOptions};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, VecDeque},
     fs,
     io::{BufRead, BufReader, Read},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Child, Command, Stdio},
-    sync::Mutex,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
     thread,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State, WindowEvent};
 
 #[derive(Default)]
 struct RunnerState {
     running: Mutex<bool>,
     stop_requested: Mutex<bool>,
     child: Mutex<Option<Child>>,
+    /// GPU workers beyond the first, which lives in `child`.
+    workers: Mutex<Vec<Child>>,
     pause_flag: Mutex<Option<PathBuf>>,
+    paused_at: Mutex<Option<u64>>,
+    report: Mutex<Option<RunReport>>,
+    recent_logs: Mutex<VecDeque<LogEvent>>,
+    last_request: Mutex<Option<StartRequest>>,
+    /// Why the run paused itself (e.g. battery); `None` for a user pause.
+    pause_reason: Mutex<Option<String>>,
+    log_context: Mutex<LogContext>,
+    stop_mode: Mutex<StopMode>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Run and file stamped onto log events while a run is active.
+#[derive(Default)]
+struct LogContext {
+    run_id: Option<String>,
+    source_file: Option<String>,
+    /// The engines print every segment, so log lines are redacted before
+    /// they are kept, written or served.
+    redactor: Option<redaction::Redactor>,
+}
+
+const RECENT_LOG_LINES: usize = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StartRequest {
     input_folders: Vec<String>,
     output_folder: String,
     whisper_exe: String,
+    /// Whisper builds to try in order (CUDA, BLAS, generic), ahead of
+    /// `whisper_exe`; the first that starts on this machine is used.
+    #[serde(default)]
+    whisper_exe_candidates: Vec<String>,
     model_file: String,
     before_date: Option<String>,
+    /// With `before_date`, limits the run to an inclusive date range.
+    #[serde(default)]
+    after_date: Option<String>,
     threads: u32,
     limit: Option<u32>,
     fast_scan: bool,
     force: bool,
     no_recursive: bool,
     keep_audio: bool,
+    /// Transcribe recordings longer than this many minutes in pieces of this
+    /// length, so a rerun after a crash resumes at the first unfinished piece.
+    /// 0 transcribes every file in one go.
+    #[serde(default)]
+    checkpoint_minutes: u32,
+    /// Model to transcribe a file with when the primary model fails or
+    /// still produces garbage, e.g. a smaller or multilingual model.
+    #[serde(default)]
+    fallback_model: Option<String>,
+    /// Re-run a file once with adjusted decoding when its transcript is
+    /// empty, stuck repeating a line or far too short for the audio.
+    #[serde(default = "default_true")]
+    garbage_retry: bool,
+    /// Split long recordings into overlapping pieces transcribed side by side.
+    #[serde(default)]
+    chunking: ChunkOptions,
+    /// Run one worker per GPU, each pinned to its device, sharing the queue.
+    #[serde(default)]
+    gpu_workers: GpuWorkerOptions,
+    /// Format the kept audio is converted to, e.g. Opus for a podcast.
+    #[serde(default)]
+    kept_audio: KeptAudioOptions,
     script_path: Option<String>,
+    exclude_patterns: Option<Vec<String>>,
+    #[serde(default)]
+    readable: ReadableOptions,
+    #[serde(default)]
+    mask_profanity: bool,
+    /// List scripture references in `transcript.json` and tag them inline in
+    /// `readable.md`.
+    #[serde(default)]
+    detect_scripture: bool,
+    #[serde(default)]
+    chapters: ChapterOptions,
+    /// Label announcements, worship, prayer and the sermon; the sections go
+    /// into `transcript.json` and replace the chapter markers.
+    #[serde(default)]
+    sections: SectionOptions,
+    #[serde(default)]
+    summarize: bool,
+    #[serde(default)]
+    output_formats: Vec<ExportFormat>,
+    #[serde(default)]
+    loudness: LoudnessOptions,
+    #[serde(default)]
+    trim_silence: SilenceTrimOptions,
+    /// Hum and hiss reduction before whisper; the QC report compares the
+    /// audio's loudness before and after.
+    #[serde(default)]
+    denoise: DenoiseOptions,
+    /// Find worship sets and other long music and mark them `[music]` or
+    /// leave them out, instead of transcribing hallucinated lyrics.
+    #[serde(default)]
+    music: MusicOptions,
+    #[serde(default)]
+    channels: ChannelOptions,
+    /// Name segments after the enrolled speaker whose voice they match.
+    #[serde(default)]
+    speakers: SpeakerOptions,
+    /// Skip copies of the same recording found on other drives or folders.
+    #[serde(default = "default_true")]
+    dedupe: bool,
+    /// Output layout such as `{year}/{month}/{slug}`; defaults to `{date}/{slug}`.
+    #[serde(default)]
+    output_template: Option<String>,
+    /// Only scan files with these extensions; empty allows every supported format.
+    #[serde(default)]
+    extensions: Vec<String>,
+    /// Skips recordings shorter (sound checks) or longer (all-day captures) than these.
+    #[serde(default)]
+    min_duration_secs: Option<u64>,
+    #[serde(default)]
+    max_duration_secs: Option<u64>,
+    /// Pause while the laptop runs on battery and resume on AC power.
+    #[serde(default)]
+    pause_on_battery: bool,
+    /// Caps the run by total audio length rather than file count.
+    #[serde(default)]
+    max_audio_hours: Option<f64>,
+    /// Queue order within each input folder, e.g. `newestFirst` so this week's
+    /// service is done before an archive backfill.
+    #[serde(default)]
+    sort_order: QueueOrder,
+    /// Whisper language code (`es`, `de`, ...); empty uses the model default.
+    #[serde(default)]
+    language: Option<String>,
+    /// Settings for individual input folders, keyed by the entry in `input_folders`.
+    #[serde(default)]
+    folder_overrides: HashMap<String, FolderOverrides>,
+    /// Restricts the run to these sources; set by `retry_failed`.
+    #[serde(default)]
+    only_files: Vec<String>,
+    /// Copy each source to local disk before transcribing it, for inputs on
+    /// a network share that stalls or drops reads.
+    #[serde(default)]
+    copy_local: bool,
+    /// Where local copies go; defaults to the system temp folder.
+    #[serde(default)]
+    staging_folder: Option<String>,
+    /// Whisper decoding overrides; unset keeps whisper-cli's defaults.
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    beam_size: Option<u32>,
+    #[serde(default)]
+    best_of: Option<u32>,
+    #[serde(default)]
+    entropy_threshold: Option<f32>,
+    /// Caption limits for SRT/VTT output, e.g. two 42-character lines for a
+    /// projector; longer segments are split across several captions.
+    #[serde(default)]
+    max_segment_chars: Option<usize>,
+    #[serde(default)]
+    max_segment_duration: Option<f64>,
+    /// Backend that transcribes each file; `whisper_exe` and `model_file`
+    /// then name that engine's executable and model.
+    #[serde(default)]
+    engine: Engine,
+    /// faster-whisper only: `cuda`, `cpu` or `auto`, and e.g. `float16`.
+    #[serde(default)]
+    device: Option<String>,
+    #[serde(default)]
+    compute_type: Option<String>,
+    /// Segments the engine is less sure of than this (0-1) are flagged in
+    /// `quality.json` for proofreading.
+    #[serde(default)]
+    confidence_threshold: Option<f32>,
+    /// Moves or copies successfully transcribed sources out of the input
+    /// folders when the run finishes.
+    #[serde(default)]
+    archive_sources: SourceArchiveOptions,
+    /// Start readable TXT/Markdown, DOCX and PDF outputs with the service
+    /// date, preacher, series, scripture, model and generation date.
+    #[serde(default)]
+    metadata_header: bool,
+    /// Also write an English (or, through the LLM endpoint, any language)
+    /// translation next to the original-language transcript.
+    #[serde(default)]
+    translation: TranslationOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct FolderOverrides {
+    language: Option<String>,
+    model_file: Option<String>,
+    before_date: Option<String>,
+    after_date: Option<String>,
+    /// Replaces the run's output folder for this input, e.g. to keep the
+    /// Spanish archive on its own drive.
+    output_folder: Option<String>,
+    /// Placed under the output folder, ahead of the output template.
+    output_subfolder: Option<String>,
+}
+
+impl StartRequest {
+    /// The request with any overrides for `input_folder` applied.
+    fn for_folder(&self, input_folder: &str) -> Result<StartRequest, String> {
+        let overrides = self
+            .folder_overrides
+            .iter()
+            .find(|(key, _)| key.trim() == input_folder.trim() || scanner::same_path(key, input_folder))
+            .map(|(_, o)| o.clone());
+        let Some(overrides) = overrides else {
+            return Ok(self.clone());
+        };
+
+        let set = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let mut request = self.clone();
+        if let Some(language) = set(overrides.language) {
+            request.language = Some(language);
+        }
+        if let Some(model_file) = set(overrides.model_file) {
+            request.model_file = model_file;
+        }
+        if let Some(before_date) = set(overrides.before_date) {
+            request.before_date = Some(before_date);
+        }
+        if let Some(after_date) = set(overrides.after_date) {
+            request.after_date = Some(after_date);
+        }
+        if let Some(output_folder) = set(overrides.output_folder) {
+            request.output_folder = output_folder;
+        }
+        if let Some(subfolder) = set(overrides.output_subfolder) {
+            archive::resolve_within(Path::new(&request.output_folder), &subfolder)?;
+            let template = request
+                .output_template
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .unwrap_or(scanner::DEFAULT_OUTPUT_TEMPLATE);
+            request.output_template = Some(format!("{}/{template}", subfolder.trim_matches(['/', '\\'])));
+        }
+        Ok(request)
+    }
+
+    /// Output folders set per input folder that differ from the run's own.
+    fn override_output_folders(&self) -> Vec<String> {
+        let mut folders: Vec<String> = Vec::new();
+        for input in &self.input_folders {
+            let Ok(effective) = self.for_folder(input) else {
+                continue;
+            };
+            let folder = effective.output_folder.trim().to_string();
+            if folder != self.output_folder.trim() && !folders.contains(&folder) {
+                folders.push(folder);
+            }
+        }
+        folders
+    }
+}
+
+/// Settings that may change when failed files are retried.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct RetryOverrides {
+    threads: Option<u32>,
+    model_file: Option<String>,
+    whisper_exe: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -41,8 +387,21 @@ struct PreflightRequest {
     input_folders: Vec<String>,
     output_folder: String,
     whisper_exe: String,
+    #[serde(default)]
+    whisper_exe_candidates: Vec<String>,
     model_file: String,
     script_path: Option<String>,
+    #[serde(default)]
+    extensions: Vec<String>,
+    #[serde(default)]
+    no_recursive: bool,
+    #[serde(default)]
+    engine: Engine,
+    #[serde(default)]
+    fallback_model: Option<String>,
+    /// Per-folder output folders, checked like `output_folder`.
+    #[serde(default)]
+    override_output_folders: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -50,6 +409,8 @@ struct PreflightRequest {
 struct PreflightCheck {
     key: String,
     ok: bool,
+    /// Passed, but worth showing the user (the run is not blocked).
+    warning: bool,
     detail: String,
     fix: String,
 }
@@ -63,11 +424,15 @@ struct PreflightReport {
     generated_at_epoch: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct LogEvent {
     stream: String,
     line: String,
+    level: LogLevel,
+    timestamp_ms: u64,
+    run_id: Option<String>,
+    source_file: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -76,6 +441,17 @@ struct StageEvent {
     index: usize,
     total: usize,
     input_folder: String,
+    /// Files queued in this folder once the plan is built.
+    files_total: usize,
+    files_done: usize,
+    files_skipped: usize,
+    current_file: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct FileProgress {
+    status: String,
+    source: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -86,28 +462,227 @@ struct FinishEvent {
     message: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// `graceful` lets the file being transcribed finish before the run ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum StopMode {
+    #[default]
+    Immediate,
+    Graceful,
+}
+
+const STOP_FLAG_FILE: &str = ".transcribe.stop";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RunnerStatus {
     running: bool,
     paused: bool,
     stop_requested: bool,
+    run_id: Option<String>,
+    #[serde(default)]
+    failed_files: Vec<String>,
+    #[serde(default)]
+    pause_reason: Option<String>,
+    #[serde(default)]
+    stop_mode: Option<StopMode>,
+}
+
+fn now_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Guesses a severity from the line's wording. whisper.cpp and ffmpeg write
+/// their routine diagnostics to stderr, so the stream alone says little.
+fn classify_log_line(stream: &str, line: &str) -> LogLevel {
+    let text = line.trim();
+    // The script's closing tally (`Done. ok=3 error=0 ...`) is not an error.
+    let lower = text.to_lowercase().replace("error=0", "");
+    if text.starts_with("[progress]") || text.is_empty() {
+        return LogLevel::Debug;
+    }
+    if text.starts_with("WARNING:") || lower.starts_with("[warn") || lower.contains("retrying") {
+        return LogLevel::Warn;
+    }
+    if ["error", "failed", "exception", "could not", "cannot ", "not found", "invalid"]
+        .iter()
+        .any(|word| lower.contains(word))
+    {
+        return LogLevel::Error;
+    }
+    // whisper.cpp model loading and timing chatter.
+    if ["whisper_", "ggml_", "system_info:", "main: ", "output_", "load_backend:"]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+    {
+        return LogLevel::Debug;
+    }
+    // ffmpeg decoder notices look like `[mp3 @ 0x55d...] Estimating duration`.
+    if text.starts_with('[') && text.contains(" @ ") {
+        return LogLevel::Warn;
+    }
+    if lower.contains("warning") {
+        return LogLevel::Warn;
+    }
+    if stream == "stderr" {
+        LogLevel::Warn
+    } else {
+        LogLevel::Info
+    }
 }
 
 fn emit_log(app: &AppHandle, stream: &str, line: impl Into<String>) {
+    let source_file = app
+        .state::<RunnerState>()
+        .log_context
+        .lock()
+        .ok()
+        .and_then(|context| context.source_file.clone());
+    emit_log_from(app, stream, line.into(), source_file);
+}
+
+/// Logs a line stamped with the file its own worker is on, so lines from
+/// parallel GPU workers aren't credited to whichever file started last.
+fn emit_log_from(app: &AppHandle, stream: &str, line: String, source_file: Option<String>) {
+    let state = app.state::<RunnerState>();
+    let (run_id, line) = match state.log_context.lock() {
+        Ok(context) => {
+            let line = match &context.redactor {
+                Some(redactor) => redactor.apply(&line),
+                None => line,
+            };
+            (context.run_id.clone(), line)
+        }
+        Err(_) => (None, line),
+    };
     let payload = LogEvent {
         stream: stream.to_string(),
-        line: line.into(),
+        level: classify_log_line(stream, &line),
+        line,
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        run_id,
+        source_file,
     };
+
+    if let Ok(mut recent) = state.recent_logs.lock() {
+        if recent.len() >= RECENT_LOG_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(payload.clone());
+    }
+    cli::write_log(app, stream, &payload.line);
+    logfile::append(app, &payload);
+
+    let admission = logfilter::admit(app, &payload);
+    if !admission.emit {
+        return;
+    }
+    if admission.suppressed > 0 {
+        let _ = app.emit(
+            "transcribe://log",
+            LogEvent {
+                stream: "system".to_string(),
+                line: format!("... {} log lines hidden (see log files for all output)", admission.suppressed),
+                level: LogLevel::Debug,
+                ..payload.clone()
+            },
+        );
+    }
     let _ = app.emit("transcribe://log", payload);
 }
 
-fn spawn_log_reader<R: Read + Send + 'static>(reader: R, stream: &'static str, app: AppHandle) {
+/// The `\\server\share` root of a UNC path when the share itself can't be
+/// opened, which otherwise reads as a missing folder.
+fn unreachable_share(path: &str) -> Option<String> {
+    longpath::unc_share(path).filter(|share| !Path::new(&format!("{share}\\")).is_dir())
+}
+
+/// Creates `folder` if needed and checks a file can be written there.
+fn check_writable_folder(folder: &str) -> Result<(), String> {
+    if let Some(share) = unreachable_share(folder) {
+        return Err(format!("Network share {share} is not reachable (output {folder})"));
+    }
+    let long = longpath::extended(Path::new(folder));
+    if long.exists() && !long.is_dir() {
+        return Err(format!("Output path is a file, not a folder: {folder}"));
+    }
+    fs::create_dir_all(&long).map_err(|err| format!("Failed to create output folder {folder}: {err}"))?;
+    let probe = long.join(".church-transcriber-write-test");
+    fs::write(&probe, b"ok").map_err(|err| format!("Cannot write to output folder {folder}: {err}"))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Parses the batch script's `=== <path> ===` file marker. GPU workers append
+/// their tag after it (`=== <path> === [gpu0]`), which is not part of the path.
+fn parse_file_marker(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("=== ")?;
+    let (path, tag) = rest.rsplit_once(" ===")?;
+    let tag = tag.trim();
+    (tag.is_empty() || (tag.starts_with('[') && tag.ends_with(']'))).then_some(path)
+}
+
+/// Parses the batch script's `[progress] done=.. total=.. status=.. source=..` lines
+/// into per-file completion notices. `source` is last so it may contain spaces.
+fn parse_progress_line(line: &str) -> Option<FileProgress> {
+    let rest = line.trim().strip_prefix("[progress]")?;
+    let (head, source) = rest.split_once(" source=")?;
+    let status = head
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix("status="))?;
+
+    Some(FileProgress {
+        status: status.to_string(),
+        source: longpath::plain(source.trim()),
+    })
+}
+
+fn spawn_log_reader<R: Read + Send + 'static>(
+    reader: R,
+    stream: &'static str,
+    app: AppHandle,
+    progress: Option<Sender<FileProgress>>,
+    mut keywords: Option<KeywordWatch>,
+    source_file: Arc<Mutex<Option<String>>>,
+) {
     thread::spawn(move || {
-        let buf = BufReader::new(reader);
-        for line in buf.lines() {
-            match line {
-                Ok(text) => emit_log(&app, stream, text),
+        let mut buf = BufReader::new(reader);
+        let mut bytes = Vec::new();
+        loop {
+            bytes.clear();
+            match buf.read_until(b'\n', &mut bytes) {
+                Ok(0) => break,
+                Ok(_) => {
+                    // Lossy, so a line in the console's code page can't end the log.
+                    let text = String::from_utf8_lossy(&bytes).trim_end_matches(['\r', '\n']).to_string();
+                    if let Some(tx) = progress.as_ref() {
+                        if let Some(update) = parse_progress_line(&text) {
+                            let _ = tx.send(update);
+                        }
+                    }
+                    let state = app.state::<RunnerState>();
+                    if let Some(path) = parse_file_marker(&text).filter(|_| stream == "stdout") {
+                        if let Ok(mut current) = source_file.lock() {
+                            *current = Some(path.to_string());
+                        }
+                        // System lines go to the most recently started file.
+                        if let Ok(mut context) = state.log_context.lock() {
+                            context.source_file = Some(path.to_string());
+                        }
+                    }
+                    let current = source_file.lock().ok().and_then(|current| current.clone());
+                    emit_log_from(&app, stream, text.clone(), current.clone());
+                    if let Some(watch) = keywords.as_mut() {
+                        let run_id = state.log_context.lock().ok().and_then(|context| context.run_id.clone());
+                        watch.check_line(&app, &text, run_id, current);
+                    }
+                }
                 Err(err) => {
                     emit_log(&app, "system", format!("log read error: {err}"));
                     break;
@@ -175,6 +750,52 @@ fn command_exists(bin: &str) -> bool {
         .unwrap_or(false)
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PowerShellProbe {
+    ps_version: String,
+    ps_major: u32,
+    language_mode: String,
+    execution_policy: String,
+}
+
+/// Runs the batch script in `-Probe` mode the way a run launches it, so policy
+/// and AppLocker blocks surface in preflight instead of mid-run.
+fn probe_powershell(powershell_bin: &str, script: &Path) -> Result<PowerShellProbe, String> {
+    let output = Command::new(powershell_bin)
+        .args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-File"])
+        .arg(script)
+        .arg("-Probe")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("Could not start {powershell_bin}: {err}"))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if let Some(json) = stdout.lines().find_map(|l| l.trim().strip_prefix("[probe] ")) {
+        return serde_json::from_str(json).map_err(|err| format!("Unreadable PowerShell probe output: {err}"));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = stderr.split_whitespace().collect::<Vec<_>>().join(" ");
+    Err(if message.is_empty() {
+        format!("The batch script did not run (exit code {:?}).", output.status.code())
+    } else {
+        message
+    })
+}
+
+fn powershell_block_fix(error: &str) -> &'static str {
+    let lower = error.to_lowercase();
+    if lower.contains("running scripts is disabled") {
+        "A Group Policy execution policy blocks scripts. Ask IT to allow RemoteSigned for this machine or user."
+    } else if lower.contains("not digitally signed") {
+        "The execution policy requires signed scripts (AllSigned). Ask IT to sign the script or relax the policy."
+    } else if lower.contains("group policy") || lower.contains("applocker") || lower.contains("application control") {
+        "AppLocker/WDAC blocks scripts from this folder. Ask IT to allow the app's install folder."
+    } else {
+        "Run the batch script manually in PowerShell to see the full error."
+    }
+}
+
 fn looks_like_path(value: &str) -> bool {
     value.contains('\\') || value.contains('/') || value.contains(':')
 }
@@ -186,6 +807,7 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
         checks.push(PreflightCheck {
             key: key.to_string(),
             ok,
+            warning: false,
             detail,
             fix: fix.to_string(),
         });
@@ -231,18 +853,69 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
     } else {
         for folder in &request.input_folders {
             let trimmed = folder.trim();
+            if stream::is_stream_url(trimmed) {
+                if stream::is_youtube(trimmed) {
+                    let ok = command_exists("yt-dlp");
+                    push(
+                        "yt-dlp",
+                        ok,
+                        if ok {
+                            format!("YouTube input will be resolved with yt-dlp: {trimmed}")
+                        } else {
+                            format!("yt-dlp not found on PATH (needed for {trimmed})")
+                        },
+                        "Install yt-dlp (example: winget install yt-dlp.yt-dlp) and reopen the app.",
+                    );
+                } else {
+                    push(
+                        "inputStream",
+                        true,
+                        format!("Stream input will be captured with ffmpeg: {trimmed}"),
+                        "",
+                    );
+                }
+                continue;
+            }
             let p = PathBuf::from(trimmed);
-            let ok = !trimmed.is_empty() && p.exists() && p.is_dir();
+            let long = longpath::extended(&p);
+            let ok = !trimmed.is_empty()
+                && (long.is_dir() || (long.is_file() && scanner::is_media_file(&p, &request.extensions)));
+            if !ok {
+                if let Some(share) = unreachable_share(trimmed) {
+                    push(
+                        "inputFolder",
+                        false,
+                        format!("Network share {share} is not reachable (input {})", p.display()),
+                        "Check the NAS is on and this Windows user can open the share in Explorer.",
+                    );
+                    continue;
+                }
+            }
             push(
                 "inputFolder",
                 ok,
                 if ok {
                     format!("Input folder OK: {}", p.display())
                 } else {
-                    format!("Input folder missing/not directory: {}", p.display())
+                    format!("Input folder missing/not directory or media file: {}", p.display())
                 },
-                "Select a valid folder containing church media files.",
+                "Select a valid folder containing church media files, or drop the media files themselves.",
             );
+            if ok {
+                if let Ok((matching, total)) = scanner::count_media_files(&p, &request.extensions, !request.no_recursive) {
+                    let filter = if request.extensions.is_empty() {
+                        "supported media".to_string()
+                    } else {
+                        request.extensions.join(", ")
+                    };
+                    push(
+                        "mediaFiles",
+                        true,
+                        format!("{matching} of {total} file(s) match {filter} in {}", p.display()),
+                        "",
+                    );
+                }
+            }
         }
     }
 
@@ -256,18 +929,22 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
         );
     } else {
         let output_path = PathBuf::from(output_trimmed);
+        let long_output = longpath::extended(&output_path);
         let mut ok = true;
         let detail: String;
 
-        if output_path.exists() {
-            if output_path.is_dir() {
+        if let Some(share) = unreachable_share(output_trimmed) {
+            ok = false;
+            detail = format!("Network share {share} is not reachable (output {})", output_path.display());
+        } else if long_output.exists() {
+            if long_output.is_dir() {
                 detail = format!("Output folder exists: {}", output_path.display());
             } else {
                 ok = false;
                 detail = format!("Output path is a file, not a folder: {}", output_path.display());
             }
         } else {
-            match fs::create_dir_all(&output_path) {
+            match fs::create_dir_all(&long_output) {
                 Ok(_) => {
                     detail = format!("Output folder created: {}", output_path.display());
                 }
@@ -282,7 +959,7 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
         }
 
         if ok {
-            let probe = output_path.join(".church-transcriber-write-test");
+            let probe = long_output.join(".church-transcriber-write-test");
             match fs::write(&probe, b"ok") {
                 Ok(_) => {
                     let _ = fs::remove_file(&probe);
@@ -307,8 +984,40 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
         );
     }
 
+    for folder in &request.override_output_folders {
+        let (ok, detail) = match check_writable_folder(folder.trim()) {
+            Ok(()) => (true, format!("Per-folder output folder OK: {}", folder.trim())),
+            Err(err) => (false, err),
+        };
+        push(
+            "outputFolderOverride",
+            ok,
+            detail,
+            "Pick a writable output folder in that input folder's settings.",
+        );
+    }
+
     let whisper_trimmed = request.whisper_exe.trim();
-    if whisper_trimmed.is_empty() {
+    if !request.whisper_exe_candidates.is_empty() {
+        match whisperbin::select(whisper_trimmed, &request.whisper_exe_candidates) {
+            Ok(selection) => push(
+                "whisperExe",
+                true,
+                if selection.skipped.is_empty() {
+                    format!("Whisper executable OK: {}", selection.exe)
+                } else {
+                    format!("Using {} (skipped {})", selection.exe, selection.skipped.join("; "))
+                },
+                "",
+            ),
+            Err(err) => push(
+                "whisperExe",
+                false,
+                err,
+                "Add a build that runs on this PC, e.g. the generic CPU build of whisper-cli.exe.",
+            ),
+        }
+    } else if whisper_trimmed.is_empty() {
         push(
             "whisperExe",
             false,
@@ -343,9 +1052,16 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
             "Model file path is empty".to_string(),
             "Set model path (example: C:\\ai\\whisper-models\\ggml-small.en.bin).",
         );
+    } else if !request.engine.requires_model_file() && !looks_like_path(model_trimmed) {
+        push(
+            "modelFile",
+            true,
+            format!("{} model {model_trimmed} (downloaded on first use)", request.engine.label()),
+            "",
+        );
     } else {
         let p = PathBuf::from(model_trimmed);
-        let ok = p.exists() && p.is_file();
+        let ok = p.is_file() || (!request.engine.requires_model_file() && p.is_dir());
         push(
             "modelFile",
             ok,
@@ -357,6 +1073,20 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
             "Download model file and set the correct full path.",
         );
     }
+    if let Some(fallback) = request.fallback_model.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        let p = PathBuf::from(fallback);
+        let ok = p.is_file() || (!request.engine.requires_model_file() && (p.is_dir() || !looks_like_path(fallback)));
+        push(
+            "fallbackModel",
+            ok,
+            if ok {
+                format!("Fallback model OK: {fallback}")
+            } else {
+                format!("Fallback model missing: {}", p.display())
+            },
+            "Download the fallback model or clear the fallback model setting.",
+        );
+    }
 
     let mut resolved_script_path: Option<String> = None;
     match resolve_script_path(app, request.script_path.clone()) {
@@ -368,6 +1098,30 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
                 format!("Batch script resolved: {}", path.display()),
                 "",
             );
+
+            if powershell_ok {
+                match probe_powershell(powershell_bin, &path) {
+                    Ok(probe) => {
+                        push(
+                            "powershellVersion",
+                            probe.ps_major >= 5,
+                            format!("PowerShell {} (execution policy {})", probe.ps_version, probe.execution_policy),
+                            "Install Windows PowerShell 5.1 or PowerShell 7.",
+                        );
+                        let full = probe.language_mode == "FullLanguage";
+                        push(
+                            "powershellLanguageMode",
+                            full,
+                            format!("Language mode: {}", probe.language_mode),
+                            "ConstrainedLanguage is enforced by AppLocker/WDAC or __PSLockdownPolicy. Ask IT to allow the app's install folder or sign the script.",
+                        );
+                    }
+                    Err(err) => {
+                        let fix = powershell_block_fix(&err);
+                        push("powershellScript", false, format!("Batch script was blocked: {err}"), fix);
+                    }
+                }
+            }
         }
         Err(err) => {
             push(
@@ -379,16 +1133,70 @@ fn build_preflight_report(app: &AppHandle, request: &PreflightRequest) -> Prefli
         }
     }
 
+    let model_path = Path::new(model_trimmed);
+    if model_path.is_file() && request.engine.requires_model_file() {
+        if let Some(fit) = hardware::model_memory_fit(model_path) {
+            checks.push(PreflightCheck {
+                key: "modelMemory".to_string(),
+                ok: fit.ok,
+                warning: fit.warning,
+                detail: fit.detail,
+                fix: fit.fix,
+            });
+        }
+        let format_check = match modelinfo::inspect(model_path) {
+            Ok(info) => {
+                let version = if whisper_trimmed.is_empty() {
+                    None
+                } else {
+                    updater::current_version(app, whisper_trimmed)
+                };
+                match modelinfo::check_compatible(&info, version.as_deref()) {
+                    Err(err) => (false, false, err, "Update whisper.cpp or use an unquantized ggml model."),
+                    Ok(()) if info.format == modelinfo::ModelFormat::Gguf => (
+                        true,
+                        true,
+                        "GGUF model; most whisper-cli builds only load ggml .bin models.".to_string(),
+                        "If whisper fails to load it, use the ggml .bin version of the model.",
+                    ),
+                    Ok(()) => (
+                        true,
+                        false,
+                        format!(
+                            "ggml model, {}{}",
+                            info.quantization.as_deref().unwrap_or("unknown quantization"),
+                            info.size_class.map(|c| format!(" ({c})")).unwrap_or_default()
+                        ),
+                        "",
+                    ),
+                }
+            }
+            Err(err) => (false, false, err, "Download model file and set the correct full path."),
+        };
+        let (ok, warning, detail, fix) = format_check;
+        checks.push(PreflightCheck {
+            key: "modelFormat".to_string(),
+            ok,
+            warning,
+            detail,
+            fix: fix.to_string(),
+        });
+    }
+
+    if request.engine == Engine::Cloud {
+        checks.extend(cloudengine::preflight_checks(
+            &settings::load(app).cloud_transcription,
+            request,
+        ));
+    }
+
     let ready = checks.iter().all(|c| c.ok);
 
     PreflightReport {
         ready,
         checks,
         resolved_script_path,
-        generated_at_epoch: SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
+        generated_at_epoch: now_epoch(),
     }
 }
 
@@ -404,16 +1212,40 @@ fn get_status(state: &RunnerState) -> RunnerStatus {
         .map(|p| p.exists())
         .unwrap_or(false);
 
+    let (run_id, failed_files) = state
+        .report
+        .lock()
+        .ok()
+        .and_then(|r| r.as_ref().map(|r| (Some(r.run_id.clone()), r.failed_sources())))
+        .unwrap_or_default();
+
+    let pause_reason = if paused {
+        state.pause_reason.lock().ok().and_then(|r| r.clone())
+    } else {
+        None
+    };
+
+    let stop_mode = if stop_requested {
+        state.stop_mode.lock().ok().map(|m| *m)
+    } else {
+        None
+    };
+
     RunnerStatus {
         running,
         paused,
         stop_requested,
+        run_id,
+        failed_files,
+        pause_reason,
+        stop_mode,
     }
 }
 
 fn emit_status(app: &AppHandle) {
     let state = app.state::<RunnerState>();
     let status = get_status(&state);
+    tray::refresh(app, &status);
     let _ = app.emit("transcribe://status", status);
 }
 
@@ -434,6 +1266,17 @@ fn current_stop_requested(app: &AppHandle) -> bool {
     state.stop_requested.lock().map(|v| *v).unwrap_or(true)
 }
 
+/// True once a stop that should end the script right away was requested.
+fn current_stop_immediate(app: &AppHandle) -> bool {
+    let state = app.state::<RunnerState>();
+    current_stop_requested(app) && state.stop_mode.lock().map(|m| *m == StopMode::Immediate).unwrap_or(true)
+}
+
+fn stop_flag_path(state: &RunnerState) -> Option<PathBuf> {
+    let pause_path = state.pause_flag.lock().ok().and_then(|p| p.clone())?;
+    Some(pause_path.with_file_name(STOP_FLAG_FILE))
+}
+
 fn clear_pause_flag_file(state: &RunnerState) {
     let pause_path = state.pause_flag.lock().ok().and_then(|p| p.clone());
     if let Some(path) = pause_path {
@@ -448,16 +1291,92 @@ fn cleanup_after_run(app: &AppHandle, success: bool, code: i32, message: impl In
     let state = app.state::<RunnerState>();
 
     clear_pause_flag_file(&state);
+    if let Some(stop_flag) = stop_flag_path(&state) {
+        let _ = fs::remove_file(stop_flag);
+    }
+    if let Ok(mut mode) = state.stop_mode.lock() {
+        *mode = StopMode::default();
+    }
 
     if let Ok(mut child) = state.child.lock() {
         *child = None;
     }
+    if let Ok(mut workers) = state.workers.lock() {
+        workers.clear();
+    }
 
     if let Ok(mut pause) = state.pause_flag.lock() {
         *pause = None;
     }
 
-    set_running(&state, false);
+    if let Ok(mut paused_at) = state.paused_at.lock() {
+        *paused_at = None;
+    }
+
+    if let Ok(mut reason) = state.pause_reason.lock() {
+        *reason = None;
+    }
+
+    if let Ok(mut context) = state.log_context.lock() {
+        *context = LogContext::default();
+    }
+
+    let finished_report = state.report.lock().ok().and_then(|mut slot| {
+        let report = slot.as_mut()?;
+        report.finished_at_epoch = Some(now_epoch());
+        report.success = Some(success);
+        report.code = Some(code);
+        report.message = Some(msg.clone());
+        Some(report.clone())
+    });
+    if let Some(report) = &finished_report {
+        if let Err(err) = history::save_report(app, report) {
+            emit_log(app, "system", err);
+        }
+    }
+    // Stopped or failed runs keep their checkpoint so `resume_last_run` still works.
+    if success {
+        checkpoint::clear(app);
+    }
+
+    let publish_app = app.clone();
+    let publishing = thread::spawn(move || {
+        if let Some(report) = finished_report {
+            if !report.results.is_empty() {
+                match qcreport::write_run_report(&publish_app, &report) {
+                    Ok(qc) => emit_log(
+                        &publish_app,
+                        "system",
+                        format!("QC report: {} file(s) with issues, {}", qc.files_with_issues, qc.html_path),
+                    ),
+                    Err(err) => emit_log(&publish_app, "system", err),
+                }
+                match runsummary::write_summary(&report) {
+                    Ok(path) => emit_log(&publish_app, "system", format!("Run summary: {}", path.display())),
+                    Err(err) => emit_log(&publish_app, "system", err),
+                }
+                match highlights::write_run_highlights(&publish_app, &report) {
+                    Ok(Some(run)) if !run.highlights.is_empty() => emit_log(
+                        &publish_app,
+                        "system",
+                        format!("Highlights: {} candidate clip(s), {}", run.highlights.len(), run.json_path),
+                    ),
+                    Ok(_) => {}
+                    Err(err) => emit_log(&publish_app, "system", err),
+                }
+            }
+            notify::run_finished(&publish_app, &report);
+            podcast::update_feed(&publish_app, &report);
+            sourcearchive::archive_sources(&publish_app, &report);
+        }
+        cloudsync::sync_after_run(&publish_app);
+        // Stay busy until sources have moved and synced, so the next run
+        // doesn't scan half-moved files.
+        set_running(&publish_app.state::<RunnerState>(), false);
+        emit_status(&publish_app);
+        digest::run_if_due(&publish_app);
+    });
+
     set_stop_requested(&state, false);
 
     let _ = app.emit(
@@ -470,81 +1389,741 @@ fn cleanup_after_run(app: &AppHandle, success: bool, code: i32, message: impl In
     );
 
     emit_status(app);
-}
-
-fn spawn_worker(app: AppHandle, request: StartRequest) {
-    thread::spawn(move || {
-        let state = app.state::<RunnerState>();
 
-        let script_path = match resolve_script_path(&app, request.script_path.clone()) {
-            Ok(path) => path,
-            Err(err) => {
-                emit_log(&app, "system", err.clone());
-                cleanup_after_run(&app, false, 1, err);
-                return;
-            }
-        };
+    // Headless runs end the process, but only after the post-run steps finish.
+    if cli::is_headless(app) {
+        let _ = publishing.join();
+        app.exit(code);
+    }
+}
 
+fn record_plan(app: &AppHandle, plan: &FolderPlan) {
+    for skip in &plan.skipped {
         emit_log(
-            &app,
+            app,
             "system",
-            format!("Using batch script: {}", script_path.display()),
+            format!("[skip] {} ({})", skip.source, skip.detail),
         );
+    }
 
-        let pause_path = PathBuf::from(request.output_folder.trim()).join(".transcribe.pause");
-        if let Ok(mut pause) = state.pause_flag.lock() {
-            *pause = Some(pause_path.clone());
-        }
+    emit_log(
+        app,
+        "system",
+        format!(
+            "Plan for {}: {} to transcribe, {} skipped.",
+            plan.input_folder,
+            plan.files.len(),
+            plan.skipped.len()
+        ),
+    );
 
-        if pause_path.exists() {
-            let _ = fs::remove_file(&pause_path);
+    let state = app.state::<RunnerState>();
+    if let Ok(mut slot) = state.report.lock() {
+        if let Some(report) = slot.as_mut() {
+            // A resumed run re-plans the folder it was interrupted in.
+            for file in &plan.files {
+                if !report.planned.iter().any(|p| p.source == file.source) {
+                    report.planned.push(file.clone());
+                }
+            }
+            report.skipped.extend(plan.skipped.iter().cloned());
+            if let Err(err) = history::save_report(app, report) {
+                emit_log(app, "system", err);
+            }
         }
+    }
 
-        let powershell_bin = if cfg!(target_os = "windows") {
-            "powershell"
-        } else {
-            "pwsh"
-        };
-
-        let total = request.input_folders.len();
-        let mut final_code = 0;
-        let mut final_message = "Transcription complete.".to_string();
-        let mut success = true;
+    let _ = app.emit("transcribe://plan", plan.clone());
+}
 
-        for (index, folder) in request.input_folders.iter().enumerate() {
-            if current_stop_requested(&app) {
-                success = false;
-                final_code = 130;
-                final_message = "Stopped by user before next folder.".to_string();
-                emit_log(&app, "system", &final_message);
-                break;
-            }
+fn write_plan_file(path: &Path, plan: &FolderPlan) -> Result<(), String> {
+    let body = serde_json::to_string(plan)
+        .map_err(|err| format!("Failed to serialize folder plan: {err}"))?;
+    fs::write(path, body)
+        .map_err(|err| format!("Failed to write plan file {}: {err}", path.display()))
+}
 
-            let _ = app.emit(
-                "transcribe://stage",
-                StageEvent {
-                    index: index + 1,
-                    total,
-                    input_folder: folder.clone(),
-                },
-            );
+/// Runs the Rust-side stages for a file the batch script just finished.
+fn run_post_stages(app: &AppHandle, request: &StartRequest, planned: &PlannedFile) {
+    if request.fast_scan {
+        return;
+    }
 
-            let mut cmd = Command::new(powershell_bin);
-            cmd.arg("-NoProfile")
-                .arg("-ExecutionPolicy")
-                .arg("Bypass")
-                .arg("-File")
-                .arg(&script_path)
+    let output_dir = longpath::extended(Path::new(&planned.output_dir));
+    let mut segments = match transcript::load_segments(&output_dir) {
+        Ok(segments) => segments,
+        Err(err) => {
+            emit_log(app, "system", format!("Post-processing skipped: {err}"));
+            return;
+        }
+    };
+    if let Some(primary) = transcript::fallback_from(&output_dir) {
+        emit_log(
+            app,
+            "system",
+            format!(
+                "Transcribed with the fallback model {} after {primary} failed.",
+                transcript::model_file(&output_dir).unwrap_or_default()
+            ),
+        );
+    }
+    let details = filemeta::lookup(app, &planned.source);
+    let title = filemeta::title(&details, &planned.source);
+    let header = if request.metadata_header {
+        filemeta::header_fields(
+            &details,
+            &planned.date_bucket,
+            transcript::model_file(&output_dir).as_deref(),
+        )
+    } else {
+        Vec::new()
+    };
+
+    let redactor = redaction::Redactor::new(&settings::load(app).redaction);
+    let threshold = request.confidence_threshold.unwrap_or(quality::DEFAULT_THRESHOLD);
+    match quality::write_report(&output_dir, threshold, redactor.as_ref()) {
+        Ok(Some((path, report))) if !report.flagged.is_empty() => emit_log(
+            app,
+            "system",
+            format!(
+                "Quality: {} low-confidence range(s), {}s to review: {}",
+                report.flagged.len(),
+                report.flagged_secs,
+                path.display()
+            ),
+        ),
+        Ok(_) => {}
+        Err(err) => emit_log(app, "system", err),
+    }
+
+    if request.speakers.enabled {
+        let profiles = speakers::load(app);
+        if profiles.is_empty() {
+            emit_log(app, "system", "Speaker labels skipped: no speakers are enrolled.");
+        } else {
+            let source = longpath::extended(Path::new(&planned.source));
+            match speakers::label_segments(&source, &mut segments, &profiles, request.speakers.min_similarity) {
+                Ok(count) => emit_log(
+                    app,
+                    "system",
+                    format!("Speakers: {count} of {} segment(s) labelled.", segments.len()),
+                ),
+                Err(err) => emit_log(app, "system", format!("Speaker labels skipped: {err}")),
+            }
+        }
+    }
+
+    // Published formats are rendered from a filtered copy; raw outputs stay as-is.
+    let mut published = segments;
+    if let Some(redactor) = &redactor {
+        published = redactor.apply_segments(&published);
+        match redactor.apply_to_files(&output_dir, postprocess::SCRIPT_PUBLISHED_FILES) {
+            Ok(count) if count > 0 => emit_log(
+                app,
+                "system",
+                format!("Redacted {count} script output(s); originals kept in {}.", redaction::PRIVATE_DIR),
+            ),
+            Ok(_) => {}
+            Err(err) => emit_log(app, "system", err),
+        }
+    }
+    let mask = if request.mask_profanity {
+        WordMask::new(&settings::load(app).profanity_words)
+    } else {
+        None
+    };
+    if let Some(mask) = &mask {
+        published = mask.apply_segments(&published);
+        match mask.apply_to_files(&output_dir, postprocess::SCRIPT_PUBLISHED_FILES) {
+            Ok(count) if count > 0 => {
+                emit_log(app, "system", format!("Profanity masked in {count} script output(s)."))
+            }
+            Ok(_) => {}
+            Err(err) => emit_log(app, "system", err),
+        }
+    }
+    if request.music.enabled {
+        let ranges = transcript::music_ranges(&output_dir);
+        if !ranges.is_empty() {
+            let before = published.len();
+            published = music::apply(&published, &ranges, request.music.mode);
+            emit_log(
+                app,
+                "system",
+                format!(
+                    "Music: {} range(s), {} segment(s) dropped.",
+                    ranges.len(),
+                    before.saturating_sub(published.iter().filter(|s| s.text != music::MUSIC_MARKER).count())
+                ),
+            );
+        }
+    }
+
+    let references = if request.detect_scripture {
+        let found = scripture::detect_in_segments(&published);
+        if !found.is_empty() {
+            emit_log(
+                app,
+                "system",
+                format!("Scripture references found: {}", found.len()),
+            );
+        }
+        Some(found)
+    } else {
+        None
+    };
+
+    if request.readable.enabled {
+        match postprocess::write_readable(
+            &output_dir,
+            &title,
+            &planned.source,
+            &header,
+            &published,
+            &request.readable,
+            references.as_deref(),
+        ) {
+            Ok(paths) => {
+                for path in paths {
+                    emit_log(app, "system", format!("Readable transcript: {}", path.display()));
+                }
+            }
+            Err(err) => emit_log(app, "system", err),
+        }
+    }
+
+    if request.channels.enabled {
+        match channels::write_channel_transcript(
+            &output_dir,
+            &title,
+            &request.channels,
+            redactor.as_ref(),
+            mask.as_ref(),
+        ) {
+            Ok(Some(path)) => emit_log(app, "system", format!("Channel transcript: {}", path.display())),
+            Ok(None) => emit_log(app, "system", "No per-channel transcripts were produced."),
+            Err(err) => emit_log(app, "system", err),
+        }
+    }
+
+    if !request.output_formats.is_empty() {
+        let header = DocumentHeader {
+            title: &title,
+            date: &planned.date_bucket,
+            source: &planned.source,
+            fields: &header,
+        };
+        let paragraphs = postprocess::build_paragraphs(&published, &request.readable);
+        // Kept audio is converted after post-processing, so name the final file.
+        let kept_audio = request.kept_audio.file_name();
+        let audio = request.keep_audio.then(|| PlayerAudio {
+            file: &kept_audio,
+            offset_ms: transcript::trim_offset_ms(&output_dir),
+        });
+        match export::write_exports(
+            &output_dir,
+            &header,
+            &paragraphs,
+            &published,
+            &request.output_formats,
+            CaptionLimits {
+                max_chars: request.max_segment_chars,
+                max_duration_ms: request.max_segment_duration.filter(|d| *d > 0.0).map(|d| (d * 1000.0) as u64),
+            },
+            audio.as_ref(),
+        ) {
+            Ok(paths) => {
+                for path in paths {
+                    emit_log(app, "system", format!("Exported: {}", path.display()));
+                }
+            }
+            Err(err) => emit_log(app, "system", err),
+        }
+    }
+
+    // Recordings a folder config already translated have nothing left to translate.
+    if request.translation.enabled && !planned.translate {
+        match translation::write_translation(
+            &output_dir,
+            &request.translation,
+            &settings::load(app).llm,
+            &published,
+            &request.output_formats,
+            CaptionLimits {
+                max_chars: request.max_segment_chars,
+                max_duration_ms: request.max_segment_duration.filter(|d| *d > 0.0).map(|d| (d * 1000.0) as u64),
+            },
+        ) {
+            Ok(paths) => {
+                for path in paths {
+                    emit_log(app, "system", format!("Translation: {}", path.display()));
+                }
+            }
+            Err(err) => emit_log(app, "system", format!("Translation failed: {err}")),
+        }
+    }
+
+    let gaps = if request.chapters.enabled || request.sections.enabled {
+        detect_gaps(app, &request.chapters, planned, &published)
+    } else {
+        Vec::new()
+    };
+    let sections = if request.sections.enabled {
+        let music = transcript::music_ranges(&output_dir);
+        sections::classify(&published, &gaps, &music, &request.chapters, &request.sections)
+    } else {
+        Vec::new()
+    };
+    if let Some(sermon) = sections.iter().find(|s| s.kind == sections::SectionKind::Sermon) {
+        emit_log(
+            app,
+            "system",
+            format!(
+                "Sections: {}; sermon at {}",
+                sections.len(),
+                transcript::format_timestamp(sermon.start_ms)
+            ),
+        );
+    }
+    let chapters = if !sections.is_empty() {
+        sections::to_chapters(&sections)
+    } else if request.chapters.enabled {
+        chapters::build_chapters(&published, &gaps, &request.chapters)
+    } else {
+        Vec::new()
+    };
+    if !chapters.is_empty() {
+        match chapters::write_ffmetadata(&output_dir, &title, &chapters) {
+            Ok(path) => emit_log(app, "system", format!("Chapter markers: {}", path.display())),
+            Err(err) => emit_log(app, "system", err),
+        }
+    }
+
+    if request.summarize {
+        let llm = settings::load(app).llm;
+        emit_log(app, "system", format!("Requesting summary for {title}..."));
+        match summarize::request_summary(&llm, &title, &published)
+            .and_then(|summary| summarize::write_summary(&output_dir, &title, &summary))
+        {
+            Ok(path) => emit_log(app, "system", format!("Sermon summary: {}", path.display())),
+            Err(err) => emit_log(app, "system", format!("Summary failed: {err}")),
+        }
+    }
+
+    let enriched = EnrichedTranscript {
+        title,
+        source_file: planned.source.clone(),
+        generated_at_epoch: now_epoch(),
+        segments: published,
+        scripture_references: references.unwrap_or_default(),
+        chapters,
+        sections,
+    };
+    if let Err(err) = transcript::write_enriched(&output_dir, &enriched) {
+        emit_log(app, "system", err);
+    }
+}
+
+/// Prefers ffmpeg silence detection on the source media and falls back to gaps
+/// between whisper segments.
+fn detect_gaps(
+    app: &AppHandle,
+    options: &ChapterOptions,
+    planned: &PlannedFile,
+    segments: &[transcript::Segment],
+) -> Vec<chapters::Gap> {
+    if options.use_silence_detection {
+        match chapters::silence_gaps(Path::new(&planned.source), options) {
+            Ok(gaps) => gaps,
+            Err(err) => {
+                emit_log(app, "system", format!("{err}; using segment gaps for chapters."));
+                chapters::segment_gaps(segments, options)
+            }
+        }
+    } else {
+        chapters::segment_gaps(segments, options)
+    }
+}
+
+/// Kills the current folder's GPU workers beyond the first.
+fn kill_workers(state: &RunnerState) {
+    if let Ok(mut workers) = state.workers.lock() {
+        for worker in workers.iter_mut() {
+            proctree::kill(worker);
+        }
+    }
+}
+
+/// Drops GPU workers that have exited, keeping the first failing exit code in
+/// `failed`. Returns how many are still running.
+fn reap_workers(app: &AppHandle, state: &RunnerState, failed: &mut i32) -> usize {
+    let Ok(mut workers) = state.workers.lock() else {
+        return 0;
+    };
+    workers.retain_mut(|worker| {
+        let code = match worker.try_wait() {
+            Ok(None) => return true,
+            Ok(Some(status)) => status.code().unwrap_or(1),
+            Err(err) => {
+                emit_log(app, "system", format!("Process wait error: {err}"));
+                1
+            }
+        };
+        proctree::release(worker);
+        if *failed == 0 {
+            *failed = code;
+        }
+        false
+    });
+    workers.len()
+}
+
+/// Handles per-file completions reported by the script and re-emits the stage
+/// event with the next file. A non-zero `wait` keeps listening until the reader
+/// threads hang up or the timeout passes.
+fn drain_file_progress(
+    app: &AppHandle,
+    request: &StartRequest,
+    plan: &FolderPlan,
+    stage: &mut StageEvent,
+    progress_rx: &Receiver<FileProgress>,
+    wait: Duration,
+) {
+    loop {
+        let update = if wait.is_zero() {
+            match progress_rx.try_recv() {
+                Ok(update) => update,
+                Err(_) => return,
+            }
+        } else {
+            match progress_rx.recv_timeout(wait) {
+                Ok(update) => update,
+                Err(_) => return,
+            }
+        };
+
+        let planned = plan
+            .files
+            .iter()
+            .find(|f| scanner::same_path(&f.source, &update.source));
+
+        if update.status == "ok" {
+            if let Some(planned) = planned {
+                manifest::record(app, planned, planned.model_file.as_deref().unwrap_or(&request.model_file));
+                run_post_stages(app, request, planned);
+                if request.keep_audio {
+                    match preprocess::convert_kept_audio(&longpath::extended(Path::new(&planned.output_dir)), &request.kept_audio) {
+                        Ok(Some(path)) => emit_log(app, "system", format!("Kept audio: {}", path.display())),
+                        Ok(None) => {}
+                        Err(err) => emit_log(app, "system", err),
+                    }
+                }
+                search::index_transcript(app, planned);
+                spawn_upload(app, request, planned);
+            }
+        }
+
+        record_file_result(
+            app,
+            FileResult {
+                source: update.source,
+                status: update.status,
+                output_dir: planned.map(|p| p.output_dir.clone()),
+                finished_at_epoch: now_epoch(),
+            },
+        );
+
+        // The script works through the plan in order; with GPU workers sharing
+        // it, this is only an estimate of what starts next.
+        stage.files_done += 1;
+        stage.current_file = plan.files.get(stage.files_done).map(|f| f.source.clone());
+        let _ = app.emit("transcribe://stage", stage.clone());
+    }
+}
+
+/// Uploads run off the worker thread so slow links don't hold up the next file.
+fn spawn_upload(app: &AppHandle, request: &StartRequest, planned: &PlannedFile) {
+    let s3_settings = settings::load(app).s3;
+    if !s3_settings.enabled {
+        return;
+    }
+
+    let app = app.clone();
+    let source = planned.source.clone();
+    let output_root = PathBuf::from(request.output_folder.trim());
+    let output_dir = PathBuf::from(&planned.output_dir);
+    thread::spawn(move || {
+        s3::upload_outputs(&app, &s3_settings, &source, &output_root, &output_dir);
+    });
+}
+
+fn record_file_result(app: &AppHandle, result: FileResult) {
+    let state = app.state::<RunnerState>();
+    let Ok(mut slot) = state.report.lock() else {
+        return;
+    };
+    if let Some(report) = slot.as_mut() {
+        notify::file_finished(app, &report.run_id, &result);
+        report.results.push(result);
+        // Saved per file so an interrupted run can be resumed from history.
+        if let Err(err) = history::save_report(app, report) {
+            emit_log(app, "system", err);
+        }
+    }
+}
+
+/// Moves files that already have a result in this run's report out of the
+/// plan, so a resumed run does not transcribe them twice.
+fn skip_finished_files(app: &AppHandle, plan: &mut FolderPlan) {
+    let state = app.state::<RunnerState>();
+    let finished: Vec<String> = state
+        .report
+        .lock()
+        .ok()
+        .and_then(|r| r.as_ref().map(|r| r.results.iter().map(|f| f.source.clone()).collect()))
+        .unwrap_or_default();
+    if finished.is_empty() {
+        return;
+    }
+
+    let (done, remaining): (Vec<_>, Vec<_>) = plan
+        .files
+        .drain(..)
+        .partition(|f| finished.iter().any(|s| scanner::same_path(s, &f.source)));
+    plan.files = remaining;
+    plan.skipped.extend(done.into_iter().map(|f| SkippedFile {
+        source: f.source,
+        reason: SkipReason::AlreadyTranscribed,
+        detail: "Finished before the run was interrupted".to_string(),
+    }));
+}
+
+fn spawn_worker(app: AppHandle, request: StartRequest, first_folder: usize) {
+    thread::spawn(move || {
+        let state = app.state::<RunnerState>();
+
+        let script_path = match resolve_script_path(&app, request.script_path.clone()) {
+            Ok(path) => path,
+            Err(err) => {
+                emit_log(&app, "system", err.clone());
+                cleanup_after_run(&app, false, 1, err);
+                return;
+            }
+        };
+
+        emit_log(
+            &app,
+            "system",
+            format!("Using batch script: {}", script_path.display()),
+        );
+
+        // Extended so the script can poll the flags inside long or UNC output paths.
+        let pause_path = longpath::extended(&PathBuf::from(request.output_folder.trim()).join(".transcribe.pause"));
+        if let Ok(mut pause) = state.pause_flag.lock() {
+            *pause = Some(pause_path.clone());
+        }
+
+        if pause_path.exists() {
+            let _ = fs::remove_file(&pause_path);
+        }
+        let stop_path = pause_path.with_file_name(STOP_FLAG_FILE);
+        let _ = fs::remove_file(&stop_path);
+
+        let powershell_bin = if cfg!(target_os = "windows") {
+            "powershell"
+        } else {
+            "pwsh"
+        };
+
+        let run_id = state
+            .report
+            .lock()
+            .ok()
+            .and_then(|r| r.as_ref().map(|r| r.run_id.clone()))
+            .unwrap_or_else(|| format!("run-{}", now_epoch()));
+
+        let total = request.input_folders.len();
+        let mut final_code = 0;
+        let mut final_message = "Transcription complete.".to_string();
+        let mut success = true;
+
+        let mut seen_fingerprints = HashMap::new();
+        let audio_budget = request.max_audio_hours.filter(|h| *h > 0.0);
+        let mut audio_remaining_secs = audio_budget.map(|h| (h * 3600.0).round() as u64);
+
+        for (index, folder) in request.input_folders.iter().enumerate().skip(first_folder) {
+            if current_stop_requested(&app) {
+                success = false;
+                final_code = 130;
+                final_message = "Stopped by user before next folder.".to_string();
+                emit_log(&app, "system", &final_message);
+                break;
+            }
+
+            let mut stage = StageEvent {
+                index: index + 1,
+                total,
+                input_folder: folder.clone(),
+                files_total: 0,
+                files_done: 0,
+                files_skipped: 0,
+                current_file: None,
+            };
+            let _ = app.emit("transcribe://stage", stage.clone());
+
+            checkpoint::advance(&app, index, folder);
+
+            let request = match request.for_folder(folder) {
+                Ok(request) => request,
+                Err(err) => {
+                    success = false;
+                    final_code = 1;
+                    final_message = err;
+                    emit_log(&app, "system", &final_message);
+                    break;
+                }
+            };
+
+            // Streams are captured into a staging folder that is then scanned like any other.
+            let folder = if stream::is_stream_url(folder) {
+                match stream::capture(&app, folder, &request.output_folder) {
+                    Ok(staging) => staging.display().to_string(),
+                    Err(err) => {
+                        success = false;
+                        final_code = 1;
+                        final_message = err;
+                        emit_log(&app, "system", &final_message);
+                        break;
+                    }
+                }
+            } else {
+                folder.clone()
+            };
+            if current_stop_requested(&app) {
+                success = false;
+                final_code = 130;
+                final_message = "Stopped by user.".to_string();
+                emit_log(&app, "system", &final_message);
+                break;
+            }
+
+            let scan_options = ScanOptions {
+                before_date: request.before_date.clone(),
+                after_date: request.after_date.clone(),
+                exclude_patterns: request
+                    .exclude_patterns
+                    .clone()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(request.archive_sources.exclude_pattern())
+                    .collect(),
+                limit: request.limit,
+                no_recursive: request.no_recursive,
+                output_template: request.output_template.clone(),
+                extensions: request.extensions.clone(),
+                min_duration_secs: request.min_duration_secs,
+                max_duration_secs: request.max_duration_secs,
+                order: request.sort_order,
+                metadata: filemeta::sources(&app),
+            };
+            let mut plan = match scanner::build_folder_plan(&folder, &request.output_folder, &scan_options) {
+                Ok(plan) => plan,
+                Err(err) => {
+                    success = false;
+                    final_code = 1;
+                    final_message = err;
+                    emit_log(&app, "system", &final_message);
+                    break;
+                }
+            };
+
+            if !request.only_files.is_empty() {
+                plan.files
+                    .retain(|f| request.only_files.iter().any(|s| scanner::same_path(s, &f.source)));
+                plan.skipped.clear();
+            }
+            skip_finished_files(&app, &mut plan);
+            manifest::skip_transcribed(&app, &mut plan, &request.model_file, request.force);
+            if request.dedupe {
+                dedupe::skip_duplicates(&app, &mut plan, &mut seen_fingerprints);
+            }
+            if let (Some(hours), Some(remaining)) = (audio_budget, audio_remaining_secs.as_mut()) {
+                scanner::cap_audio_budget(&mut plan, remaining, &format!("{hours} h"));
+            }
+            record_plan(&app, &plan);
+
+            let staging_dir = staging::staging_dir(request.staging_folder.as_deref(), &run_id, index);
+            if request.copy_local {
+                staging::assign(&mut plan, &staging_dir);
+            }
+
+            stage.files_total = plan.files.len();
+            stage.files_skipped = plan.skipped.len();
+            stage.current_file = plan.files.first().map(|f| f.source.clone());
+            let _ = app.emit("transcribe://stage", stage.clone());
+
+            if plan.files.is_empty() {
+                emit_log(
+                    &app,
+                    "system",
+                    format!(
+                        "Nothing to transcribe in folder {}/{} ({} skipped).",
+                        index + 1,
+                        total,
+                        plan.skipped.len()
+                    ),
+                );
+                continue;
+            }
+
+            let plan_path = std::env::temp_dir().join(format!(
+                "church-transcriber-{}-plan-{}.json",
+                run_id,
+                index + 1
+            ));
+            let copier = if request.copy_local {
+                match staging::prefetch(&app, &plan, &staging_dir) {
+                    Ok(copier) => Some(copier),
+                    Err(err) => {
+                        success = false;
+                        final_code = 1;
+                        final_message = err;
+                        emit_log(&app, "system", &final_message);
+                        break;
+                    }
+                }
+            } else {
+                None
+            };
+            if let Err(err) = write_plan_file(&plan_path, &plan) {
+                staging::finish(&staging_dir, copier);
+                success = false;
+                final_code = 1;
+                final_message = err;
+                emit_log(&app, "system", &final_message);
+                break;
+            }
+
+            let mut cmd = Command::new(powershell_bin);
+            cmd.arg("-NoProfile")
+                .arg("-ExecutionPolicy")
+                .arg("Bypass")
+                .arg("-File")
+                .arg(&script_path)
                 .arg("-InputFolder")
-                .arg(folder)
+                .arg(longpath::extended(Path::new(&folder)))
                 .arg("-OutputFolder")
-                .arg(request.output_folder.trim())
+                .arg(longpath::extended(Path::new(request.output_folder.trim())))
                 .arg("-WhisperExe")
                 .arg(request.whisper_exe.trim())
                 .arg("-ModelFile")
                 .arg(request.model_file.trim())
                 .arg("-PauseFlagFile")
                 .arg(&pause_path)
+                .arg("-StopFlagFile")
+                .arg(&stop_path)
+                .arg("-PlanFile")
+                .arg(&plan_path)
                 .arg("-Threads")
                 .arg(request.threads.to_string())
                 .stdout(Stdio::piped())
@@ -556,11 +2135,8 @@ fn spawn_worker(app: AppHandle, request: StartRequest) {
                     cmd.arg("-BeforeDate").arg(trimmed);
                 }
             }
-
-            if let Some(limit) = request.limit {
-                if limit > 0 {
-                    cmd.arg("-Limit").arg(limit.to_string());
-                }
+            if let Some(after_date) = request.after_date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+                cmd.arg("-AfterDate").arg(after_date);
             }
 
             if request.fast_scan {
@@ -569,12 +2145,67 @@ fn spawn_worker(app: AppHandle, request: StartRequest) {
             if request.force {
                 cmd.arg("-Force");
             }
-            if request.no_recursive {
-                cmd.arg("-NoRecursive");
-            }
             if request.keep_audio {
                 cmd.arg("-KeepAudio");
             }
+            if request.checkpoint_minutes > 0 {
+                cmd.arg("-CheckpointMinutes").arg(request.checkpoint_minutes.max(5).to_string());
+            }
+            chunking::configure(&mut cmd, &request.chunking);
+            if !request.garbage_retry {
+                cmd.arg("-NoGarbageRetry");
+            }
+            if let Some(fallback) = request.fallback_model.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+                cmd.arg("-FallbackModel").arg(fallback);
+            }
+            // `begin_run` has already rejected a missing RNNoise model.
+            if let Ok(Some(filter)) = preprocess::audio_filter(&request.loudness, &request.denoise) {
+                cmd.arg("-AudioFilter").arg(filter);
+            }
+            if request.denoise.enabled {
+                cmd.arg("-DenoiseStats");
+            }
+            if let Some(detect) = preprocess::silence_detect(&request.trim_silence) {
+                cmd.arg("-SilenceDetect").arg(detect);
+            }
+            if let Some(detect) = music::pause_detect(&request.music) {
+                cmd.arg("-MusicDetect").arg(detect);
+                cmd.arg("-MusicMinSecs").arg(request.music.min_music_secs.max(10).to_string());
+                cmd.arg("-MusicMode").arg(request.music.mode.cli_name());
+            }
+            if request.channels.enabled {
+                cmd.arg("-SplitChannels");
+            }
+            if request.translation.enabled && request.translation.method == TranslationMethod::Whisper {
+                cmd.arg("-TranslationPass");
+            }
+            if let Some(language) = request.language.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+                cmd.arg("-Language").arg(language);
+            }
+            if let Some(temperature) = request.temperature {
+                cmd.arg("-Temperature").arg(temperature.to_string());
+            }
+            if let Some(beam_size) = request.beam_size.filter(|b| *b > 0) {
+                cmd.arg("-BeamSize").arg(beam_size.to_string());
+            }
+            if let Some(best_of) = request.best_of.filter(|b| *b > 0) {
+                cmd.arg("-BestOf").arg(best_of.to_string());
+            }
+            if let Some(threshold) = request.entropy_threshold {
+                cmd.arg("-EntropyThreshold").arg(threshold.to_string());
+            }
+            if request.engine != Engine::WhisperCpp {
+                cmd.arg("-Engine").arg(request.engine.cli_name());
+            }
+            if request.engine == Engine::Cloud {
+                cloudengine::configure(&mut cmd, &settings::load(&app).cloud_transcription);
+            }
+            if let Some(device) = request.device.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+                cmd.arg("-Device").arg(device);
+            }
+            if let Some(compute_type) = request.compute_type.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                cmd.arg("-ComputeType").arg(compute_type);
+            }
 
             emit_log(
                 &app,
@@ -582,66 +2213,145 @@ fn spawn_worker(app: AppHandle, request: StartRequest) {
                 format!("Starting folder {}/{}: {}", index + 1, total, folder),
             );
 
-            let mut child = match cmd.spawn() {
-                Ok(process) => process,
-                Err(err) => {
+            let devices = request.gpu_workers.worker_devices();
+            let claim_dir = std::env::temp_dir().join(format!(
+                "church-transcriber-{}-claims-{}",
+                run_id,
+                index + 1
+            ));
+            let commands = if devices.is_empty() {
+                vec![cmd]
+            } else {
+                if let Err(err) = fs::create_dir_all(&claim_dir) {
+                    let _ = fs::remove_file(&plan_path);
+                    staging::finish(&staging_dir, copier);
                     success = false;
                     final_code = 1;
-                    final_message = format!("Failed to start PowerShell process: {err}");
+                    final_message = format!("Could not create {}: {err}", claim_dir.display());
                     emit_log(&app, "system", &final_message);
                     break;
                 }
+                let names: Vec<String> = devices.iter().map(|d| format!("GPU {d}")).collect();
+                emit_log(&app, "system", format!("Sharing the folder between workers on {}.", names.join(", ")));
+                devices
+                    .iter()
+                    .map(|&device| gpuworkers::worker_command(&cmd, device, &claim_dir))
+                    .collect()
             };
 
-            if let Some(stdout) = child.stdout.take() {
-                spawn_log_reader(stdout, "stdout", app.clone());
+            let (progress_tx, progress_rx) = mpsc::channel();
+            let mut children: Vec<Child> = Vec::new();
+            let mut spawn_error = None;
+            for mut command in commands {
+                proctree::isolate(&mut command);
+                let mut child = match command.spawn() {
+                    Ok(process) => process,
+                    Err(err) => {
+                        spawn_error = Some(err);
+                        break;
+                    }
+                };
+
+                if let Err(err) = proctree::track(&child) {
+                    emit_log(&app, "system", err);
+                }
+
+                // Each worker's stdout and stderr share the file that worker is on.
+                let source_file = Arc::new(Mutex::new(None));
+                if let Some(stdout) = child.stdout.take() {
+                    spawn_log_reader(
+                        stdout,
+                        "stdout",
+                        app.clone(),
+                        Some(progress_tx.clone()),
+                        KeywordWatch::load(&app),
+                        source_file.clone(),
+                    );
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    spawn_log_reader(stderr, "stderr", app.clone(), None, None, source_file);
+                }
+                children.push(child);
             }
-            if let Some(stderr) = child.stderr.take() {
-                spawn_log_reader(stderr, "stderr", app.clone());
+            // The readers hold the remaining senders; the channel closes when they finish.
+            drop(progress_tx);
+
+            if let Some(err) = spawn_error {
+                for child in children.iter_mut() {
+                    proctree::kill(child);
+                    proctree::release(child);
+                }
+                let _ = fs::remove_file(&plan_path);
+                let _ = fs::remove_dir_all(&claim_dir);
+                staging::finish(&staging_dir, copier);
+                success = false;
+                final_code = 1;
+                final_message = format!("Failed to start PowerShell process: {err}");
+                emit_log(&app, "system", &final_message);
+                break;
             }
 
+            let mut children = children.into_iter();
             if let Ok(mut child_slot) = state.child.lock() {
-                *child_slot = Some(child);
+                *child_slot = children.next();
+            }
+            if let Ok(mut workers) = state.workers.lock() {
+                workers.extend(children);
             }
 
+            let mut first_code: Option<i32> = None;
+            let mut worker_code = 0;
             let exit_code = loop {
-                if current_stop_requested(&app) {
+                if current_stop_immediate(&app) {
                     if let Ok(mut child_slot) = state.child.lock() {
                         if let Some(ch) = child_slot.as_mut() {
-                            let _ = ch.kill();
+                            proctree::kill(ch);
                         }
                     }
+                    kill_workers(&state);
                 }
 
-                let mut done: Option<i32> = None;
-                if let Ok(mut child_slot) = state.child.lock() {
-                    if let Some(ch) = child_slot.as_mut() {
-                        match ch.try_wait() {
-                            Ok(Some(status)) => {
-                                done = Some(status.code().unwrap_or(1));
-                                *child_slot = None;
-                            }
-                            Ok(None) => {}
-                            Err(err) => {
-                                emit_log(&app, "system", format!("Process wait error: {err}"));
-                                done = Some(1);
-                                *child_slot = None;
+                if first_code.is_none() {
+                    if let Ok(mut child_slot) = state.child.lock() {
+                        if let Some(ch) = child_slot.as_mut() {
+                            match ch.try_wait() {
+                                Ok(Some(status)) => {
+                                    first_code = Some(status.code().unwrap_or(1));
+                                    proctree::release(ch);
+                                    *child_slot = None;
+                                }
+                                Ok(None) => {}
+                                Err(err) => {
+                                    emit_log(&app, "system", format!("Process wait error: {err}"));
+                                    first_code = Some(1);
+                                    proctree::release(ch);
+                                    *child_slot = None;
+                                }
                             }
+                        } else {
+                            first_code = Some(1);
                         }
                     } else {
-                        done = Some(1);
+                        first_code = Some(1);
                     }
-                } else {
-                    done = Some(1);
                 }
+                let workers_left = reap_workers(&app, &state, &mut worker_code);
+
+                drain_file_progress(&app, &request, &plan, &mut stage, &progress_rx, Duration::ZERO);
 
-                if let Some(code) = done {
-                    break code;
+                if let (Some(code), 0) = (first_code, workers_left) {
+                    break if code != 0 { code } else { worker_code };
                 }
 
                 thread::sleep(Duration::from_millis(180));
             };
 
+            drain_file_progress(&app, &request, &plan, &mut stage, &progress_rx, Duration::from_secs(2));
+
+            let _ = fs::remove_file(&plan_path);
+            let _ = fs::remove_dir_all(&claim_dir);
+            staging::finish(&staging_dir, copier);
+
             if exit_code != 0 {
                 success = false;
                 final_code = exit_code;
@@ -671,39 +2381,167 @@ fn run_preflight(app: AppHandle, request: PreflightRequest) -> PreflightReport {
 }
 
 #[tauri::command]
-fn export_run_logs(output_folder: String, lines: Vec<String>) -> Result<String, String> {
-    let folder = output_folder.trim();
-    if folder.is_empty() {
-        return Err("Output folder is required for log export.".to_string());
-    }
-
-    let output_path = PathBuf::from(folder);
-    fs::create_dir_all(&output_path)
-        .map_err(|err| format!("Could not create output folder {}: {err}", output_path.display()))?;
+fn start_transcription(
+    app: AppHandle,
+    state: State<RunnerState>,
+    request: StartRequest,
+) -> Result<RunnerStatus, String> {
+    let report = RunReport::new(
+        format!("run-{}", now_epoch()),
+        request.input_folders.clone(),
+        request.output_folder.trim().to_string(),
+    );
+    begin_run(app, state, request, report, 0)
+}
 
-    let ts = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
+/// Transcribes one recording (say, one the pastor just emailed) with the
+/// given run settings. The folder filters don't apply; progress, events and
+/// the run report are the same as for any other run.
+#[tauri::command]
+fn transcribe_single_file(
+    app: AppHandle,
+    state: State<RunnerState>,
+    path: String,
+    options: StartRequest,
+) -> Result<RunnerStatus, String> {
+    let path = longpath::plain(path.trim());
+    if !longpath::extended(Path::new(&path)).is_file() {
+        return Err(format!("File not found: {path}"));
+    }
+    if !scanner::is_media_file(Path::new(&path), &[]) {
+        return Err(format!("Not a supported media file: {path}"));
+    }
+    let request = StartRequest {
+        input_folders: vec![path],
+        before_date: None,
+        after_date: None,
+        limit: None,
+        exclude_patterns: None,
+        extensions: Vec::new(),
+        min_duration_secs: None,
+        max_duration_secs: None,
+        max_audio_hours: None,
+        folder_overrides: HashMap::new(),
+        only_files: Vec::new(),
+        ..options
+    };
+    start_transcription(app, state, request)
+}
 
-    let file_path = output_path.join(format!("church-transcriber-log-{ts}.txt"));
-    let body = lines.join("\n");
+/// Picks up the run recorded in the checkpoint at the folder it was working
+/// on, skipping files that already have a result.
+#[tauri::command]
+fn resume_last_run(app: AppHandle, state: State<RunnerState>) -> Result<RunnerStatus, String> {
+    let checkpoint = checkpoint::load(&app).ok_or_else(|| "There is no interrupted run to resume.".to_string())?;
+    let mut report = history::load_report(&app, &checkpoint.run_id)?;
+    report.finished_at_epoch = None;
+    report.success = None;
+    report.code = None;
+    report.message = None;
+
+    emit_log(
+        &app,
+        "system",
+        format!(
+            "Resuming {} at folder {}/{} ({} files already finished).",
+            report.run_id,
+            checkpoint.folder_index + 1,
+            checkpoint.request.input_folders.len(),
+            report.results.len()
+        ),
+    );
+    begin_run(app, state, checkpoint.request, report, checkpoint.folder_index)
+}
 
-    fs::write(&file_path, body)
-        .map_err(|err| format!("Failed to write log export {}: {err}", file_path.display()))?;
+/// Re-queues only the files that failed in a run (the latest by default),
+/// optionally with a different model, whisper build, or thread count.
+#[tauri::command]
+fn retry_failed(
+    app: AppHandle,
+    state: State<RunnerState>,
+    run_id: Option<String>,
+    overrides: Option<RetryOverrides>,
+) -> Result<RunnerStatus, String> {
+    let report = match run_id.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+        Some(run_id) => history::load_report(&app, run_id)?,
+        None => history::list_reports(&app)
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No runs recorded yet.".to_string())?,
+    };
+    let failed = report.failed_sources();
+    if failed.is_empty() {
+        return Err(format!("Run {} has no failed files.", report.run_id));
+    }
+    let mut request = report.request.clone().ok_or_else(|| {
+        format!("Run {} predates saved run settings; start a new run instead.", report.run_id)
+    })?;
 
-    Ok(file_path.display().to_string())
+    let overrides = overrides.unwrap_or_default();
+    if let Some(threads) = overrides.threads.filter(|t| *t > 0) {
+        request.threads = threads;
+    }
+    if let Some(model_file) = overrides.model_file.filter(|m| !m.trim().is_empty()) {
+        request.model_file = model_file;
+    }
+    if let Some(whisper_exe) = overrides.whisper_exe.filter(|w| !w.trim().is_empty()) {
+        request.whisper_exe = whisper_exe;
+        request.whisper_exe_candidates.clear();
+    }
+    // Failed files can leave partial outputs behind, so always overwrite them.
+    request.force = true;
+    request.limit = None;
+    request.only_files = failed;
+
+    emit_log(
+        &app,
+        "system",
+        format!("Retrying {} failed file(s) from {}.", request.only_files.len(), report.run_id),
+    );
+    start_transcription(app, state, request)
 }
 
-#[tauri::command]
-fn start_transcription(
+fn begin_run(
     app: AppHandle,
     state: State<RunnerState>,
-    request: StartRequest,
+    mut request: StartRequest,
+    mut report: RunReport,
+    first_folder: usize,
 ) -> Result<RunnerStatus, String> {
+    if settings::load(&app).viewer.enabled {
+        return Err("This install is in read-only viewer mode; runs can only be started on the host.".to_string());
+    }
+
     if request.input_folders.is_empty() {
         return Err("At least one input folder is required.".to_string());
     }
+    if request.temperature.is_some_and(|t| !(0.0..=1.0).contains(&t)) {
+        return Err("Temperature must be between 0 and 1.".to_string());
+    }
+    if request.entropy_threshold.is_some_and(|t| !t.is_finite() || t < 0.0) {
+        return Err("Entropy threshold must be a positive number.".to_string());
+    }
+    preprocess::audio_filter(&request.loudness, &request.denoise)?;
+
+    for folder in &request.input_folders {
+        let effective = request.for_folder(folder)?;
+        if let Some(template) = effective.output_template.as_deref().filter(|t| !t.trim().is_empty()) {
+            scanner::validate_output_template(template.trim())?;
+        }
+        if let (Some(after), Some(before)) = (effective.after_date.as_deref(), effective.before_date.as_deref()) {
+            let (after, before) = (after.trim(), before.trim());
+            if !after.is_empty() && !before.is_empty() && after > before {
+                return Err(format!("After date {after} is later than before date {before} for {folder}."));
+            }
+        }
+        if effective.model_file.trim() != request.model_file.trim()
+            && request.engine.requires_model_file()
+            && !Path::new(effective.model_file.trim()).is_file()
+        {
+            return Err(format!("Model file for {folder} not found: {}", effective.model_file.trim()));
+        }
+    }
+    report.extra_output_folders = request.override_output_folders();
 
     {
         let running = state
@@ -720,8 +2558,14 @@ fn start_transcription(
         input_folders: request.input_folders.clone(),
         output_folder: request.output_folder.clone(),
         whisper_exe: request.whisper_exe.clone(),
+        whisper_exe_candidates: request.whisper_exe_candidates.clone(),
         model_file: request.model_file.clone(),
         script_path: request.script_path.clone(),
+        extensions: request.extensions.clone(),
+        no_recursive: request.no_recursive,
+        engine: request.engine,
+        fallback_model: request.fallback_model.clone(),
+        override_output_folders: report.extra_output_folders.clone(),
     };
 
     let preflight = build_preflight_report(&app, &preflight_req);
@@ -735,6 +2579,14 @@ fn start_transcription(
             .join(" | ");
         return Err(format!("Preflight failed. {failed}"));
     }
+    // Later runs and resumed checkpoints use the build that worked here.
+    if !request.whisper_exe_candidates.is_empty() {
+        let selection = whisperbin::select(&request.whisper_exe, &request.whisper_exe_candidates)?;
+        if selection.exe != request.whisper_exe.trim() {
+            emit_log(&app, "system", format!("Whisper executable: {}", selection.exe));
+        }
+        request.whisper_exe = selection.exe;
+    }
 
     let mut running = state
         .running
@@ -747,15 +2599,79 @@ fn start_transcription(
 
     *running = true;
     drop(running);
+    recent::record_run(&app, &request.input_folders, &request.output_folder);
 
     set_stop_requested(&state, false);
 
-    spawn_worker(app.clone(), request);
+    let checkpoint = checkpoint::RunCheckpoint {
+        run_id: report.run_id.clone(),
+        request: request.clone(),
+        folder_index: first_folder,
+        current_folder: request.input_folders.get(first_folder).cloned().unwrap_or_default(),
+        updated_at_epoch: now_epoch(),
+    };
+    if let Err(err) = checkpoint::save(&app, &checkpoint) {
+        emit_log(&app, "system", format!("Run checkpoint unavailable: {err}"));
+    }
+
+    report.request = Some(request.clone());
+    if let Ok(mut context) = state.log_context.lock() {
+        *context = LogContext {
+            run_id: Some(report.run_id.clone()),
+            source_file: None,
+            redactor: redaction::Redactor::new(&settings::load(&app).redaction),
+        };
+    }
+    notify::run_started(&app, &report);
+    if let Ok(mut slot) = state.report.lock() {
+        *slot = Some(report);
+    }
+    if let Ok(mut slot) = state.last_request.lock() {
+        *slot = Some(request.clone());
+    }
+
+    let pause_on_battery = request.pause_on_battery;
+    let threads = request.threads;
+    spawn_worker(app.clone(), request, first_folder);
+    if let Some(run_id) = get_status(&state).run_id {
+        if pause_on_battery {
+            power::watch(&app, run_id.clone());
+        }
+        telemetry::watch(&app, run_id, threads);
+    }
     emit_status(&app);
 
     Ok(get_status(&state))
 }
 
+/// Reminds the operator when a run has sat paused longer than configured. The
+/// pause timestamp doubles as a token so a resume/re-pause cancels stale watchers.
+fn watch_pause(app: &AppHandle, state: &RunnerState) {
+    let minutes = settings::load(app).notifications.paused_alert_minutes;
+    let since = now_epoch();
+    let Ok(mut paused_at) = state.paused_at.lock() else {
+        return;
+    };
+    if paused_at.is_some() || minutes == 0 {
+        return;
+    }
+    *paused_at = Some(since);
+
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(u64::from(minutes) * 60));
+        let state = app.state::<RunnerState>();
+        let still_paused = state.paused_at.lock().map(|p| *p == Some(since)).unwrap_or(false);
+        if still_paused && get_status(&state).paused {
+            notify::desktop(
+                &app,
+                "Transcription still paused",
+                &format!("The run has been paused for {minutes} minutes."),
+            );
+        }
+    });
+}
+
 #[tauri::command]
 fn toggle_pause(app: AppHandle, state: State<RunnerState>, paused: bool) -> Result<RunnerStatus, String> {
     let is_running = state
@@ -775,6 +2691,10 @@ fn toggle_pause(app: AppHandle, state: State<RunnerState>, paused: bool) -> Resu
         .clone()
         .ok_or_else(|| "Pause flag path not initialized.".to_string())?;
 
+    if let Ok(mut reason) = state.pause_reason.lock() {
+        *reason = None;
+    }
+
     if paused {
         fs::write(&pause_path, b"paused")
             .map_err(|err| format!("Failed to write pause flag: {err}"))?;
@@ -783,18 +2703,29 @@ fn toggle_pause(app: AppHandle, state: State<RunnerState>, paused: bool) -> Resu
             "system",
             format!("Pause requested (flag: {}).", pause_path.display()),
         );
+        watch_pause(&app, &state);
     } else if pause_path.exists() {
         fs::remove_file(&pause_path).map_err(|err| format!("Failed to clear pause flag: {err}"))?;
         emit_log(&app, "system", "Resume requested.");
     }
+    if !paused {
+        if let Ok(mut paused_at) = state.paused_at.lock() {
+            *paused_at = None;
+        }
+    }
 
-    let status = get_status(&state);
-    let _ = app.emit("transcribe://status", status.clone());
-    Ok(status)
+    emit_status(&app);
+    Ok(get_status(&state))
 }
 
+/// Immediate stops kill the batch script; graceful ones let it finish the
+/// current file (resuming it if paused) and end the run before the next.
 #[tauri::command]
-fn stop_transcription(app: AppHandle, state: State<RunnerState>) -> Result<RunnerStatus, String> {
+fn stop_transcription(
+    app: AppHandle,
+    state: State<RunnerState>,
+    stop_mode: Option<StopMode>,
+) -> Result<RunnerStatus, String> {
     let is_running = state
         .running
         .lock()
@@ -805,18 +2736,54 @@ fn stop_transcription(app: AppHandle, state: State<RunnerState>) -> Result<Runne
     }
     drop(is_running);
 
+    let mode = stop_mode.unwrap_or_default();
+    if mode == StopMode::Graceful {
+        if let Some(stop_flag) = stop_flag_path(&state) {
+            fs::write(&stop_flag, b"stop")
+                .map_err(|err| format!("Failed to create stop flag {}: {err}", stop_flag.display()))?;
+        }
+        clear_pause_flag_file(&state);
+    }
+    if let Ok(mut slot) = state.stop_mode.lock() {
+        *slot = mode;
+    }
     set_stop_requested(&state, true);
 
+    if mode == StopMode::Graceful {
+        emit_log(&app, "system", "Stop requested. The current file will finish first.");
+        emit_status(&app);
+        return Ok(get_status(&state));
+    }
+
     if let Ok(mut child_slot) = state.child.lock() {
         if let Some(ch) = child_slot.as_mut() {
-            let _ = ch.kill();
+            proctree::kill(ch);
         }
     }
+    kill_workers(&state);
 
     emit_log(&app, "system", "Stop requested. Finishing current checkpoint...");
-    let status = get_status(&state);
-    let _ = app.emit("transcribe://status", status.clone());
-    Ok(status)
+    emit_status(&app);
+    Ok(get_status(&state))
+}
+
+#[tauri::command]
+fn get_skipped_files(
+    app: AppHandle,
+    state: State<RunnerState>,
+    run_id: String,
+) -> Result<Vec<SkippedFile>, String> {
+    let current = state
+        .report
+        .lock()
+        .map_err(|_| "Runner state lock failed".to_string())?
+        .clone()
+        .filter(|r| r.run_id == run_id.trim());
+
+    match current {
+        Some(report) => Ok(report.skipped),
+        None => history::load_report(&app, &run_id).map(|r| r.skipped),
+    }
 }
 
 #[tauri::command]
@@ -826,18 +2793,131 @@ fn get_runner_status(state: State<RunnerState>) -> RunnerStatus {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let mut headless = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(headless) => headless,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut context = tauri::generate_context!();
+    if headless.is_some() {
+        context.config_mut().app.windows.clear();
+    }
+
     tauri::Builder::default()
+        // Registered first so a second launch exits before it builds a runner.
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| cli::handoff(app, args)))
         .manage(RunnerState::default())
+        .manage(logfile::LogFileState::default())
+        .manage(logfilter::LogFilterState::default())
+        .manage(recovery::RecoveryState::default())
+        .manage(remote::RemoteApiState::default())
+        .manage(cloudsync::CloudSyncState::default())
+        .manage(schedule::SchedulerState::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(move |app| {
+            let handle = app.handle().clone();
+            remote::apply_settings(&handle, &settings::load(&handle).remote_api);
+            match headless.take() {
+                Some(options) => {
+                    if let Err(err) = cli::start(&handle, options) {
+                        eprintln!("{err}");
+                        handle.exit(2);
+                    }
+                }
+                None => {
+                    tray::install(&handle)?;
+                    // Before the scheduler, so its cleanup can't touch a run it starts.
+                    recovery::recover(&handle);
+                    schedule::start(&handle);
+                }
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // Closing the window mid-run hides it to the tray instead of killing the run.
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                let state = window.state::<RunnerState>();
+                if get_status(&state).running {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             run_preflight,
-            export_run_logs,
+            benchmark::run_benchmark,
+            benchmark::compare_models,
+            hardware::suggest_model,
+            updater::check_whisper_update,
+            updater::install_whisper_update,
+            modelinfo::list_models,
+            modelinfo::inspect_model,
+            quality::get_quality_report,
+            transcriptdiff::diff_transcripts,
+            versions::list_transcript_versions,
+            filemeta::get_file_metadata,
+            filemeta::set_file_metadata,
+            rename::rename_outputs,
+            reveal::open_output_folder,
+            reveal::reveal_transcript,
+            recent::get_recent_folders,
+            recent::pin_favorite_folder,
+            stats::get_archive_stats,
+            subtitles::burn_subtitles,
+            clip::render_clip,
+            dropped::add_dropped_paths,
+            support::export_run_logs,
+            logfile::get_log_files,
+            logfile::open_log_file,
+            logfilter::set_log_verbosity,
             start_transcription,
+            transcribe_single_file,
+            resume_last_run,
+            recovery::get_interrupted_run,
+            retry_failed,
             toggle_pause,
             stop_transcription,
-            get_runner_status
+            get_runner_status,
+            get_skipped_files,
+            settings::get_settings,
+            settings::save_settings,
+            redaction::set_redaction_list,
+            digest::generate_digest_now,
+            notify::send_test_email,
+            publish::youtube_authorize,
+            publish::upload_youtube_captions,
+            cloudsync::cloud_sync_begin_auth,
+            cloudsync::cloud_sync_finish_auth,
+            cloudsync::sync_now,
+            manifest::rebuild_manifest,
+            search::search_transcripts,
+            search::rebuild_search_index,
+            viewer::get_transcript,
+            viewer::save_transcript_edit,
+            bundle::export_app_config,
+            bundle::import_app_config,
+            profiles::save_profile,
+            profiles::list_profiles,
+            profiles::delete_profile,
+            profiles::start_from_profile,
+            speakers::enroll_speaker,
+            speakers::list_speakers,
+            speakers::delete_speaker,
+            schedule::create_schedule,
+            schedule::list_schedules,
+            schedule::set_schedule_enabled,
+            schedule::delete_schedule,
+            remote::viewer_get_status,
+            remote::viewer_list_runs,
+            remote::viewer_list_transcripts,
+            remote::viewer_read_transcript,
+            remote::viewer_search
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
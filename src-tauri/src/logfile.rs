@@ -0,0 +1,179 @@
+use crate::LogEvent;
+use chrono::{Local, TimeZone};
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::UNIX_EPOCH,
+};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+const LOG_DIR: &str = "logs";
+const LOG_PREFIX: &str = "church-transcriber-";
+/// A day's log rolls over to `-1`, `-2`, ... once it reaches this size.
+const MAX_FILE_BYTES: u64 = 5 << 20;
+const MAX_FILES: usize = 30;
+
+struct OpenLog {
+    file: File,
+    day: String,
+    bytes: u64,
+}
+
+#[derive(Default)]
+pub struct LogFileState {
+    current: Mutex<Option<OpenLog>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFile {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified_epoch: u64,
+}
+
+fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?
+        .join(LOG_DIR);
+    fs::create_dir_all(&dir).map_err(|err| format!("Could not create log folder {}: {err}", dir.display()))?;
+    Ok(dir)
+}
+
+fn is_log_file(name: &str) -> bool {
+    name.starts_with(LOG_PREFIX) && name.ends_with(".log")
+}
+
+/// Today's first log file that still has room.
+fn next_path(dir: &Path, day: &str) -> (PathBuf, u64) {
+    let mut part = 0;
+    loop {
+        let name = if part == 0 {
+            format!("{LOG_PREFIX}{day}.log")
+        } else {
+            format!("{LOG_PREFIX}{day}-{part}.log")
+        };
+        let path = dir.join(name);
+        let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if bytes < MAX_FILE_BYTES {
+            return (path, bytes);
+        }
+        part += 1;
+    }
+}
+
+fn list(dir: &Path) -> Vec<LogFile> {
+    let mut files: Vec<LogFile> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| is_log_file(&e.file_name().to_string_lossy()))
+                .filter_map(|e| {
+                    let meta = e.metadata().ok()?;
+                    Some(LogFile {
+                        name: e.file_name().to_string_lossy().to_string(),
+                        path: e.path().display().to_string(),
+                        size_bytes: meta.len(),
+                        modified_epoch: meta
+                            .modified()
+                            .ok()
+                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                            .map(|d| d.as_secs())
+                            .unwrap_or(0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by(|a, b| b.modified_epoch.cmp(&a.modified_epoch).then(b.name.cmp(&a.name)));
+    files
+}
+
+/// Drops the oldest files beyond `MAX_FILES`.
+fn prune(dir: &Path) {
+    for old in list(dir).into_iter().skip(MAX_FILES) {
+        let _ = fs::remove_file(old.path);
+    }
+}
+
+fn open_log(app: &AppHandle, day: &str) -> Option<OpenLog> {
+    let dir = log_dir(app).ok()?;
+    let (path, bytes) = next_path(&dir, day);
+    let file = OpenOptions::new().create(true).append(true).open(&path).ok()?;
+    prune(&dir);
+    Some(OpenLog {
+        file,
+        day: day.to_string(),
+        bytes,
+    })
+}
+
+/// Appends a log line to today's file, rotating by day and size. Failures are
+/// ignored so logging never interrupts a run.
+pub fn append(app: &AppHandle, event: &LogEvent) {
+    let Some(state) = app.try_state::<LogFileState>() else {
+        return;
+    };
+    let Ok(mut current) = state.current.lock() else {
+        return;
+    };
+
+    let now = Local
+        .timestamp_millis_opt(event.timestamp_ms as i64)
+        .single()
+        .unwrap_or_else(Local::now);
+    let day = now.format("%Y-%m-%d").to_string();
+    let stale = current
+        .as_ref()
+        .map(|log| log.day != day || log.bytes >= MAX_FILE_BYTES)
+        .unwrap_or(true);
+    if stale {
+        *current = open_log(app, &day);
+    }
+
+    if let Some(log) = current.as_mut() {
+        let text = format!(
+            "{} {:<5} [{}] {}\n",
+            now.format("%Y-%m-%dT%H:%M:%S%.3f"),
+            event.level.label(),
+            event.stream,
+            event.line
+        );
+        if log.file.write_all(text.as_bytes()).is_ok() {
+            log.bytes += text.len() as u64;
+        }
+    }
+}
+
+/// Log files on disk, newest first.
+#[tauri::command]
+pub fn get_log_files(app: AppHandle) -> Result<Vec<LogFile>, String> {
+    Ok(list(&log_dir(&app)?))
+}
+
+/// Opens a log file, or the log folder when `name` is empty, in the system
+/// viewer.
+#[tauri::command]
+pub fn open_log_file(app: AppHandle, name: String) -> Result<(), String> {
+    let dir = log_dir(&app)?;
+    let name = name.trim();
+    let path = if name.is_empty() {
+        dir
+    } else {
+        list(&dir)
+            .into_iter()
+            .find(|f| f.name == name)
+            .map(|f| PathBuf::from(f.path))
+            .ok_or_else(|| format!("Log file not found: {name}"))?
+    };
+    app.opener()
+        .open_path(path.display().to_string(), None::<&str>)
+        .map_err(|err| format!("Could not open {}: {err}", path.display()))
+}
@@ -0,0 +1,134 @@
+use crate::{
+    settings::{self, LogLevel, LogSettings},
+    LogEvent,
+};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager};
+
+/// whisper.cpp prints every segment (`[00:01:02.000 --> 00:01:05.000] ...`);
+/// the panel shows at most one of them per interval.
+const SEGMENT_LINE_INTERVAL: Duration = Duration::from_secs(1);
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+struct Throttle {
+    settings: Option<LogSettings>,
+    window_start: Instant,
+    sent_in_window: u32,
+    last_segment_line: Option<Instant>,
+    suppressed: u64,
+}
+
+pub struct LogFilterState {
+    throttle: Mutex<Throttle>,
+}
+
+impl Default for LogFilterState {
+    fn default() -> Self {
+        Self {
+            throttle: Mutex::new(Throttle {
+                settings: None,
+                window_start: Instant::now(),
+                sent_in_window: 0,
+                last_segment_line: None,
+                suppressed: 0,
+            }),
+        }
+    }
+}
+
+/// What `emit_log` should send to the webview for one event.
+pub struct Admission {
+    pub emit: bool,
+    /// Lines dropped since the last emitted one, to report before it.
+    pub suppressed: u64,
+}
+
+fn is_segment_line(line: &str) -> bool {
+    let text = line.trim_start();
+    (text.starts_with('[') && text.contains(" --> ")) || text.starts_with("whisper_print_progress_callback")
+}
+
+/// Decides whether an event goes to the webview: below the verbosity it is
+/// dropped silently; segment chatter and info lines over the rate limit are
+/// dropped and counted. Warnings and errors are never rate limited.
+pub fn admit(app: &AppHandle, event: &LogEvent) -> Admission {
+    let pass = Admission {
+        emit: true,
+        suppressed: 0,
+    };
+    let Some(state) = app.try_state::<LogFilterState>() else {
+        return pass;
+    };
+    let Ok(mut throttle) = state.throttle.lock() else {
+        return pass;
+    };
+    let settings = throttle
+        .settings
+        .get_or_insert_with(|| settings::load(app).logging)
+        .clone();
+
+    if event.level < settings.verbosity {
+        return Admission {
+            emit: false,
+            suppressed: 0,
+        };
+    }
+
+    let now = Instant::now();
+    if now.duration_since(throttle.window_start) >= RATE_WINDOW {
+        throttle.window_start = now;
+        throttle.sent_in_window = 0;
+    }
+
+    let urgent = event.level >= LogLevel::Warn;
+    let chatter = is_segment_line(&event.line)
+        && throttle
+            .last_segment_line
+            .is_some_and(|last| now.duration_since(last) < SEGMENT_LINE_INTERVAL);
+    let over_rate = settings.max_events_per_second > 0 && throttle.sent_in_window >= settings.max_events_per_second;
+    if !urgent && (chatter || over_rate) {
+        throttle.suppressed += 1;
+        return Admission {
+            emit: false,
+            suppressed: 0,
+        };
+    }
+
+    if is_segment_line(&event.line) {
+        throttle.last_segment_line = Some(now);
+    }
+    throttle.sent_in_window += 1;
+    Admission {
+        emit: true,
+        suppressed: std::mem::take(&mut throttle.suppressed),
+    }
+}
+
+pub fn apply_settings(app: &AppHandle, logging: &LogSettings) {
+    if let Some(state) = app.try_state::<LogFilterState>() {
+        if let Ok(mut throttle) = state.throttle.lock() {
+            throttle.settings = Some(logging.clone());
+        }
+    }
+}
+
+/// Sets the minimum level sent to the log panel, and optionally the rate
+/// limit, and saves both to settings.
+#[tauri::command]
+pub fn set_log_verbosity(
+    app: AppHandle,
+    level: LogLevel,
+    max_events_per_second: Option<u32>,
+) -> Result<LogSettings, String> {
+    let mut all = settings::load(&app);
+    all.logging.verbosity = level;
+    if let Some(rate) = max_events_per_second {
+        all.logging.max_events_per_second = rate;
+    }
+    settings::save(&app, &all)?;
+    apply_settings(&app, &all.logging);
+    Ok(all.logging)
+}
@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+
+/// Windows refuses directories this long (and files past 260 characters)
+/// unless the path carries the `\\?\` prefix.
+const LONG_PATH: usize = 248;
+const VERBATIM: &str = r"\\?\";
+const VERBATIM_UNC: &str = r"\\?\UNC\";
+
+/// The `\\?\` form of a long absolute path, which Windows file APIs accept
+/// past MAX_PATH; `\\server\share\...` becomes `\\?\UNC\server\share\...`.
+/// Short and relative paths, and every path off Windows, come back as-is.
+pub fn extended(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if !cfg!(windows) || text.starts_with(VERBATIM) || text.chars().count() < LONG_PATH {
+        return path.to_path_buf();
+    }
+    // Verbatim paths skip normalization, so separators must be backslashes.
+    let text = text.replace('/', "\\");
+    if let Some(share) = text.strip_prefix(r"\\") {
+        PathBuf::from(format!("{VERBATIM_UNC}{share}"))
+    } else if path.is_absolute() {
+        PathBuf::from(format!("{VERBATIM}{text}"))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// `path` without a `\\?\` prefix, as users typed it and as the manifest and
+/// search index store it.
+pub fn plain(path: &str) -> String {
+    if let Some(share) = path.strip_prefix(VERBATIM_UNC) {
+        format!(r"\\{share}")
+    } else {
+        path.strip_prefix(VERBATIM).unwrap_or(path).to_string()
+    }
+}
+
+/// `\\server\share` for a UNC path, so preflight can say the share itself is
+/// unreachable rather than that a folder is missing.
+pub fn unc_share(path: &str) -> Option<String> {
+    let plain = plain(path.trim()).replace('/', "\\");
+    let rest = plain.strip_prefix(r"\\")?;
+    let mut parts = rest.split('\\').filter(|p| !p.is_empty());
+    let (server, share) = (parts.next()?, parts.next()?);
+    Some(format!(r"\\{server}\{share}"))
+}
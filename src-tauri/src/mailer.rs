@@ -0,0 +1,64 @@
+use crate::settings::SmtpSettings;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    Message, SmtpTransport, Transport,
+};
+
+fn parse_mailbox(value: &str) -> Result<Mailbox, String> {
+    value
+        .trim()
+        .parse::<Mailbox>()
+        .map_err(|err| format!("Invalid email address '{value}': {err}"))
+}
+
+fn build_transport(smtp: &SmtpSettings) -> Result<SmtpTransport, String> {
+    let host = smtp.host.trim();
+    if host.is_empty() {
+        return Err("SMTP host is not configured.".to_string());
+    }
+
+    let builder = if smtp.starttls {
+        SmtpTransport::starttls_relay(host)
+    } else {
+        SmtpTransport::relay(host)
+    }
+    .map_err(|err| format!("Could not configure SMTP relay {host}: {err}"))?
+    .port(smtp.port);
+
+    let builder = if smtp.username.trim().is_empty() {
+        builder
+    } else {
+        builder.credentials(Credentials::new(
+            smtp.username.trim().to_string(),
+            smtp.password.clone(),
+        ))
+    };
+
+    Ok(builder.build())
+}
+
+/// Sends a plain-text email to every recipient in one message.
+pub fn send_mail(smtp: &SmtpSettings, to: &[String], subject: &str, body: &str) -> Result<(), String> {
+    let recipients: Vec<&String> = to.iter().filter(|r| !r.trim().is_empty()).collect();
+    if recipients.is_empty() {
+        return Err("No email recipients configured.".to_string());
+    }
+
+    let mut message = Message::builder()
+        .from(parse_mailbox(&smtp.from)?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN);
+    for recipient in recipients {
+        message = message.to(parse_mailbox(recipient)?);
+    }
+
+    let message = message
+        .body(body.to_string())
+        .map_err(|err| format!("Could not build email: {err}"))?;
+
+    build_transport(smtp)?
+        .send(&message)
+        .map(|_| ())
+        .map_err(|err| format!("SMTP send failed: {err}"))
+}
@@ -0,0 +1,254 @@
+use crate::{
+    archive,
+    filemeta::FileMetadata,
+    scanner::{self, FolderPlan, PlannedFile, SkipReason, SkippedFile},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager};
+
+const MANIFEST_FILE: &str = "transcript-manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    pub fingerprint: String,
+    pub model: String,
+    pub source: String,
+    pub output_dir: String,
+    pub transcribed_at_epoch: u64,
+}
+
+/// Which recordings have been transcribed with which model, keyed by content
+/// fingerprint so renamed or moved files and outputs are still recognised.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Manifest {
+    entries: HashMap<String, ManifestEntry>,
+    /// Details set with `set_file_metadata`, keyed by source path.
+    metadata: HashMap<String, FileMetadata>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildSummary {
+    pub transcripts: usize,
+    pub imported: usize,
+    pub missing_source: usize,
+    pub missing_model: usize,
+}
+
+/// Models are compared by file name so the same model in another folder matches.
+pub fn model_key(model_file: &str) -> String {
+    Path::new(model_file.trim())
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn entry_key(fingerprint: &str, model: &str) -> String {
+    format!("{fingerprint}:{model}")
+}
+
+fn manifest_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(MANIFEST_FILE))
+}
+
+fn load(app: &AppHandle) -> Manifest {
+    manifest_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, manifest: &Manifest) -> Result<(), String> {
+    let path = manifest_path(app)?;
+    let body = serde_json::to_string(manifest)
+        .map_err(|err| format!("Failed to serialize transcript manifest: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Fingerprints each planned file and moves the ones the manifest already has
+/// for this model into the skipped list. A match from a different source path
+/// is reported as a duplicate. Recordings the manifest has never seen fall back
+/// to the old `raw.txt` check until `rebuild_manifest` imports the archive.
+/// `force` still fingerprints but skips nothing.
+pub fn skip_transcribed(app: &AppHandle, plan: &mut FolderPlan, model_file: &str, force: bool) {
+    let manifest = if force { Manifest::default() } else { load(app) };
+    let mut kept = Vec::new();
+
+    for mut file in plan.files.drain(..) {
+        let model = model_key(file.model_file.as_deref().unwrap_or(model_file));
+        let fingerprint = match scanner::content_fingerprint(Path::new(&file.source)) {
+            Ok(fingerprint) => fingerprint,
+            Err(err) => {
+                crate::emit_log(app, "system", format!("Manifest check skipped: {err}"));
+                kept.push(file);
+                continue;
+            }
+        };
+
+        match manifest.entries.get(&entry_key(&fingerprint, &model)) {
+            Some(done) if scanner::same_path(&done.source, &file.source) => plan.skipped.push(SkippedFile {
+                source: file.source,
+                reason: SkipReason::AlreadyTranscribed,
+                detail: format!("Already transcribed with {model} (output: {})", done.output_dir),
+            }),
+            Some(done) => plan.skipped.push(SkippedFile {
+                source: file.source,
+                reason: SkipReason::Duplicate,
+                detail: format!("Same recording as {} (transcript in {})", done.source, done.output_dir),
+            }),
+            None if !force
+                && !manifest.entries.values().any(|e| e.fingerprint == fingerprint)
+                && Path::new(&file.output_dir).join("raw.txt").exists() =>
+            {
+                plan.skipped.push(SkippedFile {
+                    detail: format!("raw.txt already exists in {}", file.output_dir),
+                    source: file.source,
+                    reason: SkipReason::AlreadyTranscribed,
+                })
+            }
+            None => {
+                file.fingerprint = Some(fingerprint);
+                kept.push(file);
+            }
+        }
+    }
+
+    plan.files = kept;
+}
+
+/// Adds a finished transcript to the manifest.
+pub fn record(app: &AppHandle, planned: &PlannedFile, model_file: &str) {
+    let Some(fingerprint) = planned.fingerprint.clone() else {
+        return;
+    };
+    let model = model_key(model_file);
+    let mut manifest = load(app);
+    manifest.entries.insert(
+        entry_key(&fingerprint, &model),
+        ManifestEntry {
+            fingerprint,
+            model,
+            source: planned.source.clone(),
+            output_dir: planned.output_dir.clone(),
+            transcribed_at_epoch: crate::now_epoch(),
+        },
+    );
+    if let Err(err) = save(app, &manifest) {
+        crate::emit_log(app, "system", err);
+    }
+}
+
+/// Every recorded transcript, one per recording and model.
+pub fn entries(app: &AppHandle) -> Vec<ManifestEntry> {
+    load(app).entries.into_values().collect()
+}
+
+/// Every recording's saved details, keyed by source path.
+pub fn file_metadata(app: &AppHandle) -> HashMap<String, FileMetadata> {
+    load(app).metadata
+}
+
+/// Stores `metadata` for `source`, replacing any earlier entry for the same
+/// file; empty metadata removes it.
+pub fn set_file_metadata(app: &AppHandle, source: &str, metadata: FileMetadata) -> Result<(), String> {
+    let mut manifest = load(app);
+    manifest.metadata.retain(|key, _| !scanner::same_path(key, source));
+    if metadata != FileMetadata::default() {
+        manifest.metadata.insert(source.to_string(), metadata);
+    }
+    save(app, &manifest)
+}
+
+/// Points entries for a transcript folder that was moved at its new location.
+pub fn move_output(app: &AppHandle, old: &str, new: &str) -> Result<(), String> {
+    let mut manifest = load(app);
+    let mut changed = false;
+    for entry in manifest.entries.values_mut().filter(|e| scanner::same_path(&e.output_dir, old) || e.output_dir == old) {
+        entry.output_dir = new.to_string();
+        changed = true;
+    }
+    if changed {
+        save(app, &manifest)?;
+    }
+    Ok(())
+}
+
+/// Follows a source recording that was moved, along with its saved details.
+pub fn move_source(app: &AppHandle, old: &str, new: &str) -> Result<(), String> {
+    let mut manifest = load(app);
+    for entry in manifest.entries.values_mut().filter(|e| e.source == old) {
+        entry.source = new.to_string();
+    }
+    if let Some(metadata) = manifest.metadata.remove(old) {
+        manifest.metadata.insert(new.to_string(), metadata);
+    }
+    save(app, &manifest)
+}
+
+/// Imports an existing archive by fingerprinting the source recorded in each
+/// transcript's `metadata.json`. Sources that are no longer reachable can't be
+/// fingerprinted and are counted instead.
+fn rebuild(app: &AppHandle, archive_root: &Path) -> Result<RebuildSummary, String> {
+    let entries = archive::list_entries(archive_root)?;
+    let mut manifest = load(app);
+    let mut summary = RebuildSummary {
+        transcripts: entries.len(),
+        ..RebuildSummary::default()
+    };
+
+    for entry in entries {
+        let Some(model) = entry.model_file.as_deref().map(model_key).filter(|m| !m.is_empty()) else {
+            summary.missing_model += 1;
+            continue;
+        };
+        let Some(source) = entry.source_file.filter(|s| Path::new(s).is_file()) else {
+            summary.missing_source += 1;
+            continue;
+        };
+        let fingerprint = match scanner::content_fingerprint(Path::new(&source)) {
+            Ok(fingerprint) => fingerprint,
+            Err(err) => {
+                crate::emit_log(app, "system", err);
+                summary.missing_source += 1;
+                continue;
+            }
+        };
+
+        let output_dir = archive_root.join(&entry.relative_dir).display().to_string();
+        manifest.entries.insert(
+            entry_key(&fingerprint, &model),
+            ManifestEntry {
+                fingerprint,
+                model,
+                source,
+                output_dir,
+                transcribed_at_epoch: crate::now_epoch(),
+            },
+        );
+        summary.imported += 1;
+    }
+
+    save(app, &manifest)?;
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn rebuild_manifest(app: AppHandle, archive_folder: String) -> Result<RebuildSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || rebuild(&app, Path::new(archive_folder.trim())))
+        .await
+        .map_err(|err| format!("Manifest rebuild task failed: {err}"))?
+}
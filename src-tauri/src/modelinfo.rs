@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::{fs::File, io::Read, path::Path};
+
+/// "ggml" read as a little-endian u32, as whisper.cpp writes it.
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+/// ggml ftype values carry the quantization version times this.
+const QNT_VERSION_FACTOR: i32 = 1000;
+/// The first whisper.cpp release that loads quantized models.
+const QUANTIZED_SINCE: &str = "v1.4.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelFormat {
+    Ggml,
+    Gguf,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub format: ModelFormat,
+    /// `f16`, `q5_0`, ...; unknown for GGUF and unrecognized files.
+    pub quantization: Option<String>,
+    pub quantized: bool,
+    /// `tiny`, `base.en`, `large-v3`, ... from the layer count and vocabulary.
+    pub size_class: Option<String>,
+    pub english_only: bool,
+}
+
+fn read_i32s(file: &mut File, count: usize) -> Option<Vec<i32>> {
+    let mut buffer = vec![0u8; count * 4];
+    file.read_exact(&mut buffer).ok()?;
+    Some(
+        buffer
+            .chunks_exact(4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    )
+}
+
+fn quantization_name(ftype: i32) -> String {
+    match ftype % QNT_VERSION_FACTOR {
+        0 => "f32".to_string(),
+        1 => "f16".to_string(),
+        2 => "q4_0".to_string(),
+        3 => "q4_1".to_string(),
+        7 => "q8_0".to_string(),
+        8 => "q5_0".to_string(),
+        9 => "q5_1".to_string(),
+        10 => "q2_k".to_string(),
+        11 => "q3_k".to_string(),
+        12 => "q4_k".to_string(),
+        13 => "q5_k".to_string(),
+        14 => "q6_k".to_string(),
+        other => format!("type {other}"),
+    }
+}
+
+fn size_class(audio_layers: i32, vocab: i32) -> Option<String> {
+    let name = match audio_layers {
+        4 => "tiny",
+        6 => "base",
+        12 => "small",
+        24 => "medium",
+        32 if vocab >= 51866 => "large-v3",
+        32 => "large",
+        _ => return None,
+    };
+    Some(if vocab == 51864 { format!("{name}.en") } else { name.to_string() })
+}
+
+/// Reads the model header to tell ggml from GGUF and find its quantization.
+pub fn inspect(path: &Path) -> Result<ModelInfo, String> {
+    let mut file = File::open(path).map_err(|err| format!("Could not open {}: {err}", path.display()))?;
+    let size_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)
+        .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+
+    let mut info = ModelInfo {
+        path: path.display().to_string(),
+        size_bytes,
+        format: ModelFormat::Unknown,
+        quantization: None,
+        quantized: false,
+        size_class: None,
+        english_only: false,
+    };
+    if &magic == GGUF_MAGIC {
+        info.format = ModelFormat::Gguf;
+    } else if u32::from_le_bytes(magic) == GGML_MAGIC {
+        info.format = ModelFormat::Ggml;
+        // n_vocab, audio ctx/state/head/layer, text ctx/state/head/layer, n_mels, ftype
+        if let Some(hparams) = read_i32s(&mut file, 11) {
+            let ftype = hparams[10];
+            info.quantization = Some(quantization_name(ftype));
+            info.quantized = ftype % QNT_VERSION_FACTOR > 1;
+            info.size_class = size_class(hparams[4], hparams[0]);
+            info.english_only = hparams[0] == 51864;
+        }
+    }
+    Ok(info)
+}
+
+/// Whether `whisper_version` can load the model. `Err` carries the reason
+/// it can't; an unknown version gets the benefit of the doubt.
+pub fn check_compatible(info: &ModelInfo, whisper_version: Option<&str>) -> Result<(), String> {
+    let too_old = |since: &str| {
+        whisper_version.is_some_and(|v| crate::updater::version_parts(v) < crate::updater::version_parts(since))
+    };
+    match info.format {
+        ModelFormat::Unknown => Err("Not a whisper.cpp model file (no ggml or GGUF header).".to_string()),
+        ModelFormat::Ggml if info.quantized && too_old(QUANTIZED_SINCE) => Err(format!(
+            "This {} model needs whisper.cpp {QUANTIZED_SINCE} or newer; whisper-cli is {}.",
+            info.quantization.as_deref().unwrap_or("quantized"),
+            whisper_version.unwrap_or_default()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Every model in `folder` with its format, quantization and size class.
+#[tauri::command]
+pub fn list_models(folder: String) -> Vec<ModelInfo> {
+    let mut models: Vec<ModelInfo> = crate::hardware::installed_models(Path::new(folder.trim()))
+        .into_iter()
+        .filter_map(|(path, _)| inspect(&path).ok())
+        .collect();
+    models.sort_by_key(|m| m.size_bytes);
+    models
+}
+
+#[tauri::command]
+pub fn inspect_model(model_file: String) -> Result<ModelInfo, String> {
+    inspect(Path::new(model_file.trim()))
+}
@@ -0,0 +1,94 @@
+use crate::transcript::Segment;
+use serde::{Deserialize, Serialize};
+
+pub const MUSIC_MARKER: &str = "[music]";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MusicMode {
+    /// Replace whatever whisper heard in a music range with `[music]`.
+    #[default]
+    Mark,
+    /// Mute music ranges before whisper and leave them out of the transcript.
+    Skip,
+}
+
+impl MusicMode {
+    pub fn cli_name(self) -> &'static str {
+        match self {
+            MusicMode::Mark => "mark",
+            MusicMode::Skip => "skip",
+        }
+    }
+}
+
+/// Finds worship sets and other music-dominant stretches, where whisper
+/// tends to hallucinate lyrics: long runs of sound with no breath pauses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MusicOptions {
+    pub enabled: bool,
+    pub mode: MusicMode,
+    /// Shortest unbroken stretch treated as music.
+    pub min_music_secs: u32,
+    /// Quieter than this counts as a pause.
+    pub pause_noise_db: i32,
+    /// Speech pauses for at least this long every so often; music doesn't.
+    pub min_pause_secs: f64,
+}
+
+impl Default for MusicOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: MusicMode::Mark,
+            min_music_secs: 60,
+            pause_noise_db: -35,
+            min_pause_secs: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MusicRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// `silencedetect` arguments for the batch script's music pass.
+pub fn pause_detect(options: &MusicOptions) -> Option<String> {
+    options.enabled.then(|| {
+        format!(
+            "noise={}dB:d={:.2}",
+            options.pause_noise_db.clamp(-90, -10),
+            options.min_pause_secs.clamp(0.05, 5.0)
+        )
+    })
+}
+
+/// Drops segments that are mostly inside a music range; in mark mode a
+/// `[music]` segment stands in for each range.
+pub fn apply(segments: &[Segment], ranges: &[MusicRange], mode: MusicMode) -> Vec<Segment> {
+    if ranges.is_empty() {
+        return segments.to_vec();
+    }
+    let inside = |s: &Segment| {
+        let length = s.end_ms.saturating_sub(s.start_ms).max(1);
+        ranges.iter().any(|r| {
+            let overlap = s.end_ms.min(r.end_ms).saturating_sub(s.start_ms.max(r.start_ms));
+            overlap * 2 >= length
+        })
+    };
+    let mut kept: Vec<Segment> = segments.iter().filter(|s| !inside(s)).cloned().collect();
+    if mode == MusicMode::Mark {
+        kept.extend(ranges.iter().map(|r| Segment {
+            start_ms: r.start_ms,
+            end_ms: r.end_ms,
+            text: MUSIC_MARKER.to_string(),
+            speaker: None,
+        }));
+        kept.sort_by_key(|s| s.start_ms);
+    }
+    kept
+}
@@ -0,0 +1,288 @@
+use crate::{
+    digest::format_duration,
+    history::{FileResult, RunReport},
+    mailer,
+    settings::{self, AppSettings, WebhookSettings},
+    transcript,
+};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::{path::Path, thread, time::Duration};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+const WEBHOOK_ATTEMPTS: u32 = 3;
+
+/// Totals shown in run notifications.
+pub struct RunSummary {
+    pub done: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub audio_ms: u64,
+    pub elapsed_secs: u64,
+}
+
+impl RunSummary {
+    pub fn from_report(report: &RunReport) -> Self {
+        let ok: Vec<_> = report.results.iter().filter(|r| r.status == "ok").collect();
+        let audio_ms = ok
+            .iter()
+            .filter_map(|r| r.output_dir.as_deref())
+            .filter_map(|dir| transcript::load_segments(Path::new(dir)).ok())
+            .filter_map(|segments| segments.last().map(|s| s.end_ms))
+            .sum();
+
+        Self {
+            done: ok.len(),
+            failed: report
+                .results
+                .iter()
+                .filter(|r| r.failed())
+                .count(),
+            skipped: report.skipped.len(),
+            audio_ms,
+            elapsed_secs: report
+                .finished_at_epoch
+                .unwrap_or_else(crate::now_epoch)
+                .saturating_sub(report.started_at_epoch),
+        }
+    }
+}
+
+fn render_email(report: &RunReport, summary: &RunSummary) -> String {
+    let outcome = if report.success == Some(true) {
+        "completed"
+    } else {
+        "failed"
+    };
+    let mut lines = vec![
+        format!("Transcription run {} {outcome}.", report.run_id),
+        String::new(),
+        format!("Files transcribed: {}", summary.done),
+        format!("Files failed:      {}", summary.failed),
+        format!("Files skipped:     {}", summary.skipped),
+        format!("Audio transcribed: {}", format_duration(summary.audio_ms)),
+        format!(
+            "Elapsed time:      {}",
+            format_duration(summary.elapsed_secs * 1000)
+        ),
+        String::new(),
+        format!("Input folders: {}", report.input_folders.join(", ")),
+        format!("Output folder: {}", report.output_folder),
+    ];
+    if let Some(message) = report.message.as_deref().filter(|m| !m.is_empty()) {
+        lines.push(format!("Result: {message}"));
+    }
+
+    let failures: Vec<_> = report
+        .results
+        .iter()
+        .filter(|r| r.failed())
+        .collect();
+    if !failures.is_empty() {
+        lines.push(String::new());
+        lines.push("Failed files:".to_string());
+        for failure in failures {
+            lines.push(format!("- {} ({})", failure.source, failure.status));
+        }
+    }
+
+    lines.join("\r\n")
+}
+
+fn email_run_result(settings: &AppSettings, report: &RunReport) -> Result<bool, String> {
+    let notifications = &settings.notifications;
+    let success = report.success == Some(true);
+    let wanted = if success {
+        notifications.email_on_success
+    } else {
+        notifications.email_on_failure
+    };
+    if !wanted {
+        return Ok(false);
+    }
+
+    let summary = RunSummary::from_report(report);
+    let subject = format!(
+        "Transcription {}: {} done, {} failed",
+        if success { "finished" } else { "failed" },
+        summary.done,
+        summary.failed
+    );
+    mailer::send_mail(
+        &settings.smtp,
+        &notifications.email_recipients,
+        &subject,
+        &render_email(report, &summary),
+    )?;
+    Ok(true)
+}
+
+fn signature(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn deliver(hook: &WebhookSettings, event: &str, body: &str) -> Result<(), String> {
+    let agent = ureq::AgentBuilder::new().timeout(WEBHOOK_TIMEOUT).build();
+    let mut attempt = 1;
+    loop {
+        let mut call = agent
+            .post(hook.url.trim())
+            .set("Content-Type", "application/json")
+            .set("X-Transcriber-Event", event);
+        if !hook.secret.is_empty() {
+            call = call.set("X-Transcriber-Signature", &signature(&hook.secret, body));
+        }
+
+        let retry = match call.send_string(body) {
+            Ok(_) => return Ok(()),
+            Err(ureq::Error::Status(code, _)) if code < 500 && code != 429 => {
+                return Err(format!("{} returned {code}", hook.url))
+            }
+            Err(err) => err,
+        };
+        if attempt >= WEBHOOK_ATTEMPTS {
+            return Err(format!("{}: {retry}", hook.url));
+        }
+        thread::sleep(Duration::from_secs(2 << attempt));
+        attempt += 1;
+    }
+}
+
+/// POSTs a lifecycle event to every matching webhook on a background thread.
+/// `text`/`content` carry a one-line message so Slack and Discord incoming
+/// webhooks can take the payload as-is.
+fn send_webhooks(app: &AppHandle, event: &str, run_id: &str, message: String, data: Value) {
+    let hooks: Vec<WebhookSettings> = settings::load(app)
+        .webhooks
+        .into_iter()
+        .filter(|h| h.enabled && !h.url.trim().is_empty())
+        .filter(|h| h.events.is_empty() || h.events.iter().any(|e| e == event))
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+
+    let body = json!({
+        "event": event,
+        "runId": run_id,
+        "timestampEpoch": crate::now_epoch(),
+        "text": message,
+        "content": message,
+        "data": data,
+    })
+    .to_string();
+    let event = event.to_string();
+    let app = app.clone();
+    thread::spawn(move || {
+        for hook in hooks {
+            if let Err(err) = deliver(&hook, &event, &body) {
+                crate::emit_log(&app, "system", format!("Webhook {event} failed: {err}"));
+            }
+        }
+    });
+}
+
+pub fn run_started(app: &AppHandle, report: &RunReport) {
+    send_webhooks(
+        app,
+        "run.started",
+        &report.run_id,
+        format!("Transcription run started: {}", report.input_folders.join(", ")),
+        json!({
+            "inputFolders": report.input_folders,
+            "outputFolder": report.output_folder,
+        }),
+    );
+}
+
+pub fn file_finished(app: &AppHandle, run_id: &str, result: &FileResult) {
+    send_webhooks(
+        app,
+        "file.finished",
+        run_id,
+        format!("{} — {}", crate::scanner::title_from_source(&result.source), result.status),
+        serde_json::to_value(result).unwrap_or(Value::Null),
+    );
+}
+
+/// Shows an OS notification; the app is usually minimized during long runs.
+pub fn desktop(app: &AppHandle, title: &str, body: &str) {
+    if let Err(err) = app.notification().builder().title(title).body(body).show() {
+        crate::emit_log(app, "system", format!("Desktop notification failed: {err}"));
+    }
+}
+
+/// Called from `cleanup_after_run` once the report is final.
+pub fn run_finished(app: &AppHandle, report: &RunReport) {
+    let settings = settings::load(app);
+    let success = report.success == Some(true);
+    let summary = RunSummary::from_report(report);
+    let wants_desktop = if success {
+        settings.notifications.desktop_on_success
+    } else {
+        settings.notifications.desktop_on_failure
+    };
+    if wants_desktop {
+        let body = format!(
+            "{} done, {} failed in {}.",
+            summary.done,
+            summary.failed,
+            format_duration(summary.elapsed_secs * 1000)
+        );
+        let title = if success {
+            "Transcription finished"
+        } else {
+            "Transcription failed"
+        };
+        desktop(app, title, &body);
+    }
+
+    match email_run_result(&settings, report) {
+        Ok(true) => crate::emit_log(app, "system", "Run summary email sent."),
+        Ok(false) => {}
+        Err(err) => crate::emit_log(app, "system", format!("Run summary email failed: {err}")),
+    }
+
+    send_webhooks(
+        app,
+        "run.finished",
+        &report.run_id,
+        format!(
+            "Transcription run {}: {} done, {} failed ({} of audio).",
+            if success { "finished" } else { "failed" },
+            summary.done,
+            summary.failed,
+            format_duration(summary.audio_ms)
+        ),
+        json!({
+            "success": report.success,
+            "code": report.code,
+            "message": report.message,
+            "done": summary.done,
+            "failed": summary.failed,
+            "skipped": summary.skipped,
+            "audioMs": summary.audio_ms,
+            "elapsedSecs": summary.elapsed_secs,
+        }),
+    );
+}
+
+#[tauri::command]
+pub async fn send_test_email(app: AppHandle) -> Result<(), String> {
+    let settings = settings::load(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        mailer::send_mail(
+            &settings.smtp,
+            &settings.notifications.email_recipients,
+            "Church transcriber test email",
+            "SMTP settings are working. Run summaries will be sent to this address.",
+        )
+    })
+    .await
+    .map_err(|err| format!("Test email task failed: {err}"))?
+}
@@ -0,0 +1,272 @@
+use crate::{
+    digest,
+    export::{self, xml_escape},
+    filemeta::{self, MetadataSources},
+    history::RunReport,
+    redaction, scanner,
+    settings::{self, PodcastSettings},
+    transcript,
+};
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager, Url};
+
+const EPISODES_FILE: &str = "podcast-episodes.json";
+
+/// Feed state is kept as JSON so each run can merge new episodes and rewrite
+/// the XML without parsing it back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Episode {
+    guid: String,
+    title: String,
+    description: String,
+    published_epoch: i64,
+    media_url: String,
+    media_type: String,
+    media_bytes: u64,
+    duration_secs: Option<u64>,
+    transcript_url: Option<String>,
+    transcript_type: Option<String>,
+    /// The speaker set with `set_file_metadata`; the feed's author otherwise.
+    #[serde(default)]
+    author: Option<String>,
+}
+
+fn episodes_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(EPISODES_FILE))
+}
+
+fn load_episodes(app: &AppHandle) -> Vec<Episode> {
+    episodes_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save_episodes(app: &AppHandle, episodes: &[Episode]) -> Result<(), String> {
+    let path = episodes_path(app)?;
+    let body = serde_json::to_string_pretty(episodes)
+        .map_err(|err| format!("Failed to serialize podcast episodes: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+fn media_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "mp3" => "audio/mpeg",
+        "m4a" | "aac" => "audio/mp4",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "ogg" => "audio/ogg",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        _ => "video/mp4",
+    }
+}
+
+/// Appends a relative path to a base URL, percent-encoding each segment.
+fn public_url(base: &str, relative: &str) -> Option<String> {
+    let mut url = Url::parse(base.trim()).ok()?;
+    url.path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .extend(relative.split(['/', '\\']).filter(|s| !s.is_empty()));
+    Some(url.to_string())
+}
+
+fn find_transcript(output_dir: &Path, withheld: &[&str]) -> Option<(PathBuf, &'static str)> {
+    [
+        (export::VTT_FILE, "text/vtt"),
+        (export::SRT_FILE, "application/x-subrip"),
+        (transcript::ENRICHED_FILE, "application/json"),
+        ("raw.txt", "text/plain"),
+    ]
+    .into_iter()
+    .filter(|(name, _)| !withheld.contains(name))
+    .map(|(name, kind)| (output_dir.join(name), kind))
+    .find(|(path, _)| path.exists())
+}
+
+fn published_epoch(date_bucket: Option<&str>, fallback: u64) -> i64 {
+    date_bucket
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .and_then(|d| d.and_hms_opt(12, 0, 0))
+        .and_then(|d| Local.from_local_datetime(&d).single())
+        .map(|d| d.timestamp())
+        .unwrap_or(fallback as i64)
+}
+
+fn collect_episodes(
+    report: &RunReport,
+    podcast: &PodcastSettings,
+    metadata: &MetadataSources,
+    withheld: &[&str],
+) -> Vec<Episode> {
+    let roots = report.archive_roots();
+    let mut episodes = Vec::new();
+
+    for result in report.results.iter().filter(|r| r.status == "ok") {
+        let Some(output_dir) = result.output_dir.as_deref().map(PathBuf::from) else {
+            continue;
+        };
+        let media = PathBuf::from(&result.source);
+        let Some(file_name) = media.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let Some(media_url) = public_url(&podcast.media_base_url, &file_name) else {
+            continue;
+        };
+
+        let transcript = find_transcript(&output_dir, withheld).and_then(|(path, kind)| {
+            // Override folders publish under their own name, as cloud sync mirrors them.
+            let relative = roots.iter().find_map(|root| {
+                let inner = path.strip_prefix(&root.path).ok()?.to_string_lossy().replace('\\', "/");
+                Some(root.prefixed(&inner))
+            })?;
+            Some((public_url(&podcast.transcript_base_url, &relative)?, kind))
+        });
+        let date_bucket = report
+            .planned
+            .iter()
+            .find(|p| scanner::same_path(&p.source, &result.source))
+            .map(|p| p.date_bucket.as_str());
+        let details = filemeta::resolve(metadata, &result.source);
+
+        episodes.push(Episode {
+            guid: media_url.clone(),
+            title: filemeta::title(&details, &result.source),
+            description: digest::summary_for(&output_dir),
+            published_epoch: published_epoch(details.service_date.as_deref().or(date_bucket), result.finished_at_epoch),
+            media_type: media_type(&media).to_string(),
+            media_bytes: fs::metadata(&media).map(|m| m.len()).unwrap_or(0),
+            duration_secs: scanner::media_duration_secs(&media).or_else(|| {
+                transcript::load_segments(&output_dir)
+                    .ok()
+                    .and_then(|s| s.last().map(|seg| seg.end_ms / 1000))
+            }),
+            media_url,
+            transcript_url: transcript.as_ref().map(|(url, _)| url.clone()),
+            transcript_type: transcript.map(|(_, kind)| kind.to_string()),
+            author: details.speaker,
+        });
+    }
+
+    episodes
+}
+
+fn rfc2822(epoch: i64) -> String {
+    Local
+        .timestamp_opt(epoch, 0)
+        .single()
+        .map(|t| t.to_rfc2822())
+        .unwrap_or_default()
+}
+
+fn render_feed(podcast: &PodcastSettings, episodes: &[Episode]) -> String {
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        r#"<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd" xmlns:podcast="https://podcastindex.org/namespace/1.0">"#.to_string(),
+        "<channel>".to_string(),
+        format!("<title>{}</title>", xml_escape(&podcast.title)),
+        format!("<link>{}</link>", xml_escape(&podcast.link)),
+        format!("<description>{}</description>", xml_escape(&podcast.description)),
+        format!("<language>{}</language>", xml_escape(&podcast.language)),
+        format!("<itunes:author>{}</itunes:author>", xml_escape(&podcast.author)),
+    ];
+    if !podcast.image_url.trim().is_empty() {
+        lines.push(format!(
+            r#"<itunes:image href="{}"/>"#,
+            xml_escape(podcast.image_url.trim())
+        ));
+    }
+
+    for episode in episodes {
+        lines.push("<item>".to_string());
+        lines.push(format!("<title>{}</title>", xml_escape(&episode.title)));
+        lines.push(format!("<description>{}</description>", xml_escape(&episode.description)));
+        lines.push(format!(
+            r#"<guid isPermaLink="false">{}</guid>"#,
+            xml_escape(&episode.guid)
+        ));
+        lines.push(format!("<pubDate>{}</pubDate>", rfc2822(episode.published_epoch)));
+        if let Some(author) = &episode.author {
+            lines.push(format!("<itunes:author>{}</itunes:author>", xml_escape(author)));
+        }
+        lines.push(format!(
+            r#"<enclosure url="{}" length="{}" type="{}"/>"#,
+            xml_escape(&episode.media_url),
+            episode.media_bytes,
+            episode.media_type
+        ));
+        if let Some(secs) = episode.duration_secs {
+            lines.push(format!("<itunes:duration>{secs}</itunes:duration>"));
+        }
+        if let (Some(url), Some(kind)) = (&episode.transcript_url, &episode.transcript_type) {
+            lines.push(format!(
+                r#"<podcast:transcript url="{}" type="{kind}"/>"#,
+                xml_escape(url)
+            ));
+        }
+        lines.push("</item>".to_string());
+    }
+
+    lines.push("</channel>".to_string());
+    lines.push("</rss>".to_string());
+    lines.join("\n")
+}
+
+/// Merges the run's finished files into the stored episode list and rewrites
+/// the feed at the configured path.
+pub fn update_feed(app: &AppHandle, report: &RunReport) {
+    let podcast = settings::load(app).podcast;
+    if !podcast.enabled {
+        return;
+    }
+    if podcast.feed_path.trim().is_empty() || podcast.media_base_url.trim().is_empty() {
+        crate::emit_log(
+            app,
+            "system",
+            "Podcast feed skipped: feed path and media base URL are required.",
+        );
+        return;
+    }
+
+    let mut episodes = load_episodes(app);
+    let withheld = redaction::withheld_files(&settings::load(app).redaction);
+    for episode in collect_episodes(report, &podcast, &filemeta::sources(app), withheld) {
+        episodes.retain(|e| e.guid != episode.guid);
+        episodes.push(episode);
+    }
+    episodes.sort_by_key(|e| std::cmp::Reverse(e.published_epoch));
+
+    let path = PathBuf::from(podcast.feed_path.trim());
+    let result = save_episodes(app, &episodes).and_then(|_| {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Could not create {}: {err}", parent.display()))?;
+        }
+        fs::write(&path, render_feed(&podcast, &episodes))
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))
+    });
+
+    match result {
+        Ok(()) => crate::emit_log(app, "system", format!("Podcast feed updated: {}", path.display())),
+        Err(err) => crate::emit_log(app, "system", format!("Podcast feed failed: {err}")),
+    }
+}
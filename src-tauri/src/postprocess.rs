@@ -0,0 +1,432 @@
+use crate::{
+    scripture::{self, ScriptureReference},
+    transcript::{format_timestamp, Segment},
+};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const READABLE_TXT_FILE: &str = "readable.txt";
+pub const READABLE_MD_FILE: &str = "readable.md";
+
+/// Script-generated outputs meant for publishing; `raw.txt` and `timestamps.json`
+/// are never rewritten.
+pub const SCRIPT_PUBLISHED_FILES: &[&str] = &["clean.md", "summary.md"];
+
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "st", "rev", "jr", "sr", "vs", "etc", "e.g", "i.e", "gen", "ex",
+    "lev", "num", "deut", "matt", "rom", "cor", "gal", "eph", "phil", "col", "heb",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReadableOptions {
+    pub enabled: bool,
+    pub max_sentence_chars: usize,
+    pub max_paragraph_chars: usize,
+    pub paragraph_gap_secs: f64,
+}
+
+impl Default for ReadableOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_sentence_chars: 400,
+            max_paragraph_chars: 700,
+            paragraph_gap_secs: 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Paragraph {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// Set when speakers were labelled; a paragraph never spans two.
+    pub speaker: Option<String>,
+}
+
+fn ends_sentence(word: &str) -> bool {
+    let trimmed = word.trim_end_matches(['"', '\'', ')', ']', '\u{201d}', '\u{2019}']);
+    let Some(last) = trimmed.chars().last() else {
+        return false;
+    };
+
+    if last == '!' || last == '?' {
+        return true;
+    }
+    if last != '.' {
+        return false;
+    }
+
+    let stem = trimmed
+        .trim_end_matches('.')
+        .trim_start_matches(['"', '\'', '(', '[', '\u{201c}'])
+        .to_lowercase();
+    let single_initial = stem.chars().count() == 1 && stem.chars().all(char::is_alphabetic);
+
+    !single_initial && !ABBREVIATIONS.contains(&stem.as_str())
+}
+
+/// Re-flows whisper segments into sentences, keeping the start/end time of the
+/// segments each sentence was built from.
+fn build_sentences(segments: &[Segment], max_chars: usize) -> Vec<Paragraph> {
+    let mut sentences = Vec::new();
+    let mut current: Option<Paragraph> = None;
+
+    for segment in segments {
+        if current.as_ref().is_some_and(|s| s.speaker != segment.speaker) {
+            sentences.extend(current.take());
+        }
+        for word in segment.text.split_whitespace() {
+            let sentence = current.get_or_insert_with(|| Paragraph {
+                start_ms: segment.start_ms,
+                end_ms: segment.end_ms,
+                text: String::new(),
+                speaker: segment.speaker.clone(),
+            });
+
+            if !sentence.text.is_empty() {
+                sentence.text.push(' ');
+            }
+            sentence.text.push_str(word);
+            sentence.end_ms = segment.end_ms;
+
+            if ends_sentence(word) || sentence.text.len() >= max_chars {
+                sentences.extend(current.take());
+            }
+        }
+    }
+
+    sentences.extend(current);
+    sentences
+}
+
+/// Groups sentences into paragraphs, starting a new one after a long pause, at
+/// the length limit or when the speaker changes. A labelled speaker's name
+/// leads their first paragraph, as in `Pastor Dave: Good morning.`
+pub fn build_paragraphs(segments: &[Segment], options: &ReadableOptions) -> Vec<Paragraph> {
+    let max_sentence = options.max_sentence_chars.max(40);
+    let max_paragraph = options.max_paragraph_chars.max(max_sentence);
+    let gap_ms = (options.paragraph_gap_secs.max(0.0) * 1000.0) as u64;
+
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut buffer: Option<Paragraph> = None;
+
+    for sentence in build_sentences(segments, max_sentence) {
+        let long_pause = buffer
+            .as_ref()
+            .map(|b| gap_ms > 0 && sentence.start_ms.saturating_sub(b.end_ms) >= gap_ms)
+            .unwrap_or(false);
+        let new_speaker = buffer.as_ref().is_some_and(|b| b.speaker != sentence.speaker);
+        if long_pause || new_speaker {
+            paragraphs.extend(buffer.take());
+        }
+
+        match buffer.as_mut() {
+            Some(b) => {
+                b.text.push(' ');
+                b.text.push_str(&sentence.text);
+                b.end_ms = sentence.end_ms;
+            }
+            None => buffer = Some(sentence),
+        }
+
+        if buffer.as_ref().map(|b| b.text.len() >= max_paragraph).unwrap_or(false) {
+            paragraphs.extend(buffer.take());
+        }
+    }
+
+    paragraphs.extend(buffer);
+
+    let mut previous: Option<String> = None;
+    for paragraph in &mut paragraphs {
+        if let Some(name) = paragraph.speaker.as_deref().filter(|_| paragraph.speaker != previous) {
+            paragraph.text = format!("{name}: {}", paragraph.text);
+        }
+        previous = paragraph.speaker.clone();
+    }
+    paragraphs
+}
+
+fn render_markdown(
+    title: &str,
+    source: &str,
+    header: &[(&str, String)],
+    paragraphs: &[Paragraph],
+    scripture: Option<&[ScriptureReference]>,
+) -> String {
+    let mut lines = vec![format!("# {title}"), String::new()];
+    for (label, value) in header {
+        lines.push(format!("- **{label}:** {value}"));
+    }
+    if !header.is_empty() {
+        lines.push(String::new());
+    }
+    lines.push(format!("**Source file:** `{source}`"));
+    lines.push(String::new());
+
+    for p in paragraphs {
+        let text = match scripture {
+            Some(_) => scripture::tag_inline(&p.text),
+            None => p.text.clone(),
+        };
+        lines.push(format!("**[{}]** {}", format_timestamp(p.start_ms), text));
+        lines.push(String::new());
+    }
+
+    if let Some(references) = scripture.filter(|r| !r.is_empty()) {
+        lines.push("## Scripture references".to_string());
+        lines.push(String::new());
+        for r in references {
+            lines.push(format!("- {} ({})", r.reference, format_timestamp(r.start_ms)));
+        }
+        lines.push(String::new());
+    }
+
+    lines.join("\r\n")
+}
+
+fn render_text(header: &[(&str, String)], paragraphs: &[Paragraph]) -> String {
+    let body = paragraphs
+        .iter()
+        .map(|p| p.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\r\n\r\n");
+    if header.is_empty() {
+        return body;
+    }
+    let lines: Vec<String> = header.iter().map(|(label, value)| format!("{label}: {value}")).collect();
+    format!("{}\r\n\r\n{body}", lines.join("\r\n"))
+}
+
+/// Writes `readable.txt` and `readable.md` next to the timestamped outputs,
+/// each starting with the `header` lines when there are any.
+pub fn write_readable(
+    output_dir: &Path,
+    title: &str,
+    source: &str,
+    header: &[(&str, String)],
+    segments: &[Segment],
+    options: &ReadableOptions,
+    scripture: Option<&[ScriptureReference]>,
+) -> Result<Vec<PathBuf>, String> {
+    let paragraphs = build_paragraphs(segments, options);
+
+    let txt_path = output_dir.join(READABLE_TXT_FILE);
+    fs::write(&txt_path, render_text(header, &paragraphs))
+        .map_err(|err| format!("Failed to write {}: {err}", txt_path.display()))?;
+
+    let md_path = output_dir.join(READABLE_MD_FILE);
+    fs::write(&md_path, render_markdown(title, source, header, &paragraphs, scripture))
+        .map_err(|err| format!("Failed to write {}: {err}", md_path.display()))?;
+
+    Ok(vec![txt_path, md_path])
+}
+
+/// One case-insensitive whole-word regex for a list of words and phrases; a
+/// trailing `*` matches any ending.
+pub fn word_pattern(words: &[String]) -> Option<Regex> {
+    let alternatives: Vec<String> = words
+        .iter()
+        .map(|w| w.trim())
+        .filter(|w| !w.is_empty() && *w != "*")
+        .map(|w| match w.strip_suffix('*') {
+            Some(prefix) => format!(r"{}\w*", regex::escape(prefix)),
+            None => regex::escape(w),
+        })
+        .collect();
+
+    if alternatives.is_empty() {
+        return None;
+    }
+
+    RegexBuilder::new(&format!(r"\b(?:{})\b", alternatives.join("|")))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Masks listed words in published text. A trailing `*` on an entry also matches
+/// longer words starting with it (`bless*` would match "blessed").
+pub struct WordMask {
+    pattern: Regex,
+}
+
+impl WordMask {
+    pub fn new(words: &[String]) -> Option<Self> {
+        word_pattern(words).map(|pattern| Self { pattern })
+    }
+
+    /// Keeps the first letter so readers can still follow the sentence.
+    pub fn apply(&self, text: &str) -> String {
+        self.pattern
+            .replace_all(text, |caps: &regex::Captures| {
+                let word = &caps[0];
+                let mut chars = word.chars();
+                let first = chars.next().map(String::from).unwrap_or_default();
+                format!("{first}{}", "*".repeat(chars.count()))
+            })
+            .into_owned()
+    }
+
+    pub fn apply_segments(&self, segments: &[Segment]) -> Vec<Segment> {
+        segments
+            .iter()
+            .map(|s| Segment {
+                text: self.apply(&s.text),
+                ..s.clone()
+            })
+            .collect()
+    }
+
+    /// Rewrites script-generated published files in place.
+    pub fn apply_to_files(&self, output_dir: &Path, names: &[&str]) -> Result<usize, String> {
+        let mut rewritten = 0;
+        for name in names {
+            let path = output_dir.join(name);
+            if !path.exists() {
+                continue;
+            }
+
+            let body = fs::read_to_string(&path)
+                .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+            let masked = self.apply(&body);
+            if masked != body {
+                fs::write(&path, masked)
+                    .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+                rewritten += 1;
+            }
+        }
+        Ok(rewritten)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn sentence_ends_at_terminal_punctuation() {
+        assert!(ends_sentence("amen."));
+        assert!(ends_sentence("amen!"));
+        assert!(ends_sentence("really?\""));
+        assert!(ends_sentence("him.)"));
+        assert!(!ends_sentence("amen"));
+        assert!(!ends_sentence("3.14"));
+    }
+
+    #[test]
+    fn abbreviations_and_initials_do_not_end_sentences() {
+        assert!(!ends_sentence("Dr."));
+        assert!(!ends_sentence("St."));
+        assert!(!ends_sentence("e.g."));
+        assert!(!ends_sentence("(Rom."));
+        assert!(!ends_sentence("C."));
+        assert!(ends_sentence("CS."));
+    }
+
+    #[test]
+    fn sentences_span_segments() {
+        let segments = [
+            segment(0, 2_000, "As Dr. Smith read in"),
+            segment(2_000, 4_000, "John chapter three. Then we"),
+            segment(4_000, 5_000, "prayed."),
+        ];
+        let options = ReadableOptions::default();
+        let paragraphs = build_paragraphs(&segments, &options);
+        assert_eq!(paragraphs.len(), 1);
+        assert_eq!(
+            paragraphs[0].text,
+            "As Dr. Smith read in John chapter three. Then we prayed."
+        );
+        assert_eq!((paragraphs[0].start_ms, paragraphs[0].end_ms), (0, 5_000));
+    }
+
+    #[test]
+    fn speaker_changes_start_a_named_paragraph() {
+        let mut segments = [
+            segment(0, 1_000, "Good morning, church."),
+            segment(1_000, 2_000, "Please stand"),
+            segment(2_000, 3_000, "Amen."),
+            segment(3_000, 4_000, "with me."),
+        ];
+        for (segment, speaker) in segments.iter_mut().zip(["Pastor Dave", "Pastor Dave", "Sister Ann", "Pastor Dave"]) {
+            segment.speaker = Some(speaker.to_string());
+        }
+        let texts: Vec<String> = build_paragraphs(&segments, &ReadableOptions::default())
+            .into_iter()
+            .map(|p| p.text)
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "Pastor Dave: Good morning, church. Please stand",
+                "Sister Ann: Amen.",
+                "Pastor Dave: with me."
+            ]
+        );
+    }
+
+    #[test]
+    fn long_pause_starts_a_paragraph() {
+        let segments = [
+            segment(0, 1_000, "Welcome this morning."),
+            segment(1_500, 2_500, "Please be seated."),
+            segment(6_000, 7_000, "Turn with me to Romans."),
+        ];
+        let paragraphs = build_paragraphs(&segments, &ReadableOptions::default());
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(paragraphs[0].text, "Welcome this morning. Please be seated.");
+        assert_eq!(paragraphs[1].start_ms, 6_000);
+
+        let no_gap = ReadableOptions {
+            paragraph_gap_secs: 0.0,
+            ..ReadableOptions::default()
+        };
+        assert_eq!(build_paragraphs(&segments, &no_gap).len(), 1);
+    }
+
+    #[test]
+    fn paragraphs_close_at_max_length() {
+        let segments: Vec<Segment> = (0..5)
+            .map(|i| segment(i * 1_000, i * 1_000 + 900, "This is one more sentence."))
+            .collect();
+        let options = ReadableOptions {
+            max_sentence_chars: 40,
+            max_paragraph_chars: 40,
+            ..ReadableOptions::default()
+        };
+        let paragraphs = build_paragraphs(&segments, &options);
+        assert_eq!(paragraphs.len(), 3);
+        assert!(paragraphs[..2].iter().all(|p| p.text.len() >= 40));
+        assert_eq!(paragraphs[2].text, "This is one more sentence.");
+    }
+
+    #[test]
+    fn runaway_sentences_are_cut() {
+        let words = ["word"; 60].join(" ");
+        let segments = [segment(0, 10_000, &words)];
+        let options = ReadableOptions {
+            max_sentence_chars: 40,
+            max_paragraph_chars: 40,
+            ..ReadableOptions::default()
+        };
+        let paragraphs = build_paragraphs(&segments, &options);
+        assert!(paragraphs.len() > 1);
+        assert!(paragraphs.iter().all(|p| p.text.len() <= 44));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::RunnerState;
+use serde::Serialize;
+use std::{thread, time::Duration};
+use tauri::{AppHandle, Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const BATTERY_PAUSE_REASON: &str = "On battery power; resumes when plugged in.";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PowerEvent {
+    on_battery: bool,
+    auto_paused: bool,
+    message: String,
+}
+
+/// `Some(true)` when running on battery, `None` when it can't be told (desktops
+/// without a battery report AC).
+#[cfg(windows)]
+fn on_battery() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        Some(true)
+    } else if text.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mains: Vec<bool> = entries
+        .flatten()
+        .filter(|e| {
+            std::fs::read_to_string(e.path().join("type"))
+                .map(|t| t.trim() == "Mains")
+                .unwrap_or(false)
+        })
+        .filter_map(|e| std::fs::read_to_string(e.path().join("online")).ok())
+        .map(|online| online.trim() == "1")
+        .collect();
+    if mains.is_empty() {
+        None
+    } else {
+        Some(!mains.iter().any(|online| *online))
+    }
+}
+
+fn set_reason(app: &AppHandle, reason: Option<&str>) {
+    let state = app.state::<RunnerState>();
+    if let Ok(mut slot) = state.pause_reason.lock() {
+        *slot = reason.map(str::to_string);
+    };
+}
+
+fn announce(app: &AppHandle, on_battery: bool, auto_paused: bool, message: String) {
+    crate::emit_log(app, "system", &message);
+    let _ = app.emit(
+        "transcribe://power",
+        PowerEvent {
+            on_battery,
+            auto_paused,
+            message,
+        },
+    );
+}
+
+/// Pauses the run when the machine switches to battery and resumes it on AC,
+/// for as long as run `run_id` is active. Only its own pause is undone; once
+/// the user pauses or resumes by hand, that choice stays put.
+pub fn watch(app: &AppHandle, run_id: String) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut was_on_battery = false;
+        let mut auto_paused = false;
+        loop {
+            let status = crate::get_status(&app.state::<RunnerState>());
+            if !status.running || status.run_id.as_deref() != Some(run_id.as_str()) {
+                return;
+            }
+
+            let battery = on_battery().unwrap_or(false);
+            if battery && !was_on_battery && !status.paused {
+                match crate::toggle_pause(app.clone(), app.state::<RunnerState>(), true) {
+                    Ok(_) => {
+                        auto_paused = true;
+                        set_reason(&app, Some(BATTERY_PAUSE_REASON));
+                        crate::emit_status(&app);
+                        announce(&app, true, true, "Switched to battery power; pausing the run.".to_string());
+                    }
+                    Err(err) => announce(&app, true, false, format!("Could not pause on battery: {err}")),
+                }
+            } else if !battery && was_on_battery && auto_paused {
+                auto_paused = false;
+                if status.paused && status.pause_reason.as_deref() == Some(BATTERY_PAUSE_REASON) {
+                    match crate::toggle_pause(app.clone(), app.state::<RunnerState>(), false) {
+                        Ok(_) => announce(&app, false, false, "Back on AC power; resuming the run.".to_string()),
+                        Err(err) => announce(&app, false, false, format!("Could not resume on AC power: {err}")),
+                    }
+                }
+            }
+            was_on_battery = battery;
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
@@ -0,0 +1,250 @@
+use crate::subtitles;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The 16 kHz mono WAV the batch script extracts for whisper, kept in the
+/// output folder with `keep_audio`.
+pub const EXTRACTED_AUDIO_FILE: &str = "audio-source.wav";
+const KEPT_AUDIO_STEM: &str = "audio-source";
+
+/// ffmpeg `loudnorm` pass applied while extracting audio for whisper; helps the
+/// very quiet cassette digitizations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LoudnessOptions {
+    pub enabled: bool,
+    pub target_lufs: f64,
+    pub true_peak_db: f64,
+    pub loudness_range: f64,
+}
+
+impl Default for LoudnessOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_lufs: -16.0,
+            true_peak_db: -1.5,
+            loudness_range: 11.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DenoiseMethod {
+    /// ffmpeg's FFT denoiser; good on steady hiss and hum.
+    #[default]
+    Afftdn,
+    /// ffmpeg's RNNoise filter; needs an `.rnnn` model file.
+    Rnnoise,
+}
+
+/// Noise reduction for old recordings with HVAC hum or tape hiss, applied
+/// ahead of `loudnorm` while extracting audio for whisper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DenoiseOptions {
+    pub enabled: bool,
+    pub method: DenoiseMethod,
+    /// afftdn: how much to reduce the noise by, in dB.
+    pub reduction_db: f64,
+    /// afftdn: the noise floor to assume, in dB.
+    pub noise_floor_db: f64,
+    /// Cuts rumble and mains hum below this; 0 turns it off.
+    pub highpass_hz: u32,
+    /// Rnnoise: path to the model file.
+    pub rnnoise_model: Option<String>,
+}
+
+impl Default for DenoiseOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: DenoiseMethod::Afftdn,
+            reduction_db: 12.0,
+            noise_floor_db: -50.0,
+            highpass_hz: 80,
+            rnnoise_model: None,
+        }
+    }
+}
+
+/// Loudness of a recording as ffmpeg's `ebur128` and `astats` measure it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AudioStats {
+    pub integrated_lufs: Option<f64>,
+    pub rms_db: Option<f64>,
+    pub noise_floor_db: Option<f64>,
+}
+
+/// The source's audio against what whisper heard, recorded by the batch
+/// script in `metadata.json` when denoising is on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DenoiseStats {
+    pub before: AudioStats,
+    pub after: AudioStats,
+}
+
+/// Cuts leading and trailing dead air before whisper runs, found with ffmpeg
+/// `silencedetect`. Output timestamps are shifted back by the trimmed lead-in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SilenceTrimOptions {
+    pub enabled: bool,
+    pub noise_db: i32,
+    pub min_silence_secs: u32,
+}
+
+impl Default for SilenceTrimOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            noise_db: -40,
+            min_silence_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeptAudioFormat {
+    /// The extracted 16 kHz WAV as-is.
+    #[default]
+    Wav,
+    Flac,
+    Opus,
+}
+
+/// What `keep_audio` leaves in the output folder, converted from the
+/// extracted WAV once the file is transcribed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct KeptAudioOptions {
+    pub format: KeptAudioFormat,
+    /// Opus only.
+    pub bitrate_kbps: u32,
+}
+
+impl Default for KeptAudioOptions {
+    fn default() -> Self {
+        Self {
+            format: KeptAudioFormat::Wav,
+            bitrate_kbps: 48,
+        }
+    }
+}
+
+impl KeptAudioOptions {
+    /// The kept audio's file name once converted.
+    pub fn file_name(&self) -> String {
+        match self.format {
+            KeptAudioFormat::Wav => EXTRACTED_AUDIO_FILE.to_string(),
+            KeptAudioFormat::Flac => format!("{KEPT_AUDIO_STEM}.flac"),
+            KeptAudioFormat::Opus => format!("{KEPT_AUDIO_STEM}.opus"),
+        }
+    }
+}
+
+/// Whether `name` is the kept audio, in any of the kept formats.
+pub fn is_kept_audio(name: &str) -> bool {
+    Path::new(name)
+        .file_stem()
+        .is_some_and(|stem| stem == KEPT_AUDIO_STEM)
+}
+
+/// Converts the kept WAV in `output_dir` to the chosen format with ffmpeg
+/// and removes the WAV. Returns the converted file, or `None` when the WAV
+/// is kept or missing.
+pub fn convert_kept_audio(output_dir: &Path, options: &KeptAudioOptions) -> Result<Option<PathBuf>, String> {
+    let wav = output_dir.join(EXTRACTED_AUDIO_FILE);
+    let (extension, codec_args): (&str, Vec<String>) = match options.format {
+        KeptAudioFormat::Wav => return Ok(None),
+        KeptAudioFormat::Flac => ("flac", vec!["-c:a".into(), "flac".into()]),
+        KeptAudioFormat::Opus => (
+            "opus",
+            vec![
+                "-c:a".into(),
+                "libopus".into(),
+                "-b:a".into(),
+                format!("{}k", options.bitrate_kbps.clamp(6, 256)),
+            ],
+        ),
+    };
+    if !wav.exists() {
+        return Ok(None);
+    }
+
+    let target = output_dir.join(format!("{KEPT_AUDIO_STEM}.{extension}"));
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-y", "-i"])
+        .arg(&wav)
+        .args(&codec_args)
+        .arg(&target)
+        .output()
+        .map_err(|err| format!("Could not run ffmpeg to convert kept audio: {err}"))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&target);
+        return Err(format!(
+            "ffmpeg could not convert {} to {extension} (code {:?})",
+            wav.display(),
+            output.status.code()
+        ));
+    }
+    fs::remove_file(&wav).map_err(|err| format!("Could not remove {}: {err}", wav.display()))?;
+    Ok(Some(target))
+}
+
+/// The `-af` chain handed to the batch script, or `None` when nothing is enabled.
+/// Values are clamped to the ranges ffmpeg accepts.
+pub fn audio_filter(loudness: &LoudnessOptions, denoise: &DenoiseOptions) -> Result<Option<String>, String> {
+    let mut filters = Vec::new();
+    if denoise.enabled {
+        if denoise.highpass_hz > 0 {
+            filters.push(format!("highpass=f={}", denoise.highpass_hz.clamp(20, 300)));
+        }
+        match denoise.method {
+            DenoiseMethod::Afftdn => filters.push(format!(
+                "afftdn=nr={:.1}:nf={:.1}",
+                denoise.reduction_db.clamp(0.01, 97.0),
+                denoise.noise_floor_db.clamp(-80.0, -20.0)
+            )),
+            DenoiseMethod::Rnnoise => {
+                let model = denoise
+                    .rnnoise_model
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|m| Path::new(m).is_file())
+                    .ok_or_else(|| "RNNoise denoising needs an existing .rnnn model file.".to_string())?;
+                // Forward slashes work on Windows and need no escaping.
+                filters.push(format!("arnndn=m={}", subtitles::filter_value(&model.replace('\\', "/"))));
+            }
+        }
+    }
+    if loudness.enabled {
+        filters.push(format!(
+            "loudnorm=I={:.1}:TP={:.1}:LRA={:.1}",
+            loudness.target_lufs.clamp(-70.0, -5.0),
+            loudness.true_peak_db.clamp(-9.0, 0.0),
+            loudness.loudness_range.clamp(1.0, 50.0)
+        ));
+    }
+
+    Ok((!filters.is_empty()).then(|| filters.join(",")))
+}
+
+/// `silencedetect` arguments for the batch script's trim step.
+pub fn silence_detect(trim: &SilenceTrimOptions) -> Option<String> {
+    trim.enabled.then(|| {
+        format!(
+            "noise={}dB:d={}",
+            trim.noise_db.clamp(-90, -10),
+            trim.min_silence_secs.max(1)
+        )
+    })
+}
@@ -0,0 +1,116 @@
+use std::process::{Child, Command};
+
+#[cfg(windows)]
+mod job {
+    use std::{
+        collections::HashMap,
+        os::windows::io::AsRawHandle,
+        process::Child,
+        sync::{Mutex, OnceLock},
+    };
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, HANDLE},
+        System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        },
+    };
+
+    /// Job handles by child pid, stored as integers so the map is `Send`.
+    fn jobs() -> &'static Mutex<HashMap<u32, isize>> {
+        static JOBS: OnceLock<Mutex<HashMap<u32, isize>>> = OnceLock::new();
+        JOBS.get_or_init(Default::default)
+    }
+
+    pub fn track(child: &Child) -> Result<(), String> {
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err("Could not create a job object for the batch script.".to_string());
+            }
+            // Closing the handle (or the app dying) also ends anything left in the job.
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let configured = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const _,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if configured == 0 || AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE) == 0 {
+                CloseHandle(job);
+                return Err("Could not place the batch script in a job object.".to_string());
+            }
+            if let Ok(mut jobs) = jobs().lock() {
+                jobs.insert(child.id(), job as isize);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn kill(child: &Child) -> bool {
+        let job = jobs().lock().ok().and_then(|jobs| jobs.get(&child.id()).copied());
+        match job {
+            Some(job) => unsafe { TerminateJobObject(job as HANDLE, 1) != 0 },
+            None => false,
+        }
+    }
+
+    pub fn release(child: &Child) {
+        let job = jobs().lock().ok().and_then(|mut jobs| jobs.remove(&child.id()));
+        if let Some(job) = job {
+            unsafe {
+                CloseHandle(job as HANDLE);
+            }
+        }
+    }
+}
+
+/// Starts the command as the leader of a new process group on Unix.
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Puts a just-spawned child in a job object on Windows. On failure the
+/// child still runs; stopping it just falls back to killing PowerShell alone.
+pub fn track(child: &Child) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        job::track(child)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = child;
+        Ok(())
+    }
+}
+
+/// Kills the child and everything it started. Killing PowerShell alone
+/// leaves ffmpeg and whisper-cli running and holding files open.
+pub fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        // The child leads its group, so its pid is the group id.
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(windows)]
+    if job::kill(child) {
+        return;
+    }
+    let _ = child.kill();
+}
+
+/// Frees the job object once the child has exited.
+pub fn release(child: &Child) {
+    #[cfg(windows)]
+    job::release(child);
+    #[cfg(not(windows))]
+    let _ = child;
+}
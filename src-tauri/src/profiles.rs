@@ -0,0 +1,136 @@
+use crate::{RunnerState, RunnerStatus, StartRequest};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+const PROFILES_FILE: &str = "profiles.json";
+
+/// A saved set of run settings, e.g. "Weekly Sunday run" or "Spanish service".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunProfile {
+    pub name: String,
+    pub request: StartRequest,
+    pub created_at_epoch: u64,
+    pub updated_at_epoch: u64,
+    #[serde(default)]
+    pub last_used_epoch: Option<u64>,
+}
+
+fn profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(PROFILES_FILE))
+}
+
+pub fn load(app: &AppHandle) -> Vec<RunProfile> {
+    profiles_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app: &AppHandle, profiles: &[RunProfile]) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    let body = serde_json::to_string_pretty(profiles)
+        .map_err(|err| format!("Failed to serialize profiles: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Points profiles that use the `old` whisper-cli at `new`; returns how many
+/// changed.
+pub fn replace_whisper_exe(app: &AppHandle, old: &str, new: &str) -> Result<usize, String> {
+    let mut profiles = load(app);
+    let mut changed = 0;
+    for profile in profiles.iter_mut().filter(|p| p.request.whisper_exe.trim() == old) {
+        profile.request.whisper_exe = new.to_string();
+        changed += 1;
+    }
+    if changed > 0 {
+        save(app, &profiles)?;
+    }
+    Ok(changed)
+}
+
+/// Profile names are matched case-insensitively.
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Creates the profile, or replaces the settings of the one with this name.
+#[tauri::command]
+pub fn save_profile(app: AppHandle, name: String, request: StartRequest) -> Result<RunProfile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name is required.".to_string());
+    }
+    if request.input_folders.is_empty() {
+        return Err("The run profile needs at least one input folder.".to_string());
+    }
+
+    let now = crate::now_epoch();
+    let mut profiles = load(&app);
+    let profile = match profiles.iter_mut().find(|p| same_name(&p.name, &name)) {
+        Some(existing) => {
+            existing.name = name;
+            existing.request = request;
+            existing.updated_at_epoch = now;
+            existing.clone()
+        }
+        None => {
+            let profile = RunProfile {
+                name,
+                request,
+                created_at_epoch: now,
+                updated_at_epoch: now,
+                last_used_epoch: None,
+            };
+            profiles.push(profile.clone());
+            profile
+        }
+    };
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+    save(&app, &profiles)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Vec<RunProfile> {
+    load(&app)
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let mut profiles = load(&app);
+    let before = profiles.len();
+    profiles.retain(|p| !same_name(&p.name, &name));
+    if profiles.len() == before {
+        return Err(format!("Profile not found: {name}"));
+    }
+    save(&app, &profiles)
+}
+
+#[tauri::command]
+pub fn start_from_profile(
+    app: AppHandle,
+    state: State<RunnerState>,
+    name: String,
+) -> Result<RunnerStatus, String> {
+    let mut profiles = load(&app);
+    let profile = profiles
+        .iter_mut()
+        .find(|p| same_name(&p.name, &name))
+        .ok_or_else(|| format!("Profile not found: {name}"))?;
+
+    let status = crate::start_transcription(app.clone(), state, profile.request.clone())?;
+    profile.last_used_epoch = Some(crate::now_epoch());
+    if let Err(err) = save(&app, &profiles) {
+        crate::emit_log(&app, "system", err);
+    }
+    Ok(status)
+}
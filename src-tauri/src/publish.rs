@@ -0,0 +1,257 @@
+use crate::settings::{self, YoutubeSettings};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Url};
+use tauri_plugin_opener::OpenerExt;
+use tiny_http::{Header, Response, Server};
+
+const GOOGLE_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const YOUTUBE_CAPTIONS_UPLOAD_URL: &str = "https://www.googleapis.com/upload/youtube/v3/captions";
+const YOUTUBE_SCOPE: &str = "https://www.googleapis.com/auth/youtube.force-ssl";
+const AUTH_TIMEOUT: Duration = Duration::from_secs(300);
+const API_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CaptionResource {
+    id: String,
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(API_TIMEOUT).build()
+}
+
+pub fn describe_error(context: &str, err: ureq::Error) -> String {
+    match err {
+        ureq::Error::Status(code, response) => {
+            let detail = response.into_string().unwrap_or_default();
+            format!("{context} failed ({code}): {}", detail.trim())
+        }
+        err => format!("{context} failed: {err}"),
+    }
+}
+
+fn token_request(form: &[(&str, &str)]) -> Result<TokenResponse, String> {
+    agent()
+        .post(GOOGLE_TOKEN_URL)
+        .send_form(form)
+        .map_err(|err| describe_error("Google token request", err))?
+        .into_json()
+        .map_err(|err| format!("Unexpected Google token response: {err}"))
+}
+
+fn require_client(youtube: &YoutubeSettings) -> Result<(), String> {
+    if youtube.client_id.trim().is_empty() || youtube.client_secret.trim().is_empty() {
+        return Err("YouTube OAuth client ID/secret are not configured.".to_string());
+    }
+    Ok(())
+}
+
+fn html_response(message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes("Content-Type", "text/html; charset=utf-8")
+        .expect("static header is valid");
+    Response::from_string(format!(
+        "<html><body style=\"font-family:sans-serif\"><p>{message}</p></body></html>"
+    ))
+    .with_header(header)
+}
+
+/// Installed-app OAuth: opens Google consent in the browser and catches the
+/// redirect on a one-shot loopback listener, then stores the refresh token.
+fn authorize(app: &AppHandle) -> Result<(), String> {
+    let youtube = settings::load(app).youtube;
+    require_client(&youtube)?;
+
+    let server = Server::http("127.0.0.1:0")
+        .map_err(|err| format!("Could not start OAuth callback listener: {err}"))?;
+    let port = server
+        .server_addr()
+        .to_ip()
+        .map(|addr| addr.port())
+        .ok_or_else(|| "OAuth callback listener has no TCP port.".to_string())?;
+    let redirect_uri = format!("http://127.0.0.1:{port}");
+    let state = settings::generate_token();
+
+    let consent_url = Url::parse_with_params(
+        GOOGLE_AUTH_URL,
+        [
+            ("client_id", youtube.client_id.trim()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", YOUTUBE_SCOPE),
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+            ("state", state.as_str()),
+        ],
+    )
+    .map_err(|err| format!("Could not build consent URL: {err}"))?;
+    app.opener()
+        .open_url(consent_url.as_str(), None::<&str>)
+        .map_err(|err| format!("Could not open browser for YouTube sign-in: {err}"))?;
+
+    let deadline = Instant::now() + AUTH_TIMEOUT;
+    let code = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err("Timed out waiting for YouTube sign-in.".to_string());
+        }
+        let request = match server.recv_timeout(remaining) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(err) => return Err(format!("YouTube sign-in listener failed: {err}")),
+        };
+
+        let Ok(url) = Url::parse(&format!("{redirect_uri}{}", request.url())) else {
+            continue;
+        };
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.to_string())
+        };
+
+        // Browsers also ask for /favicon.ico; only the redirect carries `state`.
+        if param("state").as_deref() != Some(state.as_str()) {
+            let _ = request.respond(Response::empty(404));
+            continue;
+        }
+        if let Some(error) = param("error") {
+            let _ = request.respond(html_response("YouTube sign-in was cancelled. You can close this tab."));
+            return Err(format!("YouTube sign-in failed: {error}"));
+        }
+        if let Some(code) = param("code") {
+            let _ = request.respond(html_response(
+                "YouTube is connected. You can close this tab and return to the transcriber.",
+            ));
+            break code;
+        }
+    };
+
+    let tokens = token_request(&[
+        ("code", code.as_str()),
+        ("client_id", youtube.client_id.trim()),
+        ("client_secret", youtube.client_secret.trim()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("grant_type", "authorization_code"),
+    ])?;
+    let refresh_token = tokens
+        .refresh_token
+        .ok_or_else(|| "Google did not return a refresh token.".to_string())?;
+
+    let mut current = settings::load(app);
+    current.youtube.refresh_token = refresh_token;
+    settings::save(app, &current)
+}
+
+fn access_token(youtube: &YoutubeSettings) -> Result<String, String> {
+    require_client(youtube)?;
+    if youtube.refresh_token.trim().is_empty() {
+        return Err("YouTube is not connected. Sign in from Settings first.".to_string());
+    }
+
+    token_request(&[
+        ("refresh_token", youtube.refresh_token.trim()),
+        ("client_id", youtube.client_id.trim()),
+        ("client_secret", youtube.client_secret.trim()),
+        ("grant_type", "refresh_token"),
+    ])
+    .map(|t| t.access_token)
+}
+
+/// Accepts a bare video ID or a watch / youtu.be / live URL.
+fn video_id_from(input: &str) -> String {
+    let trimmed = input.trim();
+    let Ok(url) = Url::parse(trimmed) else {
+        return trimmed.to_string();
+    };
+    url.query_pairs()
+        .find(|(k, _)| k == "v")
+        .map(|(_, v)| v.to_string())
+        .or_else(|| url.path_segments().and_then(|mut s| s.next_back()).map(str::to_string))
+        .unwrap_or_else(|| trimmed.to_string())
+}
+
+fn upload_captions(youtube: &YoutubeSettings, video_id: &str, caption_path: &Path) -> Result<String, String> {
+    let extension = caption_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if extension != "srt" && extension != "vtt" {
+        return Err(format!(
+            "Captions must be an .srt or .vtt file: {}",
+            caption_path.display()
+        ));
+    }
+    let captions = fs::read(caption_path)
+        .map_err(|err| format!("Could not read {}: {err}", caption_path.display()))?;
+
+    let video_id = video_id_from(video_id);
+    if video_id.is_empty() {
+        return Err("A YouTube video ID is required.".to_string());
+    }
+
+    let metadata = json!({
+        "snippet": {
+            "videoId": video_id,
+            "language": youtube.caption_language,
+            "name": youtube.caption_name,
+            "isDraft": false,
+        }
+    });
+    let boundary = format!("captions-{}", settings::generate_token());
+    let mut body = format!(
+        "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n--{boundary}\r\nContent-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(&captions);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let token = access_token(youtube)?;
+    let created: CaptionResource = agent()
+        .post(YOUTUBE_CAPTIONS_UPLOAD_URL)
+        .query("uploadType", "multipart")
+        .query("part", "snippet")
+        .set("Authorization", &format!("Bearer {token}"))
+        .set(
+            "Content-Type",
+            &format!("multipart/related; boundary={boundary}"),
+        )
+        .send_bytes(&body)
+        .map_err(|err| describe_error("YouTube caption upload", err))?
+        .into_json()
+        .map_err(|err| format!("Unexpected YouTube response: {err}"))?;
+    Ok(created.id)
+}
+
+#[tauri::command]
+pub async fn youtube_authorize(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || authorize(&app))
+        .await
+        .map_err(|err| format!("YouTube sign-in task failed: {err}"))?
+}
+
+/// Returns the new caption track ID.
+#[tauri::command]
+pub async fn upload_youtube_captions(
+    app: AppHandle,
+    video_id: String,
+    caption_path: String,
+) -> Result<String, String> {
+    let youtube = settings::load(&app).youtube;
+    tauri::async_runtime::spawn_blocking(move || {
+        upload_captions(&youtube, &video_id, Path::new(caption_path.trim()))
+    })
+    .await
+    .map_err(|err| format!("Caption upload task failed: {err}"))?
+}
@@ -0,0 +1,248 @@
+use crate::{
+    export::xml_escape,
+    history::RunReport,
+    preprocess::{AudioStats, DenoiseStats},
+    quality, scanner,
+    transcript::{self, GarbageRetry},
+};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Emitter};
+
+const RAW_FILE: &str = "raw.txt";
+/// Sermons run 120-160 words a minute; far below that, whisper likely
+/// dropped speech or the recording is mostly music.
+const SHORT_WORDS_PER_MINUTE: f64 = 40.0;
+/// Recordings shorter than this aren't judged on words per minute.
+const SHORT_CHECK_MIN_SECS: u64 = 300;
+/// The same segment text this many times in a row is a hallucination loop.
+const REPEAT_RUN: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileQc {
+    pub source: String,
+    pub status: String,
+    pub output_dir: Option<String>,
+    pub audio_secs: Option<u64>,
+    pub words: usize,
+    pub words_per_minute: Option<f64>,
+    pub empty: bool,
+    pub short: bool,
+    /// Runs of `REPEAT_RUN`+ identical consecutive segments.
+    pub repeated_phrases: usize,
+    pub low_confidence_ranges: Option<usize>,
+    /// Set when the run denoised the audio.
+    pub denoise: Option<DenoiseStats>,
+    /// Set when the first attempt produced garbage and the file was re-run.
+    pub garbage_retry: Option<GarbageRetry>,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunQc {
+    pub run_id: String,
+    pub generated_at_epoch: u64,
+    pub files_processed: usize,
+    pub failures: usize,
+    pub empty_transcripts: usize,
+    pub short_transcripts: usize,
+    pub repeated_phrases: usize,
+    /// Files re-run after garbage output that were still garbage.
+    pub garbage_after_retry: usize,
+    pub files_with_issues: usize,
+    pub files: Vec<FileQc>,
+    pub json_path: String,
+    pub html_path: String,
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn repeated_phrases(output_dir: &Path) -> usize {
+    let segments = transcript::load_segments(output_dir).unwrap_or_default();
+    let mut runs = 0;
+    let mut streak = 1;
+    for pair in segments.windows(2) {
+        let same = {
+            let text = normalize(&pair[1].text);
+            !text.is_empty() && text == normalize(&pair[0].text)
+        };
+        streak = if same { streak + 1 } else { 1 };
+        if streak == REPEAT_RUN {
+            runs += 1;
+        }
+    }
+    runs
+}
+
+fn check_file(report: &RunReport, index: usize) -> FileQc {
+    let result = &report.results[index];
+    let mut qc = FileQc {
+        source: result.source.clone(),
+        status: result.status.clone(),
+        output_dir: result.output_dir.clone(),
+        audio_secs: None,
+        words: 0,
+        words_per_minute: None,
+        empty: false,
+        short: false,
+        repeated_phrases: 0,
+        low_confidence_ranges: None,
+        denoise: None,
+        garbage_retry: None,
+        issues: Vec::new(),
+    };
+    if result.failed() {
+        qc.issues.push(format!("Failed ({})", result.status));
+        return qc;
+    }
+    // Skipped files were transcribed (or ruled out) by an earlier run.
+    let Some(output_dir) = result.output_dir.as_deref().filter(|_| result.status == "ok").map(PathBuf::from) else {
+        return qc;
+    };
+
+    qc.audio_secs = report
+        .planned
+        .iter()
+        .find(|p| p.source == result.source)
+        .and_then(|p| p.duration_secs)
+        .or_else(|| scanner::media_duration_secs(Path::new(&result.source)));
+    qc.words = fs::read_to_string(output_dir.join(RAW_FILE))
+        .map(|text| text.split_whitespace().count())
+        .unwrap_or(0);
+    qc.empty = qc.words == 0;
+    if qc.empty {
+        qc.issues.push("Empty transcript".to_string());
+    }
+    if let Some(secs) = qc.audio_secs.filter(|s| *s > 0) {
+        let wpm = qc.words as f64 * 60.0 / secs as f64;
+        qc.words_per_minute = Some(wpm);
+        qc.short = !qc.empty && secs >= SHORT_CHECK_MIN_SECS && wpm < SHORT_WORDS_PER_MINUTE;
+        if qc.short {
+            qc.issues.push(format!("Only {wpm:.0} words/minute for {} of audio", transcript::format_timestamp(secs * 1000)));
+        }
+    }
+    qc.repeated_phrases = repeated_phrases(&output_dir);
+    if qc.repeated_phrases > 0 {
+        qc.issues.push(format!("{} repeated-phrase loop(s)", qc.repeated_phrases));
+    }
+    qc.low_confidence_ranges = fs::read_to_string(output_dir.join(quality::QUALITY_FILE))
+        .ok()
+        .and_then(|body| serde_json::from_str::<quality::QualityReport>(&body).ok())
+        .map(|q| q.flagged.len());
+    qc.denoise = transcript::denoise_stats(&output_dir);
+    qc.garbage_retry = transcript::garbage_retry(&output_dir);
+    if let Some(retry) = qc.garbage_retry.as_ref().filter(|r| !r.remaining.is_empty()) {
+        qc.issues.push(format!("Still garbage after a retry: {}", retry.remaining));
+    }
+    qc
+}
+
+/// `before -> after` for one measure, blank when neither was measured.
+fn change(stats: &DenoiseStats, measure: fn(&AudioStats) -> Option<f64>, unit: &str) -> String {
+    let show = |value: Option<f64>| value.map(|v| format!("{v:.1}")).unwrap_or_else(|| "?".to_string());
+    match (measure(&stats.before), measure(&stats.after)) {
+        (None, None) => String::new(),
+        (before, after) => format!("{} &rarr; {} {unit}", show(before), show(after)),
+    }
+}
+
+fn denoise_cell(stats: Option<&DenoiseStats>) -> String {
+    let Some(stats) = stats else {
+        return String::new();
+    };
+    [
+        change(stats, |s| s.integrated_lufs, "LUFS"),
+        change(stats, |s| s.noise_floor_db, "dB floor"),
+    ]
+    .into_iter()
+    .filter(|c| !c.is_empty())
+    .collect::<Vec<_>>()
+    .join("<br>")
+}
+
+fn render_html(qc: &RunQc) -> String {
+    // The denoise column only appears for runs that denoised.
+    let denoised = qc.files.iter().any(|f| f.denoise.is_some());
+    let rows: String = qc
+        .files
+        .iter()
+        .map(|f| {
+            format!(
+                "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>{}<td>{}</td></tr>\n",
+                if f.issues.is_empty() { "ok" } else { "issue" },
+                xml_escape(&f.source),
+                xml_escape(&f.status),
+                f.audio_secs.map(|s| transcript::format_timestamp(s * 1000)).unwrap_or_default(),
+                f.words,
+                f.low_confidence_ranges.map(|n| n.to_string()).unwrap_or_default(),
+                if denoised { format!("<td>{}</td>", denoise_cell(f.denoise.as_ref())) } else { String::new() },
+                xml_escape(&f.issues.join("; ")),
+            )
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>QC report {run}</title>\n\
+         <style>body{{font-family:sans-serif}}td,th{{padding:4px 8px;text-align:left}}\
+         tr.issue{{background:#fdecea}}</style></head><body>\n\
+         <h1>QC report {run}</h1>\n<ul>\
+         <li>Files processed: {processed}</li><li>Failures: {failures}</li>\
+         <li>Empty transcripts: {empty}</li><li>Unusually short transcripts: {short}</li>\
+         <li>Repeated-phrase loops: {repeated}</li>\
+         <li>Garbage after an automatic retry: {garbage}</li></ul>\n\
+         <table><tr><th>Source</th><th>Status</th><th>Audio</th><th>Words</th>\
+         <th>Low-confidence ranges</th>{denoise}<th>Issues</th></tr>\n{rows}</table></body></html>\n",
+        run = xml_escape(&qc.run_id),
+        processed = qc.files_processed,
+        failures = qc.failures,
+        empty = qc.empty_transcripts,
+        short = qc.short_transcripts,
+        repeated = qc.repeated_phrases,
+        garbage = qc.garbage_after_retry,
+        denoise = if denoised { "<th>Denoise (before &rarr; after)</th>" } else { "" },
+    )
+}
+
+/// Checks every file of the finished run for empty, short and looping
+/// transcripts, writes `qc-<run id>.json` and `.html` into the output
+/// folder, and emits `transcribe://qc-report`.
+pub fn write_run_report(app: &AppHandle, report: &RunReport) -> Result<RunQc, String> {
+    let files: Vec<FileQc> = (0..report.results.len()).map(|i| check_file(report, i)).collect();
+    let folder = PathBuf::from(report.output_folder.trim());
+    let json_path = folder.join(format!("qc-{}.json", report.run_id));
+    let html_path = folder.join(format!("qc-{}.html", report.run_id));
+    let qc = RunQc {
+        run_id: report.run_id.clone(),
+        generated_at_epoch: crate::now_epoch(),
+        files_processed: files.iter().filter(|f| f.status == "ok").count(),
+        failures: files.iter().filter(|f| f.status.starts_with("error")).count(),
+        empty_transcripts: files.iter().filter(|f| f.empty).count(),
+        short_transcripts: files.iter().filter(|f| f.short).count(),
+        repeated_phrases: files.iter().map(|f| f.repeated_phrases).sum(),
+        garbage_after_retry: files
+            .iter()
+            .filter(|f| f.garbage_retry.as_ref().is_some_and(|r| !r.remaining.is_empty()))
+            .count(),
+        files_with_issues: files.iter().filter(|f| !f.issues.is_empty()).count(),
+        files,
+        json_path: json_path.display().to_string(),
+        html_path: html_path.display().to_string(),
+    };
+
+    let body = serde_json::to_string_pretty(&qc).map_err(|err| format!("Failed to serialize QC report: {err}"))?;
+    fs::write(&json_path, body).map_err(|err| format!("Failed to write {}: {err}", json_path.display()))?;
+    fs::write(&html_path, render_html(&qc))
+        .map_err(|err| format!("Failed to write {}: {err}", html_path.display()))?;
+    let _ = app.emit("transcribe://qc-report", qc.clone());
+    Ok(qc)
+}
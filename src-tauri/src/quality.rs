@@ -0,0 +1,132 @@
+use crate::{
+    redaction::Redactor,
+    settings,
+    transcript::{self, format_timestamp},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::AppHandle;
+
+pub const QUALITY_FILE: &str = "quality.json";
+pub const DEFAULT_THRESHOLD: f32 = 0.5;
+/// Flagged segments closer together than this are reviewed as one range.
+const MERGE_GAP_MS: u64 = 2000;
+
+/// A stretch of low-confidence speech for a proofreader to check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// `HH:MM:SS` of the start, for jumping to it in a player.
+    pub timestamp: String,
+    /// Lowest segment confidence in the range.
+    pub confidence: f32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityReport {
+    pub generated_at_epoch: u64,
+    pub threshold: f32,
+    pub total_segments: usize,
+    pub scored_segments: usize,
+    /// Weighted by segment duration, 0-1.
+    pub average_confidence: Option<f32>,
+    pub flagged_secs: u64,
+    pub flagged_percent: f32,
+    pub flagged: Vec<FlaggedRange>,
+}
+
+/// Scores the transcript in `output_dir` and writes `quality.json`, with the
+/// flagged text redacted. Returns `None` when the engine output has no
+/// confidence data (older `-oj` files).
+pub fn write_report(
+    output_dir: &Path,
+    threshold: f32,
+    redactor: Option<&Redactor>,
+) -> Result<Option<(PathBuf, QualityReport)>, String> {
+    let segments = transcript::load_scored_segments(output_dir)?;
+    let scored: Vec<_> = segments
+        .iter()
+        .filter_map(|s| s.confidence.map(|c| (s, c)))
+        .collect();
+    if scored.is_empty() {
+        return Ok(None);
+    }
+
+    let duration = |start: u64, end: u64| end.saturating_sub(start).max(1);
+    let total_ms: u64 = scored.iter().map(|(s, _)| duration(s.start_ms, s.end_ms)).sum();
+    let weighted: f64 = scored
+        .iter()
+        .map(|(s, c)| *c as f64 * duration(s.start_ms, s.end_ms) as f64)
+        .sum();
+
+    let mut flagged: Vec<FlaggedRange> = Vec::new();
+    for (segment, confidence) in scored.iter().filter(|(_, c)| *c < threshold) {
+        match flagged.last_mut() {
+            Some(range) if segment.start_ms <= range.end_ms + MERGE_GAP_MS => {
+                range.end_ms = range.end_ms.max(segment.end_ms);
+                range.confidence = range.confidence.min(*confidence);
+                range.text.push(' ');
+                range.text.push_str(&segment.text);
+            }
+            _ => flagged.push(FlaggedRange {
+                start_ms: segment.start_ms,
+                end_ms: segment.end_ms,
+                timestamp: format_timestamp(segment.start_ms),
+                confidence: *confidence,
+                text: segment.text.clone(),
+            }),
+        }
+    }
+    if let Some(redactor) = redactor {
+        for range in flagged.iter_mut() {
+            range.text = redactor.apply(&range.text);
+        }
+    }
+    let flagged_ms: u64 = flagged.iter().map(|r| r.end_ms.saturating_sub(r.start_ms)).sum();
+
+    let report = QualityReport {
+        generated_at_epoch: crate::now_epoch(),
+        threshold,
+        total_segments: segments.len(),
+        scored_segments: scored.len(),
+        average_confidence: Some((weighted / total_ms as f64) as f32),
+        flagged_secs: flagged_ms / 1000,
+        flagged_percent: (flagged_ms as f64 * 100.0 / total_ms as f64).min(100.0) as f32,
+        flagged,
+    };
+    let path = output_dir.join(QUALITY_FILE);
+    let body = serde_json::to_string_pretty(&report)
+        .map_err(|err| format!("Failed to serialize quality report: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(Some((path, report)))
+}
+
+/// The quality report for a transcript folder, scoring it now if the run
+/// didn't write one.
+#[tauri::command]
+pub fn get_quality_report(
+    app: AppHandle,
+    output_dir: String,
+    threshold: Option<f32>,
+) -> Result<QualityReport, String> {
+    let dir = PathBuf::from(output_dir.trim());
+    if threshold.is_none() {
+        if let Some(report) = fs::read_to_string(dir.join(QUALITY_FILE))
+            .ok()
+            .and_then(|body| serde_json::from_str(&body).ok())
+        {
+            return Ok(report);
+        }
+    }
+    let redactor = Redactor::new(&settings::load(&app).redaction);
+    write_report(&dir, threshold.unwrap_or(DEFAULT_THRESHOLD), redactor.as_ref())?
+        .map(|(_, report)| report)
+        .ok_or_else(|| format!("{} has no confidence data; re-run it to score it.", dir.display()))
+}
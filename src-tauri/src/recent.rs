@@ -0,0 +1,147 @@
+use crate::{longpath, scanner, stream};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+const RECENT_FILE: &str = "recent-folders.json";
+/// Unpinned folders kept per list; favorites don't count against it.
+const MAX_RECENT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FolderRole {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFolder {
+    pub path: String,
+    pub last_used_epoch: u64,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Whether the folder is reachable now; a NAS share may be offline.
+    #[serde(default, skip_deserializing)]
+    pub exists: bool,
+}
+
+/// Favorites first, then the most recently used.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RecentFolders {
+    pub inputs: Vec<RecentFolder>,
+    pub outputs: Vec<RecentFolder>,
+}
+
+impl RecentFolders {
+    fn list_mut(&mut self, role: FolderRole) -> &mut Vec<RecentFolder> {
+        match role {
+            FolderRole::Input => &mut self.inputs,
+            FolderRole::Output => &mut self.outputs,
+        }
+    }
+}
+
+fn recent_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(RECENT_FILE))
+}
+
+fn load(app: &AppHandle) -> RecentFolders {
+    recent_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, recent: &RecentFolders) -> Result<(), String> {
+    let path = recent_path(app)?;
+    let body = serde_json::to_string_pretty(recent)
+        .map_err(|err| format!("Failed to serialize recent folders: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Sorts favorites ahead of the rest and drops the oldest unpinned entries.
+fn tidy(list: &mut Vec<RecentFolder>) {
+    list.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.last_used_epoch.cmp(&a.last_used_epoch)));
+    let mut unpinned = 0;
+    list.retain(|folder| {
+        unpinned += usize::from(!folder.pinned);
+        folder.pinned || unpinned <= MAX_RECENT
+    });
+}
+
+/// The entry for `path`, added when it isn't listed yet.
+fn entry<'a>(list: &'a mut Vec<RecentFolder>, path: &str) -> &'a mut RecentFolder {
+    let index = match list.iter().position(|folder| scanner::same_path(&folder.path, path)) {
+        Some(index) => index,
+        None => {
+            list.push(RecentFolder {
+                path: path.to_string(),
+                last_used_epoch: 0,
+                pinned: false,
+                exists: false,
+            });
+            list.len() - 1
+        }
+    };
+    &mut list[index]
+}
+
+/// Remembers a run's input and output folders. Stream URLs aren't folders
+/// and are left out.
+pub fn record_run(app: &AppHandle, input_folders: &[String], output_folder: &str) {
+    let now = crate::now_epoch();
+    let mut recent = load(app);
+    let inputs = input_folders.iter().map(|f| f.trim()).filter(|f| !f.is_empty() && !stream::is_stream_url(f));
+    for folder in inputs {
+        entry(&mut recent.inputs, folder).last_used_epoch = now;
+    }
+    let output_folder = output_folder.trim();
+    if !output_folder.is_empty() {
+        entry(&mut recent.outputs, output_folder).last_used_epoch = now;
+    }
+    tidy(&mut recent.inputs);
+    tidy(&mut recent.outputs);
+    if let Err(err) = save(app, &recent) {
+        crate::emit_log(app, "system", err);
+    }
+}
+
+#[tauri::command]
+pub fn get_recent_folders(app: AppHandle) -> RecentFolders {
+    let mut recent = load(&app);
+    for folder in recent.inputs.iter_mut().chain(recent.outputs.iter_mut()) {
+        folder.exists = longpath::extended(&PathBuf::from(&folder.path)).exists();
+    }
+    recent
+}
+
+/// Pins `path` as a favorite input or output folder (adding it if it isn't
+/// listed), or unpins it with `pinned: false`. Favorites stay listed however
+/// long they go unused.
+#[tauri::command]
+pub fn pin_favorite_folder(
+    app: AppHandle,
+    path: String,
+    role: FolderRole,
+    pinned: bool,
+) -> Result<RecentFolders, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Folder path is required.".to_string());
+    }
+    let mut recent = load(&app);
+    let list = recent.list_mut(role);
+    entry(list, path).pinned = pinned;
+    tidy(list);
+    save(&app, &recent)?;
+    Ok(get_recent_folders(app))
+}
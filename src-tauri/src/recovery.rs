@@ -0,0 +1,161 @@
+use crate::{checkpoint, history, staging, RunnerState};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+const INTERRUPTED_MESSAGE: &str = "Interrupted: the app closed before the run finished.";
+
+/// A run the app was in the middle of when it last exited.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedRun {
+    pub run_id: String,
+    pub current_folder: String,
+    pub folder_index: usize,
+    pub total_folders: usize,
+    pub files_finished: usize,
+    /// False when only a user stop left the checkpoint behind.
+    pub crashed: bool,
+    pub last_activity_epoch: u64,
+    pub cleaned_up: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct RecoveryState {
+    interrupted: Mutex<Option<InterruptedRun>>,
+}
+
+fn remove(path: &Path, cleaned: &mut Vec<String>) {
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path).is_ok()
+    } else {
+        path.exists() && fs::remove_file(path).is_ok()
+    };
+    if removed {
+        cleaned.push(path.display().to_string());
+    }
+}
+
+/// Plan files and local copies in the temp folder; nothing is running at
+/// launch, so anything of ours there is left over.
+fn temp_artifacts() -> Vec<PathBuf> {
+    let temp = std::env::temp_dir();
+    let mut found: Vec<PathBuf> = fs::read_dir(&temp)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    name.starts_with("church-transcriber-") && name.contains("-plan-") && name.ends_with(".json")
+                })
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    found.push(staging::default_root());
+    found
+}
+
+/// Clears what a crashed run left behind (pause flag, plan files, staged
+/// copies, half-extracted audio), closes its history record, and emits
+/// `transcribe://interrupted` so the UI can offer `resume_last_run`.
+pub fn recover(app: &AppHandle) {
+    let mut cleaned = Vec::new();
+    for path in temp_artifacts() {
+        remove(&path, &mut cleaned);
+    }
+
+    let Some(checkpoint) = checkpoint::load(app) else {
+        if !cleaned.is_empty() {
+            crate::emit_log(app, "system", format!("Removed {} leftover temp files.", cleaned.len()));
+        }
+        return;
+    };
+    let request = &checkpoint.request;
+    let output = PathBuf::from(request.output_folder.trim());
+    remove(&output.join(".transcribe.pause"), &mut cleaned);
+    remove(&output.join(crate::STOP_FLAG_FILE), &mut cleaned);
+    remove(&output.join(".church-transcriber-write-test"), &mut cleaned);
+    if let Some(root) = request.staging_folder.as_deref().filter(|r| !r.trim().is_empty()) {
+        for index in 0..request.input_folders.len() {
+            remove(&staging::staging_dir(Some(root), &checkpoint.run_id, index), &mut cleaned);
+        }
+    }
+
+    let mut files_finished = 0;
+    let mut crashed = false;
+    if let Ok(mut report) = history::load_report(app, &checkpoint.run_id) {
+        files_finished = report.results.len();
+        if report.finished_at_epoch.is_none() {
+            crashed = true;
+            // The file in flight when the app died may have left extracted audio.
+            if !request.keep_audio {
+                for planned in &report.planned {
+                    if report.results.iter().any(|r| r.source == planned.source) {
+                        continue;
+                    }
+                    for name in ["audio-source.wav", "audio-trimmed.wav"] {
+                        remove(&Path::new(&planned.output_dir).join(name), &mut cleaned);
+                    }
+                }
+            }
+            report.finished_at_epoch = Some(checkpoint.updated_at_epoch);
+            report.success = Some(false);
+            report.message = Some(INTERRUPTED_MESSAGE.to_string());
+            if let Err(err) = history::save_report(app, &report) {
+                crate::emit_log(app, "system", err);
+            }
+        }
+    }
+
+    let interrupted = InterruptedRun {
+        run_id: checkpoint.run_id.clone(),
+        current_folder: checkpoint.current_folder.clone(),
+        folder_index: checkpoint.folder_index,
+        total_folders: request.input_folders.len(),
+        files_finished,
+        crashed,
+        last_activity_epoch: checkpoint.updated_at_epoch,
+        cleaned_up: cleaned,
+    };
+
+    crate::emit_log(
+        app,
+        "system",
+        format!(
+            "Run {} did not finish (last folder: {}). Use resume to continue it.",
+            interrupted.run_id, interrupted.current_folder
+        ),
+    );
+    if !interrupted.cleaned_up.is_empty() {
+        crate::emit_log(
+            app,
+            "system",
+            format!("Cleaned up {} leftovers from the interrupted run.", interrupted.cleaned_up.len()),
+        );
+    }
+    let _ = app.emit("transcribe://interrupted", interrupted.clone());
+    if let Ok(mut slot) = app.state::<RecoveryState>().interrupted.lock() {
+        *slot = Some(interrupted);
+    }
+}
+
+/// The interrupted run found at launch, if it has not been resumed or
+/// replaced since. The window may load after the launch event was sent.
+#[tauri::command]
+pub fn get_interrupted_run(app: AppHandle) -> Option<InterruptedRun> {
+    if crate::get_status(&app.state::<RunnerState>()).running {
+        return None;
+    }
+    let checkpoint = checkpoint::load(&app)?;
+    app.state::<RecoveryState>()
+        .interrupted
+        .lock()
+        .ok()?
+        .clone()
+        .filter(|run| run.run_id == checkpoint.run_id)
+}
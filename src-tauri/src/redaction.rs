@@ -0,0 +1,167 @@
+use crate::{
+    channels, postprocess,
+    settings::{self, RedactionSettings},
+    transcript::{Segment, TIMESTAMPS_FILE},
+    translation,
+};
+use regex::Regex;
+use std::{fs, path::Path};
+use tauri::AppHandle;
+
+/// Hidden folder in a transcript folder where the unredacted script outputs
+/// are kept; the archive listing and the remote API never reach into hidden
+/// folders.
+pub const PRIVATE_DIR: &str = ".private";
+
+/// Script outputs the app reads back and so leaves unredacted in place.
+const UNREDACTED_FILES: &[&str] = &[
+    "raw.txt",
+    TIMESTAMPS_FILE,
+    "raw.en.txt",
+    translation::WHISPER_TIMESTAMPS_FILE,
+    channels::CHANNEL_OUTPUT_FILES[0],
+    channels::CHANNEL_OUTPUT_FILES[1],
+];
+
+/// Files kept out of the remote API and cloud sync: the unredacted script
+/// outputs, while a redaction list is set.
+pub fn withheld_files(settings: &RedactionSettings) -> &'static [&'static str] {
+    if Redactor::new(settings).is_some() {
+        UNREDACTED_FILES
+    } else {
+        &[]
+    }
+}
+
+/// Replaces names and terms from the redaction list in published text.
+pub struct Redactor {
+    pattern: Regex,
+    replacement: String,
+}
+
+impl Redactor {
+    /// `None` when the list is empty.
+    pub fn new(settings: &RedactionSettings) -> Option<Self> {
+        let replacement = match settings.replacement.trim() {
+            "" => RedactionSettings::default().replacement,
+            text => text.to_string(),
+        };
+        postprocess::word_pattern(&settings.terms).map(|pattern| Self { pattern, replacement })
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        self.pattern.replace_all(text, regex::NoExpand(&self.replacement)).into_owned()
+    }
+
+    pub fn apply_segments(&self, segments: &[Segment]) -> Vec<Segment> {
+        segments
+            .iter()
+            .map(|s| Segment {
+                text: self.apply(&s.text),
+                ..s.clone()
+            })
+            .collect()
+    }
+
+    /// Redacts script-generated published files in place, first copying each
+    /// one that changes into `.private/`.
+    pub fn apply_to_files(&self, output_dir: &Path, names: &[&str]) -> Result<usize, String> {
+        let private_dir = output_dir.join(PRIVATE_DIR);
+        let mut rewritten = 0;
+        for name in names {
+            let path = output_dir.join(name);
+            let Ok(body) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let redacted = self.apply(&body);
+            if redacted == body {
+                continue;
+            }
+            fs::create_dir_all(&private_dir)
+                .map_err(|err| format!("Could not create {}: {err}", private_dir.display()))?;
+            let original = private_dir.join(name);
+            fs::write(&original, &body)
+                .map_err(|err| format!("Failed to write {}: {err}", original.display()))?;
+            fs::write(&path, redacted).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+            rewritten += 1;
+        }
+        Ok(rewritten)
+    }
+}
+
+/// Replaces the redaction list and saves it to settings. Applies to runs
+/// started afterwards.
+#[tauri::command]
+pub fn set_redaction_list(
+    app: AppHandle,
+    terms: Vec<String>,
+    replacement: Option<String>,
+) -> Result<RedactionSettings, String> {
+    let mut all = settings::load(&app);
+    let mut terms: Vec<String> = terms
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    terms.sort_by_key(|t| t.to_lowercase());
+    terms.dedup_by_key(|t| t.to_lowercase());
+    all.redaction.terms = terms;
+    if let Some(replacement) = replacement {
+        all.redaction.replacement = replacement;
+    }
+    settings::save(&app, &all)?;
+    Ok(all.redaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{channels::ChannelOptions, quality};
+
+    fn whisper_json(text: &str) -> String {
+        serde_json::json!({
+            "transcription": [{
+                "offsets": { "from": 0, "to": 4000 },
+                "text": text,
+                "tokens": [{ "text": text, "p": 0.1 }],
+            }]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn redacted_terms_only_reach_withheld_files() {
+        let dir = std::env::temp_dir().join(format!("church-transcriber-redaction-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let spoken = "Please pray for Smithers this week.";
+        fs::write(dir.join("raw.txt"), spoken).unwrap();
+        fs::write(dir.join("clean.md"), spoken).unwrap();
+        fs::write(dir.join(TIMESTAMPS_FILE), whisper_json(spoken)).unwrap();
+        for file in channels::CHANNEL_OUTPUT_FILES {
+            fs::write(dir.join(file), whisper_json(spoken)).unwrap();
+        }
+
+        let settings = RedactionSettings {
+            terms: vec!["Smithers".to_string()],
+            ..RedactionSettings::default()
+        };
+        let redactor = Redactor::new(&settings).unwrap();
+        quality::write_report(&dir, 0.5, Some(&redactor)).unwrap().unwrap();
+        channels::write_channel_transcript(&dir, "Service", &ChannelOptions::default(), Some(&redactor), None)
+            .unwrap()
+            .unwrap();
+        redactor.apply_to_files(&dir, postprocess::SCRIPT_PUBLISHED_FILES).unwrap();
+
+        let withheld = withheld_files(&settings);
+        for entry in fs::read_dir(&dir).unwrap().flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') || withheld.contains(&name.as_str()) {
+                continue;
+            }
+            let body = fs::read_to_string(entry.path()).unwrap();
+            assert!(!body.contains("Smithers"), "{name} leaks a redacted term");
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,463 @@
+use crate::{
+    archive::{self, ArchiveEntry, ArchiveMatch, ArchiveRoot},
+    history::{self, FileResult, RunReport},
+    redaction,
+    scanner::{self, PlannedFile},
+    settings::{self, RemoteApiSettings},
+    LogEvent, RunnerState, RunnerStatus, StartRequest,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::Read,
+    net::IpAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Manager, Url};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const MAX_SEARCH_RESULTS: usize = 50;
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+#[derive(Default)]
+pub struct RemoteApiState {
+    server: Mutex<Option<Arc<Server>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    pub status: RunnerStatus,
+    pub report: Option<RunReport>,
+    pub recent_logs: Vec<LogEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteQueue {
+    pub run_id: Option<String>,
+    pub pending: Vec<PlannedFile>,
+    pub finished: Vec<FileResult>,
+}
+
+struct ApiError {
+    code: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(code: u16, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self::new(400, message)
+    }
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+        .expect("static header is valid")
+}
+
+fn respond_json<T: Serialize>(request: Request, code: u16, body: &T) {
+    let text = serde_json::to_string(body).unwrap_or_else(|_| "null".to_string());
+    let response = Response::from_string(text)
+        .with_status_code(code)
+        .with_header(json_header());
+    let _ = request.respond(response);
+}
+
+fn request_token(request: &Request, url: &Url) -> Option<String> {
+    let from_header = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer ").map(str::to_string));
+
+    from_header.or_else(|| {
+        url.query_pairs()
+            .find(|(k, _)| k == "token")
+            .map(|(_, v)| v.to_string())
+    })
+}
+
+/// Compares every byte rather than stopping at the first difference, so
+/// response times don't tell a guesser how much of a token was right.
+fn token_matches(given: &str, expected: &str) -> bool {
+    !expected.is_empty()
+        && given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Whether the listener only accepts connections from this PC.
+fn is_loopback(bind_address: &str) -> bool {
+    let address = bind_address.trim();
+    address.eq_ignore_ascii_case("localhost")
+        || address
+            .trim_matches(['[', ']'])
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+fn query_param(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.to_string())
+}
+
+/// The configured archive folder, or every output folder of the latest run.
+fn archive_roots(app: &AppHandle, settings: &RemoteApiSettings) -> Result<Vec<ArchiveRoot>, ApiError> {
+    let configured = settings.archive_folder.trim();
+    if !configured.is_empty() {
+        return Ok(vec![ArchiveRoot::single(PathBuf::from(configured))]);
+    }
+
+    let state = app.state::<RunnerState>();
+    let last_run = state
+        .report
+        .lock()
+        .ok()
+        .and_then(|r| r.clone())
+        .or_else(|| history::list_reports(app).into_iter().next());
+
+    last_run
+        .filter(|r| !r.output_folder.trim().is_empty())
+        .map(|r| r.archive_roots())
+        .ok_or_else(|| ApiError::new(404, "No archive folder configured on the host."))
+}
+
+fn withheld(app: &AppHandle) -> &'static [&'static str] {
+    redaction::withheld_files(&settings::load(app).redaction)
+}
+
+pub fn remote_status(app: &AppHandle) -> RemoteStatus {
+    let state = app.state::<RunnerState>();
+    RemoteStatus {
+        status: crate::get_status(&state),
+        report: state.report.lock().ok().and_then(|r| r.clone()),
+        recent_logs: state
+            .recent_logs
+            .lock()
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Files planned so far in the current run, split by whether the script has
+/// reported them yet. Later folders join the queue once they are scanned.
+pub fn remote_queue(app: &AppHandle) -> RemoteQueue {
+    let state = app.state::<RunnerState>();
+    let report = state.report.lock().ok().and_then(|r| r.clone());
+    let Some(report) = report else {
+        return RemoteQueue {
+            run_id: None,
+            pending: Vec::new(),
+            finished: Vec::new(),
+        };
+    };
+
+    let pending = report
+        .planned
+        .into_iter()
+        .filter(|p| {
+            !report
+                .results
+                .iter()
+                .any(|r| scanner::same_path(&r.source, &p.source))
+        })
+        .collect();
+    RemoteQueue {
+        run_id: Some(report.run_id),
+        pending,
+        finished: report.results,
+    }
+}
+
+fn recent_logs(app: &AppHandle, limit: usize) -> Vec<LogEvent> {
+    let state = app.state::<RunnerState>();
+    let Ok(logs) = state.recent_logs.lock() else {
+        return Vec::new();
+    };
+    logs.iter()
+        .skip(logs.len().saturating_sub(limit))
+        .cloned()
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: T) -> Result<serde_json::Value, ApiError> {
+    serde_json::to_value(value).map_err(|err| ApiError::new(500, err.to_string()))
+}
+
+fn route_read(
+    app: &AppHandle,
+    settings: &RemoteApiSettings,
+    url: &Url,
+) -> Result<serde_json::Value, ApiError> {
+    let path = url.path().trim_end_matches('/');
+
+    match path {
+        "/api/status" => to_json(remote_status(app)),
+        "/api/queue" => to_json(remote_queue(app)),
+        "/api/logs" => {
+            let limit = query_param(url, "limit")
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(crate::RECENT_LOG_LINES);
+            to_json(recent_logs(app, limit))
+        }
+        "/api/runs" => to_json(history::list_reports(app)),
+        "/api/transcripts" => {
+            let roots = archive_roots(app, settings)?;
+            to_json(archive::list_all(&roots, withheld(app))?)
+        }
+        "/api/transcript" => {
+            let roots = archive_roots(app, settings)?;
+            let dir = query_param(url, "dir").unwrap_or_default();
+            let withheld = withheld(app);
+            // With a redaction list set, the default is the redacted clean copy.
+            let default_file = if withheld.contains(&"raw.txt") { "clean.md" } else { "raw.txt" };
+            let file = query_param(url, "file").unwrap_or_else(|| default_file.to_string());
+            to_json(archive::read_file_in(&roots, &dir, &file, withheld)?)
+        }
+        "/api/search" => {
+            let roots = archive_roots(app, settings)?;
+            let query = query_param(url, "q").unwrap_or_default();
+            to_json(archive::search(&roots, &query, MAX_SEARCH_RESULTS, withheld(app))?)
+        }
+        _ => match path.strip_prefix("/api/runs/") {
+            Some(run_id) => to_json(
+                history::load_report(app, run_id).map_err(|err| ApiError::new(404, err))?,
+            ),
+            None => Err(ApiError::new(404, format!("Unknown endpoint: {path}"))),
+        },
+    }
+}
+
+/// An empty body on `/api/start` repeats the last run started in this session.
+fn start_request(app: &AppHandle, body: &str) -> Result<StartRequest, ApiError> {
+    if !body.trim().is_empty() {
+        return serde_json::from_str(body).map_err(|err| ApiError::new(400, format!("Invalid run config: {err}")));
+    }
+    app.state::<RunnerState>()
+        .last_request
+        .lock()
+        .ok()
+        .and_then(|r| r.clone())
+        .ok_or_else(|| ApiError::new(400, "No previous run to repeat; send a run config as the body."))
+}
+
+fn route_control(app: &AppHandle, url: &Url, body: &str) -> Result<serde_json::Value, ApiError> {
+    let state = app.state::<RunnerState>();
+    let status = match url.path().trim_end_matches('/') {
+        "/api/start" => crate::start_transcription(app.clone(), state, start_request(app, body)?),
+        "/api/stop" => {
+            let graceful = url.query_pairs().any(|(key, value)| key == "mode" && value == "graceful");
+            let mode = graceful.then_some(crate::StopMode::Graceful);
+            crate::stop_transcription(app.clone(), state, mode)
+        }
+        "/api/pause" => crate::toggle_pause(app.clone(), state, true),
+        "/api/resume" => crate::toggle_pause(app.clone(), state, false),
+        path => return Err(ApiError::new(404, format!("Unknown endpoint: {path}"))),
+    };
+    to_json(status.map_err(|err| ApiError::new(409, err))?)
+}
+
+fn handle_request(app: &AppHandle, settings: &RemoteApiSettings, mut request: Request) {
+    let url = match Url::parse(&format!("http://localhost{}", request.url())) {
+        Ok(url) => url,
+        Err(err) => {
+            let error = ApiError::new(400, format!("Bad request URL: {err}"));
+            respond_json(request, error.code, &error.message);
+            return;
+        }
+    };
+
+    let token = request_token(&request, &url).unwrap_or_default();
+    let is_control = token_matches(&token, &settings.control_token);
+    let is_viewer = token_matches(&token, &settings.viewer_token);
+    if !is_control && !is_viewer {
+        respond_json(request, 401, &"Missing or invalid access token.");
+        return;
+    }
+
+    let result = match request.method() {
+        Method::Get => route_read(app, settings, &url),
+        Method::Post if is_control => {
+            let mut body = String::new();
+            match request
+                .as_reader()
+                .take(MAX_BODY_BYTES)
+                .read_to_string(&mut body)
+            {
+                Ok(_) => route_control(app, &url, &body),
+                Err(err) => Err(ApiError::new(400, format!("Could not read request body: {err}"))),
+            }
+        }
+        Method::Post => Err(ApiError::new(403, "This token is read-only; use the control token.")),
+        _ => Err(ApiError::new(405, "Use GET to read or POST to control.")),
+    };
+
+    match result {
+        Ok(body) => respond_json(request, 200, &body),
+        Err(error) => respond_json(request, error.code, &error.message),
+    }
+}
+
+fn start_server(app: &AppHandle, settings: &RemoteApiSettings) -> Result<Arc<Server>, String> {
+    let address = format!("{}:{}", settings.bind_address.trim(), settings.port);
+    let server = Server::http(&address)
+        .map_err(|err| format!("Could not start remote API on {address}: {err}"))?;
+    let server = Arc::new(server);
+
+    let worker_server = Arc::clone(&server);
+    let worker_app = app.clone();
+    let worker_settings = settings.clone();
+    thread::spawn(move || {
+        for request in worker_server.incoming_requests() {
+            handle_request(&worker_app, &worker_settings, request);
+        }
+    });
+
+    Ok(server)
+}
+
+/// Stops any running listener and starts a new one when the settings enable it.
+pub fn apply_settings(app: &AppHandle, settings: &RemoteApiSettings) {
+    let state = app.state::<RemoteApiState>();
+    let Ok(mut slot) = state.server.lock() else {
+        return;
+    };
+
+    if let Some(server) = slot.take() {
+        server.unblock();
+    }
+
+    if !settings.enabled {
+        return;
+    }
+
+    match start_server(app, settings) {
+        Ok(server) => {
+            crate::emit_log(
+                app,
+                "system",
+                format!(
+                    "Remote API listening on {}:{}",
+                    settings.bind_address, settings.port
+                ),
+            );
+            if !is_loopback(&settings.bind_address) {
+                crate::emit_log(
+                    app,
+                    "system",
+                    "Warning: the remote API is reachable from other devices on the network. Anyone with the viewer token can read transcripts and logs; keep the tokens private, or bind to 127.0.0.1.",
+                );
+            }
+            *slot = Some(server);
+        }
+        Err(err) => crate::emit_log(app, "system", err),
+    }
+}
+
+fn viewer_get<T: DeserializeOwned>(app: &AppHandle, path: &str, query: &[(&str, &str)]) -> Result<T, String> {
+    let viewer = settings::load(app).viewer;
+    let base = viewer.server_url.trim().trim_end_matches('/');
+    if base.is_empty() {
+        return Err("Viewer server URL is not set.".to_string());
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(15))
+        .build();
+    let mut call = agent
+        .get(&format!("{base}{path}"))
+        .set("Authorization", &format!("Bearer {}", viewer.token.trim()));
+    for (key, value) in query {
+        call = call.query(key, value);
+    }
+
+    match call.call() {
+        Ok(response) => response
+            .into_json::<T>()
+            .map_err(|err| format!("Unexpected response from {base}: {err}")),
+        Err(ureq::Error::Status(code, response)) => {
+            let message = response
+                .into_json::<String>()
+                .unwrap_or_else(|_| "request failed".to_string());
+            Err(format!("Host returned {code}: {message}"))
+        }
+        Err(err) => Err(format!("Could not reach {base}: {err}")),
+    }
+}
+
+#[tauri::command]
+pub fn viewer_get_status(app: AppHandle) -> Result<RemoteStatus, String> {
+    viewer_get(&app, "/api/status", &[])
+}
+
+#[tauri::command]
+pub fn viewer_list_runs(app: AppHandle) -> Result<Vec<RunReport>, String> {
+    viewer_get(&app, "/api/runs", &[])
+}
+
+#[tauri::command]
+pub fn viewer_list_transcripts(app: AppHandle) -> Result<Vec<ArchiveEntry>, String> {
+    viewer_get(&app, "/api/transcripts", &[])
+}
+
+#[tauri::command]
+pub fn viewer_read_transcript(
+    app: AppHandle,
+    relative_dir: String,
+    file: String,
+) -> Result<String, String> {
+    viewer_get(
+        &app,
+        "/api/transcript",
+        &[("dir", relative_dir.as_str()), ("file", file.as_str())],
+    )
+}
+
+#[tauri::command]
+pub fn viewer_search(app: AppHandle, query: String) -> Result<Vec<ArchiveMatch>, String> {
+    viewer_get(&app, "/api/search", &[("q", query.as_str())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_must_match_exactly() {
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc124", "abc123"));
+        assert!(!token_matches("abc12", "abc123"));
+        assert!(!token_matches("", ""));
+    }
+
+    #[test]
+    fn generated_tokens_are_random_hex() {
+        let first = settings::generate_token();
+        let second = settings::generate_token();
+        assert_eq!(first.len(), 32);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+        assert_ne!(first[..16], second[..16]);
+    }
+
+    #[test]
+    fn loopback_addresses() {
+        assert!(is_loopback("127.0.0.1"));
+        assert!(is_loopback("localhost"));
+        assert!(is_loopback("[::1]"));
+        assert!(!is_loopback("0.0.0.0"));
+        assert!(!is_loopback("192.168.1.20"));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    archive,
+    filemeta::{self, MetadataSources},
+    manifest, scanner, search,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::AppHandle;
+
+const METADATA_FILE: &str = "metadata.json";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameRequest {
+    pub output_folder: String,
+    /// Defaults to `{date}/{slug}`, the batch script's own layout.
+    #[serde(default)]
+    pub output_template: Option<String>,
+    /// Only report what would move.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// One transcript folder and where the template puts it, relative to the
+/// output folder.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputMove {
+    pub from: String,
+    pub to: String,
+    /// Why the folder can't move; it is left where it is.
+    pub conflict: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameSummary {
+    pub dry_run: bool,
+    pub transcripts: usize,
+    pub unchanged: usize,
+    /// Folders without a recorded source file, which the template can't name.
+    pub missing_source: usize,
+    pub renamed: usize,
+    pub conflicts: usize,
+    pub moves: Vec<OutputMove>,
+}
+
+fn relative(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Where each transcript folder belongs under `template`. A target that
+/// already exists or that two folders would share is a conflict.
+fn plan(
+    root: &Path,
+    template: &str,
+    sources: &MetadataSources,
+    summary: &mut RenameSummary,
+) -> Result<Vec<OutputMove>, String> {
+    let entries = archive::list_entries(root)?;
+    summary.transcripts = entries.len();
+
+    let mut claimed: HashSet<String> = HashSet::new();
+    let mut moves = Vec::new();
+    for entry in entries {
+        let Some(source) = entry.source_file.as_deref().filter(|s| !s.trim().is_empty()) else {
+            summary.missing_source += 1;
+            continue;
+        };
+        let details = filemeta::resolve(sources, source);
+        let bucket = details.service_date.clone().unwrap_or_else(|| {
+            if chrono::NaiveDate::parse_from_str(&entry.date_bucket, "%Y-%m-%d").is_ok() {
+                entry.date_bucket.clone()
+            } else {
+                scanner::date_bucket(Path::new(source))
+            }
+        });
+        let to = relative(&scanner::output_subdir(template, Path::new(source), &bucket, &details));
+        if to.eq_ignore_ascii_case(&entry.relative_dir) {
+            summary.unchanged += 1;
+            continue;
+        }
+
+        let conflict = if !claimed.insert(to.to_lowercase()) {
+            Some("Another transcript maps to the same folder.".to_string())
+        } else if root.join(&to).exists() {
+            Some("The target folder already exists.".to_string())
+        } else if to.to_lowercase().starts_with(&format!("{}/", entry.relative_dir.to_lowercase())) {
+            Some("The target folder is inside the current one.".to_string())
+        } else {
+            None
+        };
+        moves.push(OutputMove {
+            from: entry.relative_dir,
+            to,
+            conflict,
+        });
+    }
+    Ok(moves)
+}
+
+/// Points `metadata.json` at the folder's new location.
+fn update_metadata(dir: &Path) -> Result<(), String> {
+    let path = dir.join(METADATA_FILE);
+    let Ok(body) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let mut metadata: Value = serde_json::from_str(body.trim_start_matches('\u{feff}'))
+        .map_err(|err| format!("Could not parse {}: {err}", path.display()))?;
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("outputDir".to_string(), Value::String(dir.display().to_string()));
+    }
+    let body = serde_json::to_string_pretty(&metadata)
+        .map_err(|err| format!("Failed to serialize {}: {err}", path.display()))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Removes folders left empty by a move, up to the output folder.
+fn remove_empty_parents(root: &Path, dir: &Path) {
+    let mut current = dir.parent();
+    while let Some(parent) = current.filter(|p| p.starts_with(root) && *p != root) {
+        if fs::remove_dir(parent).is_err() {
+            break;
+        }
+        current = parent.parent();
+    }
+}
+
+fn move_folder(app: &AppHandle, root: &Path, entry: &OutputMove) -> Result<(), String> {
+    let (from, to) = (root.join(&entry.from), root.join(&entry.to));
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("Could not create {}: {err}", parent.display()))?;
+    }
+    fs::rename(&from, &to)
+        .map_err(|err| format!("Could not move {} to {}: {err}", from.display(), to.display()))?;
+    remove_empty_parents(root, &from);
+
+    update_metadata(&to)?;
+    let (old, new) = (from.display().to_string(), to.display().to_string());
+    manifest::move_output(app, &old, &new)?;
+    search::move_output(app, &old, &new)
+}
+
+fn rename(app: &AppHandle, request: &RenameRequest) -> Result<RenameSummary, String> {
+    let root = PathBuf::from(request.output_folder.trim());
+    let template = request
+        .output_template
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(scanner::DEFAULT_OUTPUT_TEMPLATE);
+    scanner::validate_output_template(template)?;
+
+    let mut summary = RenameSummary {
+        dry_run: request.dry_run,
+        ..RenameSummary::default()
+    };
+    let mut moves = plan(&root, template, &filemeta::sources(app), &mut summary)?;
+    for entry in moves.iter_mut() {
+        if entry.conflict.is_none() && !request.dry_run {
+            match move_folder(app, &root, entry) {
+                Ok(()) => summary.renamed += 1,
+                Err(err) => entry.conflict = Some(err),
+            }
+        }
+        if entry.conflict.is_some() {
+            summary.conflicts += 1;
+        }
+    }
+    summary.moves = moves;
+    Ok(summary)
+}
+
+/// Moves existing transcript folders to where `output_template` would put
+/// them now, keeping the manifest and search index pointed at them. With
+/// `dry_run` nothing moves and the plan is returned.
+#[tauri::command]
+pub async fn rename_outputs(app: AppHandle, request: RenameRequest) -> Result<RenameSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let summary = rename(&app, &request)?;
+        if !request.dry_run {
+            crate::emit_log(
+                &app,
+                "system",
+                format!(
+                    "Renamed {} transcript folder(s); {} conflict(s).",
+                    summary.renamed, summary.conflicts
+                ),
+            );
+        }
+        Ok(summary)
+    })
+    .await
+    .map_err(|err| format!("Rename task failed: {err}"))?
+}
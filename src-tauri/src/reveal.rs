@@ -0,0 +1,51 @@
+use crate::{export, longpath, postprocess, tray};
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+/// Transcript files worth selecting in a transcript folder, best first.
+const TRANSCRIPT_FILES: &[&str] = &[
+    postprocess::READABLE_TXT_FILE,
+    export::DOCX_FILE,
+    export::PDF_FILE,
+    "raw.txt",
+];
+
+/// Opens the given output folder, or the current (else last) run's, in the
+/// file manager.
+#[tauri::command]
+pub fn open_output_folder(app: AppHandle, folder: Option<String>) -> Result<(), String> {
+    let folder = folder
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| tray::output_folder(&app))
+        .ok_or_else(|| "No output folder to open yet.".to_string())?;
+    if !longpath::extended(&folder).is_dir() {
+        return Err(format!("Output folder not found: {}", folder.display()));
+    }
+    app.opener()
+        .open_path(folder.display().to_string(), None::<&str>)
+        .map_err(|err| format!("Could not open {}: {err}", folder.display()))
+}
+
+/// Shows `path` selected in the file manager. For a transcript folder (as
+/// the run log reports them) the transcript inside it is selected.
+#[tauri::command]
+pub fn reveal_transcript(app: AppHandle, path: String) -> Result<(), String> {
+    let path = PathBuf::from(longpath::plain(path.trim()));
+    let target = if path.is_dir() {
+        TRANSCRIPT_FILES
+            .iter()
+            .map(|name| path.join(name))
+            .find(|file| file.is_file())
+            .unwrap_or(path)
+    } else if path.is_file() {
+        path
+    } else {
+        return Err(format!("Transcript not found: {}", path.display()));
+    };
+    app.opener()
+        .reveal_item_in_dir(&target)
+        .map_err(|err| format!("Could not show {}: {err}", target.display()))
+}
@@ -0,0 +1,193 @@
+use crate::{
+    digest::{file_link, format_duration},
+    export::{xml_escape, DOCX_FILE, PDF_FILE},
+    history::RunReport,
+    postprocess, scanner, transcript,
+};
+use chrono::{DateTime, Local};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Width of the timing chart's bar area, in SVG units.
+const CHART_WIDTH: f64 = 600.0;
+const CHART_ROW: usize = 18;
+
+/// Transcript files linked from the report, best first.
+const TRANSCRIPT_FILES: &[&str] = &[
+    postprocess::READABLE_MD_FILE,
+    postprocess::READABLE_TXT_FILE,
+    DOCX_FILE,
+    PDF_FILE,
+    "raw.txt",
+];
+
+struct FileRow {
+    name: String,
+    source: String,
+    status: String,
+    audio_ms: Option<u64>,
+    /// Seconds since the run started when the file began and finished.
+    started_secs: u64,
+    finished_secs: u64,
+    transcript: Option<PathBuf>,
+}
+
+fn local_time(epoch: u64) -> String {
+    DateTime::from_timestamp(epoch as i64, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn seconds(secs: u64) -> String {
+    transcript::format_timestamp(secs * 1000)
+}
+
+/// A link that still works when the share is mounted elsewhere: relative
+/// below the output folder, `file://` otherwise.
+fn href(root: &Path, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative
+            .to_string_lossy()
+            .replace('\\', "/")
+            .replace('%', "%25")
+            .replace(' ', "%20")
+            .replace('#', "%23")
+            .replace('?', "%3F"),
+        Err(_) => file_link(path),
+    }
+}
+
+fn rows(report: &RunReport) -> Vec<FileRow> {
+    let mut results: Vec<_> = report.results.iter().collect();
+    results.sort_by_key(|r| r.finished_at_epoch);
+    let mut previous = report.started_at_epoch;
+    results
+        .into_iter()
+        .map(|result| {
+            let started = previous;
+            previous = result.finished_at_epoch.max(previous);
+            let output_dir = result.output_dir.as_deref().filter(|_| result.status == "ok").map(PathBuf::from);
+            FileRow {
+                name: scanner::title_from_source(&result.source),
+                source: result.source.clone(),
+                status: result.status.clone(),
+                audio_ms: output_dir
+                    .as_deref()
+                    .and_then(|dir| transcript::load_segments(dir).ok())
+                    .and_then(|segments| segments.last().map(|s| s.end_ms)),
+                started_secs: started.saturating_sub(report.started_at_epoch),
+                finished_secs: result.finished_at_epoch.saturating_sub(report.started_at_epoch),
+                transcript: output_dir.and_then(|dir| {
+                    TRANSCRIPT_FILES.iter().map(|name| dir.join(name)).find(|file| file.is_file())
+                }),
+            }
+        })
+        .collect()
+}
+
+/// One bar per file across the run's timeline.
+fn timing_chart(rows: &[FileRow]) -> String {
+    let span = rows.iter().map(|r| r.finished_secs).max().unwrap_or(0).max(1) as f64;
+    let bars: String = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let x = row.started_secs as f64 / span * CHART_WIDTH;
+            let took = row.finished_secs.saturating_sub(row.started_secs);
+            let width = (took as f64 / span * CHART_WIDTH).max(1.0);
+            let color = if row.status == "ok" {
+                "#4a90d9"
+            } else if row.status.starts_with("error") {
+                "#d9534f"
+            } else {
+                "#bbb"
+            };
+            format!(
+                "<rect x=\"{x:.1}\" y=\"{y}\" width=\"{width:.1}\" height=\"{h}\" fill=\"{color}\">\
+                 <title>{name}: {took}</title></rect>\n",
+                y = i * CHART_ROW,
+                h = CHART_ROW - 4,
+                name = xml_escape(&row.name),
+                took = seconds(took),
+            )
+        })
+        .collect();
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH}\" height=\"{height}\">\n{bars}</svg>\n",
+        height = (rows.len() * CHART_ROW).max(CHART_ROW),
+    )
+}
+
+fn render_html(report: &RunReport, root: &Path, rows: &[FileRow]) -> String {
+    let ok = rows.iter().filter(|r| r.status == "ok").count();
+    let failed = rows.iter().filter(|r| r.status.starts_with("error")).count();
+    let audio_ms: u64 = rows.iter().filter_map(|r| r.audio_ms).sum();
+    let finished = report.finished_at_epoch.unwrap_or_else(crate::now_epoch);
+    let qc = root.join(format!("qc-{}.html", report.run_id));
+
+    let table: String = rows
+        .iter()
+        .map(|row| {
+            let link = row
+                .transcript
+                .as_deref()
+                .map(|file| {
+                    let name = file.file_name().unwrap_or_default().to_string_lossy();
+                    format!("<a href=\"{}\">{}</a>", xml_escape(&href(root, file)), xml_escape(&name))
+                })
+                .unwrap_or_default();
+            format!(
+                "<tr class=\"{class}\"><td title=\"{source}\">{name}</td><td>{status}</td>\
+                 <td>{audio}</td><td>{took}</td><td>{link}</td></tr>\n",
+                class = if row.status.starts_with("error") { "error" } else { "" },
+                source = xml_escape(&row.source),
+                name = xml_escape(&row.name),
+                status = xml_escape(&row.status),
+                audio = row.audio_ms.map(transcript::format_timestamp).unwrap_or_default(),
+                took = seconds(row.finished_secs.saturating_sub(row.started_secs)),
+            )
+        })
+        .collect();
+    let inputs: String = report
+        .input_folders
+        .iter()
+        .map(|f| format!("<li>{}</li>", xml_escape(f)))
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Run {run}</title>\n\
+         <style>body{{font-family:sans-serif}}td,th{{padding:4px 8px;text-align:left}}\
+         tr.error{{background:#fdecea}}</style></head><body>\n\
+         <h1>Transcription run {run}</h1>\n\
+         <p>{start} to {end} ({elapsed}). {ok} transcribed, {failed} failed, {other} skipped; {audio} of audio.{qc}</p>\n\
+         <p>Input folders:</p><ul>{inputs}</ul>\n\
+         <table><tr><th>Recording</th><th>Status</th><th>Audio</th><th>Took</th><th>Transcript</th></tr>\n{table}</table>\n\
+         <h2>Timing</h2>\n{chart}</body></html>\n",
+        run = xml_escape(&report.run_id),
+        start = local_time(report.started_at_epoch),
+        end = local_time(finished),
+        elapsed = seconds(finished.saturating_sub(report.started_at_epoch)),
+        other = rows.len() - ok - failed,
+        audio = format_duration(audio_ms),
+        qc = if qc.is_file() {
+            format!(" <a href=\"{}\">QC report</a>", xml_escape(&href(root, &qc)))
+        } else {
+            String::new()
+        },
+        chart = timing_chart(rows),
+    )
+}
+
+/// Writes `run-<run id>.html` into the output folder: each file's status,
+/// audio length and processing time with a link to its transcript, and a
+/// timing chart, for people browsing the share without the app.
+pub fn write_summary(report: &RunReport) -> Result<PathBuf, String> {
+    let root = PathBuf::from(report.output_folder.trim());
+    let path = root.join(format!("run-{}.html", report.run_id));
+    let rows = rows(report);
+    fs::write(&path, render_html(report, &root, &rows))
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
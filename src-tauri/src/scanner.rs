@@ -0,0 +1,769 @@
+use crate::{
+    filemeta::{self, FileMetadata, MetadataSources},
+    folderconfig::FolderConfigs,
+    longpath,
+};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+const FINGERPRINT_SAMPLE_BYTES: u64 = 1 << 20;
+
+pub const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "mov", "mkv", "avi", "m4v", "webm", "mp3", "m4a", "wav", "aac", "flac", "ogg", "wma",
+];
+
+/// The layout the batch script has always used: `<date>/<slug>/`.
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{date}/{slug}";
+const TEMPLATE_TOKENS: &[&str] = &["date", "year", "month", "day", "series", "slug"];
+
+/// Order files are queued in within each input folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueOrder {
+    #[default]
+    Alphabetical,
+    NewestFirst,
+    OldestFirst,
+    ShortestFirst,
+    LongestFirst,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub before_date: Option<String>,
+    pub after_date: Option<String>,
+    pub exclude_patterns: Vec<String>,
+    pub limit: Option<u32>,
+    pub no_recursive: bool,
+    pub output_template: Option<String>,
+    pub extensions: Vec<String>,
+    pub min_duration_secs: Option<u64>,
+    pub max_duration_secs: Option<u64>,
+    pub order: QueueOrder,
+    /// Where recording details come from; their titles, series and service
+    /// dates feed the output template.
+    pub metadata: MetadataSources,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    AlreadyTranscribed,
+    FilteredByDate,
+    FilteredByDuration,
+    UnsupportedFormat,
+    ExcludedPattern,
+    OverLimit,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    pub source: String,
+    pub reason: SkipReason,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    pub source: String,
+    pub date_bucket: String,
+    pub output_dir: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// Local copy the script reads instead of `source` in copy-local mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staged_source: Option<String>,
+    /// Overrides from `.transcriber.toml` files above the recording.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_file: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub translate: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderPlan {
+    pub input_folder: String,
+    pub files: Vec<PlannedFile>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// A single file (e.g. one dropped on the window) is its own input.
+fn collect_files(root: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    if longpath::extended(root).is_file() {
+        return Ok(vec![root.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(longpath::extended(&dir))
+            .map_err(|err| format!("Could not read folder {}: {err}", dir.display()))?;
+
+        for entry in entries.flatten() {
+            // Joined onto `dir` so paths stay as the user spelled them, without
+            // the extended prefix used to read long folders.
+            let path = dir.join(entry.file_name());
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// `extensions` narrows the built-in media list (`mp4`, `.WAV` and `*.mp3` are
+/// all accepted); an empty list allows every supported format.
+pub fn is_media_file(path: &Path, extensions: &[String]) -> bool {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase) else {
+        return false;
+    };
+    if extensions.is_empty() {
+        return MEDIA_EXTENSIONS.contains(&ext.as_str());
+    }
+    extensions
+        .iter()
+        .any(|allowed| allowed.trim().trim_start_matches('*').trim_start_matches('.').eq_ignore_ascii_case(&ext))
+}
+
+/// Asks ffprobe for the container duration in seconds.
+pub fn media_duration_secs(media: &Path) -> Option<u64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "csv=p=0"])
+        .arg(media)
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|secs| secs.round() as u64)
+}
+
+fn format_minutes(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, (secs / 60) % 60)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Media files in `folder` that pass the extension filter.
+pub fn media_files(folder: &Path, extensions: &[String], recursive: bool) -> Result<Vec<PathBuf>, String> {
+    let mut files = collect_files(folder, recursive)?;
+    files.retain(|f| is_media_file(f, extensions));
+    Ok(files)
+}
+
+/// Files in `folder` that pass the extension filter, and the total scanned.
+pub fn count_media_files(folder: &Path, extensions: &[String], recursive: bool) -> Result<(usize, usize), String> {
+    let files = collect_files(folder, recursive)?;
+    let matching = files.iter().filter(|f| is_media_file(f, extensions)).count();
+    Ok((matching, files.len()))
+}
+
+/// True when both strings point at the same file, tolerating the different
+/// spellings PowerShell and Rust produce for one path.
+pub fn same_path(a: &str, b: &str) -> bool {
+    let (a, b) = (longpath::plain(a), longpath::plain(b));
+    if Path::new(&a) == Path::new(&b) {
+        return true;
+    }
+
+    match (fs::canonicalize(longpath::extended(Path::new(&a))), fs::canonicalize(longpath::extended(Path::new(&b)))) {
+        (Ok(x), Ok(y)) => x == y,
+        _ => false,
+    }
+}
+
+/// Identifies a recording by size plus hashes of its start, middle, and end, so
+/// copies on different drives match without reading whole multi-GB videos.
+pub fn content_fingerprint(path: &Path) -> Result<String, String> {
+    let mut file = File::open(longpath::extended(path)).map_err(|err| format!("Could not open {}: {err}", path.display()))?;
+    let size = file
+        .metadata()
+        .map_err(|err| format!("Could not read {}: {err}", path.display()))?
+        .len();
+
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+    let offsets = [
+        0,
+        (size / 2).saturating_sub(FINGERPRINT_SAMPLE_BYTES / 2),
+        size.saturating_sub(FINGERPRINT_SAMPLE_BYTES),
+    ];
+    let mut sample = Vec::new();
+    for offset in offsets {
+        sample.clear();
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| (&mut file).take(FINGERPRINT_SAMPLE_BYTES).read_to_end(&mut sample))
+            .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+        hasher.update(&sample);
+    }
+    Ok(format!("{size:x}-{}", hex::encode(hasher.finalize())))
+}
+
+/// Case-insensitive match supporting `*` and `?` wildcards.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+fn matching_exclude_pattern<'a>(patterns: &'a [String], root: &Path, path: &Path) -> Option<&'a str> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let relative = path
+        .strip_prefix(root)
+        .map(|r| r.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| name.clone());
+
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .find(|p| {
+            let normalized = p.replace('\\', "/");
+            wildcard_match(&normalized, &name) || wildcard_match(&normalized, &relative)
+        })
+}
+
+/// Letters NFD doesn't split into a base letter and a mark, folded first
+/// as in the batch script's `$slugFolds`.
+fn fold_letter(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ø' => "o",
+        'ł' => "l",
+        'đ' => "d",
+        _ => return None,
+    })
+}
+
+/// Mirrors `Get-Slug` in the batch script: accented letters keep their base
+/// letter (NFD with the marks dropped), anything else becomes `-`.
+pub fn slugify(text: &str) -> String {
+    let mut folded = String::new();
+    for ch in text.to_lowercase().chars() {
+        match fold_letter(ch) {
+            Some(letters) => folded.push_str(letters),
+            None => folded.push(ch),
+        }
+    }
+
+    let mut slug = String::new();
+    for ch in folded.nfd().filter(|c| !is_combining_mark(*c)) {
+        if ch.is_ascii_lowercase() || ch.is_ascii_digit() {
+            slug.push(ch);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let mut slug = slug.trim_matches('-').to_string();
+    if slug.len() > 96 {
+        slug.truncate(96);
+        slug = slug.trim_matches('-').to_string();
+    }
+
+    if slug.is_empty() {
+        "service".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Mirrors the title the batch script derives from a source file name.
+pub fn title_from_source(source: &str) -> String {
+    let path = Path::new(source);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let title = stem.replace(['_', '-'], " ").trim().to_string();
+
+    if title.is_empty() {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| source.to_string())
+    } else {
+        title
+    }
+}
+
+/// Mirrors `Get-DateBucket` in the batch script: a date in the file name wins,
+/// otherwise the file's last-modified date is used.
+pub fn date_bucket(path: &Path) -> String {
+    static DASHED: OnceLock<Regex> = OnceLock::new();
+    static COMPACT: OnceLock<Regex> = OnceLock::new();
+
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let dashed = DASHED.get_or_init(|| Regex::new(r"(20\d{2})[-_](\d{2})[-_](\d{2})").unwrap());
+    let compact = COMPACT.get_or_init(|| Regex::new(r"(20\d{2})(\d{2})(\d{2})").unwrap());
+
+    for re in [dashed, compact] {
+        if let Some(caps) = re.captures(&name) {
+            return format!("{}-{}-{}", &caps[1], &caps[2], &caps[3]);
+        }
+    }
+
+    fs::metadata(longpath::extended(path))
+        .and_then(|m| m.modified())
+        .map(|t| DateTime::<Local>::from(t).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| Local::now().format("%Y-%m-%d").to_string())
+}
+
+/// Rejects templates with unknown `{tokens}` or without `{slug}` (which keeps
+/// recordings from sharing a folder).
+pub fn validate_output_template(template: &str) -> Result<(), String> {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    let token = TOKEN.get_or_init(|| Regex::new(r"\{([^{}]*)\}").unwrap());
+    for caps in token.captures_iter(template) {
+        if !TEMPLATE_TOKENS.contains(&&caps[1]) {
+            return Err(format!(
+                "Unknown output template token {{{}}}; use {}",
+                &caps[1],
+                TEMPLATE_TOKENS.iter().map(|t| format!("{{{t}}}")).collect::<Vec<_>>().join(", ")
+            ));
+        }
+    }
+    if !template.contains("{slug}") {
+        return Err("Output template must include {slug}.".to_string());
+    }
+    Ok(())
+}
+
+/// Series name for `{series}`: the text before ` - ` in the file name once the
+/// date is removed (`2024-06-02 Romans - Part 3` gives `romans`), otherwise the
+/// folder the recording sits in.
+fn series_name(path: &Path) -> String {
+    static DATE: OnceLock<Regex> = OnceLock::new();
+    let date = DATE.get_or_init(|| Regex::new(r"20\d{2}[-_]?\d{2}[-_]?\d{2}").unwrap());
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let undated = date.replace_all(&stem, "");
+
+    let from_name = undated
+        .split_once(" - ")
+        .map(|(series, _)| slugify(series))
+        .filter(|s| s != "service");
+    from_name
+        .or_else(|| {
+            path.parent()
+                .and_then(|p| p.file_name())
+                .map(|n| slugify(&n.to_string_lossy()))
+        })
+        .unwrap_or_else(|| "general".to_string())
+}
+
+/// Expands an output template for one recording into a path relative to the
+/// output folder. A title or series set in `details` replaces the one taken
+/// from the file name. Empty components and `..` are dropped.
+pub fn output_subdir(template: &str, path: &Path, bucket: &str, details: &FileMetadata) -> PathBuf {
+    let mut parts = bucket.splitn(3, '-');
+    let (year, month, day) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    let stem = details.title.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+
+    let mut expanded = template
+        .replace("{date}", bucket)
+        .replace("{year}", year)
+        .replace("{month}", month)
+        .replace("{day}", day)
+        .replace("{slug}", &slugify(&stem));
+    if expanded.contains("{series}") {
+        let series = match &details.series_name {
+            Some(series) => slugify(series),
+            None => series_name(path),
+        };
+        expanded = expanded.replace("{series}", &series);
+    }
+
+    expanded
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|c| !c.is_empty() && *c != "." && *c != "..")
+        .collect()
+}
+
+/// Sorts collected files into queue order. Duration orders probe each media
+/// file once and keep the results in `durations` for the duration filters.
+fn order_files(
+    files: &mut [PathBuf],
+    order: QueueOrder,
+    extensions: &[String],
+    durations: &mut HashMap<PathBuf, Option<u64>>,
+) {
+    match order {
+        QueueOrder::Alphabetical => {}
+        QueueOrder::NewestFirst => files.sort_by_cached_key(|p| Reverse(date_bucket(p))),
+        QueueOrder::OldestFirst => files.sort_by_cached_key(|p| date_bucket(p)),
+        QueueOrder::ShortestFirst | QueueOrder::LongestFirst => {
+            for path in files.iter().filter(|p| is_media_file(p, extensions)) {
+                durations.insert(path.clone(), media_duration_secs(path));
+            }
+            // Files ffprobe can't read go last either way.
+            let duration = |p: &PathBuf| durations.get(p).copied().flatten();
+            if order == QueueOrder::ShortestFirst {
+                files.sort_by_key(|p| (duration(p).is_none(), duration(p)));
+            } else {
+                files.sort_by_key(|p| (duration(p).is_none(), Reverse(duration(p))));
+            }
+        }
+    }
+}
+
+/// Seconds of audio assumed per byte for files ffprobe can't measure: 128 kbps,
+/// typical of sermon MP3s. Video carries more bytes per second, so its
+/// estimate runs long and the budget errs on the side of stopping early.
+const ESTIMATE_BYTES_PER_SEC: u64 = 16_000;
+
+/// The seconds a planned file takes from an audio budget: its measured length,
+/// else an estimate from its size. `None` when neither can be read.
+fn budget_cost(file: &PlannedFile) -> Option<u64> {
+    file.duration_secs.or_else(|| {
+        fs::metadata(longpath::extended(Path::new(&file.source)))
+            .ok()
+            .map(|meta| meta.len().div_ceil(ESTIMATE_BYTES_PER_SEC))
+    })
+}
+
+/// Keeps planned files, in queue order, until `remaining_secs` of audio is used
+/// up; the first file that doesn't fit and everything after it are skipped.
+/// Files ffprobe can't measure are estimated from their size, and one that
+/// can't even be sized stops the run there.
+pub fn cap_audio_budget(plan: &mut FolderPlan, remaining_secs: &mut u64, budget_label: &str) {
+    let mut exhausted = false;
+    let mut kept = Vec::new();
+    for mut file in plan.files.drain(..) {
+        if !exhausted {
+            if file.duration_secs.is_none() {
+                file.duration_secs = media_duration_secs(Path::new(&file.source));
+            }
+            if let Some(cost) = budget_cost(&file).filter(|cost| *cost <= *remaining_secs) {
+                *remaining_secs -= cost;
+                kept.push(file);
+                continue;
+            }
+            exhausted = true;
+        }
+        plan.skipped.push(SkippedFile {
+            source: file.source,
+            reason: SkipReason::OverLimit,
+            detail: format!("Audio budget of {budget_label} reached"),
+        });
+    }
+    plan.files = kept;
+}
+
+pub fn build_folder_plan(
+    input_folder: &str,
+    output_folder: &str,
+    options: &ScanOptions,
+) -> Result<FolderPlan, String> {
+    let root = PathBuf::from(input_folder.trim());
+    let output_root = PathBuf::from(output_folder.trim());
+    let mut files = collect_files(&root, !options.no_recursive)?;
+    let mut durations = HashMap::new();
+    order_files(&mut files, options.order, &options.extensions, &mut durations);
+
+    let before_date = options
+        .before_date
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let after_date = options
+        .after_date
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let limit = options.limit.filter(|l| *l > 0).map(|l| l as usize);
+    let template = options
+        .output_template
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_OUTPUT_TEMPLATE);
+
+    let mut planned = Vec::new();
+    let mut skipped = Vec::new();
+    let mut considered = 0usize;
+    let mut configs = FolderConfigs::new(&root);
+
+    for path in files {
+        let source = path.display().to_string();
+
+        if !is_media_file(&path, &options.extensions) {
+            let ext = path
+                .extension()
+                .map(|e| format!(".{}", e.to_string_lossy()))
+                .unwrap_or_else(|| "(none)".to_string());
+            let detail = if options.extensions.is_empty() {
+                format!("Extension {ext} is not a supported media format")
+            } else {
+                format!("Extension {ext} is not in the extension filter")
+            };
+            skipped.push(SkippedFile {
+                source,
+                reason: SkipReason::UnsupportedFormat,
+                detail,
+            });
+            continue;
+        }
+
+        if let Some(pattern) = matching_exclude_pattern(&options.exclude_patterns, &root, &path) {
+            skipped.push(SkippedFile {
+                source,
+                reason: SkipReason::ExcludedPattern,
+                detail: format!("Matches exclude pattern '{pattern}'"),
+            });
+            continue;
+        }
+
+        if let Some(max) = limit {
+            if considered >= max {
+                skipped.push(SkippedFile {
+                    source,
+                    reason: SkipReason::OverLimit,
+                    detail: format!("File limit of {max} reached"),
+                });
+                continue;
+            }
+        }
+        considered += 1;
+
+        let details = filemeta::resolve(&options.metadata, &source);
+        let bucket = details.service_date.clone().unwrap_or_else(|| date_bucket(&path));
+        if let Some(cutoff) = before_date {
+            if bucket.as_str() > cutoff {
+                skipped.push(SkippedFile {
+                    source,
+                    reason: SkipReason::FilteredByDate,
+                    detail: format!("Date {bucket} is after cutoff {cutoff}"),
+                });
+                continue;
+            }
+        }
+        if let Some(start) = after_date {
+            if bucket.as_str() < start {
+                skipped.push(SkippedFile {
+                    source,
+                    reason: SkipReason::FilteredByDate,
+                    detail: format!("Date {bucket} is before start {start}"),
+                });
+                continue;
+            }
+        }
+
+        // Only probe when a duration filter is set; ffprobe is slow on network shares.
+        let min_secs = options.min_duration_secs.filter(|s| *s > 0);
+        let max_secs = options.max_duration_secs.filter(|s| *s > 0);
+        let duration_secs = match durations.remove(&path) {
+            Some(probed) => probed,
+            None if min_secs.is_some() || max_secs.is_some() => media_duration_secs(&path),
+            None => None,
+        };
+        if let Some(duration) = duration_secs {
+            let outside = match (min_secs, max_secs) {
+                (Some(min), _) if duration < min => Some(format!("shorter than {}", format_minutes(min))),
+                (_, Some(max)) if duration > max => Some(format!("longer than {}", format_minutes(max))),
+                _ => None,
+            };
+            if let Some(why) = outside {
+                skipped.push(SkippedFile {
+                    source,
+                    reason: SkipReason::FilteredByDuration,
+                    detail: format!("Duration {} is {why}", format_minutes(duration)),
+                });
+                continue;
+            }
+        }
+
+        let output_dir = output_root.join(output_subdir(template, &path, &bucket, &details));
+        let config = configs.for_file(&path)?;
+
+        planned.push(PlannedFile {
+            source,
+            date_bucket: bucket,
+            output_dir: output_dir.display().to_string(),
+            fingerprint: None,
+            duration_secs,
+            staged_source: None,
+            language: config.language,
+            model_file: config.model,
+            translate: config.translate.unwrap_or(false),
+        });
+    }
+
+    Ok(FolderPlan {
+        input_folder: input_folder.to_string(),
+        files: planned,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planned(source: &Path, duration_secs: Option<u64>) -> PlannedFile {
+        PlannedFile {
+            source: source.display().to_string(),
+            date_bucket: String::new(),
+            output_dir: String::new(),
+            fingerprint: None,
+            duration_secs,
+            staged_source: None,
+            language: None,
+            model_file: None,
+            translate: false,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("church-transcriber-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn budget_plan(files: Vec<PlannedFile>) -> FolderPlan {
+        FolderPlan {
+            input_folder: String::new(),
+            files,
+            skipped: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn slugs_keep_the_base_letter_of_accented_names() {
+        let stem = |name: &str| Path::new(name).file_stem().unwrap().to_string_lossy().to_string();
+        assert_eq!(slugify(&stem("Sermón — Juan [2].mp3")), "sermon-juan-2");
+        assert_eq!(slugify("Țara Sfântă"), "tara-sfanta");
+        assert_eq!(slugify("Lời Chúa Tết"), "loi-chua-tet");
+        assert_eq!(slugify("Straße Łódź Ærø"), "strasse-lodz-aero");
+        assert_eq!(slugify("Пасха 2024"), "2024");
+        assert_eq!(slugify("主日"), "service");
+    }
+
+    #[test]
+    fn unmeasurable_files_are_estimated_from_size() {
+        let dir = temp_dir("budget-size");
+        // Not real audio, so ffprobe (if installed) can't measure them either.
+        let names = ["a.mp3", "b.mp3", "c.mp3", "d.mp3"];
+        for name in names {
+            fs::write(dir.join(name), vec![0u8; 32_000]).unwrap();
+        }
+        let mut plan = budget_plan(names.iter().map(|n| planned(&dir.join(n), None)).collect());
+        let mut remaining = 5;
+        cap_audio_budget(&mut plan, &mut remaining, "5 s");
+
+        assert_eq!(plan.files.len(), 2);
+        assert_eq!(remaining, 1);
+        assert_eq!(plan.skipped.len(), 2);
+        assert!(plan.skipped.iter().all(|s| s.reason == SkipReason::OverLimit));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn measured_and_estimated_files_share_the_budget() {
+        let dir = temp_dir("budget-mixed");
+        fs::write(dir.join("small.mp3"), vec![0u8; 16_000]).unwrap();
+        let mut plan = budget_plan(vec![
+            planned(&dir.join("measured.mp3"), Some(10)),
+            planned(&dir.join("small.mp3"), None),
+            planned(&dir.join("long.mp3"), Some(600)),
+            planned(&dir.join("later.mp3"), Some(1)),
+        ]);
+        let mut remaining = 20;
+        cap_audio_budget(&mut plan, &mut remaining, "20 s");
+
+        let kept: Vec<&str> = plan.files.iter().map(|f| f.source.as_str()).collect();
+        assert_eq!(kept.len(), 2);
+        assert!(kept[1].ends_with("small.mp3"));
+        assert_eq!(remaining, 9);
+        assert_eq!(plan.skipped.len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_file_stops_the_budget() {
+        let dir = temp_dir("budget-missing");
+        let mut plan = budget_plan(vec![
+            planned(&dir.join("gone.mp3"), None),
+            planned(&dir.join("next.mp3"), Some(1)),
+        ]);
+        let mut remaining = 3_600;
+        cap_audio_budget(&mut plan, &mut remaining, "1 h");
+        assert!(plan.files.is_empty());
+        assert_eq!(plan.skipped.len(), 2);
+        assert_eq!(remaining, 3_600);
+    }
+}
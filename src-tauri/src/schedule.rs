@@ -0,0 +1,345 @@
+use crate::{RunnerState, StartRequest};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Mutex, thread, time::Duration};
+use tauri::{AppHandle, Manager};
+
+const SCHEDULES_FILE: &str = "schedules.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Serializes reads/writes of the schedule file between commands and the poller.
+#[derive(Default)]
+pub struct SchedulerState {
+    file: Mutex<()>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    /// Five-field cron expression: minute hour day-of-month month day-of-week.
+    pub cron: String,
+    pub enabled: bool,
+    pub profile: StartRequest,
+    pub created_at_epoch: u64,
+    pub next_run_epoch: Option<u64>,
+    #[serde(default)]
+    pub last_run_epoch: Option<u64>,
+    #[serde(default)]
+    pub last_result: Option<String>,
+}
+
+/// Parsed cron expression; each field is a bitmask of allowed values.
+pub struct CronSpec {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_value(text: &str, names: &[&str], min: u32, max: u32) -> Result<u32, String> {
+    let lower = text.to_ascii_lowercase();
+    let value = match names.iter().position(|n| *n == lower) {
+        Some(index) => index as u32,
+        None => text
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid cron value: {text}"))?,
+    };
+    if value < min || value > max {
+        return Err(format!("Cron value {value} is outside {min}-{max}."));
+    }
+    Ok(value)
+}
+
+fn parse_field(field: &str, names: &[&str], min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid cron step: {part}"))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, names, min, max)?, parse_value(b, names, min, max)?)
+        } else {
+            let value = parse_value(range, names, min, max)?;
+            // `5/15` means "from 5, every 15"; a bare `5` is just 5.
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start > end {
+            return Err(format!("Invalid cron range: {range}"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn allows(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+impl CronSpec {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Cron expression needs 5 fields (minute hour day month weekday): {expression}"
+            ));
+        };
+
+        let mut weekdays = parse_field(weekday, &WEEKDAY_NAMES, 0, 7)?;
+        // Both 0 and 7 mean Sunday.
+        if allows(weekdays, 7) {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, &[], 0, 59)?,
+            hours: parse_field(hour, &[], 0, 23)?,
+            days: parse_field(day, &[], 1, 31)?,
+            months: parse_field(month, &[], 1, 12)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// Standard cron rule: when both day fields are restricted, either may match.
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if !allows(self.months, date.month()) {
+            return false;
+        }
+        let day = allows(self.days, date.day());
+        let weekday = allows(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// Next matching local minute strictly after `epoch`.
+    pub fn next_after(&self, epoch: u64) -> Option<u64> {
+        let start = DateTime::from_timestamp(epoch as i64, 0)?
+            .with_timezone(&Local)
+            .naive_local();
+        let mut t: NaiveDateTime =
+            start.date().and_hms_opt(start.hour(), start.minute(), 0)? + ChronoDuration::minutes(1);
+        // Long enough to reach a Feb 29 schedule.
+        let limit = t + ChronoDuration::days(366 * 5);
+
+        while t < limit {
+            if !self.day_matches(t.date()) {
+                t = (t.date() + ChronoDuration::days(1)).and_hms_opt(0, 0, 0)?;
+            } else if !allows(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + ChronoDuration::hours(1);
+            } else if !allows(self.minutes, t.minute()) {
+                t += ChronoDuration::minutes(1);
+            } else {
+                // Times skipped by a DST jump have no local mapping.
+                match Local.from_local_datetime(&t).earliest() {
+                    Some(local) => return Some(local.timestamp() as u64),
+                    None => t += ChronoDuration::minutes(1),
+                }
+            }
+        }
+        None
+    }
+}
+
+fn schedules_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(SCHEDULES_FILE))
+}
+
+pub fn load(app: &AppHandle) -> Vec<Schedule> {
+    schedules_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, schedules: &[Schedule]) -> Result<(), String> {
+    let path = schedules_path(app)?;
+    let body = serde_json::to_string_pretty(schedules)
+        .map_err(|err| format!("Failed to serialize schedules: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Edits the schedule file under the state lock.
+fn update<T>(
+    app: &AppHandle,
+    edit: impl FnOnce(&mut Vec<Schedule>) -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<SchedulerState>();
+    let _guard = state
+        .file
+        .lock()
+        .map_err(|_| "Schedule state lock failed".to_string())?;
+    let mut schedules = load(app);
+    let value = edit(&mut schedules)?;
+    save(app, &schedules)?;
+    Ok(value)
+}
+
+/// Points schedules that use the `old` whisper-cli at `new`; returns how many
+/// changed.
+pub fn replace_whisper_exe(app: &AppHandle, old: &str, new: &str) -> Result<usize, String> {
+    update(app, |schedules| {
+        let mut changed = 0;
+        for schedule in schedules.iter_mut().filter(|s| s.profile.whisper_exe.trim() == old) {
+            schedule.profile.whisper_exe = new.to_string();
+            changed += 1;
+        }
+        Ok(changed)
+    })
+}
+
+/// Starts every due schedule. A schedule that comes due while another run is in
+/// progress stays due and starts once the runner is free; occurrences missed
+/// while the app was closed fire once on the next launch.
+fn run_due(app: &AppHandle) -> Result<(), String> {
+    let now = crate::now_epoch();
+    update(app, |schedules| {
+        for schedule in schedules.iter_mut().filter(|s| s.enabled) {
+            if schedule.next_run_epoch.is_none_or(|due| due > now) {
+                continue;
+            }
+            let runner = app.state::<RunnerState>();
+            if crate::get_status(&runner).running {
+                continue;
+            }
+
+            crate::emit_log(app, "system", format!("Schedule \"{}\" is starting a run.", schedule.name));
+            let result = match crate::start_transcription(app.clone(), runner, schedule.profile.clone()) {
+                Ok(_) => "started".to_string(),
+                Err(err) => {
+                    crate::emit_log(app, "system", format!("Schedule \"{}\" failed to start: {err}", schedule.name));
+                    err
+                }
+            };
+            schedule.last_run_epoch = Some(now);
+            schedule.last_result = Some(result);
+            schedule.next_run_epoch = CronSpec::parse(&schedule.cron)
+                .ok()
+                .and_then(|spec| spec.next_after(now));
+        }
+        Ok(())
+    })
+}
+
+/// Polls for due schedules for the life of the app.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || loop {
+        if let Err(err) = run_due(&app) {
+            crate::emit_log(&app, "system", format!("Scheduler: {err}"));
+        }
+        thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn create_schedule(
+    app: AppHandle,
+    name: String,
+    cron: String,
+    profile: StartRequest,
+) -> Result<Schedule, String> {
+    let cron = cron.trim().to_string();
+    let now = crate::now_epoch();
+    let next_run_epoch = CronSpec::parse(&cron)?
+        .next_after(now)
+        .ok_or_else(|| format!("Cron expression never matches: {cron}"))?;
+    if profile.input_folders.is_empty() {
+        return Err("The run profile needs at least one input folder.".to_string());
+    }
+
+    let schedule = Schedule {
+        id: format!("schedule-{}", crate::settings::generate_token()),
+        name: if name.trim().is_empty() {
+            cron.clone()
+        } else {
+            name.trim().to_string()
+        },
+        cron,
+        enabled: true,
+        profile,
+        created_at_epoch: now,
+        next_run_epoch: Some(next_run_epoch),
+        last_run_epoch: None,
+        last_result: None,
+    };
+    update(&app, |schedules| {
+        schedules.push(schedule.clone());
+        Ok(schedule)
+    })
+}
+
+#[tauri::command]
+pub fn list_schedules(app: AppHandle) -> Vec<Schedule> {
+    load(&app)
+}
+
+/// Re-enabling skips occurrences that passed while the schedule was off.
+#[tauri::command]
+pub fn set_schedule_enabled(app: AppHandle, id: String, enabled: bool) -> Result<Schedule, String> {
+    update(&app, |schedules| {
+        let schedule = schedules
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Schedule not found: {id}"))?;
+        if enabled && !schedule.enabled {
+            schedule.next_run_epoch = CronSpec::parse(&schedule.cron)?.next_after(crate::now_epoch());
+        }
+        schedule.enabled = enabled;
+        Ok(schedule.clone())
+    })
+}
+
+#[tauri::command]
+pub fn delete_schedule(app: AppHandle, id: String) -> Result<(), String> {
+    update(&app, |schedules| {
+        let before = schedules.len();
+        schedules.retain(|s| s.id != id);
+        if schedules.len() == before {
+            return Err(format!("Schedule not found: {id}"));
+        }
+        Ok(())
+    })
+}
+
+/// Merges schedules from a config bundle, replacing any with the same id.
+/// Next-run times are recomputed so imported schedules don't fire immediately.
+pub fn import(app: &AppHandle, imported: Vec<Schedule>) -> Result<usize, String> {
+    let now = crate::now_epoch();
+    update(app, |schedules| {
+        let count = imported.len();
+        for mut schedule in imported {
+            schedule.next_run_epoch = CronSpec::parse(&schedule.cron)
+                .ok()
+                .and_then(|spec| spec.next_after(now));
+            schedules.retain(|s| s.id != schedule.id);
+            schedules.push(schedule);
+        }
+        Ok(count)
+    })
+}
@@ -0,0 +1,288 @@
+use crate::transcript::Segment;
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// (canonical name, spoken base name, book number, chapter count)
+const BOOKS: &[(&str, &str, Option<u8>, u16)] = &[
+    ("Genesis", "Genesis", None, 50),
+    ("Exodus", "Exodus", None, 40),
+    ("Leviticus", "Leviticus", None, 27),
+    ("Numbers", "Numbers", None, 36),
+    ("Deuteronomy", "Deuteronomy", None, 34),
+    ("Joshua", "Joshua", None, 24),
+    ("Judges", "Judges", None, 21),
+    ("Ruth", "Ruth", None, 4),
+    ("1 Samuel", "Samuel", Some(1), 31),
+    ("2 Samuel", "Samuel", Some(2), 24),
+    ("1 Kings", "Kings", Some(1), 22),
+    ("2 Kings", "Kings", Some(2), 25),
+    ("1 Chronicles", "Chronicles", Some(1), 29),
+    ("2 Chronicles", "Chronicles", Some(2), 36),
+    ("Ezra", "Ezra", None, 10),
+    ("Nehemiah", "Nehemiah", None, 13),
+    ("Esther", "Esther", None, 10),
+    ("Job", "Job", None, 42),
+    ("Psalms", "Psalms", None, 150),
+    ("Psalms", "Psalm", None, 150),
+    ("Proverbs", "Proverbs", None, 31),
+    ("Ecclesiastes", "Ecclesiastes", None, 12),
+    ("Song of Solomon", "Song of Solomon", None, 8),
+    ("Song of Solomon", "Song of Songs", None, 8),
+    ("Isaiah", "Isaiah", None, 66),
+    ("Jeremiah", "Jeremiah", None, 52),
+    ("Lamentations", "Lamentations", None, 5),
+    ("Ezekiel", "Ezekiel", None, 48),
+    ("Daniel", "Daniel", None, 12),
+    ("Hosea", "Hosea", None, 14),
+    ("Joel", "Joel", None, 3),
+    ("Amos", "Amos", None, 9),
+    ("Obadiah", "Obadiah", None, 1),
+    ("Jonah", "Jonah", None, 4),
+    ("Micah", "Micah", None, 7),
+    ("Nahum", "Nahum", None, 3),
+    ("Habakkuk", "Habakkuk", None, 3),
+    ("Zephaniah", "Zephaniah", None, 3),
+    ("Haggai", "Haggai", None, 2),
+    ("Zechariah", "Zechariah", None, 14),
+    ("Malachi", "Malachi", None, 4),
+    ("Matthew", "Matthew", None, 28),
+    ("Mark", "Mark", None, 16),
+    ("Luke", "Luke", None, 24),
+    ("John", "John", None, 21),
+    ("1 John", "John", Some(1), 5),
+    ("2 John", "John", Some(2), 1),
+    ("3 John", "John", Some(3), 1),
+    ("Acts", "Acts", None, 28),
+    ("Romans", "Romans", None, 16),
+    ("1 Corinthians", "Corinthians", Some(1), 16),
+    ("2 Corinthians", "Corinthians", Some(2), 13),
+    ("Galatians", "Galatians", None, 6),
+    ("Ephesians", "Ephesians", None, 6),
+    ("Philippians", "Philippians", None, 4),
+    ("Colossians", "Colossians", None, 4),
+    ("1 Thessalonians", "Thessalonians", Some(1), 5),
+    ("2 Thessalonians", "Thessalonians", Some(2), 3),
+    ("1 Timothy", "Timothy", Some(1), 6),
+    ("2 Timothy", "Timothy", Some(2), 4),
+    ("Titus", "Titus", None, 3),
+    ("Philemon", "Philemon", None, 1),
+    ("Hebrews", "Hebrews", None, 13),
+    ("James", "James", None, 5),
+    ("1 Peter", "Peter", Some(1), 5),
+    ("2 Peter", "Peter", Some(2), 3),
+    ("Jude", "Jude", None, 1),
+    ("Revelation", "Revelation", None, 22),
+    ("Revelation", "Revelations", None, 22),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptureReference {
+    pub reference: String,
+    pub book: String,
+    pub chapter: u16,
+    pub verse_start: Option<u16>,
+    pub verse_end: Option<u16>,
+    pub spoken: String,
+    pub start_ms: u64,
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        let mut names: Vec<&str> = BOOKS.iter().map(|b| b.1).collect();
+        names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+        names.dedup();
+        let books = names
+            .iter()
+            .map(|n| regex::escape(n).replace(' ', r"\s+"))
+            .collect::<Vec<_>>()
+            .join("|");
+
+        let pattern = format!(
+            r"\b(?:(first|second|third|1st|2nd|3rd|[123])\s+)?({books})\s+(?:chapter\s+)?(\d{{1,3}})(?:(?:\s*[:.]\s*|,?\s+(?:verses?\s+)?)(\d{{1,3}})(?:\s*(?:-|–|to|through)\s*(\d{{1,3}}))?)?\b"
+        );
+        RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .expect("scripture pattern is valid")
+    })
+}
+
+fn book_number(prefix: &str) -> Option<u8> {
+    match prefix.to_lowercase().as_str() {
+        "first" | "1st" | "1" => Some(1),
+        "second" | "2nd" | "2" => Some(2),
+        "third" | "3rd" | "3" => Some(3),
+        _ => None,
+    }
+}
+
+fn parse_number(caps: &Captures, index: usize) -> Option<u16> {
+    caps.get(index).and_then(|m| m.as_str().parse().ok())
+}
+
+/// Validates a regex match against the book table and normalizes it to
+/// `Book chapter:verse-verse`.
+fn normalize(caps: &Captures) -> Option<ScriptureReference> {
+    let spoken_book = caps.get(2)?.as_str().split_whitespace().collect::<Vec<_>>().join(" ");
+    let number = caps.get(1).and_then(|m| book_number(m.as_str()));
+
+    let candidates: Vec<_> = BOOKS
+        .iter()
+        .filter(|b| b.1.eq_ignore_ascii_case(&spoken_book))
+        .collect();
+    let book = candidates
+        .iter()
+        .find(|b| b.2 == number)
+        .or_else(|| candidates.iter().find(|b| b.2.is_none()))?;
+    // A spoken number only counts when this book actually has numbered volumes.
+    if number.is_some() && book.2.is_none() && candidates.iter().all(|b| b.2.is_none()) {
+        return None;
+    }
+
+    let mut chapter = parse_number(caps, 3)?;
+    let mut verse_start = parse_number(caps, 4);
+    let mut verse_end = parse_number(caps, 5);
+
+    // Single-chapter books are cited by verse alone ("Jude 3").
+    if book.3 == 1 && verse_start.is_none() && chapter > 1 {
+        verse_start = Some(chapter);
+        chapter = 1;
+    }
+    if chapter == 0 || chapter > book.3 {
+        return None;
+    }
+    if verse_start == Some(0) {
+        return None;
+    }
+    if let (Some(start), Some(end)) = (verse_start, verse_end) {
+        if end <= start {
+            verse_end = None;
+        }
+    }
+
+    let reference = match (verse_start, verse_end) {
+        (Some(start), Some(end)) => format!("{} {chapter}:{start}-{end}", book.0),
+        (Some(start), None) => format!("{} {chapter}:{start}", book.0),
+        _ => format!("{} {chapter}", book.0),
+    };
+
+    Some(ScriptureReference {
+        reference,
+        book: book.0.to_string(),
+        chapter,
+        verse_start,
+        verse_end,
+        spoken: caps[0].to_string(),
+        start_ms: 0,
+    })
+}
+
+/// Finds references across the whole transcript so ones split over segment
+/// boundaries are still caught; each is stamped with its segment's start time.
+pub fn detect_in_segments(segments: &[Segment]) -> Vec<ScriptureReference> {
+    let mut text = String::new();
+    let mut offsets: Vec<(usize, u64)> = Vec::new();
+    for segment in segments {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        offsets.push((text.len(), segment.start_ms));
+        text.push_str(&segment.text);
+    }
+
+    reference_regex()
+        .captures_iter(&text)
+        .filter_map(|caps| {
+            let position = caps.get(0)?.start();
+            let mut found = normalize(&caps)?;
+            found.start_ms = offsets
+                .iter()
+                .take_while(|(offset, _)| *offset <= position)
+                .last()
+                .map(|(_, ms)| *ms)
+                .unwrap_or(0);
+            Some(found)
+        })
+        .collect()
+}
+
+/// Appends a normalized tag after each spoken reference, for Markdown output.
+pub fn tag_inline(text: &str) -> String {
+    reference_regex()
+        .replace_all(text, |caps: &Captures| match normalize(caps) {
+            Some(found) => format!("{} **[{}]**", &caps[0], found.reference),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms: start_ms + 1_000,
+            text: text.to_string(),
+            speaker: None,
+        }
+    }
+
+    fn references(text: &str) -> Vec<String> {
+        detect_in_segments(&[segment(0, text)])
+            .into_iter()
+            .map(|r| r.reference)
+            .collect()
+    }
+
+    #[test]
+    fn spoken_chapter_and_verse() {
+        assert_eq!(references("turn with me to John 3 16 this morning"), ["John 3:16"]);
+        assert_eq!(references("John chapter 3 verse 16"), ["John 3:16"]);
+        assert_eq!(references("Romans 8:28-30"), ["Romans 8:28-30"]);
+        assert_eq!(references("Psalm 23"), ["Psalms 23"]);
+    }
+
+    #[test]
+    fn numbered_books() {
+        assert_eq!(references("first Corinthians 13"), ["1 Corinthians 13"]);
+        assert_eq!(references("in 2nd Timothy 3 16"), ["2 Timothy 3:16"]);
+        assert_eq!(references("second John 5"), ["2 John 1:5"]);
+        let found = detect_in_segments(&[segment(0, "first Corinthians 13")]);
+        assert_eq!(found[0].book, "1 Corinthians");
+        assert_eq!((found[0].chapter, found[0].verse_start), (13, None));
+    }
+
+    #[test]
+    fn rejects_impossible_references() {
+        assert!(references("John 30").is_empty());
+        assert!(references("first Romans 3").is_empty());
+        assert!(references("Mark 0").is_empty());
+    }
+
+    #[test]
+    fn references_span_segments() {
+        let segments = [
+            segment(1_000, "let's read together"),
+            segment(5_000, "from first Corinthians"),
+            segment(9_000, "13 4 through 7"),
+        ];
+        let found = detect_in_segments(&segments);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].reference, "1 Corinthians 13:4-7");
+        assert_eq!(found[0].start_ms, 5_000);
+    }
+
+    #[test]
+    fn inline_tags() {
+        assert_eq!(
+            tag_inline("read John 3 16 today"),
+            "read John 3 16 **[John 3:16]** today"
+        );
+        assert_eq!(tag_inline("John 30 people"), "John 30 people");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    archive,
+    scanner::{self, PlannedFile},
+    transcript::{self, format_timestamp, Segment},
+};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::Path,
+    time::Duration,
+};
+use tauri::{AppHandle, Manager};
+
+const INDEX_FILE: &str = "search-index.sqlite";
+const RAW_FILE: &str = "raw.txt";
+const WINDOW_MS: u64 = 30_000;
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub title: String,
+    pub source: String,
+    pub output_dir: String,
+    pub file: String,
+    pub start_ms: u64,
+    pub timestamp: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSummary {
+    pub transcripts: usize,
+    pub indexed: usize,
+}
+
+fn open(app: &AppHandle) -> Result<Connection, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    let path = dir.join(INDEX_FILE);
+    let conn = Connection::open(&path)
+        .map_err(|err| format!("Could not open search index {}: {err}", path.display()))?;
+    conn.busy_timeout(Duration::from_secs(10))
+        .and_then(|_| {
+            conn.execute_batch(
+                "CREATE VIRTUAL TABLE IF NOT EXISTS passages USING fts5(
+                    text,
+                    title UNINDEXED,
+                    source UNINDEXED,
+                    output_dir UNINDEXED,
+                    start_ms UNINDEXED,
+                    tokenize = 'porter unicode61'
+                );",
+            )
+        })
+        .map_err(|err| format!("Could not prepare search index: {err}"))?;
+    Ok(conn)
+}
+
+/// Groups whisper segments into ~30 second passages so phrases that straddle a
+/// segment boundary still match and hits point at a useful timestamp.
+fn passages(segments: &[Segment]) -> Vec<(u64, String)> {
+    let mut out: Vec<(u64, String)> = Vec::new();
+    for segment in segments {
+        match out.last_mut() {
+            Some((start, text)) if segment.start_ms < *start + WINDOW_MS => {
+                text.push(' ');
+                text.push_str(&segment.text);
+            }
+            _ => out.push((segment.start_ms, segment.text.clone())),
+        }
+    }
+    out
+}
+
+/// Replaces the indexed passages for one transcript folder, from the published
+/// segments so proofreading edits are searchable. Falls back to the whole of
+/// `raw.txt` (at 0:00) when there are no timed segments.
+fn index_folder(conn: &mut Connection, output_dir: &Path, title: &str, source: &str) -> Result<bool, String> {
+    let rows = match transcript::load_published_segments(output_dir) {
+        Ok(segments) => passages(&segments),
+        Err(_) => match fs::read_to_string(output_dir.join(RAW_FILE)) {
+            Ok(raw) if !raw.trim().is_empty() => vec![(0, raw)],
+            _ => return Ok(false),
+        },
+    };
+
+    let dir = output_dir.display().to_string();
+    let tx = conn
+        .transaction()
+        .map_err(|err| format!("Search index transaction failed: {err}"))?;
+    tx.execute("DELETE FROM passages WHERE output_dir = ?1", params![dir])
+        .map_err(|err| format!("Could not clear search index rows: {err}"))?;
+    for (start_ms, text) in rows {
+        tx.execute(
+            "INSERT INTO passages (text, title, source, output_dir, start_ms) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![text, title, source, dir, start_ms as i64],
+        )
+        .map_err(|err| format!("Could not index {}: {err}", output_dir.display()))?;
+    }
+    tx.commit()
+        .map_err(|err| format!("Search index commit failed: {err}"))?;
+    Ok(true)
+}
+
+/// Called by the runner for each finished file.
+pub fn index_transcript(app: &AppHandle, planned: &PlannedFile) {
+    reindex(
+        app,
+        Path::new(&planned.output_dir),
+        &scanner::title_from_source(&planned.source),
+        &planned.source,
+    );
+}
+
+/// Refreshes one transcript folder, e.g. after a proofreading edit.
+pub fn reindex(app: &AppHandle, output_dir: &Path, title: &str, source: &str) {
+    let result = open(app).and_then(|mut conn| index_folder(&mut conn, output_dir, title, source));
+    if let Err(err) = result {
+        crate::emit_log(app, "system", err);
+    }
+}
+
+/// Points a moved transcript folder's passages at its new location.
+pub fn move_output(app: &AppHandle, old: &str, new: &str) -> Result<(), String> {
+    open(app)?
+        .execute("UPDATE passages SET output_dir = ?2 WHERE output_dir = ?1", params![old, new])
+        .map(|_| ())
+        .map_err(|err| format!("Could not update search index for {new}: {err}"))
+}
+
+fn rebuild(app: &AppHandle, archive_root: &Path) -> Result<IndexSummary, String> {
+    let entries = archive::list_entries(archive_root)?;
+    let mut conn = open(app)?;
+    let mut summary = IndexSummary {
+        transcripts: entries.len(),
+        indexed: 0,
+    };
+    for entry in entries {
+        let output_dir = archive_root.join(&entry.relative_dir);
+        let source = entry.source_file.unwrap_or_default();
+        if index_folder(&mut conn, &output_dir, &entry.title, &source)? {
+            summary.indexed += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Plain words are ANDed; quoted phrases and FTS5 operators pass through.
+fn fts_query(query: &str) -> String {
+    let trimmed = query.trim();
+    if trimmed.contains('"') {
+        return trimmed.to_string();
+    }
+    trimmed
+        .split_whitespace()
+        .map(|word| format!("\"{word}\""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn search(app: &AppHandle, query: &str, limit: usize) -> Result<Vec<SearchHit>, String> {
+    let fts = fts_query(query);
+    if fts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = open(app)?;
+    let mut statement = conn
+        .prepare(
+            "SELECT title, source, output_dir, start_ms,
+                    snippet(passages, 0, '**', '**', '…', 16), bm25(passages)
+             FROM passages WHERE passages MATCH ?1
+             ORDER BY bm25(passages) LIMIT ?2",
+        )
+        .map_err(|err| format!("Search query failed: {err}"))?;
+    let rows = statement
+        .query_map(params![fts, limit as i64], |row| {
+            let start_ms = row.get::<_, i64>(3)?.max(0) as u64;
+            Ok(SearchHit {
+                title: row.get(0)?,
+                source: row.get(1)?,
+                output_dir: row.get(2)?,
+                file: RAW_FILE.to_string(),
+                start_ms,
+                timestamp: format_timestamp(start_ms),
+                snippet: row.get(4)?,
+                // bm25 is lower-is-better; flip it so larger scores rank higher.
+                score: -row.get::<_, f64>(5)?,
+            })
+        })
+        .map_err(|err| format!("Invalid search \"{query}\": {err}"))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Invalid search \"{query}\": {err}"))
+}
+
+#[tauri::command]
+pub async fn search_transcripts(app: AppHandle, query: String, limit: Option<usize>) -> Result<Vec<SearchHit>, String> {
+    let limit = limit.filter(|l| *l > 0).unwrap_or(DEFAULT_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || search(&app, &query, limit))
+        .await
+        .map_err(|err| format!("Search task failed: {err}"))?
+}
+
+#[tauri::command]
+pub async fn rebuild_search_index(app: AppHandle, archive_folder: String) -> Result<IndexSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || rebuild(&app, Path::new(archive_folder.trim())))
+        .await
+        .map_err(|err| format!("Search index rebuild task failed: {err}"))?
+}
@@ -0,0 +1,207 @@
+use crate::{
+    chapters::{self, Chapter, ChapterOptions, Gap},
+    highlights::normalize,
+    music::{MusicRange, MUSIC_MARKER},
+    transcript::Segment,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SectionKind {
+    Announcements,
+    Worship,
+    Prayer,
+    Sermon,
+    /// Spoken, but matching none of the others.
+    Other,
+}
+
+impl SectionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SectionKind::Announcements => "Announcements",
+            SectionKind::Worship => "Worship",
+            SectionKind::Prayer => "Prayer",
+            SectionKind::Sermon => "Sermon",
+            SectionKind::Other => "Service",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    pub kind: SectionKind,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Labels the parts of a service from the transcript and the music ranges
+/// found in the audio; the sections replace the plain chapter markers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SectionOptions {
+    pub enabled: bool,
+    /// Sections are cut from blocks at least this long.
+    pub min_section_secs: f64,
+    /// Phrases that mark a stretch as prayer, matched on word boundaries.
+    pub prayer_cues: Vec<String>,
+    pub announcement_cues: Vec<String>,
+    /// Cue phrases a block needs before it is labeled by them.
+    pub min_cues: usize,
+}
+
+impl Default for SectionOptions {
+    fn default() -> Self {
+        let cues = |list: &[&str]| list.iter().map(|c| c.to_string()).collect();
+        Self {
+            enabled: false,
+            min_section_secs: 60.0,
+            prayer_cues: cues(&[
+                "let us pray",
+                "let's pray",
+                "bow your heads",
+                "heavenly father",
+                "dear lord",
+                "in jesus name we pray",
+            ]),
+            announcement_cues: cues(&[
+                "announcement",
+                "announcements",
+                "this week",
+                "next sunday",
+                "sign up",
+                "signup",
+                "bulletin",
+                "potluck",
+                "youth group",
+                "register",
+                "welcome to",
+                "visitors",
+            ]),
+            min_cues: 2,
+        }
+    }
+}
+
+fn cue_count(text: &str, cues: &[String]) -> usize {
+    cues.iter()
+        .map(|cue| normalize(cue))
+        .filter(|needle| !needle.trim().is_empty())
+        .map(|needle| text.matches(needle.as_str()).count())
+        .sum()
+}
+
+fn music_ms(start_ms: u64, end_ms: u64, ranges: &[MusicRange], segments: &[&Segment]) -> u64 {
+    let from_audio: u64 = ranges
+        .iter()
+        .map(|r| end_ms.min(r.end_ms).saturating_sub(start_ms.max(r.start_ms)))
+        .sum();
+    // Without detection, whisper's own music tags are the next best sign.
+    let from_tags: u64 = segments
+        .iter()
+        .filter(|s| {
+            let text = s.text.trim().to_lowercase();
+            text == MUSIC_MARKER || text == "(music)" || text.starts_with('♪')
+        })
+        .map(|s| s.end_ms.saturating_sub(s.start_ms))
+        .sum();
+    from_audio.max(from_tags)
+}
+
+/// Splits the service at silences like the chapter markers do and labels each
+/// block: mostly music or sparse speech is worship, dense speech with enough
+/// prayer or announcement cues is that, and the longest run of the remaining
+/// dense speech is the sermon.
+pub fn classify(
+    segments: &[Segment],
+    gaps: &[Gap],
+    music: &[MusicRange],
+    chapter_options: &ChapterOptions,
+    options: &SectionOptions,
+) -> Vec<Section> {
+    let Some(end_ms) = segments.iter().map(|s| s.end_ms).max().filter(|&e| e > 0) else {
+        return Vec::new();
+    };
+    let block_options = ChapterOptions {
+        min_chapter_secs: options.min_section_secs.max(10.0),
+        ..chapter_options.clone()
+    };
+    let blocks = chapters::build_blocks(segments, gaps, end_ms, &block_options);
+
+    let mut kinds: Vec<Option<SectionKind>> = blocks
+        .iter()
+        .map(|block| {
+            let inside: Vec<&Segment> = segments
+                .iter()
+                .filter(|s| {
+                    let mid = s.start_ms + s.end_ms.saturating_sub(s.start_ms) / 2;
+                    mid >= block.start_ms && mid < block.end_ms
+                })
+                .collect();
+            let length = block.end_ms.saturating_sub(block.start_ms).max(1);
+            if music_ms(block.start_ms, block.end_ms, music, &inside) * 2 >= length || !block.dense {
+                return Some(SectionKind::Worship);
+            }
+            let text = normalize(&inside.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" "));
+            let prayer = cue_count(&text, &options.prayer_cues);
+            let announcements = cue_count(&text, &options.announcement_cues);
+            if prayer >= options.min_cues && prayer > announcements {
+                Some(SectionKind::Prayer)
+            } else if announcements >= options.min_cues {
+                Some(SectionKind::Announcements)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // The longest unlabeled dense run is the sermon; shorter ones stay `Other`.
+    let mut best: Option<(usize, usize)> = None;
+    let mut index = 0;
+    while index < kinds.len() {
+        if kinds[index].is_some() {
+            index += 1;
+            continue;
+        }
+        let first = index;
+        while index + 1 < kinds.len() && kinds[index + 1].is_none() {
+            index += 1;
+        }
+        let length = blocks[index].end_ms - blocks[first].start_ms;
+        if best.is_none_or(|(a, b)| length > blocks[b].end_ms - blocks[a].start_ms) {
+            best = Some((first, index));
+        }
+        index += 1;
+    }
+    if let Some((first, last)) = best {
+        kinds[first..=last].fill(Some(SectionKind::Sermon));
+    }
+
+    let mut sections: Vec<Section> = Vec::new();
+    for (block, kind) in blocks.iter().zip(kinds) {
+        let kind = kind.unwrap_or(SectionKind::Other);
+        match sections.last_mut() {
+            Some(last) if last.kind == kind => last.end_ms = block.end_ms,
+            _ => sections.push(Section {
+                kind,
+                start_ms: block.start_ms,
+                end_ms: block.end_ms,
+            }),
+        }
+    }
+    sections
+}
+
+/// Chapter markers named after the sections.
+pub fn to_chapters(sections: &[Section]) -> Vec<Chapter> {
+    sections
+        .iter()
+        .map(|s| Chapter {
+            title: s.kind.label().to_string(),
+            start_ms: s.start_ms,
+            end_ms: s.end_ms,
+        })
+        .collect()
+}
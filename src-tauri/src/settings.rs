@@ -0,0 +1,509 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+const DEFAULT_PROFANITY_WORDS: &[&str] = &[
+    "fuck*", "shit*", "bitch*", "bastard*", "asshole*", "damn*", "goddamn*", "crap*", "piss*",
+    "dick*", "cunt*", "motherf*",
+];
+
+const DEFAULT_HIGHLIGHT_KEYWORDS: &[&str] = &[
+    "baptism", "baptized", "communion", "lord's supper", "testimony", "dedication", "announcement",
+    "announcements", "this week", "next sunday", "sign up",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RemoteApiSettings {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    pub archive_folder: String,
+    pub viewer_token: String,
+    /// Grants start/stop/pause over the API. Empty keeps the API read-only.
+    pub control_token: String,
+}
+
+impl Default for RemoteApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "0.0.0.0".to_string(),
+            port: 8765,
+            archive_folder: String::new(),
+            viewer_token: generate_token(),
+            control_token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ViewerSettings {
+    pub enabled: bool,
+    pub server_url: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub starttls: bool,
+    pub username: String,
+    pub password: String,
+    pub from: String,
+}
+
+impl Default for SmtpSettings {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 587,
+            starttls: true,
+            username: String::new(),
+            password: String::new(),
+            from: String::new(),
+        }
+    }
+}
+
+/// `paused_alert_minutes` of 0 turns off the long-pause reminder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NotificationSettings {
+    pub email_on_success: bool,
+    pub email_on_failure: bool,
+    pub email_recipients: Vec<String>,
+    pub desktop_on_success: bool,
+    pub desktop_on_failure: bool,
+    pub paused_alert_minutes: u32,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            email_on_success: false,
+            email_on_failure: false,
+            email_recipients: Vec::new(),
+            desktop_on_success: true,
+            desktop_on_failure: true,
+            paused_alert_minutes: 15,
+        }
+    }
+}
+
+/// Phrases watched for in segments as the engine prints them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct KeywordAlertSettings {
+    pub phrases: Vec<String>,
+    /// Also show a desktop notification, once per phrase per recording.
+    pub desktop_notification: bool,
+}
+
+impl Default for KeywordAlertSettings {
+    fn default() -> Self {
+        Self {
+            phrases: Vec::new(),
+            desktop_notification: true,
+        }
+    }
+}
+
+/// Names and terms, such as people named in prayer requests, replaced in
+/// published outputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RedactionSettings {
+    pub terms: Vec<String>,
+    pub replacement: String,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            terms: Vec::new(),
+            replacement: "[redacted]".to_string(),
+        }
+    }
+}
+
+/// An empty `events` list subscribes to every event
+/// (`run.started`, `file.finished`, `run.finished`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            url: String::new(),
+            secret: String::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DigestSettings {
+    pub enabled: bool,
+    pub folder: String,
+    pub interval_days: u32,
+    pub email: bool,
+    pub recipients: Vec<String>,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder: String::new(),
+            interval_days: 7,
+            email: false,
+            recipients: Vec::new(),
+        }
+    }
+}
+
+/// OpenAI-compatible chat endpoint used for sermon summaries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LlmSettings {
+    pub endpoint: String,
+    pub api_key: String,
+    pub model: String,
+    pub max_input_chars: usize,
+    pub timeout_secs: u64,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://127.0.0.1:8080/v1".to_string(),
+            api_key: String::new(),
+            model: String::new(),
+            max_input_chars: 60_000,
+            timeout_secs: 300,
+        }
+    }
+}
+
+/// OpenAI-compatible speech-to-text API used by the cloud engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CloudTranscriptionSettings {
+    pub endpoint: String,
+    pub api_key: String,
+    pub model: String,
+    /// Price per audio minute in dollars, for the preflight estimate.
+    pub cost_per_minute: f64,
+    /// Upload size; the OpenAI API rejects files over 25 MB (about 13 minutes of WAV).
+    pub chunk_minutes: u32,
+    /// Transcribe locally with whisper.cpp when an upload fails.
+    pub fallback_to_local: bool,
+}
+
+impl Default for CloudTranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://api.openai.com/v1".to_string(),
+            api_key: String::new(),
+            model: "whisper-1".to_string(),
+            cost_per_minute: 0.006,
+            chunk_minutes: 10,
+            fallback_to_local: true,
+        }
+    }
+}
+
+/// Google OAuth desktop client; the refresh token is filled in by sign-in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct YoutubeSettings {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+    pub caption_language: String,
+    pub caption_name: String,
+}
+
+impl Default for YoutubeSettings {
+    fn default() -> Self {
+        Self {
+            client_id: String::new(),
+            client_secret: String::new(),
+            refresh_token: String::new(),
+            caption_language: "en".to_string(),
+            caption_name: "English".to_string(),
+        }
+    }
+}
+
+/// Media and transcripts are expected to be uploaded under the two base URLs
+/// with the same relative layout as on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PodcastSettings {
+    pub enabled: bool,
+    pub feed_path: String,
+    pub title: String,
+    pub description: String,
+    pub link: String,
+    pub author: String,
+    pub language: String,
+    pub image_url: String,
+    pub media_base_url: String,
+    pub transcript_base_url: String,
+}
+
+impl Default for PodcastSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feed_path: String::new(),
+            title: "Sermons".to_string(),
+            description: String::new(),
+            link: String::new(),
+            author: String::new(),
+            language: "en".to_string(),
+            image_url: String::new(),
+            media_base_url: String::new(),
+            transcript_base_url: String::new(),
+        }
+    }
+}
+
+/// Any S3-compatible store (AWS, Backblaze B2, Wasabi, MinIO, R2).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct S3Settings {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub prefix: String,
+    pub path_style: bool,
+    pub include_audio: bool,
+    pub max_retries: u32,
+}
+
+impl Default for S3Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "https://s3.us-east-1.amazonaws.com".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            prefix: "transcripts".to_string(),
+            path_style: true,
+            include_audio: false,
+            max_retries: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloudProvider {
+    #[default]
+    GoogleDrive,
+    Dropbox,
+}
+
+/// Ordered from most to least verbose, so a verbosity setting is a minimum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+/// What reaches the log panel; log files on disk always get every line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LogSettings {
+    pub verbosity: LogLevel,
+    /// Info and debug lines beyond this rate are dropped and counted.
+    pub max_events_per_second: u32,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            verbosity: LogLevel::Info,
+            max_events_per_second: 20,
+        }
+    }
+}
+
+/// An empty `local_folder` syncs the most recent run's output folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CloudSyncSettings {
+    pub enabled: bool,
+    pub provider: CloudProvider,
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+    pub remote_folder: String,
+    pub local_folder: String,
+    pub sync_after_run: bool,
+}
+
+impl Default for CloudSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: CloudProvider::default(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            refresh_token: String::new(),
+            remote_folder: "Church Transcripts".to_string(),
+            local_folder: String::new(),
+            sync_after_run: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppSettings {
+    pub remote_api: RemoteApiSettings,
+    pub viewer: ViewerSettings,
+    pub profanity_words: Vec<String>,
+    pub smtp: SmtpSettings,
+    pub notifications: NotificationSettings,
+    pub webhooks: Vec<WebhookSettings>,
+    pub digest: DigestSettings,
+    pub llm: LlmSettings,
+    pub youtube: YoutubeSettings,
+    pub podcast: PodcastSettings,
+    pub s3: S3Settings,
+    pub cloud_sync: CloudSyncSettings,
+    pub logging: LogSettings,
+    pub cloud_transcription: CloudTranscriptionSettings,
+    /// Regexes matched against each recording's file name (without the
+    /// extension). Named groups `year`, `month`, `day`, `title`, `speaker`
+    /// and `series` fill in its metadata; the first matching pattern wins.
+    pub filename_patterns: Vec<String>,
+    /// Words and phrases whose segments are listed as clip candidates after
+    /// each run; matching ignores case and punctuation.
+    pub highlight_keywords: Vec<String>,
+    pub keyword_alerts: KeywordAlertSettings,
+    pub redaction: RedactionSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            remote_api: RemoteApiSettings::default(),
+            viewer: ViewerSettings::default(),
+            smtp: SmtpSettings::default(),
+            notifications: NotificationSettings::default(),
+            webhooks: Vec::new(),
+            digest: DigestSettings::default(),
+            llm: LlmSettings::default(),
+            youtube: YoutubeSettings::default(),
+            podcast: PodcastSettings::default(),
+            s3: S3Settings::default(),
+            cloud_sync: CloudSyncSettings::default(),
+            logging: LogSettings::default(),
+            cloud_transcription: CloudTranscriptionSettings::default(),
+            filename_patterns: Vec::new(),
+            profanity_words: DEFAULT_PROFANITY_WORDS
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            keyword_alerts: KeywordAlertSettings::default(),
+            redaction: RedactionSettings::default(),
+            highlight_keywords: DEFAULT_HIGHLIGHT_KEYWORDS
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// 128-bit hex token from the OS random number generator.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("the OS random number generator is available");
+    hex::encode(bytes)
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|err| format!("Could not resolve app config folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create config folder {}: {err}", dir.display()))?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+pub fn load(app: &AppHandle) -> AppSettings {
+    let Ok(path) = settings_path(app) else {
+        return AppSettings::default();
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(body) => serde_json::from_str(&body).unwrap_or_default(),
+        Err(_) => {
+            // Persist first-run defaults so generated tokens stay stable.
+            let defaults = AppSettings::default();
+            let _ = save(app, &defaults);
+            defaults
+        }
+    }
+}
+
+pub fn save(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    let body = serde_json::to_string_pretty(settings)
+        .map_err(|err| format!("Failed to serialize settings: {err}"))?;
+    fs::write(&path, body)
+        .map_err(|err| format!("Failed to write settings {}: {err}", path.display()))
+}
+
+#[tauri::command]
+pub fn get_settings(app: AppHandle) -> AppSettings {
+    load(&app)
+}
+
+#[tauri::command]
+pub fn save_settings(app: AppHandle, settings: AppSettings) -> Result<AppSettings, String> {
+    crate::filemeta::compile_patterns(&settings.filename_patterns)?;
+    save(&app, &settings)?;
+    crate::remote::apply_settings(&app, &settings.remote_api);
+    crate::logfilter::apply_settings(&app, &settings.logging);
+    Ok(settings)
+}
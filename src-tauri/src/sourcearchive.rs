@@ -0,0 +1,134 @@
+use crate::{history::RunReport, manifest};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveMode {
+    #[default]
+    Move,
+    Copy,
+}
+
+/// Files transcribed successfully are moved (or copied) out of the input
+/// folder once the run finishes, so what's left still needs processing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SourceArchiveOptions {
+    pub enabled: bool,
+    pub mode: ArchiveMode,
+    /// Relative to each input folder (and skipped when scanning it), or an
+    /// absolute folder holding one subfolder per input folder.
+    pub folder: String,
+}
+
+impl Default for SourceArchiveOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ArchiveMode::Move,
+            folder: "Archived".to_string(),
+        }
+    }
+}
+
+impl SourceArchiveOptions {
+    /// The exclude pattern that keeps an archive inside the input folder
+    /// from being scanned again.
+    pub fn exclude_pattern(&self) -> Option<String> {
+        let folder = self.folder.trim().trim_matches(['/', '\\']);
+        (self.enabled && !folder.is_empty() && Path::new(folder).is_relative()).then(|| format!("{folder}/*"))
+    }
+}
+
+/// Where `source` goes, keeping its path below the input folder.
+fn destination(options: &SourceArchiveOptions, input_folders: &[String], source: &Path) -> Option<PathBuf> {
+    let (input, relative) = input_folders.iter().find_map(|folder| {
+        let folder = Path::new(folder.trim());
+        // A single-file input archives next to the file, as its folder would.
+        let folder = if folder.is_file() { folder.parent()? } else { folder };
+        source.strip_prefix(folder).ok().map(|rel| (folder, rel))
+    })?;
+    let folder = Path::new(options.folder.trim());
+    let base = if folder.is_absolute() {
+        folder.join(input.file_name().unwrap_or(input.as_os_str()))
+    } else {
+        input.join(folder)
+    };
+    Some(base.join(relative))
+}
+
+/// `name (2).ext`, `name (3).ext`, ... when the archive already has the name.
+fn free_name(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{stem} ({n}){ext}")))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+fn archive_file(mode: ArchiveMode, source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("Could not create {}: {err}", parent.display()))?;
+    }
+    let copy = || {
+        fs::copy(source, target)
+            .map(|_| ())
+            .map_err(|err| format!("Could not copy {} to {}: {err}", source.display(), target.display()))
+    };
+    match mode {
+        ArchiveMode::Copy => copy(),
+        // A rename fails across drives; copy and delete instead.
+        ArchiveMode::Move => fs::rename(source, target).or_else(|_| {
+            copy()?;
+            fs::remove_file(source).map_err(|err| format!("Copied but could not remove {}: {err}", source.display()))
+        }),
+    }
+}
+
+/// Archives the run's successfully transcribed sources and points the
+/// manifest at their new location.
+pub fn archive_sources(app: &AppHandle, report: &RunReport) {
+    let Some(options) = report
+        .request
+        .as_ref()
+        .map(|r| &r.archive_sources)
+        .filter(|o| o.enabled && !o.folder.trim().is_empty())
+    else {
+        return;
+    };
+    let mut archived = 0;
+    for result in report.results.iter().filter(|r| r.status == "ok") {
+        let source = Path::new(&result.source);
+        if !source.is_file() {
+            continue;
+        }
+        let Some(target) = destination(options, &report.input_folders, source).map(free_name) else {
+            crate::emit_log(app, "system", format!("Not archived (outside the input folders): {}", source.display()));
+            continue;
+        };
+        let moved = archive_file(options.mode, source, &target).and_then(|_| match options.mode {
+            ArchiveMode::Move => manifest::move_source(app, &result.source, &target.display().to_string()),
+            ArchiveMode::Copy => Ok(()),
+        });
+        match moved {
+            Ok(()) => archived += 1,
+            Err(err) => crate::emit_log(app, "system", err),
+        }
+    }
+    if archived > 0 {
+        let verb = match options.mode {
+            ArchiveMode::Move => "Moved",
+            ArchiveMode::Copy => "Copied",
+        };
+        crate::emit_log(app, "system", format!("{verb} {archived} source file(s) to the archive folder."));
+    }
+}
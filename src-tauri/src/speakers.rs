@@ -0,0 +1,476 @@
+use crate::transcript::Segment;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Arc,
+};
+use tauri::{AppHandle, Manager};
+
+const SPEAKERS_FILE: &str = "speakers.json";
+const SAMPLE_RATE: usize = 16_000;
+/// 32 ms analysis frames every 20 ms.
+const FRAME: usize = 512;
+const HOP: usize = 320;
+const HOP_MS: u64 = (HOP * 1000 / SAMPLE_RATE) as u64;
+const MEL_BANDS: usize = 24;
+const MEL_LOW_HZ: f32 = 100.0;
+const MEL_HIGH_HZ: f32 = 7600.0;
+/// Frames quieter than this are pauses and left out of a voiceprint.
+const SILENCE_DBFS: f32 = -45.0;
+/// Enrollment needs this much speech for a usable voiceprint.
+const MIN_ENROLL_SECS: f64 = 5.0;
+/// Segments with less speech than this (1 s) take the previous segment's label.
+const MIN_LABEL_FRAMES: usize = 50;
+
+/// A regular speaker enrolled from short voice samples. The embedding is the
+/// shape of their average log-mel spectrum plus its spread, unit length.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerProfile {
+    pub name: String,
+    pub embedding: Vec<f32>,
+    pub sample_files: Vec<String>,
+    pub voiced_secs: f64,
+    pub enrolled_at_epoch: u64,
+}
+
+/// Labels transcript segments with the enrolled speaker whose voice they
+/// match, so transcripts say "Pastor Dave:" rather than nothing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SpeakerOptions {
+    pub enabled: bool,
+    /// Cosine similarity a segment needs with a profile to take its name.
+    pub min_similarity: f32,
+}
+
+impl Default for SpeakerOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_similarity: 0.8,
+        }
+    }
+}
+
+/// Running sums of a stretch of voiced frames.
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    frames: usize,
+    sum: [f64; MEL_BANDS],
+    sum_sq: [f64; MEL_BANDS],
+}
+
+impl Accumulator {
+    fn add(&mut self, bands: &[f32]) {
+        self.frames += 1;
+        for (i, value) in bands.iter().enumerate() {
+            self.sum[i] += *value as f64;
+            self.sum_sq[i] += (*value as f64).powi(2);
+        }
+    }
+
+    /// Band means with their average removed, so recording level doesn't
+    /// matter, followed by each band's spread.
+    fn embedding(&self) -> Option<Vec<f32>> {
+        if self.frames == 0 {
+            return None;
+        }
+        let n = self.frames as f64;
+        let means: Vec<f64> = self.sum.iter().map(|s| s / n).collect();
+        let level = means.iter().sum::<f64>() / MEL_BANDS as f64;
+        let spreads = self
+            .sum_sq
+            .iter()
+            .zip(&means)
+            .map(|(sq, mean)| (sq / n - mean * mean).max(0.0).sqrt());
+        let vector: Vec<f64> = means.iter().map(|m| m - level).chain(spreads).collect();
+        let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+        (norm > 0.0).then(|| vector.iter().map(|v| (v / norm) as f32).collect())
+    }
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular mel filters over the FFT bins, as (bin, weight) pairs.
+fn mel_filters() -> Vec<Vec<(usize, f32)>> {
+    let (low, high) = (hz_to_mel(MEL_LOW_HZ), hz_to_mel(MEL_HIGH_HZ));
+    let bin_of = |mel: f32| mel_to_hz(mel) * FRAME as f32 / SAMPLE_RATE as f32;
+    let edges: Vec<f32> = (0..MEL_BANDS + 2)
+        .map(|i| bin_of(low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32))
+        .collect();
+    edges
+        .windows(3)
+        .map(|e| {
+            (e[0].floor() as usize..=e[2].ceil() as usize)
+                .filter_map(|bin| {
+                    let x = bin as f32;
+                    let weight = if x <= e[1] {
+                        (x - e[0]) / (e[1] - e[0]).max(f32::EPSILON)
+                    } else {
+                        (e[2] - x) / (e[2] - e[1]).max(f32::EPSILON)
+                    };
+                    (weight > 0.0).then_some((bin, weight))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Cuts 16 kHz mono samples into frames and hands each voiced one's log-mel
+/// energies to a visitor.
+struct Analyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    filters: Vec<Vec<(usize, f32)>>,
+    pending: Vec<f32>,
+    frames: u64,
+}
+
+impl Analyzer {
+    fn new() -> Self {
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(FRAME),
+            window: (0..FRAME)
+                .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME as f32).cos())
+                .collect(),
+            filters: mel_filters(),
+            pending: Vec::new(),
+            frames: 0,
+        }
+    }
+
+    /// Calls `visit` with each voiced frame's start in ms; stops early when
+    /// it returns false.
+    fn push(&mut self, samples: &[f32], visit: &mut impl FnMut(u64, &[f32]) -> bool) -> bool {
+        self.pending.extend_from_slice(samples);
+        let mut start = 0;
+        let mut more = true;
+        while more && self.pending.len() - start >= FRAME {
+            let frame = &self.pending[start..start + FRAME];
+            let start_ms = self.frames * HOP_MS;
+            self.frames += 1;
+            start += HOP;
+
+            let power = frame.iter().map(|s| s * s).sum::<f32>() / FRAME as f32;
+            if 10.0 * power.max(1e-12).log10() < SILENCE_DBFS {
+                continue;
+            }
+            let mut spectrum: Vec<Complex<f32>> =
+                frame.iter().zip(&self.window).map(|(s, w)| Complex::new(s * w, 0.0)).collect();
+            self.fft.process(&mut spectrum);
+            let bands: Vec<f32> = self
+                .filters
+                .iter()
+                .map(|filter| {
+                    let energy: f32 = filter.iter().map(|(bin, weight)| spectrum[*bin].norm_sqr() * weight).sum();
+                    (energy + 1e-10).ln()
+                })
+                .collect();
+            more = visit(start_ms, &bands);
+        }
+        self.pending.drain(..start);
+        more
+    }
+}
+
+/// Decodes `source` with ffmpeg and analyses it frame by frame.
+fn analyze(source: &Path, mut visit: impl FnMut(u64, &[f32]) -> bool) -> Result<(), String> {
+    let mut child = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-loglevel", "error", "-i"])
+        .arg(source)
+        .args(["-vn", "-ac", "1", "-ar", &SAMPLE_RATE.to_string(), "-f", "s16le", "-"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Could not run ffmpeg to read {}: {err}", source.display()))?;
+    let mut stdout = child.stdout.take().ok_or("ffmpeg produced no audio stream.")?;
+
+    let mut analyzer = Analyzer::new();
+    let mut bytes = vec![0u8; 64 * 1024];
+    let mut carry: Option<u8> = None;
+    loop {
+        let read = stdout
+            .read(&mut bytes)
+            .map_err(|err| format!("Could not read audio from {}: {err}", source.display()))?;
+        if read == 0 {
+            break;
+        }
+        let mut raw: Vec<u8> = carry.take().into_iter().chain(bytes[..read].iter().copied()).collect();
+        if raw.len() % 2 == 1 {
+            carry = raw.pop();
+        }
+        let samples: Vec<f32> = raw
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0)
+            .collect();
+        if !analyzer.push(&samples, &mut visit) {
+            // Everything needed has been read; don't decode the rest.
+            let _ = child.kill();
+            break;
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|err| format!("ffmpeg did not finish for {}: {err}", source.display()))?;
+    if analyzer.frames == 0 && !status.success() {
+        return Err(format!("ffmpeg could not decode {} (code {:?})", source.display(), status.code()));
+    }
+    Ok(())
+}
+
+fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// The enrolled speaker closest to `embedding`, if close enough.
+fn best_match<'a>(embedding: &[f32], profiles: &'a [SpeakerProfile], min_similarity: f32) -> Option<&'a SpeakerProfile> {
+    profiles
+        .iter()
+        .map(|p| (p, similarity(embedding, &p.embedding)))
+        .filter(|(_, score)| *score >= min_similarity)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(p, _)| p)
+}
+
+/// Names from per-segment accumulators; segments too short to judge carry
+/// the previous label on.
+fn labels(accumulators: &[Accumulator], profiles: &[SpeakerProfile], min_similarity: f32) -> Vec<Option<String>> {
+    let mut previous: Option<String> = None;
+    accumulators
+        .iter()
+        .map(|acc| {
+            if acc.frames >= MIN_LABEL_FRAMES {
+                previous = acc
+                    .embedding()
+                    .and_then(|e| best_match(&e, profiles, min_similarity))
+                    .map(|p| p.name.clone());
+            }
+            previous.clone()
+        })
+        .collect()
+}
+
+/// Sets `speaker` on every segment whose voice matches an enrolled profile;
+/// returns how many were labelled. Segments must be in time order.
+pub fn label_segments(
+    source: &Path,
+    segments: &mut [Segment],
+    profiles: &[SpeakerProfile],
+    min_similarity: f32,
+) -> Result<usize, String> {
+    let mut accumulators = vec![Accumulator::default(); segments.len()];
+    let mut cursor = 0;
+    analyze(source, |start_ms, bands| {
+        let middle = start_ms + HOP_MS;
+        while cursor < segments.len() && segments[cursor].end_ms <= middle {
+            cursor += 1;
+        }
+        if cursor < segments.len() && segments[cursor].start_ms <= middle {
+            accumulators[cursor].add(bands);
+        }
+        cursor < segments.len()
+    })?;
+
+    let mut labelled = 0;
+    for (segment, label) in segments.iter_mut().zip(labels(&accumulators, profiles, min_similarity)) {
+        if label.is_some() {
+            labelled += 1;
+        }
+        segment.speaker = label;
+    }
+    Ok(labelled)
+}
+
+fn speakers_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create app data folder {}: {err}", dir.display()))?;
+    Ok(dir.join(SPEAKERS_FILE))
+}
+
+pub fn load(app: &AppHandle) -> Vec<SpeakerProfile> {
+    speakers_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, profiles: &[SpeakerProfile]) -> Result<(), String> {
+    let path = speakers_path(app)?;
+    let body = serde_json::to_string_pretty(profiles)
+        .map_err(|err| format!("Failed to serialize speakers: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Speaker names are matched case-insensitively.
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn enroll(app: &AppHandle, name: &str, samples: &[String]) -> Result<SpeakerProfile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Speaker name is required.".to_string());
+    }
+    if samples.iter().all(|s| s.trim().is_empty()) {
+        return Err("At least one voice sample is required.".to_string());
+    }
+
+    let mut acc = Accumulator::default();
+    for sample in samples.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        analyze(&crate::longpath::extended(Path::new(sample)), |_, bands| {
+            acc.add(bands);
+            true
+        })?;
+    }
+    let voiced_secs = (acc.frames as u64 * HOP_MS) as f64 / 1000.0;
+    if voiced_secs < MIN_ENROLL_SECS {
+        return Err(format!(
+            "Only {voiced_secs:.1}s of speech found in the samples; at least {MIN_ENROLL_SECS:.0}s is needed."
+        ));
+    }
+
+    let profile = SpeakerProfile {
+        name: name.to_string(),
+        embedding: acc.embedding().ok_or("The samples have no usable speech.")?,
+        sample_files: samples.iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+        voiced_secs,
+        enrolled_at_epoch: crate::now_epoch(),
+    };
+    let mut profiles = load(app);
+    profiles.retain(|p| !same_name(&p.name, name));
+    profiles.push(profile.clone());
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+    save(app, &profiles)?;
+    Ok(profile)
+}
+
+// The `manage_speakers` commands: enroll, list and delete.
+
+/// Enrolls (or re-enrolls) a speaker from one or more short recordings of
+/// them talking; 30 seconds or more of clean speech works best.
+#[tauri::command]
+pub async fn enroll_speaker(app: AppHandle, name: String, samples: Vec<String>) -> Result<SpeakerProfile, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let profile = enroll(&app, &name, &samples)?;
+        crate::emit_log(
+            &app,
+            "system",
+            format!("Speaker enrolled: {} ({:.0}s of speech)", profile.name, profile.voiced_secs),
+        );
+        Ok(profile)
+    })
+    .await
+    .map_err(|err| format!("Speaker enrollment failed: {err}"))?
+}
+
+#[tauri::command]
+pub fn list_speakers(app: AppHandle) -> Vec<SpeakerProfile> {
+    load(&app)
+}
+
+#[tauri::command]
+pub fn delete_speaker(app: AppHandle, name: String) -> Result<(), String> {
+    let mut profiles = load(&app);
+    let before = profiles.len();
+    profiles.retain(|p| !same_name(&p.name, &name));
+    if profiles.len() == before {
+        return Err(format!("Speaker not found: {name}"));
+    }
+    save(&app, &profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A second of a buzzy "voice": harmonics of `pitch_hz` shaped by a
+    /// resonance at `formant_hz`.
+    fn voice(pitch_hz: f32, formant_hz: f32, secs: f32) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE as f32) as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                (1..40)
+                    .map(|h| pitch_hz * h as f32)
+                    .filter(|f| *f < 7900.0)
+                    .map(|f| {
+                        let gain = 1.0 / (1.0 + ((f - formant_hz) / 300.0).powi(2));
+                        gain * (2.0 * std::f32::consts::PI * f * t).sin()
+                    })
+                    .sum::<f32>()
+                    * 0.05
+            })
+            .collect()
+    }
+
+    fn voiceprint(samples: &[f32]) -> Accumulator {
+        let mut acc = Accumulator::default();
+        Analyzer::new().push(samples, &mut |_, bands| {
+            acc.add(bands);
+            true
+        });
+        acc
+    }
+
+    fn profile(name: &str, samples: &[f32]) -> SpeakerProfile {
+        SpeakerProfile {
+            name: name.to_string(),
+            embedding: voiceprint(samples).embedding().unwrap(),
+            sample_files: Vec::new(),
+            voiced_secs: 0.0,
+            enrolled_at_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn silence_is_left_out() {
+        let mut samples = vec![0.0; SAMPLE_RATE];
+        samples.extend(voice(120.0, 700.0, 1.0));
+        let mut starts = Vec::new();
+        Analyzer::new().push(&samples, &mut |start_ms, _| {
+            starts.push(start_ms);
+            true
+        });
+        assert!(!starts.is_empty());
+        assert!(starts.iter().all(|ms| *ms + 32 >= 1000), "{starts:?}");
+    }
+
+    #[test]
+    fn voices_match_their_own_profile_regardless_of_level() {
+        let profiles = [profile("Pastor Dave", &voice(110.0, 600.0, 3.0)), profile("Sister Ann", &voice(220.0, 2400.0, 3.0))];
+
+        let quiet_dave: Vec<f32> = voice(112.0, 620.0, 2.0).iter().map(|s| s * 0.3).collect();
+        let dave = voiceprint(&quiet_dave).embedding().unwrap();
+        assert_eq!(best_match(&dave, &profiles, 0.8).map(|p| p.name.as_str()), Some("Pastor Dave"));
+
+        let ann = voiceprint(&voice(215.0, 2350.0, 2.0)).embedding().unwrap();
+        assert_eq!(best_match(&ann, &profiles, 0.8).map(|p| p.name.as_str()), Some("Sister Ann"));
+        assert!(similarity(&ann, &profiles[0].embedding) < 0.8);
+        assert!(best_match(&ann, &profiles, 1.01).is_none());
+    }
+
+    #[test]
+    fn short_segments_keep_the_previous_label() {
+        let profiles = [profile("Pastor Dave", &voice(110.0, 600.0, 3.0))];
+        let long = voiceprint(&voice(110.0, 600.0, 2.0));
+        let short = voiceprint(&voice(220.0, 2400.0, 0.2));
+        let labels = labels(&[short.clone(), long, short], &profiles, 0.8);
+        assert_eq!(labels, [None, Some("Pastor Dave".to_string()), Some("Pastor Dave".to_string())]);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::scanner::{self, FolderPlan};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tauri::AppHandle;
+
+/// Copies kept on local disk ahead of the file being transcribed.
+const COPIES_AHEAD: usize = 2;
+const COPY_ATTEMPTS: u32 = 5;
+const FIRST_BACKOFF: Duration = Duration::from_secs(2);
+const READY_SUFFIX: &str = ".ready";
+const FAILED_SUFFIX: &str = ".failed";
+
+pub fn default_root() -> PathBuf {
+    std::env::temp_dir().join("church-transcriber-staging")
+}
+
+/// Local folder a run's copies for one input folder are staged in.
+pub fn staging_dir(root: Option<&str>, run_id: &str, folder_index: usize) -> PathBuf {
+    let root = root
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(default_root);
+    root.join(format!("{run_id}-{}", folder_index + 1))
+}
+
+/// Points every planned file at its local copy. The script waits for the
+/// copy's `.ready` marker and removes the copy once it moves on.
+pub fn assign(plan: &mut FolderPlan, dir: &Path) {
+    for (index, file) in plan.files.iter_mut().enumerate() {
+        let name = Path::new(&file.source)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "media".to_string());
+        file.staged_source = Some(dir.join(format!("{:04}-{name}", index + 1)).display().to_string());
+    }
+}
+
+fn marker(staged: &Path, suffix: &str) -> PathBuf {
+    let mut name = staged.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn pending_copies(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().ends_with(READY_SUFFIX))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Copies `source` to `dest`, returning the SHA-256 of the bytes read.
+fn copy_hashed(source: &Path, dest: &Path, stop: &AtomicBool) -> Result<String, String> {
+    let mut input = File::open(source).map_err(|err| format!("Could not open {}: {err}", source.display()))?;
+    let mut output = File::create(dest).map_err(|err| format!("Could not create {}: {err}", dest.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = input
+            .read(&mut buffer)
+            .map_err(|err| format!("Could not read {}: {err}", source.display()))?;
+        if read == 0 {
+            break;
+        }
+        if stop.load(Ordering::Relaxed) {
+            return Err("Run stopped".to_string());
+        }
+        hasher.update(&buffer[..read]);
+        output
+            .write_all(&buffer[..read])
+            .map_err(|err| format!("Could not write {}: {err}", dest.display()))?;
+    }
+    output
+        .sync_all()
+        .map_err(|err| format!("Could not flush {}: {err}", dest.display()))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|err| format!("Could not open {}: {err}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// One copy attempt: the bytes written must hash the same as the bytes read,
+/// and a fresh sample of the source must match the copy, which catches short
+/// or garbled reads from the share.
+fn copy_verified(source: &Path, dest: &Path, stop: &AtomicBool) -> Result<(), String> {
+    let streamed = copy_hashed(source, dest, stop)?;
+    if file_sha256(dest)? != streamed {
+        return Err(format!("Checksum mismatch writing {}", dest.display()));
+    }
+    if scanner::content_fingerprint(source)? != scanner::content_fingerprint(dest)? {
+        return Err(format!("Copy of {} does not match the source", source.display()));
+    }
+    Ok(())
+}
+
+fn copy_with_retry(app: &AppHandle, source: &Path, dest: &Path, stop: &AtomicBool) -> Result<(), String> {
+    let mut backoff = FIRST_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match copy_verified(source, dest, stop) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                let _ = fs::remove_file(dest);
+                if attempt >= COPY_ATTEMPTS || stop.load(Ordering::Relaxed) {
+                    return Err(format!("{err} (after {attempt} attempts)"));
+                }
+                crate::emit_log(
+                    app,
+                    "system",
+                    format!("Local copy failed ({err}); retrying in {}s.", backoff.as_secs()),
+                );
+                thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+pub struct Copier {
+    handle: JoinHandle<()>,
+    stop: Arc<AtomicBool>,
+}
+
+/// Copies the plan's files to their staged paths in queue order, staying at
+/// most a couple of files ahead of the script so the local disk only holds
+/// what is about to be transcribed.
+pub fn prefetch(app: &AppHandle, plan: &FolderPlan, dir: &Path) -> Result<Copier, String> {
+    fs::create_dir_all(dir).map_err(|err| format!("Could not create staging folder {}: {err}", dir.display()))?;
+    let app = app.clone();
+    let dir = dir.to_path_buf();
+    let files: Vec<(PathBuf, PathBuf)> = plan
+        .files
+        .iter()
+        .filter_map(|f| Some((PathBuf::from(&f.source), PathBuf::from(f.staged_source.as_ref()?))))
+        .collect();
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = stop.clone();
+
+    let handle = thread::spawn(move || {
+        for (source, staged) in files {
+            while pending_copies(&dir) >= COPIES_AHEAD {
+                if stop_flag.load(Ordering::Relaxed) {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(500));
+            }
+            if stop_flag.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let result = copy_with_retry(&app, &source, &staged, &stop_flag);
+            let written = match &result {
+                Ok(()) => fs::write(marker(&staged, READY_SUFFIX), ""),
+                Err(err) => {
+                    crate::emit_log(&app, "system", format!("Could not copy {} locally: {err}", source.display()));
+                    fs::write(marker(&staged, FAILED_SUFFIX), err)
+                }
+            };
+            if let Err(err) = written {
+                crate::emit_log(&app, "system", format!("Could not write staging marker: {err}"));
+                return;
+            }
+        }
+    });
+    Ok(Copier { handle, stop })
+}
+
+/// Stops the copier and removes whatever is left in the staging folder.
+pub fn finish(dir: &Path, copier: Option<Copier>) {
+    if let Some(copier) = copier {
+        copier.stop.store(true, Ordering::Relaxed);
+        let _ = copier.handle.join();
+    }
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,161 @@
+use crate::{
+    history::{self, RunReport},
+    manifest, transcript,
+};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthStats {
+    /// `YYYY-MM`, by when the files finished.
+    pub month: String,
+    pub files: usize,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model: String,
+    pub files: usize,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStats {
+    pub runs: usize,
+    /// Recordings in the manifest, however many models transcribed them.
+    pub recordings: usize,
+    pub files_transcribed: usize,
+    pub files_failed: usize,
+    /// Failed share of the files that finished, 0-1.
+    pub failure_rate: f64,
+    pub total_hours: f64,
+    /// Audio length over processing time, as in benchmarks. Processing time
+    /// is the gap since the run's previous file, so it includes ffmpeg and
+    /// post-processing.
+    pub average_realtime_factor: Option<f64>,
+    /// Oldest first.
+    pub per_month: Vec<MonthStats>,
+    /// Most files first.
+    pub models: Vec<ModelUsage>,
+}
+
+fn hours(ms: u64) -> f64 {
+    ms as f64 / 3_600_000.0
+}
+
+fn month(epoch: u64) -> String {
+    DateTime::from_timestamp(epoch as i64, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m").to_string())
+        .unwrap_or_default()
+}
+
+/// The model that made `output_dir`, by file name.
+fn model_name(report: &RunReport, output_dir: &Path) -> String {
+    let model = transcript::model_file(output_dir)
+        .or_else(|| report.request.as_ref().map(|r| r.model_file.clone()))
+        .unwrap_or_default();
+    let key = manifest::model_key(&model);
+    if key.is_empty() {
+        "unknown".to_string()
+    } else {
+        key
+    }
+}
+
+fn compute(reports: &[RunReport], recordings: usize) -> ArchiveStats {
+    let mut stats = ArchiveStats {
+        runs: reports.len(),
+        recordings,
+        ..ArchiveStats::default()
+    };
+    let mut months: BTreeMap<String, MonthStats> = BTreeMap::new();
+    let mut models: HashMap<String, ModelUsage> = HashMap::new();
+    // A retried or resumed file appears in more than one report.
+    let mut counted: HashSet<(String, u64)> = HashSet::new();
+    let (mut total_ms, mut timed_audio_ms, mut timed_secs) = (0u64, 0u64, 0u64);
+
+    for report in reports {
+        let mut results: Vec<_> = report.results.iter().collect();
+        results.sort_by_key(|r| r.finished_at_epoch);
+        let mut previous = report.started_at_epoch;
+        for result in results {
+            let elapsed = result.finished_at_epoch.saturating_sub(previous);
+            previous = result.finished_at_epoch;
+            if !counted.insert((result.source.clone(), result.finished_at_epoch)) {
+                continue;
+            }
+            if result.failed() {
+                stats.files_failed += 1;
+                continue;
+            }
+            if result.status != "ok" {
+                continue;
+            }
+            stats.files_transcribed += 1;
+
+            let output_dir = result.output_dir.as_deref().map(Path::new);
+            let audio_ms = output_dir
+                .and_then(|dir| transcript::load_segments(dir).ok())
+                .and_then(|segments| segments.last().map(|s| s.end_ms))
+                .unwrap_or(0);
+            total_ms += audio_ms;
+            if audio_ms > 0 && elapsed > 0 {
+                timed_audio_ms += audio_ms;
+                timed_secs += elapsed;
+            }
+
+            let entry = months.entry(month(result.finished_at_epoch)).or_default();
+            entry.files += 1;
+            entry.hours += hours(audio_ms);
+
+            let name = output_dir.map(|dir| model_name(report, dir)).unwrap_or_else(|| "unknown".to_string());
+            let usage = models.entry(name.clone()).or_insert_with(|| ModelUsage {
+                model: name,
+                ..ModelUsage::default()
+            });
+            usage.files += 1;
+            usage.hours += hours(audio_ms);
+        }
+    }
+
+    let finished = stats.files_transcribed + stats.files_failed;
+    if finished > 0 {
+        stats.failure_rate = stats.files_failed as f64 / finished as f64;
+    }
+    stats.total_hours = hours(total_ms);
+    if timed_secs > 0 {
+        stats.average_realtime_factor = Some(timed_audio_ms as f64 / 1000.0 / timed_secs as f64);
+    }
+    stats.per_month = months
+        .into_iter()
+        .map(|(month, entry)| MonthStats { month, ..entry })
+        .collect();
+    stats.models = models.into_values().collect();
+    stats.models.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.model.cmp(&b.model)));
+    stats
+}
+
+/// Totals across every recorded run for a stats page: hours transcribed
+/// (overall, per month and per model), speed and failure rate.
+#[tauri::command]
+pub async fn get_archive_stats(app: AppHandle) -> Result<ArchiveStats, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let recordings = manifest::entries(&app)
+            .into_iter()
+            .map(|entry| entry.fingerprint)
+            .collect::<HashSet<_>>()
+            .len();
+        compute(&history::list_reports(&app), recordings)
+    })
+    .await
+    .map_err(|err| format!("Stats task failed: {err}"))
+}
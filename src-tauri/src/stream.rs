@@ -0,0 +1,191 @@
+use crate::scanner;
+use chrono::Local;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Url};
+
+pub const STREAMS_DIR: &str = ".streams";
+const CHUNK_SECS: u32 = 600;
+const YOUTUBE_HOSTS: &[&str] = &["youtube.com", "www.youtube.com", "m.youtube.com", "youtu.be"];
+
+/// Input entries that should be pulled with ffmpeg instead of scanned as folders.
+pub fn is_stream_url(input: &str) -> bool {
+    let lower = input.trim().to_ascii_lowercase();
+    ["http://", "https://", "rtmp://", "rtmps://"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+}
+
+pub fn is_youtube(input: &str) -> bool {
+    Url::parse(input.trim())
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| YOUTUBE_HOSTS.contains(&host.as_str()))
+}
+
+/// ffmpeg can't read YouTube pages, so yt-dlp supplies the title and a direct
+/// audio URL.
+fn resolve_youtube(url: &str) -> Result<(String, String), String> {
+    let output = Command::new("yt-dlp")
+        .args(["--no-playlist", "-f", "bestaudio/best", "--print", "title", "--print", "urls", url])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("Could not run yt-dlp (is it on PATH?): {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("yt-dlp could not resolve {url}: {}", stderr.trim()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines().map(str::trim).filter(|l| !l.is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(title), Some(media_url)) => Ok((title.to_string(), media_url.to_string())),
+        _ => Err(format!("yt-dlp returned no media URL for {url}")),
+    }
+}
+
+fn title_from_url(url: &str) -> String {
+    Url::parse(url.trim())
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut s| s.next_back().map(str::to_string))
+                .filter(|s| !s.is_empty())
+                .or_else(|| u.host_str().map(str::to_string))
+        })
+        .map(|name| {
+            Path::new(&name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or(name)
+        })
+        .unwrap_or_else(|| "stream".to_string())
+}
+
+fn chunk_files(dir: &Path) -> Vec<PathBuf> {
+    let mut chunks: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .map(|n| n.to_string_lossy().starts_with("chunk-"))
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    chunks.sort();
+    chunks
+}
+
+/// Pulls `url` into 10-minute WAV chunks under `<output>/.streams/<slug>/` and
+/// joins them into one recording there. Returns the staging folder, which the
+/// runner then treats like any input folder. A dropped connection keeps the
+/// chunks captured so far.
+pub fn capture(app: &AppHandle, url: &str, output_folder: &str) -> Result<PathBuf, String> {
+    let url = url.trim();
+    let (title, media_url) = if is_youtube(url) {
+        resolve_youtube(url)?
+    } else {
+        (title_from_url(url), url.to_string())
+    };
+    let slug = format!("{}-{}", Local::now().format("%Y-%m-%d"), scanner::slugify(&title));
+    let staging = PathBuf::from(output_folder.trim()).join(STREAMS_DIR).join(&slug);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|err| format!("Could not clear {}: {err}", staging.display()))?;
+    }
+    fs::create_dir_all(&staging)
+        .map_err(|err| format!("Could not create {}: {err}", staging.display()))?;
+
+    crate::emit_log(app, "system", format!("Capturing stream \"{title}\" from {url}"));
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-y", "-loglevel", "error"]);
+    if media_url.starts_with("http") {
+        cmd.args(["-reconnect", "1", "-reconnect_streamed", "1", "-reconnect_delay_max", "30"]);
+    }
+    cmd.arg("-i")
+        .arg(&media_url)
+        .args(["-vn", "-ac", "1", "-ar", "16000", "-c:a", "pcm_s16le"])
+        .args(["-f", "segment", "-segment_time", &CHUNK_SECS.to_string(), "-reset_timestamps", "1"])
+        .arg(staging.join("chunk-%04d.wav"))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("Could not start ffmpeg for stream capture: {err}"))?;
+
+    let mut announced = 0;
+    let status = loop {
+        if crate::current_stop_requested(app) {
+            let _ = child.kill();
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {}
+            Err(_) => break None,
+        }
+        // The newest chunk is still being written; count the finished ones.
+        let finished = chunk_files(&staging).len().saturating_sub(1);
+        if finished > announced {
+            announced = finished;
+            crate::emit_log(app, "system", format!("Captured {finished} chunk(s) of \"{title}\""));
+        }
+        thread::sleep(Duration::from_millis(500));
+    };
+
+    let chunks = chunk_files(&staging);
+    if status.is_none_or(|s| !s.success()) {
+        let stderr = child
+            .wait_with_output()
+            .map(|o| String::from_utf8_lossy(&o.stderr).trim().to_string())
+            .unwrap_or_default();
+        if chunks.is_empty() {
+            return Err(format!("Stream capture failed for {url}: {stderr}"));
+        }
+        crate::emit_log(
+            app,
+            "system",
+            format!("Stream capture ended early ({stderr}); transcribing the {} chunk(s) captured.", chunks.len()),
+        );
+    }
+
+    let list_path = staging.join("chunks.txt");
+    let list: String = chunks
+        .iter()
+        .filter_map(|c| c.file_name())
+        .map(|name| format!("file '{}'\n", name.to_string_lossy()))
+        .collect();
+    fs::write(&list_path, list).map_err(|err| format!("Failed to write {}: {err}", list_path.display()))?;
+
+    let recording = staging.join(format!("{slug}.wav"));
+    let joined = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "concat", "-safe", "0", "-i"])
+        .arg(&list_path)
+        .args(["-c", "copy"])
+        .arg(&recording)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("Could not run ffmpeg to join stream chunks: {err}"))?;
+    if !joined.status.success() {
+        return Err(format!(
+            "Could not join stream chunks: {}",
+            String::from_utf8_lossy(&joined.stderr).trim()
+        ));
+    }
+
+    for chunk in chunks {
+        let _ = fs::remove_file(chunk);
+    }
+    let _ = fs::remove_file(list_path);
+    Ok(staging)
+}
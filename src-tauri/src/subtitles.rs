@@ -0,0 +1,179 @@
+use crate::scanner;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BurnOptions {
+    pub font_name: String,
+    /// In points at the video's height; libass scales it with the frame.
+    pub font_size: u32,
+    /// x264 quality, 18 (near lossless) to 28 (small).
+    pub crf: u32,
+    /// Defaults to `<video>-subtitled.mp4` next to the video.
+    pub output_path: Option<String>,
+}
+
+impl Default for BurnOptions {
+    fn default() -> Self {
+        Self {
+            font_name: "Arial".to_string(),
+            font_size: 24,
+            crf: 20,
+            output_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurnProgress {
+    pub video: String,
+    pub output: String,
+    /// 0-100, or `None` when ffprobe can't tell the video's length.
+    pub percent: Option<f64>,
+    pub done: bool,
+}
+
+/// Backslash-escapes `special` characters, one level of ffmpeg's filter
+/// escaping.
+fn escape(text: &str, special: &[char]) -> String {
+    text.chars().fold(String::new(), |mut out, ch| {
+        if special.contains(&ch) {
+            out.push('\\');
+        }
+        out.push(ch);
+        out
+    })
+}
+
+/// `value` escaped for a filter's option parser and then for the
+/// filtergraph, e.g. a file name passed to `subtitles` or `arnndn`.
+pub fn filter_value(value: &str) -> String {
+    escape(&escape(value, &['\\', '\'', ':']), &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// The `subtitles` filter for `srt_name`. Run ffmpeg from the captions' folder.
+pub fn subtitles_filter(srt_name: &str, font_name: &str, font_size: u32) -> String {
+    let name = filter_value(srt_name);
+    let font: String = font_name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ')
+        .collect();
+    let font = if font.trim().is_empty() { "Arial" } else { font.trim() };
+    format!(
+        "subtitles={name}:force_style='FontName={font},FontSize={}'",
+        font_size.clamp(8, 96)
+    )
+}
+
+fn output_path(video: &Path, options: &BurnOptions) -> PathBuf {
+    match options.output_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let stem = video.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            video.with_file_name(format!("{stem}-subtitled.mp4"))
+        }
+    }
+}
+
+fn burn(app: &AppHandle, video: &Path, srt: &Path, options: &BurnOptions) -> Result<PathBuf, String> {
+    let video = std::path::absolute(video).map_err(|err| format!("Invalid video path {}: {err}", video.display()))?;
+    if !video.is_file() {
+        return Err(format!("Video not found: {}", video.display()));
+    }
+    if !srt.is_file() {
+        return Err(format!("Caption file not found: {}", srt.display()));
+    }
+    let output = std::path::absolute(output_path(&video, options))
+        .map_err(|err| format!("Invalid output path: {err}"))?;
+    if output == video {
+        return Err("The output would overwrite the video; choose another output path.".to_string());
+    }
+    // ffmpeg runs in the captions' folder so the filter only needs the file
+    // name, not a drive path full of characters to escape.
+    let srt_dir = srt.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let srt_name = srt.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    let duration_ms = scanner::media_duration_secs(&video).filter(|s| *s > 0).map(|s| s * 1000);
+    let progress = |percent: Option<f64>, done: bool| {
+        let _ = app.emit(
+            "transcribe://burn-progress",
+            BurnProgress {
+                video: video.display().to_string(),
+                output: output.display().to_string(),
+                percent,
+                done,
+            },
+        );
+    };
+
+    let mut child = Command::new("ffmpeg")
+        .current_dir(srt_dir)
+        .args(["-hide_banner", "-nostats", "-loglevel", "error", "-y", "-i"])
+        .arg(&video)
+        .arg("-vf")
+        .arg(subtitles_filter(&srt_name, &options.font_name, options.font_size))
+        .args(["-c:v", "libx264", "-preset", "medium", "-crf", &options.crf.clamp(0, 51).to_string()])
+        .args(["-c:a", "copy", "-movflags", "+faststart", "-progress", "pipe:1"])
+        .arg(&output)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Could not start ffmpeg to burn in captions: {err}"))?;
+
+    crate::emit_log(app, "system", format!("Burning captions into {}", output.display()));
+    progress(duration_ms.map(|_| 0.0), false);
+    if let Some(stdout) = child.stdout.take() {
+        let mut last = 0.0;
+        // `-progress` reports `out_time_us=` (microseconds) a couple of times a second.
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let Some(us) = line.strip_prefix("out_time_us=").and_then(|v| v.trim().parse::<u64>().ok()) else {
+                continue;
+            };
+            if let Some(total) = duration_ms {
+                let percent = (us as f64 / 1000.0 / total as f64 * 100.0).min(100.0);
+                if percent - last >= 1.0 {
+                    last = percent;
+                    progress(Some(percent), false);
+                }
+            }
+        }
+    }
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    let status = child.wait().map_err(|err| format!("ffmpeg did not finish: {err}"))?;
+    if !status.success() {
+        return Err(format!("ffmpeg could not burn in captions (code {:?}): {}", status.code(), stderr.trim()));
+    }
+    progress(Some(100.0), true);
+    Ok(output)
+}
+
+/// Hard-codes `srt_path`'s captions into an MP4 copy of `video_path` for
+/// sites that don't take caption files. Emits `transcribe://burn-progress`
+/// as it goes and returns the new video's path.
+#[tauri::command]
+pub async fn burn_subtitles(
+    app: AppHandle,
+    video_path: String,
+    srt_path: String,
+    options: Option<BurnOptions>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let options = options.unwrap_or_default();
+        let output = burn(&app, Path::new(video_path.trim()), Path::new(srt_path.trim()), &options)?;
+        crate::emit_log(&app, "system", format!("Captions burned in: {}", output.display()));
+        Ok(output.display().to_string())
+    })
+    .await
+    .map_err(|err| format!("Burn-in task failed: {err}"))?
+}
@@ -0,0 +1,114 @@
+use crate::{settings::LlmSettings, transcript::Segment};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub const SUMMARY_FILE: &str = "sermon-summary.md";
+
+const SYSTEM_PROMPT: &str = "You summarize church service and sermon transcripts. \
+Respond in Markdown with exactly two sections: '## Summary' (one or two short paragraphs) \
+and '## Key points' (5-8 bullet points). Do not invent content that is not in the transcript.";
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Accepts either a base URL (`http://host:8080/v1`) or the full completions URL.
+fn completions_url(endpoint: &str) -> String {
+    let trimmed = endpoint.trim().trim_end_matches('/');
+    if trimmed.ends_with("/chat/completions") {
+        trimmed.to_string()
+    } else {
+        format!("{trimmed}/chat/completions")
+    }
+}
+
+fn transcript_text(segments: &[Segment], max_chars: usize) -> String {
+    let flat = segments
+        .iter()
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if flat.chars().count() <= max_chars {
+        return flat;
+    }
+    let cut: String = flat.chars().take(max_chars).collect();
+    format!("{cut}\n\n[Transcript truncated]")
+}
+
+/// Sends the transcript to an OpenAI-compatible chat endpoint (OpenAI, llama.cpp
+/// server, Ollama, ...) and returns the Markdown it produced.
+pub fn request_summary(llm: &LlmSettings, title: &str, segments: &[Segment]) -> Result<String, String> {
+    let prompt = format!(
+        "Title: {title}\n\nTranscript:\n{}",
+        transcript_text(segments, llm.max_input_chars)
+    );
+    request_chat(llm, SYSTEM_PROMPT, &prompt)
+}
+
+/// One system + user exchange with the configured chat endpoint; returns
+/// the reply's text.
+pub fn request_chat(llm: &LlmSettings, system: &str, user: &str) -> Result<String, String> {
+    if llm.endpoint.trim().is_empty() {
+        return Err("LLM endpoint is not configured.".to_string());
+    }
+
+    let url = completions_url(&llm.endpoint);
+    let body = json!({
+        "model": llm.model,
+        "temperature": 0.2,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": user },
+        ],
+    });
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(llm.timeout_secs.max(10)))
+        .build();
+    let mut call = agent.post(&url);
+    if !llm.api_key.trim().is_empty() {
+        call = call.set("Authorization", &format!("Bearer {}", llm.api_key.trim()));
+    }
+
+    let response: ChatResponse = match call.send_json(body) {
+        Ok(response) => response
+            .into_json()
+            .map_err(|err| format!("Unexpected response from {url}: {err}"))?,
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            return Err(format!("LLM endpoint returned {code}: {}", detail.trim()));
+        }
+        Err(err) => return Err(format!("Could not reach LLM endpoint {url}: {err}")),
+    };
+
+    response
+        .choices
+        .into_iter()
+        .next()
+        .map(|c| c.message.content.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| "LLM endpoint returned an empty response.".to_string())
+}
+
+pub fn write_summary(output_dir: &Path, title: &str, summary: &str) -> Result<PathBuf, String> {
+    let path = output_dir.join(SUMMARY_FILE);
+    let body = format!("# {title}\r\n\r\n{}\r\n", summary.replace("\r\n", "\n").replace('\n', "\r\n"));
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
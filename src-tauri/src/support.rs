@@ -0,0 +1,222 @@
+use crate::{history, logfile, settings, PreflightRequest, RunnerState};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
+use sysinfo::System;
+use tauri::{AppHandle, State, Url};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+/// Recent on-disk log files added to the bundle besides the panel's lines.
+const LOG_FILES_INCLUDED: usize = 2;
+/// Settings keys whose values never leave the machine.
+const SECRET_KEYS: &[&str] = &["password", "secret", "token", "apikey", "accesskeyid", "secretaccesskey"];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemInfo {
+    app_version: String,
+    os: String,
+    os_version: Option<String>,
+    arch: String,
+    cpu_count: usize,
+    total_memory_bytes: u64,
+    available_memory_bytes: u64,
+    exported_at_epoch: u64,
+}
+
+fn system_info(app: &AppHandle) -> SystemInfo {
+    let mut system = System::new();
+    system.refresh_memory();
+    SystemInfo {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        os_version: System::long_os_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_count: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        total_memory_bytes: system.total_memory(),
+        available_memory_bytes: system.available_memory(),
+        exported_at_epoch: crate::now_epoch(),
+    }
+}
+
+/// Blanks passwords, tokens and keys so the bundle is safe to send.
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                let key = key.to_lowercase().replace('_', "");
+                let secret = SECRET_KEYS.iter().any(|s| key.ends_with(s));
+                match item {
+                    Value::String(text) if secret && !text.is_empty() => *text = "[redacted]".to_string(),
+                    _ => redact(item),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Keeps a webhook URL's host: Slack and Discord webhook paths are credentials
+/// themselves.
+fn redact_url(url: &str) -> String {
+    match Url::parse(url.trim()) {
+        Ok(parsed) if parsed.host_str().is_some() => {
+            format!("{}://{}/[redacted]", parsed.scheme(), parsed.host_str().unwrap_or_default())
+        }
+        _ => "[redacted]".to_string(),
+    }
+}
+
+/// `redact`, plus the settings that are sensitive without a telling key name:
+/// webhook URLs and the redaction list's names, of which only the count stays.
+fn redact_settings(settings: &mut Value) {
+    redact(settings);
+    if let Some(hooks) = settings.get_mut("webhooks").and_then(Value::as_array_mut) {
+        for hook in hooks {
+            if let Some(Value::String(url)) = hook.get_mut("url") {
+                *url = redact_url(url);
+            }
+        }
+    }
+    if let Some(Value::Array(terms)) = settings.pointer_mut("/redaction/terms") {
+        terms.iter_mut().for_each(|term| *term = Value::from("[redacted]"));
+    }
+}
+
+fn to_pretty<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|err| format!("Failed to serialize support bundle: {err}"))
+}
+
+/// Writes a zip with the log panel's lines, recent log files, redacted
+/// settings, the last run's request, report and a fresh preflight, plus
+/// system details, ready to attach to a support request.
+#[tauri::command]
+pub fn export_run_logs(
+    app: AppHandle,
+    state: State<RunnerState>,
+    output_folder: String,
+    lines: Vec<String>,
+) -> Result<String, String> {
+    let folder = output_folder.trim();
+    if folder.is_empty() {
+        return Err("Output folder is required for log export.".to_string());
+    }
+
+    let output_path = PathBuf::from(folder);
+    fs::create_dir_all(&output_path)
+        .map_err(|err| format!("Could not create output folder {}: {err}", output_path.display()))?;
+
+    let mut parts: Vec<(String, String)> = vec![("log.txt".to_string(), lines.join("\n"))];
+
+    let mut settings = serde_json::to_value(settings::load(&app))
+        .map_err(|err| format!("Failed to serialize settings: {err}"))?;
+    redact_settings(&mut settings);
+    parts.push(("settings.json".to_string(), to_pretty(&settings)?));
+
+    let report = state
+        .report
+        .lock()
+        .ok()
+        .and_then(|slot| slot.clone())
+        .or_else(|| history::list_reports(&app).into_iter().next());
+    let request = state
+        .last_request
+        .lock()
+        .ok()
+        .and_then(|slot| slot.clone())
+        .or_else(|| report.as_ref().and_then(|r| r.request.clone()));
+
+    if let Some(request) = &request {
+        parts.push(("request.json".to_string(), to_pretty(request)?));
+        let preflight = crate::build_preflight_report(
+            &app,
+            &PreflightRequest {
+                input_folders: request.input_folders.clone(),
+                output_folder: request.output_folder.clone(),
+                whisper_exe: request.whisper_exe.clone(),
+                whisper_exe_candidates: request.whisper_exe_candidates.clone(),
+                model_file: request.model_file.clone(),
+                script_path: request.script_path.clone(),
+                extensions: request.extensions.clone(),
+                no_recursive: request.no_recursive,
+                engine: request.engine,
+                fallback_model: request.fallback_model.clone(),
+                override_output_folders: request.override_output_folders(),
+            },
+        );
+        parts.push(("preflight.json".to_string(), to_pretty(&preflight)?));
+    }
+    if let Some(report) = &report {
+        parts.push(("run-report.json".to_string(), to_pretty(report)?));
+    }
+    parts.push(("system.json".to_string(), to_pretty(&system_info(&app))?));
+
+    for log in logfile::get_log_files(app.clone()).unwrap_or_default().into_iter().take(LOG_FILES_INCLUDED) {
+        if let Ok(body) = fs::read_to_string(&log.path) {
+            parts.push((format!("logs/{}", log.name), body));
+        }
+    }
+
+    let file_path = output_path.join(format!("church-transcriber-log-{}.zip", crate::now_epoch()));
+    let file = File::create(&file_path)
+        .map_err(|err| format!("Failed to create log export {}: {err}", file_path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    for (name, body) in parts {
+        zip.start_file(name, SimpleFileOptions::default())
+            .and_then(|_| zip.write_all(body.as_bytes()).map_err(Into::into))
+            .map_err(|err| format!("Failed to write log export {}: {err}", file_path.display()))?;
+    }
+    zip.finish()
+        .map_err(|err| format!("Failed to write log export {}: {err}", file_path.display()))?;
+
+    Ok(file_path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use settings::{AppSettings, WebhookSettings};
+
+    #[test]
+    fn bundle_settings_keep_no_credentials_or_names() {
+        let mut all = AppSettings::default();
+        all.webhooks.push(WebhookSettings {
+            url: "https://hooks.slack.com/services/T000/B000/XXXXSECRET".to_string(),
+            secret: "signing-secret".to_string(),
+            ..WebhookSettings::default()
+        });
+        all.webhooks.push(WebhookSettings {
+            url: "not a url".to_string(),
+            ..WebhookSettings::default()
+        });
+        all.redaction.terms = vec!["Jane Doe".to_string(), "Smith*".to_string()];
+        all.s3.secret_access_key = "s3-secret".to_string();
+        all.s3.access_key_id = "AKIAEXAMPLE".to_string();
+        all.remote_api.viewer_token = "viewer-token".to_string();
+
+        let mut value = serde_json::to_value(&all).unwrap();
+        redact_settings(&mut value);
+        let text = value.to_string();
+        for secret in [
+            "XXXXSECRET",
+            "T000",
+            "signing-secret",
+            "Jane Doe",
+            "Smith",
+            "s3-secret",
+            "AKIAEXAMPLE",
+            "viewer-token",
+            "not a url",
+        ] {
+            assert!(!text.contains(secret), "{secret} left in the bundle");
+        }
+        assert_eq!(value["webhooks"][0]["url"], "https://hooks.slack.com/[redacted]");
+        assert_eq!(value["redaction"]["terms"].as_array().map(Vec::len), Some(2));
+        assert_eq!(value["redaction"]["replacement"], "[redacted]");
+    }
+}
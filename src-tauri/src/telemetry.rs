@@ -0,0 +1,149 @@
+use crate::RunnerState;
+use serde::Serialize;
+use std::{process::Command, thread, time::Duration};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+const INTERVAL: Duration = Duration::from_secs(5);
+/// Below this, whisper is leaving cores idle and more threads would help.
+const UNDERUSED_CPU_PERCENT: f32 = 60.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TelemetryEvent {
+    run_id: String,
+    cpu_percent: f32,
+    cpu_count: usize,
+    memory_used_bytes: u64,
+    memory_total_bytes: u64,
+    /// whisper-cli and ffmpeg combined, as a share of the whole machine.
+    transcriber_cpu_percent: f32,
+    transcriber_memory_bytes: u64,
+    disk_read_bytes_per_sec: u64,
+    disk_write_bytes_per_sec: u64,
+    /// The first GPU's figures; `gpus` has every device.
+    gpu_percent: Option<f32>,
+    gpu_memory_used_bytes: Option<u64>,
+    gpus: Vec<GpuUsage>,
+    threads: u32,
+    hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GpuUsage {
+    /// The device number GPU workers are pinned by.
+    index: u32,
+    percent: f32,
+    memory_used_bytes: u64,
+}
+
+/// Utilization and memory of each NVIDIA GPU, when `nvidia-smi` exists.
+fn gpu_usage() -> Option<Vec<GpuUsage>> {
+    let output = Command::new("nvidia-smi")
+        .args(["--query-gpu=index,utilization.gpu,memory.used", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let gpus: Vec<GpuUsage> = text
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',').map(str::trim);
+            let index = fields.next()?.parse().ok()?;
+            let percent = fields.next()?.parse().ok()?;
+            let memory_mib: u64 = fields.next()?.parse().ok()?;
+            Some(GpuUsage {
+                index,
+                percent,
+                memory_used_bytes: memory_mib << 20,
+            })
+        })
+        .collect();
+    (!gpus.is_empty()).then_some(gpus)
+}
+
+fn is_transcriber(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("whisper") || name.contains("ffmpeg")
+}
+
+fn hint(cpu_percent: f32, transcriber_cpu_percent: f32) -> Option<String> {
+    if transcriber_cpu_percent <= 0.0 {
+        None
+    } else if cpu_percent < UNDERUSED_CPU_PERCENT {
+        Some("CPU is not saturated; raising whisper threads may speed up the run.".to_string())
+    } else if cpu_percent > 95.0 && transcriber_cpu_percent < cpu_percent / 2.0 {
+        Some("Other programs are using most of the CPU.".to_string())
+    } else {
+        None
+    }
+}
+
+/// Emits `transcribe://telemetry` every few seconds while run `run_id` is
+/// active, so the UI can show whether the machine is saturated.
+pub fn watch(app: &AppHandle, run_id: String, threads: u32) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut system = System::new();
+        let processes = ProcessRefreshKind::nothing().with_cpu().with_memory().with_disk_usage();
+        let mut gpu_available = true;
+        // Usage figures are deltas between refreshes, so take a baseline first.
+        system.refresh_cpu_usage();
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, processes);
+        loop {
+            thread::sleep(INTERVAL);
+
+            let status = crate::get_status(&app.state::<RunnerState>());
+            if !status.running || status.run_id.as_deref() != Some(run_id.as_str()) {
+                return;
+            }
+
+            system.refresh_cpu_usage();
+            system.refresh_memory();
+            system.refresh_processes_specifics(ProcessesToUpdate::All, true, processes);
+
+            let cpu_count = system.cpus().len().max(1);
+            let (mut read, mut written) = (0, 0);
+            let (mut transcriber_cpu, mut transcriber_memory) = (0.0, 0);
+            for process in system.processes().values() {
+                let disk = process.disk_usage();
+                read += disk.read_bytes;
+                written += disk.written_bytes;
+                if is_transcriber(&process.name().to_string_lossy()) {
+                    transcriber_cpu += process.cpu_usage();
+                    transcriber_memory += process.memory();
+                }
+            }
+
+            let gpus = if gpu_available { gpu_usage() } else { None };
+            gpu_available = gpus.is_some();
+            let gpus = gpus.unwrap_or_default();
+            let gpu = gpus.first();
+            let cpu_percent = system.global_cpu_usage();
+            // Process CPU is per core (100% = one core); scale to the machine.
+            let transcriber_cpu_percent = transcriber_cpu / cpu_count as f32;
+            let seconds = INTERVAL.as_secs().max(1);
+
+            let _ = app.emit(
+                "transcribe://telemetry",
+                TelemetryEvent {
+                    run_id: run_id.clone(),
+                    cpu_percent,
+                    cpu_count,
+                    memory_used_bytes: system.used_memory(),
+                    memory_total_bytes: system.total_memory(),
+                    transcriber_cpu_percent,
+                    transcriber_memory_bytes: transcriber_memory,
+                    disk_read_bytes_per_sec: read / seconds,
+                    disk_write_bytes_per_sec: written / seconds,
+                    gpu_percent: gpu.map(|g| g.percent),
+                    gpu_memory_used_bytes: gpu.map(|g| g.memory_used_bytes),
+                    gpus,
+                    threads,
+                    hint: hint(cpu_percent, transcriber_cpu_percent),
+                },
+            );
+        }
+    });
+}
@@ -0,0 +1,219 @@
+use crate::{
+    chapters::Chapter,
+    engine::{Engine, EngineSegment},
+    music::MusicRange,
+    preprocess::DenoiseStats,
+    scripture::ScriptureReference,
+    sections::Section,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const TIMESTAMPS_FILE: &str = "timestamps.json";
+pub const ENRICHED_FILE: &str = "transcript.json";
+const METADATA_FILE: &str = "metadata.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// The structured transcript the app writes next to whisper's own JSON, carrying
+/// everything the Rust post-processing stages derive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichedTranscript {
+    pub title: String,
+    pub source_file: String,
+    pub generated_at_epoch: u64,
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub scripture_references: Vec<ScriptureReference>,
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    #[serde(default)]
+    pub sections: Vec<Section>,
+}
+
+/// The batch script's retry after garbage engine output.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GarbageRetry {
+    /// What the first attempt's output looked like.
+    pub reason: String,
+    /// What was still wrong after the retry; empty when the retry fixed it.
+    pub remaining: String,
+}
+
+/// What the batch script records about how it transcribed a file.
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct RunMetadata {
+    /// Lead-in the batch script cut before whisper ran; whisper's offsets start there.
+    trim_start_ms: u64,
+    engine: String,
+    model_file: String,
+    source_file: String,
+    /// Engine of the English pass, which may differ after a cloud fallback.
+    translation_engine: String,
+    denoise_stats: Option<DenoiseStats>,
+    /// On the recording's timeline.
+    music_ranges: Vec<MusicRange>,
+    garbage_retry: Option<GarbageRetry>,
+    /// The primary model, when the fallback model produced the output.
+    fallback_from: String,
+}
+
+fn run_metadata(output_dir: &Path) -> RunMetadata {
+    fs::read_to_string(output_dir.join(METADATA_FILE))
+        .ok()
+        .and_then(|body| serde_json::from_str::<RunMetadata>(body.trim_start_matches('\u{feff}')).ok())
+        .unwrap_or_default()
+}
+
+/// Lead-in trimmed by the batch script, read from `metadata.json`.
+pub fn trim_offset_ms(output_dir: &Path) -> u64 {
+    run_metadata(output_dir).trim_start_ms
+}
+
+/// The engine that produced the output in `output_dir`; older outputs
+/// predate the field and are whisper.cpp.
+pub fn engine(output_dir: &Path) -> Engine {
+    Engine::from_cli_name(&run_metadata(output_dir).engine).unwrap_or_default()
+}
+
+/// The engine of the batch script's English translation pass, if it ran.
+pub fn translation_engine(output_dir: &Path) -> Option<Engine> {
+    Engine::from_cli_name(&run_metadata(output_dir).translation_engine)
+}
+
+/// Loudness before and after denoising, when the file was denoised.
+pub fn denoise_stats(output_dir: &Path) -> Option<DenoiseStats> {
+    run_metadata(output_dir).denoise_stats
+}
+
+/// Music-dominant stretches the batch script found, if it looked.
+pub fn music_ranges(output_dir: &Path) -> Vec<MusicRange> {
+    run_metadata(output_dir).music_ranges
+}
+
+/// Set when the first transcription attempt produced garbage.
+pub fn garbage_retry(output_dir: &Path) -> Option<GarbageRetry> {
+    run_metadata(output_dir).garbage_retry
+}
+
+/// The primary model that failed, when `model_file` is the fallback.
+pub fn fallback_from(output_dir: &Path) -> Option<String> {
+    Some(run_metadata(output_dir).fallback_from).filter(|m| !m.is_empty())
+}
+
+/// The model the output in `output_dir` was transcribed with, as recorded.
+pub fn model_file(output_dir: &Path) -> Option<String> {
+    Some(run_metadata(output_dir).model_file).filter(|m| !m.is_empty())
+}
+
+/// The recording the output in `output_dir` was transcribed from, as
+/// recorded at the time.
+pub fn source_file(output_dir: &Path) -> Option<String> {
+    Some(run_metadata(output_dir).source_file).filter(|s| !s.is_empty())
+}
+
+/// Parses an engine's JSON output, adding `offset_ms` to every segment and
+/// dropping empty ones.
+fn load_engine_output(path: &Path, offset_ms: u64, engine: Engine) -> Result<Vec<EngineSegment>, String> {
+    let body = fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    let parsed = engine
+        .parse_output(body.trim_start_matches('\u{feff}'))
+        .map_err(|err| format!("Could not parse {}: {err}", path.display()))?;
+
+    Ok(parsed
+        .into_iter()
+        .map(|s| EngineSegment {
+            start_ms: s.start_ms + offset_ms,
+            end_ms: s.end_ms + offset_ms,
+            text: s.text.trim().to_string(),
+            confidence: s.confidence,
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect())
+}
+
+/// Parses an engine's JSON output, adding `offset_ms` to every segment.
+pub fn load_whisper_json(path: &Path, offset_ms: u64, engine: Engine) -> Result<Vec<Segment>, String> {
+    Ok(load_engine_output(path, offset_ms, engine)?
+        .into_iter()
+        .map(|s| Segment {
+            start_ms: s.start_ms,
+            end_ms: s.end_ms,
+            text: s.text,
+            speaker: None,
+        })
+        .collect())
+}
+
+/// The main transcript with the engine's confidence for each segment.
+pub fn load_scored_segments(output_dir: &Path) -> Result<Vec<EngineSegment>, String> {
+    let metadata = run_metadata(output_dir);
+    load_engine_output(
+        &output_dir.join(TIMESTAMPS_FILE),
+        metadata.trim_start_ms,
+        Engine::from_cli_name(&metadata.engine).unwrap_or_default(),
+    )
+}
+
+/// Reads the engine's JSON output that the batch script stores as `timestamps.json`,
+/// shifted onto the original recording's timeline when silence was trimmed.
+pub fn load_segments(output_dir: &Path) -> Result<Vec<Segment>, String> {
+    let metadata = run_metadata(output_dir);
+    load_whisper_json(
+        &output_dir.join(TIMESTAMPS_FILE),
+        metadata.trim_start_ms,
+        Engine::from_cli_name(&metadata.engine).unwrap_or_default(),
+    )
+}
+
+/// The segments as published: `transcript.json` when the app wrote one, with
+/// redaction, masking and proofreading edits applied; otherwise whisper's own.
+pub fn load_published_segments(output_dir: &Path) -> Result<Vec<Segment>, String> {
+    let enriched = fs::read_to_string(output_dir.join(ENRICHED_FILE))
+        .ok()
+        .and_then(|body| serde_json::from_str::<EnrichedTranscript>(&body).ok());
+    match enriched {
+        Some(enriched) => Ok(enriched.segments),
+        None => load_segments(output_dir),
+    }
+}
+
+/// Formats milliseconds as `HH:MM:SS`.
+pub fn format_timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+/// Parses `HH:MM:SS`, `MM:SS` or plain seconds, each with optional
+/// fractional seconds, into milliseconds.
+pub fn parse_timestamp(text: &str) -> Option<u64> {
+    let mut secs = 0.0;
+    for part in text.trim().split(':') {
+        let value: f64 = part.trim().parse().ok().filter(|v: &f64| v.is_finite() && *v >= 0.0)?;
+        secs = secs * 60.0 + value;
+    }
+    Some((secs * 1000.0).round() as u64)
+}
+
+pub fn write_enriched(output_dir: &Path, transcript: &EnrichedTranscript) -> Result<PathBuf, String> {
+    let path = output_dir.join(ENRICHED_FILE);
+    let body = serde_json::to_string_pretty(transcript)
+        .map_err(|err| format!("Failed to serialize transcript: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
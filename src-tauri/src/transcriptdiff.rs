@@ -0,0 +1,192 @@
+use crate::{
+    benchmark::{word_diff, DiffChunk, DiffKind},
+    transcript::{self, format_timestamp, Segment},
+    versions,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Word diffs above this many LCS cells (previous x current words) are skipped;
+/// the segment texts are still returned.
+const MAX_WORD_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Changed,
+    /// Only in the new transcript.
+    Added,
+    /// Only in the previous transcript.
+    Removed,
+}
+
+/// A run of segments that differ between the two transcripts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentChange {
+    pub kind: ChangeKind,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub timestamp: String,
+    pub previous_text: String,
+    pub current_text: String,
+    /// Empty when the changed run is too long to diff word by word.
+    pub diff: Vec<DiffChunk>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptDiff {
+    pub output_dir: String,
+    pub previous_dir: String,
+    pub previous_model: Option<String>,
+    pub current_model: Option<String>,
+    pub previous_segments: usize,
+    pub current_segments: usize,
+    pub unchanged_segments: usize,
+    /// Share of words that differ across the whole transcript, 0-100.
+    pub difference_percent: f64,
+    pub changes: Vec<SegmentChange>,
+}
+
+fn key(text: &str) -> String {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn joined(segments: &[Segment]) -> String {
+    segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Pairs segments whose text matches (timing is ignored, since models split
+/// speech differently), returning `(previous, current)` index pairs in order.
+fn matching_segments(previous: &[Segment], current: &[Segment]) -> Vec<(usize, usize)> {
+    let (ka, kb): (Vec<String>, Vec<String>) = (
+        previous.iter().map(|s| key(&s.text)).collect(),
+        current.iter().map(|s| key(&s.text)).collect(),
+    );
+    let mut lcs = vec![vec![0usize; kb.len() + 1]; ka.len() + 1];
+    for i in (0..ka.len()).rev() {
+        for j in (0..kb.len()).rev() {
+            lcs[i][j] = if ka[i] == kb[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < ka.len() && j < kb.len() {
+        if ka[i] == kb[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i][j + 1] >= lcs[i + 1][j] {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    pairs
+}
+
+fn change(previous: &[Segment], current: &[Segment]) -> SegmentChange {
+    let spans = previous.iter().chain(current);
+    let start_ms = spans.clone().map(|s| s.start_ms).min().unwrap_or(0);
+    let end_ms = spans.map(|s| s.end_ms).max().unwrap_or(0);
+    let (previous_text, current_text) = (joined(previous), joined(current));
+    let diff = if word_count(&previous_text) * word_count(&current_text) <= MAX_WORD_DIFF_CELLS {
+        word_diff(&previous_text, &current_text).0
+    } else {
+        Vec::new()
+    };
+    SegmentChange {
+        kind: match (previous.is_empty(), current.is_empty()) {
+            (true, _) => ChangeKind::Added,
+            (_, true) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        },
+        start_ms,
+        end_ms,
+        timestamp: format_timestamp(start_ms),
+        diff,
+        previous_text,
+        current_text,
+    }
+}
+
+fn diff(output_dir: &Path, previous_dir: &Path) -> Result<TranscriptDiff, String> {
+    let previous = transcript::load_segments(previous_dir)?;
+    let current = transcript::load_segments(output_dir)?;
+    let pairs = matching_segments(&previous, &current);
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for (pi, cj) in pairs.iter().copied().chain([(previous.len(), current.len())]) {
+        if pi > i || cj > j {
+            changes.push(change(&previous[i..pi], &current[j..cj]));
+        }
+        (i, j) = (pi + 1, cj + 1);
+    }
+
+    // Matched segments plus the words the changed runs still share.
+    let common_words: usize = pairs.iter().map(|&(_, cj)| word_count(&current[cj].text)).sum::<usize>()
+        + changes
+            .iter()
+            .flat_map(|c| &c.diff)
+            .filter(|chunk| chunk.kind == DiffKind::Same)
+            .map(|chunk| word_count(&chunk.text))
+            .sum::<usize>();
+    let longest = word_count(&joined(&previous)).max(word_count(&joined(&current)));
+
+    Ok(TranscriptDiff {
+        output_dir: output_dir.display().to_string(),
+        previous_dir: previous_dir.display().to_string(),
+        previous_model: transcript::model_file(previous_dir),
+        current_model: transcript::model_file(output_dir),
+        previous_segments: previous.len(),
+        current_segments: current.len(),
+        unchanged_segments: pairs.len(),
+        difference_percent: if longest == 0 {
+            0.0
+        } else {
+            longest.saturating_sub(common_words) as f64 * 100.0 / longest as f64
+        },
+        changes,
+    })
+}
+
+/// Compares a re-transcribed folder with an earlier version of it
+/// (`previous_dir`, or the newest one in `.versions/`) and returns the
+/// segments that changed.
+#[tauri::command]
+pub async fn diff_transcripts(output_dir: String, previous_dir: Option<String>) -> Result<TranscriptDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let output_dir = PathBuf::from(output_dir.trim());
+        let previous_dir = match previous_dir.filter(|d| !d.trim().is_empty()) {
+            Some(dir) => PathBuf::from(dir.trim()),
+            None => versions::list(&output_dir)
+                .into_iter()
+                .find(|v| v.has_timestamps)
+                .map(|v| PathBuf::from(v.path))
+                .ok_or_else(|| {
+                    format!(
+                        "No earlier version of {}; one is kept when a file is transcribed again.",
+                        output_dir.display()
+                    )
+                })?,
+        };
+        diff(&output_dir, &previous_dir)
+    })
+    .await
+    .map_err(|err| format!("Diff task failed: {err}"))?
+}
@@ -0,0 +1,161 @@
+use crate::{
+    export::{self, CaptionLimits, ExportFormat},
+    settings::LlmSettings,
+    summarize,
+    transcript::{self, Segment},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where the batch script leaves whisper's English pass.
+pub const WHISPER_TIMESTAMPS_FILE: &str = "timestamps.en.json";
+/// Segments sent to the translation API per request.
+const API_BATCH: usize = 40;
+
+const SYSTEM_PROMPT: &str = "You translate church service transcripts. Each input line is \
+'<number>|<text>'. Reply with exactly one line per input line, in the same order, as \
+'<number>|<translation>', and nothing else. Keep names and scripture references as spoken.";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TranslationMethod {
+    /// A second whisper pass with `--translate`; English only.
+    #[default]
+    Whisper,
+    /// The OpenAI-compatible chat endpoint configured for summaries.
+    Api,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TranslationOptions {
+    pub enabled: bool,
+    pub method: TranslationMethod,
+    /// Language code of the translation and the suffix on its files
+    /// (`raw.en.txt`); the whisper method always produces `en`.
+    pub target_language: String,
+}
+
+impl Default for TranslationOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: TranslationMethod::default(),
+            target_language: "en".to_string(),
+        }
+    }
+}
+
+impl TranslationOptions {
+    pub fn suffix(&self) -> String {
+        match self.method {
+            TranslationMethod::Whisper => "en".to_string(),
+            TranslationMethod::Api => {
+                let code: String = self
+                    .target_language
+                    .trim()
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+                    .collect();
+                if code.is_empty() {
+                    "en".to_string()
+                } else {
+                    code.to_ascii_lowercase()
+                }
+            }
+        }
+    }
+}
+
+/// Whisper's English pass, on the recording's timeline like the main transcript.
+fn whisper_segments(output_dir: &Path) -> Result<Vec<Segment>, String> {
+    let path = output_dir.join(WHISPER_TIMESTAMPS_FILE);
+    if !path.is_file() {
+        return Err(format!(
+            "No translation pass in {}; the whisper model may be English-only.",
+            output_dir.display()
+        ));
+    }
+    let engine = transcript::translation_engine(output_dir).unwrap_or_else(|| transcript::engine(output_dir));
+    transcript::load_whisper_json(&path, transcript::trim_offset_ms(output_dir), engine)
+}
+
+fn translate_batch(llm: &LlmSettings, language: &str, batch: &[Segment]) -> Result<Vec<String>, String> {
+    let lines: String = batch
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("{}|{}\n", i + 1, s.text.replace('\n', " ")))
+        .collect();
+    let prompt = format!("Translate into the language with code '{language}':\n\n{lines}");
+    let reply = summarize::request_chat(llm, SYSTEM_PROMPT, &prompt)?;
+
+    let mut translated = vec![None; batch.len()];
+    for line in reply.lines() {
+        let Some((number, text)) = line.split_once('|') else {
+            continue;
+        };
+        if let Some(slot) = number.trim().parse::<usize>().ok().and_then(|n| translated.get_mut(n.wrapping_sub(1))) {
+            *slot = Some(text.trim().to_string());
+        }
+    }
+    translated
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| "The translation reply skipped some lines.".to_string())
+}
+
+fn api_segments(llm: &LlmSettings, language: &str, segments: &[Segment]) -> Result<Vec<Segment>, String> {
+    let mut translated = Vec::with_capacity(segments.len());
+    for batch in segments.chunks(API_BATCH) {
+        let texts = translate_batch(llm, language, batch)?;
+        translated.extend(batch.iter().zip(texts).map(|(segment, text)| Segment {
+            text,
+            ..segment.clone()
+        }));
+    }
+    Ok(translated)
+}
+
+/// Writes the translation next to the original-language outputs with the
+/// target language as a suffix: `raw.<lang>.txt`, plus `captions.<lang>.srt`
+/// and `.vtt` when those formats are exported.
+pub fn write_translation(
+    output_dir: &Path,
+    options: &TranslationOptions,
+    llm: &LlmSettings,
+    segments: &[Segment],
+    formats: &[ExportFormat],
+    limits: CaptionLimits,
+) -> Result<Vec<PathBuf>, String> {
+    let suffix = options.suffix();
+    let translated = match options.method {
+        TranslationMethod::Whisper => whisper_segments(output_dir)?,
+        TranslationMethod::Api => api_segments(llm, &suffix, segments)?,
+    };
+    if translated.is_empty() {
+        return Err(format!("The translation of {} is empty.", output_dir.display()));
+    }
+
+    let mut files = vec![(
+        format!("raw.{suffix}.txt"),
+        translated.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join("\r\n"),
+    )];
+    let captions = export::fit_captions(&translated, limits);
+    if formats.contains(&ExportFormat::Srt) {
+        files.push((format!("captions.{suffix}.srt"), export::render_srt(&captions)));
+    }
+    if formats.contains(&ExportFormat::Vtt) {
+        files.push((format!("captions.{suffix}.vtt"), export::render_vtt(&captions)));
+    }
+
+    let mut written = Vec::new();
+    for (name, body) in files {
+        let path = output_dir.join(name);
+        fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
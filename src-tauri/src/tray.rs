@@ -0,0 +1,128 @@
+use crate::{history, RunnerState, RunnerStatus};
+use std::path::PathBuf;
+use tauri::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    tray::TrayIconBuilder,
+    AppHandle, Manager, Wry,
+};
+use tauri_plugin_opener::OpenerExt;
+
+const TRAY_ID: &str = "main";
+
+/// Menu items whose text follows the runner state.
+pub struct TrayState {
+    status: MenuItem<Wry>,
+    pause: MenuItem<Wry>,
+    stop: MenuItem<Wry>,
+}
+
+fn status_label(status: &RunnerStatus) -> &'static str {
+    match (status.running, status.paused, status.stop_requested) {
+        (false, _, _) => "Idle",
+        (true, _, true) => "Stopping...",
+        (true, true, _) => "Paused",
+        (true, false, _) => "Running",
+    }
+}
+
+pub fn install(app: &AppHandle) -> tauri::Result<()> {
+    let status = MenuItem::with_id(app, "status", "Idle", false, None::<&str>)?;
+    let pause = MenuItem::with_id(app, "pause", "Pause", false, None::<&str>)?;
+    let stop = MenuItem::with_id(app, "stop", "Stop", false, None::<&str>)?;
+    let open_output = MenuItem::with_id(app, "open-output", "Open output folder", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "Show window", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let menu = Menu::with_items(
+        app,
+        &[&status, &separator, &pause, &stop, &open_output, &show, &quit],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Church Transcriber — Idle")
+        .menu(&menu)
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayState {
+        status,
+        pause,
+        stop,
+    });
+    Ok(())
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// The current run's output folder, else the last run's.
+pub fn output_folder(app: &AppHandle) -> Option<PathBuf> {
+    let state = app.state::<RunnerState>();
+    let current = state
+        .report
+        .lock()
+        .ok()
+        .and_then(|r| r.as_ref().map(|r| r.output_folder.clone()));
+    current
+        .or_else(|| {
+            history::list_reports(app)
+                .into_iter()
+                .next()
+                .map(|r| r.output_folder)
+        })
+        .filter(|f| !f.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        "pause" => {
+            let state = app.state::<RunnerState>();
+            let paused = crate::get_status(&state).paused;
+            if let Err(err) = crate::toggle_pause(app.clone(), state, !paused) {
+                crate::emit_log(app, "system", err);
+            }
+        }
+        "stop" => {
+            if let Err(err) = crate::stop_transcription(app.clone(), app.state::<RunnerState>(), None) {
+                crate::emit_log(app, "system", err);
+            }
+        }
+        "open-output" => match output_folder(app) {
+            Some(folder) => {
+                if let Err(err) = app.opener().open_path(folder.to_string_lossy(), None::<&str>) {
+                    crate::emit_log(app, "system", format!("Could not open output folder: {err}"));
+                }
+            }
+            None => crate::emit_log(app, "system", "No output folder to open yet."),
+        },
+        "show" => show_main_window(app),
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+/// Mirrors runner status into the tray tooltip and menu.
+pub fn refresh(app: &AppHandle, status: &RunnerStatus) {
+    let label = status_label(status);
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("Church Transcriber — {label}")));
+    }
+    let Some(items) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let _ = items.status.set_text(label);
+    let _ = items
+        .pause
+        .set_text(if status.paused { "Resume" } else { "Pause" });
+    let _ = items.pause.set_enabled(status.running && !status.stop_requested);
+    let _ = items.stop.set_enabled(status.running && !status.stop_requested);
+}
@@ -0,0 +1,311 @@
+use crate::{profiles, publish::describe_error, schedule, RunnerState};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, Manager};
+use zip::ZipArchive;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/ggml-org/whisper.cpp/releases/latest";
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(1800);
+const INSTALL_DIR: &str = "whisper";
+const INSTALL_RECORD: &str = "installed.json";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+    /// `sha256:<hex>`, on releases published since GitHub started recording it.
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+/// The binary this app last installed, so its version is known without
+/// asking whisper-cli (which has no version flag in most builds).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallRecord {
+    version: String,
+    whisper_exe: String,
+    installed_at_epoch: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperUpdate {
+    pub current_version: Option<String>,
+    pub latest_version: String,
+    /// Also true when the current version can't be determined.
+    pub update_available: bool,
+    pub release_url: String,
+    pub published_at: Option<String>,
+    /// The prebuilt binary for this platform, if the release has one.
+    pub asset_name: Option<String>,
+    pub asset_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperInstall {
+    pub version: String,
+    pub whisper_exe: String,
+    pub profiles_updated: usize,
+    pub schedules_updated: usize,
+}
+
+fn install_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Could not resolve app data folder: {err}"))?
+        .join(INSTALL_DIR);
+    fs::create_dir_all(&dir).map_err(|err| format!("Could not create {}: {err}", dir.display()))?;
+    Ok(dir)
+}
+
+fn load_record(app: &AppHandle) -> Option<InstallRecord> {
+    install_root(app)
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(INSTALL_RECORD)).ok())
+        .and_then(|body| serde_json::from_str(&body).ok())
+}
+
+fn save_record(app: &AppHandle, record: &InstallRecord) -> Result<(), String> {
+    let path = install_root(app)?.join(INSTALL_RECORD);
+    let body = serde_json::to_string_pretty(record)
+        .map_err(|err| format!("Failed to serialize whisper install record: {err}"))?;
+    fs::write(&path, body).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// `v1.7.6` -> `[1, 7, 6]`, for ordering release tags.
+pub fn version_parts(version: &str) -> Vec<u32> {
+    version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split('.')
+        .map(|part| part.chars().take_while(char::is_ascii_digit).collect::<String>())
+        .map(|digits| digits.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Version of the configured binary: the install record when this app put it
+/// there, otherwise whatever version string it prints.
+pub fn current_version(app: &AppHandle, whisper_exe: &str) -> Option<String> {
+    if let Some(record) = load_record(app).filter(|r| r.whisper_exe == whisper_exe) {
+        return Some(record.version);
+    }
+    let output = Command::new(whisper_exe)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    let pattern = Regex::new(r"\bv?(\d+\.\d+\.\d+)\b").ok()?;
+    pattern.captures(&text).map(|caps| format!("v{}", &caps[1]))
+}
+
+/// Name of the release's prebuilt CPU binary for this platform. whisper.cpp
+/// only publishes Windows builds.
+fn asset_for_platform() -> Option<&'static str> {
+    if !cfg!(windows) {
+        None
+    } else if cfg!(target_arch = "x86") {
+        Some("whisper-bin-Win32.zip")
+    } else {
+        Some("whisper-bin-x64.zip")
+    }
+}
+
+fn latest_release() -> Result<Release, String> {
+    ureq::AgentBuilder::new()
+        .timeout(API_TIMEOUT)
+        .build()
+        .get(RELEASES_URL)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", "church-transcriber")
+        .call()
+        .map_err(|err| describe_error("whisper.cpp release check", err))?
+        .into_json()
+        .map_err(|err| format!("Unexpected whisper.cpp release response: {err}"))
+}
+
+fn check(app: &AppHandle, whisper_exe: &str, release: &Release) -> WhisperUpdate {
+    let current = current_version(app, whisper_exe);
+    let asset = asset_for_platform().and_then(|name| release.assets.iter().find(|a| a.name == name));
+    WhisperUpdate {
+        update_available: current
+            .as_deref()
+            .is_none_or(|current| version_parts(&release.tag_name) > version_parts(current)),
+        current_version: current,
+        latest_version: release.tag_name.clone(),
+        release_url: release.html_url.clone(),
+        published_at: release.published_at.clone(),
+        asset_name: asset.map(|a| a.name.clone()),
+        asset_size: asset.map(|a| a.size),
+    }
+}
+
+fn download(asset: &ReleaseAsset, target: &Path) -> Result<(), String> {
+    let response = ureq::AgentBuilder::new()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .get(&asset.browser_download_url)
+        .set("User-Agent", "church-transcriber")
+        .call()
+        .map_err(|err| describe_error(&format!("Download of {}", asset.name), err))?;
+    let mut file = File::create(target).map_err(|err| format!("Could not create {}: {err}", target.display()))?;
+    io::copy(&mut response.into_reader(), &mut file)
+        .map_err(|err| format!("Download of {} failed: {err}", asset.name))?;
+    Ok(())
+}
+
+fn verify_digest(asset: &ReleaseAsset, archive: &Path) -> Result<(), String> {
+    let Some(expected) = asset.digest.as_deref().and_then(|d| d.strip_prefix("sha256:")) else {
+        return Ok(());
+    };
+    let mut file = File::open(archive).map_err(|err| format!("Could not read {}: {err}", archive.display()))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(|err| format!("Could not read {}: {err}", archive.display()))?;
+    let actual = hex::encode(hasher.finalize());
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(format!("{} is corrupt (checksum mismatch); try again.", asset.name));
+    }
+    Ok(())
+}
+
+fn extract(archive: &Path, target: &Path) -> Result<(), String> {
+    let file = File::open(archive).map_err(|err| format!("Could not read {}: {err}", archive.display()))?;
+    let mut zip = ZipArchive::new(file).map_err(|err| format!("{} is not a valid zip: {err}", archive.display()))?;
+    zip.extract(target)
+        .map_err(|err| format!("Could not extract {}: {err}", archive.display()))
+}
+
+fn find_exe(dir: &Path) -> Option<PathBuf> {
+    let name = if cfg!(windows) { "whisper-cli.exe" } else { "whisper-cli" };
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_exe(&path) {
+                return Some(found);
+            }
+        } else if entry.file_name().to_string_lossy().eq_ignore_ascii_case(name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Downloads, checks and unpacks the release, then confirms the new binary
+/// starts before anything points at it.
+fn install(app: &AppHandle, previous_exe: &str) -> Result<WhisperInstall, String> {
+    let release = latest_release()?;
+    let name = asset_for_platform()
+        .ok_or_else(|| "whisper.cpp only publishes prebuilt binaries for Windows; build it from source here.".to_string())?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| format!("Release {} has no {name}.", release.tag_name))?;
+
+    let root = install_root(app)?;
+    let archive = root.join(&asset.name);
+    let target = root.join(&release.tag_name);
+    crate::emit_log(app, "system", format!("Downloading whisper.cpp {} ({})...", release.tag_name, asset.name));
+    let unpacked = download(asset, &archive)
+        .and_then(|_| verify_digest(asset, &archive))
+        .and_then(|_| {
+            let _ = fs::remove_dir_all(&target);
+            extract(&archive, &target)
+        });
+    let _ = fs::remove_file(&archive);
+    unpacked?;
+
+    let exe = find_exe(&target).ok_or_else(|| format!("No whisper-cli binary in {}.", asset.name))?;
+    let started = Command::new(&exe)
+        .arg("--help")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| format!("The downloaded whisper-cli would not start: {err}"))?;
+    if !started.success() {
+        let _ = fs::remove_dir_all(&target);
+        return Err(format!("The downloaded whisper-cli failed its self-check (code {:?}).", started.code()));
+    }
+
+    let whisper_exe = exe.display().to_string();
+    save_record(
+        app,
+        &InstallRecord {
+            version: release.tag_name.clone(),
+            whisper_exe: whisper_exe.clone(),
+            installed_at_epoch: crate::now_epoch(),
+        },
+    )?;
+    let previous = previous_exe.trim();
+    let (profiles_updated, schedules_updated) = if previous.is_empty() {
+        (0, 0)
+    } else {
+        (
+            profiles::replace_whisper_exe(app, previous, &whisper_exe)?,
+            schedule::replace_whisper_exe(app, previous, &whisper_exe)?,
+        )
+    };
+    Ok(WhisperInstall {
+        version: release.tag_name,
+        whisper_exe,
+        profiles_updated,
+        schedules_updated,
+    })
+}
+
+/// Compares the configured whisper-cli with the latest whisper.cpp release.
+#[tauri::command]
+pub async fn check_whisper_update(app: AppHandle, whisper_exe: String) -> Result<WhisperUpdate, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let release = latest_release()?;
+        Ok(check(&app, whisper_exe.trim(), &release))
+    })
+    .await
+    .map_err(|err| format!("Update check task failed: {err}"))?
+}
+
+/// Installs the latest prebuilt whisper-cli into the app data folder and
+/// points saved profiles and schedules that used `whisper_exe` at it. Emits
+/// `transcribe://whisper-updated` so the window can update its own field.
+#[tauri::command]
+pub async fn install_whisper_update(app: AppHandle, whisper_exe: String) -> Result<WhisperInstall, String> {
+    if crate::get_status(&app.state::<RunnerState>()).running {
+        return Err("Wait for the current run to finish before updating whisper.cpp.".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let installed = install(&app, &whisper_exe)?;
+        crate::emit_log(
+            &app,
+            "system",
+            format!("Installed whisper.cpp {} at {}.", installed.version, installed.whisper_exe),
+        );
+        let _ = app.emit("transcribe://whisper-updated", installed.clone());
+        Ok(installed)
+    })
+    .await
+    .map_err(|err| format!("Whisper update task failed: {err}"))?
+}
@@ -0,0 +1,62 @@
+use crate::transcript::{self, TIMESTAMPS_FILE};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where the batch script moves the output a re-run replaces, one
+/// `<yyyyMMdd-HHmmss>-<model>` folder per version.
+pub const VERSIONS_DIR: &str = ".versions";
+const RAW_FILE: &str = "raw.txt";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptVersion {
+    /// The version folder's name.
+    pub id: String,
+    pub path: String,
+    /// `yyyyMMdd-HHmmss`, local time the version was transcribed.
+    pub generated: String,
+    pub model_file: Option<String>,
+    pub engine: String,
+    pub words: usize,
+    /// False when the version predates timestamped output and can't be diffed.
+    pub has_timestamps: bool,
+}
+
+fn version(dir: PathBuf) -> Option<TranscriptVersion> {
+    let id = dir.file_name()?.to_string_lossy().to_string();
+    let generated = id.get(..15).filter(|g| g.as_bytes().get(8) == Some(&b'-'))?.to_string();
+    Some(TranscriptVersion {
+        generated,
+        model_file: transcript::model_file(&dir),
+        engine: transcript::engine(&dir).cli_name().to_string(),
+        words: fs::read_to_string(dir.join(RAW_FILE))
+            .map(|text| text.split_whitespace().count())
+            .unwrap_or(0),
+        has_timestamps: dir.join(TIMESTAMPS_FILE).exists(),
+        path: dir.display().to_string(),
+        id,
+    })
+}
+
+/// Earlier versions of the transcript in `output_dir`, newest first.
+pub fn list(output_dir: &Path) -> Vec<TranscriptVersion> {
+    let Ok(read) = fs::read_dir(output_dir.join(VERSIONS_DIR)) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<TranscriptVersion> = read
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(version)
+        .collect();
+    versions.sort_by(|a, b| b.id.cmp(&a.id));
+    versions
+}
+
+#[tauri::command]
+pub fn list_transcript_versions(output_dir: String) -> Vec<TranscriptVersion> {
+    list(Path::new(output_dir.trim()))
+}
@@ -0,0 +1,333 @@
+use crate::{
+    export::{self, SRT_FILE, VTT_FILE},
+    history::{self, TranscriptEdit},
+    search,
+    transcript::{self, EnrichedTranscript, Segment, ENRICHED_FILE, TIMESTAMPS_FILE},
+};
+use chrono::Local;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::AppHandle;
+
+const BACKUPS_DIR: &str = ".backups";
+
+/// A finished transcript as typed segments for the in-app viewer. `format` names
+/// the file the segments were read from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptView {
+    pub output_dir: String,
+    pub title: String,
+    pub source_file: Option<String>,
+    pub format: String,
+    pub segments: Vec<Segment>,
+}
+
+/// `HH:MM:SS,mmm` (SRT) or `[HH:]MM:SS.mmm` (VTT) to milliseconds.
+fn parse_cue_time(value: &str) -> Option<u64> {
+    let value = value.trim().replace(',', ".");
+    let (clock, millis) = value.split_once('.').unwrap_or((&value, "0"));
+    let mut secs = 0u64;
+    for part in clock.split(':') {
+        secs = secs * 60 + part.trim().parse::<u64>().ok()?;
+    }
+    let millis: u64 = format!("{millis:0<3}").get(..3)?.parse().ok()?;
+    Some(secs * 1000 + millis)
+}
+
+/// Splits a VTT `<v Speaker>` voice tag off the cue text.
+fn split_voice(text: &str) -> (Option<String>, String) {
+    // `<v.class Name>` carries a class before the name.
+    let rest = match text.strip_prefix("<v.") {
+        Some(classed) => classed.split_once(' ').map(|(_, r)| r),
+        None => text.strip_prefix("<v "),
+    };
+    let Some(rest) = rest else {
+        return (None, text.to_string());
+    };
+    match rest.split_once('>') {
+        Some((name, body)) => {
+            let name = name.trim();
+            let body = body.replace("</v>", "");
+            ((!name.is_empty()).then(|| name.to_string()), body.trim().to_string())
+        }
+        None => (None, text.to_string()),
+    }
+}
+
+/// Reads SRT or VTT cues; numbering, headers, and cue settings are ignored.
+fn parse_captions(body: &str) -> Vec<Segment> {
+    let body = body.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    body.split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+            let (from, to) = lines.next()?.split_once("-->")?;
+            let start_ms = parse_cue_time(from)?;
+            let end_ms = parse_cue_time(to.split_whitespace().next()?)?;
+            let text = lines.map(str::trim).collect::<Vec<_>>().join(" ");
+            let (speaker, text) = split_voice(&text);
+            (!text.is_empty()).then_some(Segment {
+                start_ms,
+                end_ms,
+                text,
+                speaker,
+            })
+        })
+        .collect()
+}
+
+fn load_view(output_dir: &Path) -> Result<TranscriptView, String> {
+    if !output_dir.is_dir() {
+        return Err(format!("Transcript folder not found: {}", output_dir.display()));
+    }
+    let view = |format: &str, title: String, source_file: Option<String>, segments| TranscriptView {
+        output_dir: output_dir.display().to_string(),
+        title,
+        source_file,
+        format: format.to_string(),
+        segments,
+    };
+    let folder_title = output_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let enriched_path = output_dir.join(ENRICHED_FILE);
+    if let Ok(body) = fs::read_to_string(&enriched_path) {
+        let enriched: EnrichedTranscript = serde_json::from_str(&body)
+            .map_err(|err| format!("Could not parse {}: {err}", enriched_path.display()))?;
+        return Ok(view(
+            ENRICHED_FILE,
+            enriched.title,
+            Some(enriched.source_file),
+            enriched.segments,
+        ));
+    }
+
+    if output_dir.join(TIMESTAMPS_FILE).exists() {
+        let segments = transcript::load_segments(output_dir)?;
+        return Ok(view(TIMESTAMPS_FILE, folder_title, None, segments));
+    }
+
+    for name in [SRT_FILE, VTT_FILE] {
+        let path = output_dir.join(name);
+        if let Ok(body) = fs::read_to_string(&path) {
+            return Ok(view(name, folder_title, None, parse_captions(&body)));
+        }
+    }
+
+    Err(format!(
+        "No timed transcript ({ENRICHED_FILE}, {TIMESTAMPS_FILE}, {SRT_FILE} or {VTT_FILE}) in {}",
+        output_dir.display()
+    ))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditResult {
+    pub files: Vec<String>,
+    pub backup_dir: String,
+    pub segments_changed: usize,
+    pub run_id: Option<String>,
+}
+
+/// Writes to a sibling temp file first so a crash never leaves a half-written
+/// transcript behind.
+fn write_atomic(path: &Path, body: &str) -> Result<(), String> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = path.with_file_name(format!(".{name}.tmp"));
+    fs::write(&temp, body).map_err(|err| format!("Failed to write {}: {err}", temp.display()))?;
+    fs::rename(&temp, path).map_err(|err| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to replace {}: {err}", path.display())
+    })
+}
+
+fn changed_count(before: &[Segment], after: &[Segment]) -> usize {
+    let differs = |a: &Segment, b: &Segment| {
+        a.text != b.text || a.start_ms != b.start_ms || a.end_ms != b.end_ms || a.speaker != b.speaker
+    };
+    let paired = before.iter().zip(after).filter(|(a, b)| differs(a, b)).count();
+    paired + before.len().abs_diff(after.len())
+}
+
+/// A new `.backups/<timestamp>` folder; saves within the same millisecond get
+/// a numbered suffix instead of sharing one.
+fn create_backup_dir(output_dir: &Path) -> Result<PathBuf, String> {
+    let root = output_dir.join(BACKUPS_DIR);
+    fs::create_dir_all(&root).map_err(|err| format!("Could not create {}: {err}", root.display()))?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    for attempt in 0..1000 {
+        let dir = match attempt {
+            0 => root.join(&stamp),
+            n => root.join(format!("{stamp}-{n}")),
+        };
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(format!("Could not create {}: {err}", dir.display())),
+        }
+    }
+    Err(format!("Could not create a backup folder in {}", root.display()))
+}
+
+/// Rewrites `transcript.json` (plus the SRT/VTT captions when the run exported
+/// them) from proofread segments. Edits stay in this published layer:
+/// `raw.txt` and `timestamps.json` keep whisper's unfiltered output. Every file
+/// about to be replaced is first copied to a new `.backups/<timestamp>/` folder.
+fn save_edit(app: &AppHandle, output_dir: &Path, segments: Vec<Segment>) -> Result<EditResult, String> {
+    let current = load_view(output_dir)?;
+    let segments: Vec<Segment> = segments
+        .into_iter()
+        .map(|s| Segment {
+            text: s.text.trim().to_string(),
+            ..s
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect();
+    if segments.is_empty() {
+        return Err("Refusing to save an empty transcript.".to_string());
+    }
+    let segments_changed = changed_count(&current.segments, &segments);
+
+    let enriched_path = output_dir.join(ENRICHED_FILE);
+    let mut enriched = match fs::read_to_string(&enriched_path) {
+        Ok(body) => serde_json::from_str::<EnrichedTranscript>(&body)
+            .map_err(|err| format!("Could not parse {}: {err}", enriched_path.display()))?,
+        Err(_) => EnrichedTranscript {
+            title: current.title.clone(),
+            source_file: current.source_file.clone().unwrap_or_default(),
+            generated_at_epoch: crate::now_epoch(),
+            segments: Vec::new(),
+            scripture_references: Vec::new(),
+            chapters: Vec::new(),
+            sections: Vec::new(),
+        },
+    };
+    enriched.segments = segments;
+    let enriched_body = serde_json::to_string_pretty(&enriched)
+        .map_err(|err| format!("Failed to serialize transcript: {err}"))?;
+    let mut writes: Vec<(PathBuf, String)> = vec![(enriched_path, enriched_body)];
+    if output_dir.join(SRT_FILE).exists() {
+        writes.push((output_dir.join(SRT_FILE), export::render_srt(&enriched.segments)));
+    }
+    if output_dir.join(VTT_FILE).exists() {
+        writes.push((output_dir.join(VTT_FILE), export::render_vtt(&enriched.segments)));
+    }
+
+    let backup_dir = create_backup_dir(output_dir)?;
+    for (path, _) in &writes {
+        if let Some(name) = path.file_name().filter(|_| path.exists()) {
+            fs::copy(path, backup_dir.join(name))
+                .map_err(|err| format!("Could not back up {}: {err}", path.display()))?;
+        }
+    }
+
+    let mut files = Vec::new();
+    for (path, body) in &writes {
+        write_atomic(path, body)?;
+        files.push(path.display().to_string());
+    }
+
+    search::reindex(app, output_dir, &enriched.title, &enriched.source_file);
+    let edit = TranscriptEdit {
+        output_dir: output_dir.display().to_string(),
+        edited_at_epoch: crate::now_epoch(),
+        segments_changed,
+        files: files.clone(),
+        backup_dir: backup_dir.display().to_string(),
+    };
+    let run_id = history::record_edit(app, edit)?;
+
+    Ok(EditResult {
+        files,
+        backup_dir: backup_dir.display().to_string(),
+        segments_changed,
+        run_id,
+    })
+}
+
+#[tauri::command]
+pub async fn get_transcript(output_dir: String) -> Result<TranscriptView, String> {
+    tauri::async_runtime::spawn_blocking(move || load_view(Path::new(output_dir.trim())))
+        .await
+        .map_err(|err| format!("Transcript load task failed: {err}"))?
+}
+
+#[tauri::command]
+pub async fn save_transcript_edit(
+    app: AppHandle,
+    output_dir: String,
+    segments: Vec<Segment>,
+) -> Result<EditResult, String> {
+    tauri::async_runtime::spawn_blocking(move || save_edit(&app, Path::new(output_dir.trim()), segments))
+        .await
+        .map_err(|err| format!("Transcript save task failed: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_folders_are_unique() {
+        let dir = std::env::temp_dir().join(format!("church-transcriber-backup-test-{}", std::process::id()));
+        let first = create_backup_dir(&dir).unwrap();
+        let second = create_backup_dir(&dir).unwrap();
+        let third = create_backup_dir(&dir).unwrap();
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+        assert!(first.is_dir() && second.is_dir() && third.is_dir());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cue_times() {
+        assert_eq!(parse_cue_time("00:01:02,500"), Some(62_500));
+        assert_eq!(parse_cue_time("01:02:03.004"), Some(3_723_004));
+        assert_eq!(parse_cue_time("01:02.5"), Some(62_500));
+        assert_eq!(parse_cue_time(" 00:00.040 "), Some(40));
+        assert_eq!(parse_cue_time("00:01:02"), Some(62_000));
+        assert_eq!(parse_cue_time("aa:01.000"), None);
+    }
+
+    #[test]
+    fn voice_tags() {
+        assert_eq!(
+            split_voice("<v Pastor Dave>Good morning</v>"),
+            (Some("Pastor Dave".to_string()), "Good morning".to_string())
+        );
+        assert_eq!(
+            split_voice("<v.loud Pastor Dave>Amen"),
+            (Some("Pastor Dave".to_string()), "Amen".to_string())
+        );
+        assert_eq!(split_voice("No voice here"), (None, "No voice here".to_string()));
+        assert_eq!(split_voice("<v unterminated"), (None, "<v unterminated".to_string()));
+    }
+
+    #[test]
+    fn srt_with_commas() {
+        let srt = "1\n00:00:01,000 --> 00:00:03,500\nWelcome this\nmorning.\n\n2\n00:00:04,000 --> 00:00:05,250\nPlease sit.\n";
+        let segments = parse_captions(srt);
+        assert_eq!(segments.len(), 2);
+        assert_eq!((segments[0].start_ms, segments[0].end_ms), (1_000, 3_500));
+        assert_eq!(segments[0].text, "Welcome this morning.");
+        assert_eq!(segments[1].end_ms, 5_250);
+        assert_eq!(segments[1].speaker, None);
+    }
+
+    #[test]
+    fn vtt_with_bom_crlf_and_voices() {
+        let vtt = "\u{feff}WEBVTT\r\n\r\nNOTE produced by whisper\r\n\r\nintro\r\n00:01.000 --> 00:04.500 align:start position:10%\r\n<v.host Pastor Dave>Good morning</v>\r\n\r\n00:00:05.000 --> 00:00:06.000\r\nSecond\r\nline\r\n\r\n00:07.000 --> 00:08.000\r\n\r\n";
+        let segments = parse_captions(vtt);
+        assert_eq!(segments.len(), 2);
+        assert_eq!((segments[0].start_ms, segments[0].end_ms), (1_000, 4_500));
+        assert_eq!(segments[0].speaker.as_deref(), Some("Pastor Dave"));
+        assert_eq!(segments[0].text, "Good morning");
+        assert_eq!(segments[1].text, "Second line");
+        assert_eq!(segments[1].start_ms, 5_000);
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long a candidate gets to print its usage before it counts as hung.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The whisper build a run uses, picked from the configured candidates.
+pub struct Selection {
+    pub exe: String,
+    /// `path: reason` for each higher-priority candidate that didn't work.
+    pub skipped: Vec<String>,
+}
+
+/// Starts `exe --help` and waits for it to exit. A CUDA or BLAS build whose
+/// runtime DLLs are missing never gets that far: Windows stops it with a
+/// status code such as 0xC0000135 instead of an ordinary exit code.
+pub fn probe(exe: &str) -> Result<(), String> {
+    if crate::looks_like_path(exe) && !Path::new(exe).is_file() {
+        return Err("not found".to_string());
+    }
+    let mut child = Command::new(exe)
+        .arg("--help")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("could not start: {err}"))?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                return match status.code() {
+                    Some(code) if (0..=255).contains(&code) => Ok(()),
+                    Some(code) => Err(format!("failed to load ({:#010x})", code as u32)),
+                    None => Err("killed while starting".to_string()),
+                };
+            }
+            Ok(None) if started.elapsed() < PROBE_TIMEOUT => thread::sleep(Duration::from_millis(100)),
+            Ok(None) => {
+                let _ = child.kill();
+                return Err("did not answer --help".to_string());
+            }
+            Err(err) => return Err(format!("could not wait for it: {err}")),
+        }
+    }
+}
+
+/// The first of `candidates`, then `primary`, that starts on this machine.
+pub fn select(primary: &str, candidates: &[String]) -> Result<Selection, String> {
+    let mut ordered: Vec<&str> = candidates.iter().map(|c| c.trim()).filter(|c| !c.is_empty()).collect();
+    if !primary.trim().is_empty() && !ordered.contains(&primary.trim()) {
+        ordered.push(primary.trim());
+    }
+    let mut skipped = Vec::new();
+    for exe in ordered {
+        match probe(exe) {
+            Ok(()) => {
+                return Ok(Selection {
+                    exe: exe.to_string(),
+                    skipped,
+                })
+            }
+            Err(reason) => skipped.push(format!("{exe}: {reason}")),
+        }
+    }
+    Err(if skipped.is_empty() {
+        "No whisper executable configured.".to_string()
+    } else {
+        format!("No whisper executable works on this machine ({}).", skipped.join("; "))
+    })
+}
@@ -13,6 +13,10 @@ type RunnerStatus = {
 type LogEvent = {
   stream: string;
   line: string;
+  level: "debug" | "info" | "warn" | "error";
+  timestampMs: number;
+  runId: string | null;
+  sourceFile: string | null;
 };
 
 type StageEvent = {